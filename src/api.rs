@@ -0,0 +1,250 @@
+//! Typed methods on [`NotionClient`], one per API endpoint.
+//!
+//! Each method sends its request through the client's raw `get`/`post`/
+//! `patch`/`delete` (so retries, rate limiting, tracing and cassettes all
+//! apply) and decodes the response into a [`models`](crate::models) type.
+//! Request bodies are anything serializable: a model or a `json!` value.
+//!
+//! Write methods can't decode the previews a dry-run client returns; use the
+//! raw methods for dry runs.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::path::Path;
+
+use crate::client::NotionClient;
+use crate::models::{
+    Block, Comment, DataSource, Database, FileUpload, List, Page, SearchResult, User,
+};
+use crate::pagination::{ListRequest, Pagination, paginate};
+
+impl NotionClient {
+    pub async fn retrieve_me(&self) -> Result<User> {
+        decode(self.get("/v1/users/me", &[]).await?, "user")
+    }
+
+    pub async fn retrieve_user(&self, user_id: &str) -> Result<User> {
+        let path = format!("/v1/users/{}", user_id);
+        decode(self.get(&path, &[]).await?, "user")
+    }
+
+    pub async fn list_users(&self, cursor: Option<&str>) -> Result<List<User>> {
+        self.list_page(&ListRequest::get("/v1/users"), cursor).await
+    }
+
+    pub async fn retrieve_page(&self, page_id: &str) -> Result<Page> {
+        let path = format!("/v1/pages/{}", page_id);
+        decode(self.get(&path, &[]).await?, "page")
+    }
+
+    pub async fn create_page(&self, body: &impl Serialize) -> Result<Page> {
+        let body = encode(body)?;
+        decode(self.post("/v1/pages", Some(&body)).await?, "page")
+    }
+
+    /// Update properties, icon, cover or trash status.
+    pub async fn update_page(&self, page_id: &str, body: &impl Serialize) -> Result<Page> {
+        let path = format!("/v1/pages/{}", page_id);
+        decode(self.patch(&path, &encode(body)?).await?, "page")
+    }
+
+    pub async fn retrieve_block(&self, block_id: &str) -> Result<Block> {
+        let path = format!("/v1/blocks/{}", block_id);
+        decode(self.get(&path, &[]).await?, "block")
+    }
+
+    pub async fn list_block_children(
+        &self,
+        block_id: &str,
+        cursor: Option<&str>,
+    ) -> Result<List<Block>> {
+        let request = ListRequest::get(format!("/v1/blocks/{}/children", block_id));
+        self.list_page(&request, cursor).await
+    }
+
+    /// Append blocks at the end of a block's children, or after `after`.
+    pub async fn append_block_children(
+        &self,
+        block_id: &str,
+        children: &[Block],
+        after: Option<&str>,
+    ) -> Result<List<Block>> {
+        let mut body = json!({ "children": encode(&children)? });
+        if let Some(after) = after {
+            body["after"] = json!(after);
+        }
+        let path = format!("/v1/blocks/{}/children", block_id);
+        decode(self.patch(&path, &body).await?, "block list")
+    }
+
+    pub async fn update_block(&self, block_id: &str, body: &impl Serialize) -> Result<Block> {
+        let path = format!("/v1/blocks/{}", block_id);
+        decode(self.patch(&path, &encode(body)?).await?, "block")
+    }
+
+    /// Move a block to the trash.
+    pub async fn delete_block(&self, block_id: &str) -> Result<Block> {
+        let path = format!("/v1/blocks/{}", block_id);
+        decode(self.delete(&path).await?, "block")
+    }
+
+    pub async fn list_comments(
+        &self,
+        block_id: &str,
+        cursor: Option<&str>,
+    ) -> Result<List<Comment>> {
+        let request = ListRequest::get("/v1/comments").query("block_id", block_id);
+        self.list_page(&request, cursor).await
+    }
+
+    pub async fn create_comment(&self, body: &impl Serialize) -> Result<Comment> {
+        let body = encode(body)?;
+        decode(self.post("/v1/comments", Some(&body)).await?, "comment")
+    }
+
+    pub async fn retrieve_database(&self, database_id: &str) -> Result<Database> {
+        let path = format!("/v1/databases/{}", database_id);
+        decode(self.get(&path, &[]).await?, "database")
+    }
+
+    pub async fn retrieve_data_source(&self, ds_id: &str) -> Result<DataSource> {
+        let path = format!("/v1/data_sources/{}", ds_id);
+        decode(self.get(&path, &[]).await?, "data source")
+    }
+
+    pub async fn create_data_source(&self, body: &impl Serialize) -> Result<DataSource> {
+        let body = encode(body)?;
+        decode(
+            self.post("/v1/data_sources", Some(&body)).await?,
+            "data source",
+        )
+    }
+
+    pub async fn update_data_source(
+        &self,
+        ds_id: &str,
+        body: &impl Serialize,
+    ) -> Result<DataSource> {
+        let path = format!("/v1/data_sources/{}", ds_id);
+        decode(self.patch(&path, &encode(body)?).await?, "data source")
+    }
+
+    /// One page of query results; `query` holds the `filter` and `sorts`.
+    pub async fn query_data_source(
+        &self,
+        ds_id: &str,
+        query: &impl Serialize,
+        cursor: Option<&str>,
+    ) -> Result<List<Page>> {
+        let path = format!("/v1/data_sources/{}/query", ds_id);
+        let request = ListRequest::post(path, body_object(query)?);
+        self.list_page(&request, cursor).await
+    }
+
+    /// Like [`query_data_source`](Self::query_data_source), but sent even
+    /// with `--dry-run`.
+    pub async fn lookup_data_source(
+        &self,
+        ds_id: &str,
+        query: &impl Serialize,
+    ) -> Result<List<Page>> {
+        let path = format!("/v1/data_sources/{}/query", ds_id);
+        let response = self.post_read(&path, Some(&body_object(query)?)).await?;
+        decode(response, "list")
+    }
+
+    /// One page of search results; `query` holds the `query`, `filter` and `sort`.
+    pub async fn search(
+        &self,
+        query: &impl Serialize,
+        cursor: Option<&str>,
+    ) -> Result<List<SearchResult>> {
+        let request = ListRequest::post("/v1/search", body_object(query)?);
+        self.list_page(&request, cursor).await
+    }
+
+    pub async fn create_file_upload(&self, body: &impl Serialize) -> Result<FileUpload> {
+        let body = encode(body)?;
+        decode(
+            self.post("/v1/file_uploads", Some(&body)).await?,
+            "file upload",
+        )
+    }
+
+    /// Send a file, or one part of a multi-part upload.
+    pub async fn send_file_upload(
+        &self,
+        upload_id: &str,
+        file: &Path,
+        part_number: Option<u32>,
+    ) -> Result<FileUpload> {
+        let path = format!("/v1/file_uploads/{}/send", upload_id);
+        decode(
+            self.post_multipart(&path, file, part_number).await?,
+            "file upload",
+        )
+    }
+
+    pub async fn complete_file_upload(&self, upload_id: &str) -> Result<FileUpload> {
+        let path = format!("/v1/file_uploads/{}/complete", upload_id);
+        decode(self.post(&path, None).await?, "file upload")
+    }
+
+    pub async fn retrieve_file_upload(&self, upload_id: &str) -> Result<FileUpload> {
+        let path = format!("/v1/file_uploads/{}", upload_id);
+        decode(self.get(&path, &[]).await?, "file upload")
+    }
+
+    pub async fn list_file_uploads(&self, cursor: Option<&str>) -> Result<List<FileUpload>> {
+        self.list_page(&ListRequest::get("/v1/file_uploads"), cursor)
+            .await
+    }
+
+    /// Every item of a list endpoint, following cursors to the end.
+    pub async fn list_all<T: DeserializeOwned>(&self, request: &ListRequest) -> Result<Vec<T>> {
+        let all = Pagination {
+            all: true,
+            ..Pagination::default()
+        };
+        let list: List<T> = decode(paginate(self, request, &all, |_| Ok(())).await?, "list")?;
+        Ok(list.results)
+    }
+
+    async fn list_page<T: DeserializeOwned>(
+        &self,
+        request: &ListRequest,
+        cursor: Option<&str>,
+    ) -> Result<List<T>> {
+        let pagination = Pagination {
+            start_cursor: cursor.map(String::from),
+            ..Pagination::default()
+        };
+        decode(
+            paginate(self, request, &pagination, |_| Ok(())).await?,
+            "list",
+        )
+    }
+}
+
+fn encode(body: &impl Serialize) -> Result<Value> {
+    serde_json::to_value(body).context("Failed to serialize request body")
+}
+
+/// A request body that pagination fields can be added to.
+fn body_object(body: &impl Serialize) -> Result<Value> {
+    match encode(body)? {
+        Value::Null => Ok(json!({})),
+        body @ Value::Object(_) => Ok(body),
+        other => anyhow::bail!("Request body should be a JSON object, got {}", other),
+    }
+}
+
+fn decode<T: DeserializeOwned>(value: Value, what: &str) -> Result<T> {
+    serde_json::from_value(value).with_context(|| format!("Unexpected {} in response", what))
+}
+
+#[cfg(test)]
+#[path = "api_tests.rs"]
+mod tests;
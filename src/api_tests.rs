@@ -0,0 +1,242 @@
+use super::*;
+use crate::mock_server::MockServer;
+use crate::models::{
+    BlockContent, FileUploadStatus, Parent, PropertyConfig, PropertyContent, TextBlock,
+};
+
+async fn mock_workspace() -> (MockServer, NotionClient) {
+    let server = MockServer::start("127.0.0.1:0").await.unwrap();
+    let client = NotionClient::with_base_url("token", server.url()).unwrap();
+    (server, client)
+}
+
+#[tokio::test]
+async fn test_users() {
+    let (server, client) = mock_workspace().await;
+    let me = client.retrieve_me().await.unwrap();
+    assert_eq!(me.id, server.seed().bot_id);
+    assert_eq!(me.kind.as_deref(), Some("bot"));
+
+    let person = client
+        .retrieve_user(&server.seed().person_id)
+        .await
+        .unwrap();
+    assert_eq!(person.email(), Some("ada@example.com"));
+
+    let users = client.list_users(None).await.unwrap();
+    assert_eq!(users.results.len(), 2);
+    assert!(!users.has_more);
+}
+
+#[tokio::test]
+async fn test_pages_blocks_and_comments() {
+    let (server, client) = mock_workspace().await;
+    let home = &server.seed().page_id;
+
+    let page = client
+        .create_page(&json!({
+            "parent": { "page_id": home },
+            "properties": { "title": { "title": [{ "text": { "content": "Plan" } }] } },
+        }))
+        .await
+        .unwrap();
+    assert_eq!(page.title(), "Plan");
+
+    let children = [
+        Block::new(BlockContent::Paragraph(TextBlock::plain("First"))),
+        Block::new(BlockContent::Paragraph(TextBlock::plain("Second"))),
+    ];
+    let appended = client
+        .append_block_children(&page.id, &children, None)
+        .await
+        .unwrap();
+    assert_eq!(appended.results.len(), 2);
+
+    let listed = client.list_block_children(&page.id, None).await.unwrap();
+    let texts: Vec<_> = listed.results.iter().map(Block::text).collect();
+    assert_eq!(texts, ["First", "Second"]);
+
+    let deleted = client.delete_block(&listed.results[0].id).await.unwrap();
+    assert!(deleted.in_trash);
+
+    let comment = client
+        .create_comment(&json!({
+            "parent": { "page_id": page.id },
+            "rich_text": [{ "text": { "content": "Looks good" } }],
+        }))
+        .await
+        .unwrap();
+    assert_eq!(comment.text(), "Looks good");
+    let comments = client.list_comments(&page.id, None).await.unwrap();
+    assert_eq!(comments.results, [comment]);
+
+    let trashed = client
+        .update_page(&page.id, &json!({ "in_trash": true }))
+        .await
+        .unwrap();
+    assert!(trashed.in_trash);
+}
+
+#[tokio::test]
+async fn test_data_sources_and_queries() {
+    let (server, client) = mock_workspace().await;
+    let data_source = client
+        .create_data_source(&json!({
+            "parent": { "page_id": server.seed().page_id },
+            "title": [{ "text": { "content": "Tasks" } }],
+            "properties": {
+                "Name": { "title": {} },
+                "Points": { "number": {} },
+            },
+        }))
+        .await
+        .unwrap();
+    assert_eq!(data_source.title_text(), "Tasks");
+    assert!(matches!(
+        data_source.properties["Points"].config,
+        PropertyConfig::Number(_)
+    ));
+    let Some(Parent::DatabaseId(database_id)) = &Some(data_source.parent.clone()) else {
+        panic!("expected a database parent, got {:?}", data_source.parent);
+    };
+    let database = client.retrieve_database(database_id).await.unwrap();
+    assert_eq!(database.data_sources[0].id, data_source.id);
+
+    for (name, points) in [("a", 1), ("b", 5), ("c", 3)] {
+        client
+            .create_page(&json!({
+                "parent": { "data_source_id": data_source.id },
+                "properties": {
+                    "Name": { "title": [{ "text": { "content": name } }] },
+                    "Points": { "number": points },
+                },
+            }))
+            .await
+            .unwrap();
+    }
+
+    let query = json!({
+        "filter": { "property": "Points", "number": { "greater_than": 1 } },
+        "sorts": [{ "property": "Points", "direction": "descending" }],
+    });
+    let pages = client
+        .query_data_source(&data_source.id, &query, None)
+        .await
+        .unwrap();
+    let titles: Vec<_> = pages.results.iter().map(Page::title).collect();
+    assert_eq!(titles, ["b", "c"]);
+    assert_eq!(
+        pages.results[0].property("Points"),
+        Some(&PropertyContent::Number(Some(5.0)))
+    );
+
+    let renamed = client
+        .update_data_source(
+            &data_source.id,
+            &json!({ "title": [{ "text": { "content": "Todo" } }] }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(renamed.title_text(), "Todo");
+    let fetched = client.retrieve_data_source(&data_source.id).await.unwrap();
+    assert_eq!(fetched.title_text(), "Todo");
+
+    let found = client
+        .search(
+            &json!({ "filter": { "property": "object", "value": "data_source" } }),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(matches!(&found.results[..], [SearchResult::DataSource(d)] if d.id == data_source.id));
+}
+
+#[tokio::test]
+async fn test_list_page_follows_cursor_and_list_all_collects() {
+    let (server, client) = mock_workspace().await;
+    let home = &server.seed().page_id;
+    let children: Vec<_> = (0..3)
+        .map(|i| Block::new(BlockContent::Paragraph(TextBlock::plain(i.to_string()))))
+        .collect();
+    client
+        .append_block_children(home, &children, None)
+        .await
+        .unwrap();
+
+    let request = ListRequest::get(format!("/v1/blocks/{}/children", home)).query("page_size", "2");
+    let first: List<Block> = client.list_page(&request, None).await.unwrap();
+    assert!(first.has_more);
+    let cursor = first.next_cursor.as_deref().unwrap();
+    let second = client
+        .list_block_children(home, Some(cursor))
+        .await
+        .unwrap();
+    assert_eq!(second.results.len(), 1);
+
+    let all: Vec<Block> = client
+        .list_all(&ListRequest::get(format!("/v1/blocks/{}/children", home)))
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 3);
+}
+
+#[tokio::test]
+async fn test_file_uploads() {
+    let (_server, client) = mock_workspace().await;
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("notes.txt");
+    std::fs::write(&file, "hello").unwrap();
+
+    let upload = client
+        .create_file_upload(&json!({ "filename": "notes.txt", "content_type": "text/plain" }))
+        .await
+        .unwrap();
+    assert_eq!(upload.status, FileUploadStatus::Pending);
+
+    let sent = client
+        .send_file_upload(&upload.id, &file, None)
+        .await
+        .unwrap();
+    assert_eq!(sent.status, FileUploadStatus::Uploaded);
+    assert_eq!(sent.content_length, Some(5));
+
+    let fetched = client.retrieve_file_upload(&upload.id).await.unwrap();
+    assert_eq!(fetched, sent);
+    let listed = client.list_file_uploads(None).await.unwrap();
+    assert_eq!(listed.results.len(), 1);
+}
+
+#[tokio::test]
+async fn test_api_errors_pass_through() {
+    let (_server, client) = mock_workspace().await;
+    let err = client
+        .retrieve_page("00000000000000000000000000000000")
+        .await
+        .unwrap_err();
+    assert_eq!(crate::error::kind(&err), crate::error::ErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn test_unexpected_response_shape() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/v1/pages/p1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"object":"page"}"#)
+        .create_async()
+        .await;
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let err = client.retrieve_page("p1").await.unwrap_err();
+    assert!(err.to_string().contains("Unexpected page in response"));
+}
+
+#[tokio::test]
+async fn test_query_body_must_be_an_object() {
+    let client = NotionClient::with_base_url("token", "http://127.0.0.1:9").unwrap();
+    let err = client
+        .query_data_source("ds", &json!([1]), None)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("should be a JSON object"));
+}
@@ -0,0 +1,259 @@
+use anyhow::{Context, Result, bail};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::error::bail_input;
+use crate::trace::redact_value;
+
+/// Whether requests are saved to or served from the cassette directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Record,
+    Replay,
+}
+
+/// Request/response pairs stored on disk for offline runs.
+///
+/// Each distinct request (method, path, query and body) gets its own JSON
+/// file named after a hash of those parts. Repeated identical requests within
+/// one run are stored as a sequence and replayed in the same order; the last
+/// response is repeated once the sequence is exhausted.
+pub struct Cassette {
+    dir: PathBuf,
+    mode: Mode,
+    /// Responses recorded, or replayed, per request key in this run
+    seen: Mutex<HashMap<String, usize>>,
+}
+
+/// What identifies a request when replaying.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    /// Sorted so parameter order doesn't matter
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub query: Vec<(String, String)>,
+    /// JSON body with object keys sorted and secrets masked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    /// Hash of a body that isn't JSON, so different uploads don't share a match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Parsed JSON, or the raw text for non-JSON bodies
+    pub body: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    request: RecordedRequest,
+    responses: Vec<RecordedResponse>,
+}
+
+impl RecordedRequest {
+    pub fn from_request(request: &reqwest::Request) -> Self {
+        let url = request.url();
+        let mut query: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+        query.sort();
+        let bytes = request.body().and_then(|b| b.as_bytes());
+        let body = bytes
+            .and_then(|bytes| serde_json::from_slice::<Value>(bytes).ok())
+            .map(|mut body| {
+                redact_value(&mut body);
+                canonical(body)
+            });
+        let body_hash = match (bytes, &body) {
+            (Some(bytes), None) if !bytes.is_empty() => Some(format!("{:016x}", fnv1a(bytes))),
+            _ => None,
+        };
+        Self {
+            method: request.method().to_string(),
+            path: url.path().to_string(),
+            query,
+            body,
+            body_hash,
+        }
+    }
+
+    /// File name for this request: method, path and a hash of everything.
+    fn file_name(&self) -> String {
+        let key = serde_json::to_string(self).unwrap_or_default();
+        let slug: String = self
+            .path
+            .trim_matches('/')
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .take(60)
+            .collect();
+        format!(
+            "{}_{}_{:016x}.json",
+            self.method,
+            slug,
+            fnv1a(key.as_bytes())
+        )
+    }
+
+    fn describe(&self) -> String {
+        let mut text = format!("{} {}", self.method, self.path);
+        if !self.query.is_empty() {
+            let query: Vec<String> = self
+                .query
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect();
+            text.push_str(&format!("?{}", query.join("&")));
+        }
+        if let Some(body) = &self.body {
+            text.push_str(&format!(" with body {}", body));
+        }
+        if let Some(hash) = &self.body_hash {
+            text.push_str(&format!(" with body hash {}", hash));
+        }
+        text
+    }
+}
+
+impl RecordedResponse {
+    pub fn new(status: StatusCode, request_id: Option<&str>, text: &str) -> Self {
+        let body = match serde_json::from_str::<Value>(text) {
+            Ok(mut body) => {
+                redact_value(&mut body);
+                body
+            }
+            Err(_) => Value::String(text.to_string()),
+        };
+        Self {
+            status: status.as_u16(),
+            request_id: request_id.map(str::to_string),
+            body,
+        }
+    }
+
+    /// Response body as it came over the wire.
+    pub fn text(&self) -> String {
+        match &self.body {
+            Value::String(text) => text.clone(),
+            body => body.to_string(),
+        }
+    }
+}
+
+impl Cassette {
+    /// Open `dir` for recording (created if needed) or replaying.
+    pub fn open(dir: &Path, mode: Mode) -> Result<Self> {
+        match mode {
+            Mode::Record => std::fs::create_dir_all(dir).with_context(|| {
+                format!("Failed to create cassette directory {}", dir.display())
+            })?,
+            Mode::Replay if !dir.is_dir() => {
+                bail_input!("Cassette directory {} does not exist", dir.display())
+            }
+            Mode::Replay => {}
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            mode,
+            seen: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Save a response. The first recording of a request in a run replaces
+    /// whatever an earlier run stored for it.
+    pub fn record(&self, request: RecordedRequest, response: RecordedResponse) -> Result<()> {
+        let name = request.file_name();
+        let path = self.dir.join(&name);
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        let count = seen.entry(name).or_insert(0);
+
+        let mut entry = if *count > 0 {
+            read_entry(&path)?
+        } else {
+            Entry {
+                request,
+                responses: Vec::new(),
+            }
+        };
+        entry.responses.push(response);
+        *count += 1;
+
+        let text = serde_json::to_string_pretty(&entry)?;
+        std::fs::write(&path, text + "\n")
+            .with_context(|| format!("Failed to write cassette {}", path.display()))
+    }
+
+    /// The next recorded response for `request`; an unrecorded request is an error.
+    pub fn replay(&self, request: &RecordedRequest) -> Result<RecordedResponse> {
+        let name = request.file_name();
+        let path = self.dir.join(&name);
+        if !path.exists() {
+            bail!(
+                "No recorded response for {} in {} (record it with --record)",
+                request.describe(),
+                self.dir.display()
+            );
+        }
+        let entry = read_entry(&path)?;
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        let count = seen.entry(name).or_insert(0);
+        let index = (*count).min(entry.responses.len().saturating_sub(1));
+        *count += 1;
+        entry
+            .responses
+            .get(index)
+            .cloned()
+            .with_context(|| format!("Cassette {} has no responses", path.display()))
+    }
+}
+
+fn read_entry(path: &Path) -> Result<Entry> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read cassette {}", path.display()))?;
+    serde_json::from_str(&text)
+        .with_context(|| format!("Failed to parse cassette {}", path.display()))
+}
+
+/// JSON with object keys sorted, so key order doesn't affect matching.
+fn canonical(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, canonical(v)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(canonical).collect()),
+        other => other,
+    }
+}
+
+/// FNV-1a: a hash that stays stable across Rust versions, unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+#[path = "cassette_tests.rs"]
+mod tests;
@@ -0,0 +1,165 @@
+use super::*;
+use serde_json::json;
+
+fn request(method: &str, url: &str, body: Option<Value>) -> reqwest::Request {
+    let client = reqwest::Client::new();
+    let mut builder = client.request(method.parse().unwrap(), url);
+    if let Some(body) = body {
+        builder = builder.json(&body);
+    }
+    builder.build().unwrap()
+}
+
+fn ok(body: &str) -> RecordedResponse {
+    RecordedResponse::new(StatusCode::OK, Some("req-1"), body)
+}
+
+#[test]
+fn test_recorded_request_sorts_query_and_body_keys() {
+    let a = RecordedRequest::from_request(&request(
+        "POST",
+        "http://localhost/v1/data_sources/ds/query?b=2&a=1",
+        Some(json!({"sorts": [], "filter": {"property": "Done", "checkbox": {"equals": true}}})),
+    ));
+    let b = RecordedRequest::from_request(&request(
+        "POST",
+        "http://localhost/v1/data_sources/ds/query?a=1&b=2",
+        Some(json!({"filter": {"checkbox": {"equals": true}, "property": "Done"}, "sorts": []})),
+    ));
+    assert_eq!(a, b);
+    assert_eq!(a.file_name(), b.file_name());
+    assert_eq!(
+        a.query,
+        vec![("a".into(), "1".into()), ("b".into(), "2".into())]
+    );
+}
+
+#[test]
+fn test_different_bodies_get_different_files() {
+    let done = RecordedRequest::from_request(&request(
+        "POST",
+        "http://localhost/v1/data_sources/ds/query",
+        Some(json!({"filter": {"property": "Done", "checkbox": {"equals": true}}})),
+    ));
+    let open = RecordedRequest::from_request(&request(
+        "POST",
+        "http://localhost/v1/data_sources/ds/query",
+        Some(json!({"filter": {"property": "Done", "checkbox": {"equals": false}}})),
+    ));
+    assert_ne!(done.file_name(), open.file_name());
+    assert!(
+        done.file_name()
+            .starts_with("POST_v1_data_sources_ds_query_")
+    );
+}
+
+#[test]
+fn test_non_json_bodies_are_hashed() {
+    let raw = |bytes: &'static [u8]| {
+        let request = reqwest::Client::new()
+            .post("http://localhost/v1/file_uploads/f1/send")
+            .body(bytes)
+            .build()
+            .unwrap();
+        RecordedRequest::from_request(&request)
+    };
+    let first = raw(b"part one");
+    let second = raw(b"part two");
+    assert_eq!(first.body, None);
+    assert!(first.body_hash.is_some());
+    assert_ne!(first.file_name(), second.file_name());
+    assert_eq!(first, raw(b"part one"));
+
+    let json = RecordedRequest::from_request(&request(
+        "POST",
+        "http://localhost/v1/pages",
+        Some(json!({})),
+    ));
+    assert_eq!(json.body_hash, None);
+}
+
+#[test]
+fn test_secrets_are_scrubbed() {
+    let recorded = RecordedRequest::from_request(&request(
+        "POST",
+        "http://localhost/v1/oauth/introspect",
+        Some(json!({"token": "ntn_secret"})),
+    ));
+    assert_eq!(recorded.body, Some(json!({"token": "[REDACTED]"})));
+
+    let response = ok(r#"{"access_token":"ntn_secret","bot_id":"b1"}"#);
+    assert_eq!(
+        response.body,
+        json!({"access_token": "[REDACTED]", "bot_id": "b1"})
+    );
+}
+
+#[test]
+fn test_non_json_response_kept_as_text() {
+    let response = RecordedResponse::new(StatusCode::BAD_GATEWAY, None, "<html>502</html>");
+    assert_eq!(response.text(), "<html>502</html>");
+    assert_eq!(ok(r#"{"object":"user"}"#).text(), r#"{"object":"user"}"#);
+}
+
+#[test]
+fn test_record_then_replay_in_order() {
+    let dir = tempfile::tempdir().unwrap();
+    let get = RecordedRequest::from_request(&request("GET", "http://localhost/v1/users/me", None));
+
+    let recorder = Cassette::open(dir.path(), Mode::Record).unwrap();
+    recorder.record(get.clone(), ok(r#"{"n":1}"#)).unwrap();
+    recorder.record(get.clone(), ok(r#"{"n":2}"#)).unwrap();
+
+    let player = Cassette::open(dir.path(), Mode::Replay).unwrap();
+    assert_eq!(player.replay(&get).unwrap().body, json!({"n": 1}));
+    assert_eq!(player.replay(&get).unwrap().body, json!({"n": 2}));
+    // The last response repeats once the sequence is used up
+    assert_eq!(player.replay(&get).unwrap().body, json!({"n": 2}));
+}
+
+#[test]
+fn test_new_recording_replaces_earlier_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let get = RecordedRequest::from_request(&request("GET", "http://localhost/v1/users/me", None));
+
+    let first = Cassette::open(dir.path(), Mode::Record).unwrap();
+    first.record(get.clone(), ok(r#"{"n":1}"#)).unwrap();
+    let second = Cassette::open(dir.path(), Mode::Record).unwrap();
+    second.record(get.clone(), ok(r#"{"n":2}"#)).unwrap();
+
+    let player = Cassette::open(dir.path(), Mode::Replay).unwrap();
+    assert_eq!(player.replay(&get).unwrap().body, json!({"n": 2}));
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn test_replay_unmatched_request_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let player = Cassette::open(dir.path(), Mode::Replay).unwrap();
+    let get = RecordedRequest::from_request(&request(
+        "GET",
+        "http://localhost/v1/users?page_size=5",
+        None,
+    ));
+    let err = player.replay(&get).unwrap_err().to_string();
+    assert!(
+        err.starts_with("No recorded response for GET /v1/users?page_size=5"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_replay_requires_existing_directory() {
+    let err = Cassette::open(Path::new("/nonexistent/cassettes"), Mode::Replay)
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("does not exist"));
+    assert_eq!(crate::error::kind(&err), crate::error::ErrorKind::Input);
+}
+
+#[test]
+fn test_fnv1a_is_stable() {
+    assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+    assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+}
@@ -1,7 +1,9 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use clap_complete::Shell;
 use std::path::PathBuf;
 
+use crate::error::ErrorFormat;
+use crate::ids::parse_id;
 use crate::output::OutputFormat;
 
 /// Notion CLI - Interact with the Notion API from the command line
@@ -10,7 +12,7 @@ use crate::output::OutputFormat;
 #[command(disable_version_flag = true)]
 #[allow(clippy::manual_non_exhaustive)]
 pub struct Cli {
-    /// Output format
+    /// Output format: pretty, json, raw, ndjson, csv or tsv
     #[arg(long, global = true, default_value = "pretty")]
     pub output: OutputFormat,
 
@@ -22,10 +24,26 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub dry_run: bool,
 
+    /// Config profile to use (see `notion profile list`)
+    #[arg(long, global = true, env = "NOTION_PROFILE")]
+    pub profile: Option<String>,
+
     /// Print version
-    #[arg(short = 'v', long = "version", action = clap::ArgAction::Version)]
+    #[arg(short = 'V', long = "version", action = clap::ArgAction::Version)]
     version: (),
 
+    /// Log requests to stderr (-v: method, URL, status, timing; -vv: also headers and bodies)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Same as -vv
+    #[arg(long, global = true)]
+    pub trace: bool,
+
+    /// Write the request log to a file instead of stderr (implies -v)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
     /// Number of items per page (max 100)
     #[arg(long, global = true)]
     pub page_size: Option<u32>,
@@ -34,15 +52,138 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub start_cursor: Option<String>,
 
+    /// Follow pagination cursors and return every result
+    #[arg(long, global = true)]
+    pub all: bool,
+
+    /// Maximum number of results to return (follows pagination cursors)
+    #[arg(long, global = true, value_parser = clap::value_parser!(u32).range(1..))]
+    pub limit: Option<u32>,
+
+    /// Per-request timeout in seconds (timed-out reads are retried)
+    #[arg(long, global = true, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout: Option<u64>,
+
+    /// Retries for rate limits, conflicts and transient server or network errors
+    #[arg(long, global = true, value_name = "N", default_value_t = 3)]
+    pub max_retries: u32,
+
+    /// Longest wait between retries in seconds (Retry-After is always honored)
+    #[arg(long, global = true, value_name = "SECONDS", default_value_t = 30)]
+    pub max_backoff: u64,
+
+    /// Requests per second shared by all requests (0 disables) [default: 3]
+    #[arg(long, global = true, value_name = "N", value_parser = parse_rate)]
+    pub rate_limit: Option<f64>,
+
+    /// Notion-Version header to send, e.g. to pin an older API version [default: 2025-09-03]
+    #[arg(long, global = true, value_name = "DATE", env = "NOTION_API_VERSION")]
+    pub api_version: Option<String>,
+
+    /// API base URL, e.g. a local stand-in server [default: https://api.notion.com]
+    #[arg(long, global = true, value_name = "URL", env = "NOTION_BASE_URL")]
+    pub base_url: Option<String>,
+
+    /// Proxy URL for all requests (default: HTTPS_PROXY / HTTP_PROXY; NO_PROXY is honored)
+    #[arg(long, global = true, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// PEM file with extra trusted root certificates (e.g. a corporate proxy's CA)
+    #[arg(long, global = true, value_name = "PATH", env = "NOTION_CA_BUNDLE")]
+    pub ca_bundle: Option<PathBuf>,
+
+    /// Save every API response to DIR (tokens scrubbed) for later --replay
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        env = "NOTION_RECORD",
+        conflicts_with = "replay"
+    )]
+    pub record: Option<PathBuf>,
+
+    /// Serve API responses from DIR instead of the network; unrecorded requests fail
+    #[arg(long, global = true, value_name = "DIR", env = "NOTION_REPLAY")]
+    pub replay: Option<PathBuf>,
+
+    /// How errors are printed on stderr: text or json
+    #[arg(long, global = true, value_name = "FORMAT", default_value = "text")]
+    pub error_format: ErrorFormat,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+impl Cli {
+    /// Trace level from `-v`, `--trace` and `--log-file`.
+    pub fn trace_level(&self) -> u8 {
+        if self.trace {
+            return 2;
+        }
+        if self.log_file.is_some() {
+            return self.verbose.max(1);
+        }
+        self.verbose
+    }
+}
+
+fn parse_key_value(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("'{}' is not KEY=VALUE", value)),
+    }
+}
+
+fn parse_rate(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate >= 0.0 => Ok(rate),
+        _ => Err(format!("'{}' is not a non-negative number", value)),
+    }
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Initialize configuration and test connection
     Init,
 
+    /// Log in with a public integration (OAuth) or an integration token
+    Login {
+        /// Authorize a public integration in the browser (OAuth)
+        #[arg(long)]
+        oauth: bool,
+
+        /// Check whether the stored OAuth token is still active
+        #[arg(long, conflicts_with = "oauth")]
+        status: bool,
+
+        /// OAuth client ID of the public integration
+        #[arg(long, env = "NOTION_OAUTH_CLIENT_ID")]
+        client_id: Option<String>,
+
+        /// OAuth client secret of the public integration
+        #[arg(long, env = "NOTION_OAUTH_CLIENT_SECRET", hide_env_values = true)]
+        client_secret: Option<String>,
+
+        /// Local port for the redirect URI (http://localhost:PORT/callback)
+        #[arg(long, default_value_t = 8765)]
+        port: u16,
+
+        /// Print the authorization URL without opening a browser
+        #[arg(long)]
+        no_browser: bool,
+    },
+
+    /// Revoke the stored OAuth token and remove it from the config
+    Logout {
+        /// OAuth client secret of the public integration
+        #[arg(long, env = "NOTION_OAUTH_CLIENT_SECRET", hide_env_values = true)]
+        client_secret: Option<String>,
+    },
+
+    /// Manage named workspace profiles
+    #[command(subcommand)]
+    Profile(ProfileCommands),
+
     /// Search pages and databases by title
     #[command(arg_required_else_help = true)]
     Search {
@@ -82,6 +223,36 @@ pub enum Commands {
     #[command(name = "file-upload", subcommand)]
     FileUpload(FileUploadCommands),
 
+    /// Send a request to any API endpoint
+    #[command(arg_required_else_help = true)]
+    Api {
+        /// HTTP method: GET, POST, PATCH or DELETE
+        #[arg(ignore_case = true)]
+        method: ApiMethod,
+
+        /// Endpoint path, e.g. /v1/users/me (the /v1 prefix is optional)
+        path: String,
+
+        /// JSON request body (@file or - for stdin)
+        #[arg(long, short)]
+        data: Option<String>,
+
+        /// Query parameter as KEY=VALUE (repeatable)
+        #[arg(long = "query", short, value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        query: Vec<(String, String)>,
+
+        /// Follow next_cursor and merge every page of results (GET and POST)
+        #[arg(long)]
+        paginate: bool,
+    },
+
+    /// Serve an in-memory fake of the API on localhost for testing
+    MockServer {
+        /// Port to listen on (0 picks a free one)
+        #[arg(long, default_value_t = 4010)]
+        port: u16,
+    },
+
     /// Generate shell completions
     #[command(arg_required_else_help = true)]
     Completions {
@@ -93,6 +264,34 @@ pub enum Commands {
     Manpage,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ApiMethod {
+    Get,
+    Post,
+    Patch,
+    Delete,
+}
+
+#[derive(Subcommand)]
+pub enum ProfileCommands {
+    /// List configured profiles
+    List,
+
+    /// Set the default profile
+    #[command(arg_required_else_help = true)]
+    Use {
+        /// Profile name
+        name: String,
+    },
+
+    /// Remove a profile
+    #[command(arg_required_else_help = true)]
+    Remove {
+        /// Profile name
+        name: String,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum UserCommands {
     /// Get the current bot user
@@ -102,6 +301,7 @@ pub enum UserCommands {
     #[command(arg_required_else_help = true)]
     Get {
         /// User ID
+        #[arg(value_parser = parse_id)]
         id: String,
     },
 
@@ -115,6 +315,7 @@ pub enum PageCommands {
     #[command(arg_required_else_help = true)]
     Get {
         /// Page ID
+        #[arg(value_parser = parse_id)]
         id: String,
 
         /// Filter to specific property IDs (comma-separated or repeated)
@@ -126,17 +327,26 @@ pub enum PageCommands {
     #[command(arg_required_else_help = true)]
     Create {
         /// Parent page or database ID
-        #[arg(long)]
+        #[arg(long, value_parser = parse_id)]
         parent: String,
 
-        /// Properties as JSON string
-        #[arg(long)]
-        properties: String,
+        /// Properties as JSON (@file or - for stdin)
+        #[arg(long, required_unless_present = "set")]
+        properties: Option<String>,
 
-        /// Children blocks as JSON string
-        #[arg(long)]
+        /// Set a property from text, checked against the parent's schema
+        /// (NAME=VALUE, repeatable)
+        #[arg(long = "set", value_name = "NAME=VALUE")]
+        set: Vec<String>,
+
+        /// Children blocks as JSON (@file or - for stdin)
+        #[arg(long, conflicts_with = "markdown")]
         children: Option<String>,
 
+        /// Page content as Markdown, from a file or - for stdin
+        #[arg(long, value_name = "FILE")]
+        markdown: Option<String>,
+
         /// Parent is a database (default: page)
         #[arg(long)]
         database_parent: bool,
@@ -146,11 +356,18 @@ pub enum PageCommands {
     #[command(arg_required_else_help = true)]
     Update {
         /// Page ID
+        #[arg(value_parser = parse_id)]
         id: String,
 
-        /// Properties as JSON string
-        #[arg(long)]
-        properties: String,
+        /// Properties as JSON (@file or - for stdin)
+        #[arg(long, required_unless_present = "set")]
+        properties: Option<String>,
+
+        /// Set a property from text, checked against the parent's schema:
+        /// NAME=VALUE, NAME+=VALUE (multi-select, people, relation) or
+        /// NAME-=VALUE (repeatable)
+        #[arg(long = "set", value_name = "NAME=VALUE")]
+        set: Vec<String>,
 
         /// Archive/unarchive the page
         #[arg(long)]
@@ -161,6 +378,7 @@ pub enum PageCommands {
     #[command(arg_required_else_help = true)]
     Move {
         /// Page ID to move
+        #[arg(value_parser = parse_id)]
         id: String,
 
         /// Parent type: page, database, or workspace
@@ -176,11 +394,24 @@ pub enum PageCommands {
     #[command(arg_required_else_help = true)]
     Property {
         /// Page ID
+        #[arg(value_parser = parse_id)]
         page_id: String,
 
         /// Property ID
         property_id: String,
     },
+
+    /// Export a page and its nested blocks as a document
+    #[command(arg_required_else_help = true)]
+    Export {
+        /// Page ID
+        #[arg(value_parser = parse_id)]
+        id: String,
+
+        /// Document format: markdown
+        #[arg(long, default_value = "markdown")]
+        format: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -189,6 +420,7 @@ pub enum BlockCommands {
     #[command(arg_required_else_help = true)]
     Get {
         /// Block ID
+        #[arg(value_parser = parse_id)]
         id: String,
     },
 
@@ -196,6 +428,7 @@ pub enum BlockCommands {
     #[command(arg_required_else_help = true)]
     Children {
         /// Block ID
+        #[arg(value_parser = parse_id)]
         id: String,
     },
 
@@ -203,14 +436,23 @@ pub enum BlockCommands {
     #[command(arg_required_else_help = true)]
     Append {
         /// Block ID
+        #[arg(value_parser = parse_id)]
         id: String,
 
-        /// Children blocks as JSON string
-        #[arg(long)]
-        children: String,
+        /// Children blocks as JSON (@file or - for stdin)
+        #[arg(
+            long,
+            required_unless_present = "markdown",
+            conflicts_with = "markdown"
+        )]
+        children: Option<String>,
+
+        /// Children as Markdown, from a file or - for stdin
+        #[arg(long, value_name = "FILE")]
+        markdown: Option<String>,
 
         /// Insert after this block ID
-        #[arg(long)]
+        #[arg(long, value_parser = parse_id)]
         after: Option<String>,
     },
 
@@ -218,9 +460,10 @@ pub enum BlockCommands {
     #[command(arg_required_else_help = true)]
     Update {
         /// Block ID
+        #[arg(value_parser = parse_id)]
         id: String,
 
-        /// Block data as JSON string
+        /// Block data as JSON (@file or - for stdin)
         #[arg(long)]
         data: String,
 
@@ -233,6 +476,7 @@ pub enum BlockCommands {
     #[command(arg_required_else_help = true)]
     Delete {
         /// Block ID
+        #[arg(value_parser = parse_id)]
         id: String,
     },
 }
@@ -243,7 +487,7 @@ pub enum CommentCommands {
     #[command(arg_required_else_help = true)]
     List {
         /// Block or page ID
-        #[arg(long)]
+        #[arg(long, value_parser = parse_id)]
         block_id: String,
     },
 
@@ -251,7 +495,7 @@ pub enum CommentCommands {
     #[command(arg_required_else_help = true)]
     Create {
         /// Page ID
-        #[arg(long)]
+        #[arg(long, value_parser = parse_id)]
         page_id: String,
 
         /// Comment text
@@ -266,6 +510,7 @@ pub enum DatabaseCommands {
     #[command(arg_required_else_help = true)]
     Get {
         /// Database ID
+        #[arg(value_parser = parse_id)]
         id: String,
     },
 }
@@ -276,6 +521,7 @@ pub enum DatasourceCommands {
     #[command(arg_required_else_help = true)]
     Get {
         /// Data source ID
+        #[arg(value_parser = parse_id)]
         id: String,
     },
 
@@ -283,14 +529,14 @@ pub enum DatasourceCommands {
     #[command(arg_required_else_help = true)]
     Create {
         /// Parent page ID
-        #[arg(long)]
+        #[arg(long, value_parser = parse_id)]
         parent: String,
 
         /// Title
         #[arg(long)]
         title: String,
 
-        /// Properties schema as JSON string
+        /// Properties schema as JSON (@file or - for stdin)
         #[arg(long)]
         properties: Option<String>,
     },
@@ -299,9 +545,10 @@ pub enum DatasourceCommands {
     #[command(arg_required_else_help = true)]
     Update {
         /// Data source ID
+        #[arg(value_parser = parse_id)]
         id: String,
 
-        /// Data as JSON string
+        /// Data as JSON (@file or - for stdin)
         #[arg(long)]
         data: String,
     },
@@ -310,22 +557,59 @@ pub enum DatasourceCommands {
     #[command(arg_required_else_help = true)]
     Query {
         /// Data source ID
+        #[arg(value_parser = parse_id)]
         id: String,
 
-        /// Filter as JSON string
+        /// Filter as JSON (@file or - for stdin)
         #[arg(long)]
         filter: Option<String>,
 
-        /// Sorts as JSON string
+        /// Filter as an expression, e.g. 'Status = Done and Due < today',
+        /// checked against the schema; today and today+N are UTC dates
+        #[arg(long = "where", value_name = "EXPR", conflicts_with = "filter")]
+        where_clause: Option<String>,
+
+        /// Sorts as JSON (@file or - for stdin)
         #[arg(long)]
         sorts: Option<String>,
+
+        /// Sort by a property or created_time/last_edited_time; prefix with
+        /// - for descending (repeatable; later sorts break ties)
+        #[arg(
+            long = "sort",
+            value_name = "[-]PROPERTY",
+            allow_hyphen_values = true,
+            conflicts_with = "sorts"
+        )]
+        sort: Vec<String>,
+
+        /// Print the query body instead of running it
+        #[arg(long)]
+        explain: bool,
     },
 
     /// List templates in a data source
     #[command(arg_required_else_help = true)]
     Templates {
         /// Data source ID
+        #[arg(value_parser = parse_id)]
+        id: String,
+    },
+
+    /// Create one page per row of a CSV or TSV file
+    #[command(arg_required_else_help = true)]
+    Import {
+        /// Data source ID
+        #[arg(value_parser = parse_id)]
         id: String,
+
+        /// CSV file to import (.tsv files are read as tab-separated)
+        #[arg(long)]
+        file: PathBuf,
+
+        /// Map a column to a property: HEADER=PROPERTY (HEADER= skips it)
+        #[arg(long = "map", value_name = "HEADER=PROPERTY")]
+        map: Vec<String>,
     },
 }
 
@@ -359,6 +643,7 @@ pub enum FileUploadCommands {
     #[command(arg_required_else_help = true)]
     Send {
         /// File upload ID
+        #[arg(value_parser = parse_id)]
         id: String,
 
         /// Path to the file to upload
@@ -374,6 +659,7 @@ pub enum FileUploadCommands {
     #[command(arg_required_else_help = true)]
     Complete {
         /// File upload ID
+        #[arg(value_parser = parse_id)]
         id: String,
     },
 
@@ -381,6 +667,7 @@ pub enum FileUploadCommands {
     #[command(arg_required_else_help = true)]
     Get {
         /// File upload ID
+        #[arg(value_parser = parse_id)]
         id: String,
     },
 
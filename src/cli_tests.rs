@@ -5,6 +5,16 @@ use std::path::PathBuf;
 
 use crate::output::OutputFormat;
 
+const AFTER_ID: &str = "7f0b4c59-7a8e-4d7e-9d1e-5b3c7a1f2e90";
+const BLOCK_ID: &str = "c02fc1d3-db8b-45c5-a222-27595b15aea7";
+const DATABASE_ID: &str = "668d797c-76fa-4934-9b05-ad288df2d136";
+const DATA_SOURCE_ID: &str = "248104cd-477e-80fd-b757-000b5fa6f5b2";
+const PAGE_ID: &str = "59833787-2cf9-4fdf-8782-e53db20768a5";
+const PARENT_ID: &str = "b55c9c91-384d-452b-81db-d1ef79372b75";
+const TARGET_ID: &str = "2d3c4b5a-6978-4a1b-8c2d-3e4f5a6b7c8d";
+const UPLOAD_ID: &str = "a3f9d3e2-1abc-42de-b904-badc0ffee000";
+const USER_ID: &str = "d40e767c-d7af-4b18-a86d-55c61f1e39a4";
+
 fn parse(args: &[&str]) -> Cli {
     Cli::parse_from(args)
 }
@@ -19,6 +29,66 @@ fn test_init_command() {
     assert!(matches!(cli.command, Commands::Init));
 }
 
+#[test]
+fn test_login_oauth_command() {
+    let cli = parse(&[
+        "notion",
+        "login",
+        "--oauth",
+        "--client-id",
+        "client-1",
+        "--client-secret",
+        "secret",
+        "--port",
+        "9000",
+        "--no-browser",
+    ]);
+    if let Commands::Login {
+        oauth,
+        status,
+        client_id,
+        client_secret,
+        port,
+        no_browser,
+    } = &cli.command
+    {
+        assert!(*oauth);
+        assert!(!*status);
+        assert_eq!(client_id.as_deref(), Some("client-1"));
+        assert_eq!(client_secret.as_deref(), Some("secret"));
+        assert_eq!(*port, 9000);
+        assert!(*no_browser);
+    } else {
+        panic!("Expected Login command");
+    }
+}
+
+#[test]
+fn test_login_default_port() {
+    let cli = parse(&["notion", "login", "--oauth"]);
+    if let Commands::Login { port, .. } = &cli.command {
+        assert_eq!(*port, 8765);
+    } else {
+        panic!("Expected Login command");
+    }
+}
+
+#[test]
+fn test_login_status_conflicts_with_oauth() {
+    let result = try_parse(&["notion", "login", "--oauth", "--status"]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_logout_command() {
+    let cli = parse(&["notion", "logout", "--client-secret", "secret"]);
+    if let Commands::Logout { client_secret } = &cli.command {
+        assert_eq!(client_secret.as_deref(), Some("secret"));
+    } else {
+        panic!("Expected Logout command");
+    }
+}
+
 #[test]
 fn test_search_command() {
     let cli = parse(&["notion", "search", "my query"]);
@@ -55,9 +125,9 @@ fn test_user_me() {
 
 #[test]
 fn test_user_get() {
-    let cli = parse(&["notion", "user", "get", "user-123"]);
+    let cli = parse(&["notion", "user", "get", USER_ID]);
     if let Commands::User(UserCommands::Get { id }) = &cli.command {
-        assert_eq!(id, "user-123");
+        assert_eq!(id, USER_ID);
     } else {
         panic!("Expected User Get command");
     }
@@ -71,13 +141,13 @@ fn test_user_list() {
 
 #[test]
 fn test_page_get() {
-    let cli = parse(&["notion", "page", "get", "page-abc"]);
+    let cli = parse(&["notion", "page", "get", PAGE_ID]);
     if let Commands::Page(PageCommands::Get {
         id,
         filter_properties,
     }) = &cli.command
     {
-        assert_eq!(id, "page-abc");
+        assert_eq!(id, PAGE_ID);
         assert!(filter_properties.is_empty());
     } else {
         panic!("Expected Page Get command");
@@ -90,7 +160,7 @@ fn test_page_get_with_filter_properties() {
         "notion",
         "page",
         "get",
-        "page-abc",
+        PAGE_ID,
         "--filter-properties",
         "title,status",
     ]);
@@ -99,7 +169,7 @@ fn test_page_get_with_filter_properties() {
         filter_properties,
     }) = &cli.command
     {
-        assert_eq!(id, "page-abc");
+        assert_eq!(id, PAGE_ID);
         assert_eq!(filter_properties, &["title", "status"]);
     } else {
         panic!("Expected Page Get command");
@@ -113,7 +183,7 @@ fn test_page_create() {
         "page",
         "create",
         "--parent",
-        "parent-id",
+        PARENT_ID,
         "--properties",
         r#"{"Name":{"title":[{"text":{"content":"Test"}}]}}"#,
     ]);
@@ -122,10 +192,11 @@ fn test_page_create() {
         properties,
         children,
         database_parent,
+        ..
     }) = &cli.command
     {
-        assert_eq!(parent, "parent-id");
-        assert!(properties.contains("Name"));
+        assert_eq!(parent, PARENT_ID);
+        assert!(properties.as_deref().unwrap().contains("Name"));
         assert!(children.is_none());
         assert!(!database_parent);
     } else {
@@ -140,7 +211,7 @@ fn test_page_create_with_database_parent() {
         "page",
         "create",
         "--parent",
-        "db-id",
+        DATABASE_ID,
         "--properties",
         "{}",
         "--database-parent",
@@ -161,7 +232,7 @@ fn test_page_update() {
         "notion",
         "page",
         "update",
-        "page-123",
+        PAGE_ID,
         "--properties",
         "{}",
         "--archived",
@@ -171,27 +242,77 @@ fn test_page_update() {
         id,
         properties,
         archived,
+        set,
     }) = &cli.command
     {
-        assert_eq!(id, "page-123");
-        assert_eq!(properties, "{}");
+        assert_eq!(id, PAGE_ID);
+        assert_eq!(properties.as_deref(), Some("{}"));
+        assert!(set.is_empty());
         assert_eq!(*archived, Some(true));
     } else {
         panic!("Expected Page Update command");
     }
 }
 
+#[test]
+fn test_page_update_with_set() {
+    let cli = parse(&[
+        "notion",
+        "page",
+        "update",
+        PAGE_ID,
+        "--set",
+        "Status=Done",
+        "--set",
+        "Tags+=urgent",
+    ]);
+    if let Commands::Page(PageCommands::Update {
+        properties, set, ..
+    }) = &cli.command
+    {
+        assert!(properties.is_none());
+        assert_eq!(set, &["Status=Done", "Tags+=urgent"]);
+    } else {
+        panic!("Expected Page Update command");
+    }
+}
+
+#[test]
+fn test_page_create_requires_properties_or_set() {
+    let result = try_parse(&["notion", "page", "create", "--parent", PARENT_ID]);
+    assert!(result.is_err());
+
+    let cli = parse(&[
+        "notion",
+        "page",
+        "create",
+        "--parent",
+        PARENT_ID,
+        "--set",
+        "title=Notes",
+    ]);
+    if let Commands::Page(PageCommands::Create {
+        properties, set, ..
+    }) = &cli.command
+    {
+        assert!(properties.is_none());
+        assert_eq!(set, &["title=Notes"]);
+    } else {
+        panic!("Expected Page Create command");
+    }
+}
+
 #[test]
 fn test_page_move() {
     let cli = parse(&[
         "notion",
         "page",
         "move",
-        "page-1",
+        PAGE_ID,
         "--parent-type",
         "database",
         "--to",
-        "db-2",
+        TARGET_ID,
     ]);
     if let Commands::Page(PageCommands::Move {
         id,
@@ -199,9 +320,9 @@ fn test_page_move() {
         to,
     }) = &cli.command
     {
-        assert_eq!(id, "page-1");
+        assert_eq!(id, PAGE_ID);
         assert_eq!(parent_type, "database");
-        assert_eq!(to, "db-2");
+        assert_eq!(to, TARGET_ID);
     } else {
         panic!("Expected Page Move command");
     }
@@ -209,13 +330,13 @@ fn test_page_move() {
 
 #[test]
 fn test_page_property() {
-    let cli = parse(&["notion", "page", "property", "page-1", "prop-abc"]);
+    let cli = parse(&["notion", "page", "property", PAGE_ID, "prop-abc"]);
     if let Commands::Page(PageCommands::Property {
         page_id,
         property_id,
     }) = &cli.command
     {
-        assert_eq!(page_id, "page-1");
+        assert_eq!(page_id, PAGE_ID);
         assert_eq!(property_id, "prop-abc");
     } else {
         panic!("Expected Page Property command");
@@ -224,9 +345,9 @@ fn test_page_property() {
 
 #[test]
 fn test_block_get() {
-    let cli = parse(&["notion", "block", "get", "block-1"]);
+    let cli = parse(&["notion", "block", "get", BLOCK_ID]);
     if let Commands::Block(BlockCommands::Get { id }) = &cli.command {
-        assert_eq!(id, "block-1");
+        assert_eq!(id, BLOCK_ID);
     } else {
         panic!("Expected Block Get command");
     }
@@ -234,9 +355,9 @@ fn test_block_get() {
 
 #[test]
 fn test_block_children() {
-    let cli = parse(&["notion", "block", "children", "block-1"]);
+    let cli = parse(&["notion", "block", "children", BLOCK_ID]);
     if let Commands::Block(BlockCommands::Children { id }) = &cli.command {
-        assert_eq!(id, "block-1");
+        assert_eq!(id, BLOCK_ID);
     } else {
         panic!("Expected Block Children command");
     }
@@ -248,21 +369,22 @@ fn test_block_append() {
         "notion",
         "block",
         "append",
-        "block-1",
+        BLOCK_ID,
         "--children",
         "[{}]",
         "--after",
-        "block-2",
+        AFTER_ID,
     ]);
     if let Commands::Block(BlockCommands::Append {
         id,
         children,
         after,
+        ..
     }) = &cli.command
     {
-        assert_eq!(id, "block-1");
-        assert_eq!(children, "[{}]");
-        assert_eq!(after.as_deref(), Some("block-2"));
+        assert_eq!(id, BLOCK_ID);
+        assert_eq!(children.as_deref(), Some("[{}]"));
+        assert_eq!(after.as_deref(), Some(AFTER_ID));
     } else {
         panic!("Expected Block Append command");
     }
@@ -270,9 +392,9 @@ fn test_block_append() {
 
 #[test]
 fn test_block_delete() {
-    let cli = parse(&["notion", "block", "delete", "block-1"]);
+    let cli = parse(&["notion", "block", "delete", BLOCK_ID]);
     if let Commands::Block(BlockCommands::Delete { id }) = &cli.command {
-        assert_eq!(id, "block-1");
+        assert_eq!(id, BLOCK_ID);
     } else {
         panic!("Expected Block Delete command");
     }
@@ -280,9 +402,9 @@ fn test_block_delete() {
 
 #[test]
 fn test_comment_list() {
-    let cli = parse(&["notion", "comment", "list", "--block-id", "page-1"]);
+    let cli = parse(&["notion", "comment", "list", "--block-id", PAGE_ID]);
     if let Commands::Comment(CommentCommands::List { block_id }) = &cli.command {
-        assert_eq!(block_id, "page-1");
+        assert_eq!(block_id, PAGE_ID);
     } else {
         panic!("Expected Comment List command");
     }
@@ -295,12 +417,12 @@ fn test_comment_create() {
         "comment",
         "create",
         "--page-id",
-        "page-1",
+        PAGE_ID,
         "--text",
         "Hello world",
     ]);
     if let Commands::Comment(CommentCommands::Create { page_id, text }) = &cli.command {
-        assert_eq!(page_id, "page-1");
+        assert_eq!(page_id, PAGE_ID);
         assert_eq!(text, "Hello world");
     } else {
         panic!("Expected Comment Create command");
@@ -309,9 +431,9 @@ fn test_comment_create() {
 
 #[test]
 fn test_database_get() {
-    let cli = parse(&["notion", "database", "get", "db-123"]);
+    let cli = parse(&["notion", "database", "get", DATABASE_ID]);
     if let Commands::Database(DatabaseCommands::Get { id }) = &cli.command {
-        assert_eq!(id, "db-123");
+        assert_eq!(id, DATABASE_ID);
     } else {
         panic!("Expected Database Get command");
     }
@@ -319,9 +441,9 @@ fn test_database_get() {
 
 #[test]
 fn test_datasource_get() {
-    let cli = parse(&["notion", "datasource", "get", "ds-1"]);
+    let cli = parse(&["notion", "datasource", "get", DATA_SOURCE_ID]);
     if let Commands::Datasource(DatasourceCommands::Get { id }) = &cli.command {
-        assert_eq!(id, "ds-1");
+        assert_eq!(id, DATA_SOURCE_ID);
     } else {
         panic!("Expected Datasource Get command");
     }
@@ -333,14 +455,17 @@ fn test_datasource_query_with_filter() {
         "notion",
         "datasource",
         "query",
-        "ds-1",
+        DATA_SOURCE_ID,
         "--filter",
         r#"{"property":"Status","equals":"Done"}"#,
         "--sorts",
         r#"[{"property":"Created","direction":"descending"}]"#,
     ]);
-    if let Commands::Datasource(DatasourceCommands::Query { id, filter, sorts }) = &cli.command {
-        assert_eq!(id, "ds-1");
+    if let Commands::Datasource(DatasourceCommands::Query {
+        id, filter, sorts, ..
+    }) = &cli.command
+    {
+        assert_eq!(id, DATA_SOURCE_ID);
         assert!(filter.is_some());
         assert!(sorts.is_some());
     } else {
@@ -348,6 +473,76 @@ fn test_datasource_query_with_filter() {
     }
 }
 
+#[test]
+fn test_datasource_query_where() {
+    let cli = parse(&[
+        "notion",
+        "datasource",
+        "query",
+        DATA_SOURCE_ID,
+        "--where",
+        "Status = Done",
+        "--explain",
+    ]);
+    if let Commands::Datasource(DatasourceCommands::Query {
+        where_clause,
+        explain,
+        ..
+    }) = &cli.command
+    {
+        assert_eq!(where_clause.as_deref(), Some("Status = Done"));
+        assert!(explain);
+    } else {
+        panic!("Expected Datasource Query command");
+    }
+
+    let result = try_parse(&[
+        "notion",
+        "datasource",
+        "query",
+        DATA_SOURCE_ID,
+        "--where",
+        "Status = Done",
+        "--filter",
+        "{}",
+    ]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_datasource_query_sort() {
+    let cli = parse(&[
+        "notion",
+        "datasource",
+        "query",
+        DATA_SOURCE_ID,
+        "--sort",
+        "Due",
+        "--sort",
+        "-Priority",
+        "--sort",
+        "created_time",
+    ]);
+    if let Commands::Datasource(DatasourceCommands::Query { sort, sorts, .. }) = &cli.command {
+        assert_eq!(sort, &["Due", "-Priority", "created_time"]);
+        assert!(sorts.is_none());
+    } else {
+        panic!("Expected Datasource Query command");
+    }
+
+    let result = try_parse(&[
+        "notion",
+        "datasource",
+        "query",
+        DATA_SOURCE_ID,
+        "--sort",
+        "Due",
+        "--sorts",
+        "[]",
+    ]);
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_datasource_create() {
     let cli = parse(&[
@@ -355,7 +550,7 @@ fn test_datasource_create() {
         "datasource",
         "create",
         "--parent",
-        "page-1",
+        PAGE_ID,
         "--title",
         "My DB",
     ]);
@@ -365,7 +560,7 @@ fn test_datasource_create() {
         properties,
     }) = &cli.command
     {
-        assert_eq!(parent, "page-1");
+        assert_eq!(parent, PAGE_ID);
         assert_eq!(title, "My DB");
         assert!(properties.is_none());
     } else {
@@ -375,9 +570,9 @@ fn test_datasource_create() {
 
 #[test]
 fn test_datasource_templates() {
-    let cli = parse(&["notion", "datasource", "templates", "ds-1"]);
+    let cli = parse(&["notion", "datasource", "templates", DATA_SOURCE_ID]);
     if let Commands::Datasource(DatasourceCommands::Templates { id }) = &cli.command {
-        assert_eq!(id, "ds-1");
+        assert_eq!(id, DATA_SOURCE_ID);
     } else {
         panic!("Expected Datasource Templates command");
     }
@@ -389,6 +584,12 @@ fn test_global_output_format() {
     assert!(matches!(cli.output, OutputFormat::Raw));
 }
 
+#[test]
+fn test_global_output_ndjson() {
+    let cli = parse(&["notion", "--output", "ndjson", "user", "list"]);
+    assert!(matches!(cli.output, OutputFormat::Ndjson));
+}
+
 #[test]
 fn test_global_raw_flag() {
     let cli = parse(&["notion", "--raw", "init"]);
@@ -407,6 +608,26 @@ fn test_global_start_cursor() {
     assert_eq!(cli.start_cursor.as_deref(), Some("cursor-abc"));
 }
 
+#[test]
+fn test_global_all_flag() {
+    let cli = parse(&["notion", "block", "children", BLOCK_ID, "--all"]);
+    assert!(cli.all);
+    assert!(cli.limit.is_none());
+}
+
+#[test]
+fn test_global_limit() {
+    let cli = parse(&["notion", "--limit", "250", "user", "list"]);
+    assert_eq!(cli.limit, Some(250));
+    assert!(!cli.all);
+}
+
+#[test]
+fn test_global_limit_rejects_zero() {
+    let result = try_parse(&["notion", "--limit", "0", "user", "list"]);
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_default_output_is_pretty() {
     let cli = parse(&["notion", "init"]);
@@ -451,6 +672,234 @@ fn test_dry_run_default_is_false() {
     assert!(!cli.dry_run);
 }
 
+#[test]
+fn test_global_profile_flag() {
+    let cli = parse(&["notion", "--profile", "work", "user", "me"]);
+    assert_eq!(cli.profile.as_deref(), Some("work"));
+}
+
+#[test]
+fn test_profile_flag_after_subcommand() {
+    let cli = parse(&["notion", "user", "me", "--profile", "work"]);
+    assert_eq!(cli.profile.as_deref(), Some("work"));
+}
+
+#[test]
+fn test_init_with_profile() {
+    let cli = parse(&["notion", "init", "--profile", "personal"]);
+    assert!(matches!(cli.command, Commands::Init));
+    assert_eq!(cli.profile.as_deref(), Some("personal"));
+}
+
+#[test]
+fn test_profile_list() {
+    let cli = parse(&["notion", "profile", "list"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Profile(ProfileCommands::List)
+    ));
+}
+
+#[test]
+fn test_profile_use() {
+    let cli = parse(&["notion", "profile", "use", "work"]);
+    if let Commands::Profile(ProfileCommands::Use { name }) = &cli.command {
+        assert_eq!(name, "work");
+    } else {
+        panic!("Expected Profile Use command");
+    }
+}
+
+#[test]
+fn test_profile_remove() {
+    let cli = parse(&["notion", "profile", "remove", "old"]);
+    if let Commands::Profile(ProfileCommands::Remove { name }) = &cli.command {
+        assert_eq!(name, "old");
+    } else {
+        panic!("Expected Profile Remove command");
+    }
+}
+
+#[test]
+fn test_profile_use_requires_name() {
+    let result = try_parse(&["notion", "profile", "use"]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_retry_defaults() {
+    let cli = parse(&["notion", "user", "me"]);
+    assert!(cli.timeout.is_none());
+    assert_eq!(cli.max_retries, 3);
+    assert_eq!(cli.max_backoff, 30);
+}
+
+#[test]
+fn test_global_timeout_and_retry_flags() {
+    let cli = parse(&[
+        "notion",
+        "user",
+        "me",
+        "--timeout",
+        "20",
+        "--max-retries",
+        "0",
+        "--max-backoff",
+        "5",
+    ]);
+    assert_eq!(cli.timeout, Some(20));
+    assert_eq!(cli.max_retries, 0);
+    assert_eq!(cli.max_backoff, 5);
+}
+
+#[test]
+fn test_global_rate_limit() {
+    let cli = parse(&["notion", "--rate-limit", "1.5", "user", "me"]);
+    assert_eq!(cli.rate_limit, Some(1.5));
+    let cli = parse(&["notion", "user", "me"]);
+    assert!(cli.rate_limit.is_none());
+}
+
+#[test]
+fn test_rate_limit_rejects_negative() {
+    assert!(try_parse(&["notion", "--rate-limit=-1", "user", "me"]).is_err());
+    assert!(try_parse(&["notion", "--rate-limit", "fast", "user", "me"]).is_err());
+}
+
+#[test]
+fn test_global_error_format() {
+    let cli = parse(&["notion", "user", "me"]);
+    assert_eq!(cli.error_format, ErrorFormat::Text);
+    let cli = parse(&["notion", "user", "me", "--error-format", "json"]);
+    assert_eq!(cli.error_format, ErrorFormat::Json);
+    assert!(try_parse(&["notion", "--error-format", "xml", "user", "me"]).is_err());
+}
+
+#[test]
+fn test_verbose_levels() {
+    assert_eq!(parse(&["notion", "user", "me"]).trace_level(), 0);
+    assert_eq!(parse(&["notion", "-v", "user", "me"]).trace_level(), 1);
+    assert_eq!(parse(&["notion", "user", "me", "-vv"]).trace_level(), 2);
+    assert_eq!(parse(&["notion", "--trace", "user", "me"]).trace_level(), 2);
+}
+
+#[test]
+fn test_log_file_implies_verbose() {
+    let cli = parse(&["notion", "--log-file", "/tmp/notion.log", "user", "me"]);
+    assert_eq!(cli.log_file, Some(PathBuf::from("/tmp/notion.log")));
+    assert_eq!(cli.trace_level(), 1);
+    let cli = parse(&[
+        "notion",
+        "--log-file",
+        "/tmp/notion.log",
+        "-vv",
+        "user",
+        "me",
+    ]);
+    assert_eq!(cli.trace_level(), 2);
+}
+
+#[test]
+fn test_version_flag_is_uppercase() {
+    let err = try_parse(&["notion", "-V"]).err().unwrap();
+    assert_eq!(err.kind(), clap::error::ErrorKind::DisplayVersion);
+    assert!(err.to_string().contains(env!("CARGO_PKG_VERSION")));
+}
+
+#[test]
+fn test_api_command() {
+    let cli = parse(&[
+        "notion",
+        "api",
+        "POST",
+        "/v1/search",
+        "--data",
+        "@body.json",
+        "-q",
+        "a=1",
+        "--query",
+        "b=x=y",
+        "--paginate",
+    ]);
+    match cli.command {
+        Commands::Api {
+            method,
+            path,
+            data,
+            query,
+            paginate,
+        } => {
+            assert_eq!(method, ApiMethod::Post);
+            assert_eq!(path, "/v1/search");
+            assert_eq!(data.as_deref(), Some("@body.json"));
+            assert_eq!(
+                query,
+                vec![("a".into(), "1".into()), ("b".into(), "x=y".into())]
+            );
+            assert!(paginate);
+        }
+        _ => panic!("Expected Api command"),
+    }
+}
+
+#[test]
+fn test_api_command_rejects_bad_input() {
+    assert!(try_parse(&["notion", "api", "PUT", "/v1/users"]).is_err());
+    assert!(try_parse(&["notion", "api", "get", "/v1/users", "--query", "novalue"]).is_err());
+}
+
+#[test]
+fn test_mock_server_command() {
+    let cli = parse(&["notion", "mock-server"]);
+    assert!(matches!(cli.command, Commands::MockServer { port: 4010 }));
+    let cli = parse(&["notion", "mock-server", "--port", "0"]);
+    assert!(matches!(cli.command, Commands::MockServer { port: 0 }));
+}
+
+#[test]
+fn test_global_api_version_and_base_url() {
+    let cli = parse(&[
+        "notion",
+        "--api-version",
+        "2022-06-28",
+        "--base-url",
+        "http://localhost:4010",
+        "user",
+        "me",
+    ]);
+    assert_eq!(cli.api_version.as_deref(), Some("2022-06-28"));
+    assert_eq!(cli.base_url.as_deref(), Some("http://localhost:4010"));
+}
+
+#[test]
+fn test_global_proxy_and_ca_bundle() {
+    let cli = parse(&[
+        "notion",
+        "--proxy",
+        "http://proxy.corp:3128",
+        "--ca-bundle",
+        "/etc/ssl/corp.pem",
+        "user",
+        "me",
+    ]);
+    assert_eq!(cli.proxy.as_deref(), Some("http://proxy.corp:3128"));
+    assert_eq!(cli.ca_bundle, Some(PathBuf::from("/etc/ssl/corp.pem")));
+}
+
+#[test]
+fn test_record_and_replay_conflict() {
+    let cli = parse(&["notion", "--replay", "fixtures", "user", "me"]);
+    assert_eq!(cli.replay, Some(PathBuf::from("fixtures")));
+    assert!(cli.record.is_none());
+    assert!(try_parse(&["notion", "--record", "a", "--replay", "b", "user", "me"]).is_err());
+}
+
+#[test]
+fn test_timeout_rejects_zero() {
+    let result = try_parse(&["notion", "--timeout", "0", "user", "me"]);
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_unknown_command_fails() {
     let result = try_parse(&["notion", "foobar"]);
@@ -492,7 +941,7 @@ fn test_file_upload_send() {
         "notion",
         "file-upload",
         "send",
-        "fu-1",
+        UPLOAD_ID,
         "--file",
         "/tmp/test.png",
     ]);
@@ -502,7 +951,7 @@ fn test_file_upload_send() {
         part_number,
     }) = &cli.command
     {
-        assert_eq!(id, "fu-1");
+        assert_eq!(id, UPLOAD_ID);
         assert_eq!(file, &PathBuf::from("/tmp/test.png"));
         assert!(part_number.is_none());
     } else {
@@ -516,7 +965,7 @@ fn test_file_upload_send_with_part_number() {
         "notion",
         "file-upload",
         "send",
-        "fu-1",
+        UPLOAD_ID,
         "--file",
         "/tmp/test.png",
         "--part-number",
@@ -528,7 +977,7 @@ fn test_file_upload_send_with_part_number() {
         part_number,
     }) = &cli.command
     {
-        assert_eq!(id, "fu-1");
+        assert_eq!(id, UPLOAD_ID);
         assert_eq!(file, &PathBuf::from("/tmp/test.png"));
         assert_eq!(*part_number, Some(2));
     } else {
@@ -538,9 +987,9 @@ fn test_file_upload_send_with_part_number() {
 
 #[test]
 fn test_file_upload_complete() {
-    let cli = parse(&["notion", "file-upload", "complete", "fu-1"]);
+    let cli = parse(&["notion", "file-upload", "complete", UPLOAD_ID]);
     if let Commands::FileUpload(FileUploadCommands::Complete { id }) = &cli.command {
-        assert_eq!(id, "fu-1");
+        assert_eq!(id, UPLOAD_ID);
     } else {
         panic!("Expected FileUpload Complete command");
     }
@@ -548,9 +997,9 @@ fn test_file_upload_complete() {
 
 #[test]
 fn test_file_upload_get() {
-    let cli = parse(&["notion", "file-upload", "get", "fu-1"]);
+    let cli = parse(&["notion", "file-upload", "get", UPLOAD_ID]);
     if let Commands::FileUpload(FileUploadCommands::Get { id }) = &cli.command {
-        assert_eq!(id, "fu-1");
+        assert_eq!(id, UPLOAD_ID);
     } else {
         panic!("Expected FileUpload Get command");
     }
@@ -610,3 +1059,107 @@ fn test_file_upload_upload_with_content_type() {
         panic!("Expected FileUpload Upload command");
     }
 }
+
+#[test]
+fn test_datasource_import_with_mappings() {
+    let cli = parse(&[
+        "notion",
+        "datasource",
+        "import",
+        DATA_SOURCE_ID,
+        "--file",
+        "rows.csv",
+        "--map",
+        "Task=Name",
+        "--map",
+        "Notes=",
+    ]);
+    if let Commands::Datasource(DatasourceCommands::Import { id, file, map }) = &cli.command {
+        assert_eq!(id, DATA_SOURCE_ID);
+        assert_eq!(file, &PathBuf::from("rows.csv"));
+        assert_eq!(map, &["Task=Name", "Notes="]);
+    } else {
+        panic!("Expected Datasource Import command");
+    }
+}
+
+#[test]
+fn test_page_export_defaults_to_markdown() {
+    let cli = parse(&["notion", "page", "export", PAGE_ID]);
+    if let Commands::Page(PageCommands::Export { id, format }) = &cli.command {
+        assert_eq!(id, PAGE_ID);
+        assert_eq!(format, "markdown");
+    } else {
+        panic!("Expected Page Export command");
+    }
+}
+
+#[test]
+fn test_block_append_markdown() {
+    let cli = parse(&["notion", "block", "append", BLOCK_ID, "--markdown", "-"]);
+    if let Commands::Block(BlockCommands::Append {
+        children, markdown, ..
+    }) = &cli.command
+    {
+        assert!(children.is_none());
+        assert_eq!(markdown.as_deref(), Some("-"));
+    } else {
+        panic!("Expected Block Append command");
+    }
+}
+
+#[test]
+fn test_block_append_requires_content() {
+    let result = Cli::try_parse_from(["notion", "block", "append", BLOCK_ID]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_markdown_conflicts_with_children() {
+    let result = Cli::try_parse_from([
+        "notion",
+        "page",
+        "create",
+        "--parent",
+        "p",
+        "--properties",
+        "{}",
+        "--children",
+        "[]",
+        "--markdown",
+        "notes.md",
+    ]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_ids_accept_notion_urls() {
+    let url = format!(
+        "https://www.notion.so/acme/Team-Wiki-{}",
+        PAGE_ID.replace('-', "")
+    );
+    let cli = parse(&["notion", "page", "get", &url]);
+    if let Commands::Page(PageCommands::Get { id, .. }) = &cli.command {
+        assert_eq!(id, PAGE_ID);
+    } else {
+        panic!("Expected Page Get command");
+    }
+}
+
+#[test]
+fn test_ids_normalize_option_values() {
+    let undashed = BLOCK_ID.replace('-', "");
+    let cli = parse(&["notion", "comment", "list", "--block-id", &undashed]);
+    if let Commands::Comment(CommentCommands::List { block_id }) = &cli.command {
+        assert_eq!(block_id, BLOCK_ID);
+    } else {
+        panic!("Expected Comment List command");
+    }
+}
+
+#[test]
+fn test_malformed_ids_are_rejected_before_any_request() {
+    let result = Cli::try_parse_from(["notion", "block", "get", "not-an-id"]);
+    let err = result.err().unwrap().to_string();
+    assert!(err.contains("Invalid Notion ID 'not-an-id'"));
+}
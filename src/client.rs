@@ -1,47 +1,162 @@
 use anyhow::{Context, Result};
-use reqwest::Client;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue};
 use reqwest::multipart;
+use reqwest::{Client, StatusCode};
 use serde_json::Value;
-use std::path::Path;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+#[cfg(test)]
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tokio::fs;
 
-const NOTION_API_BASE: &str = "https://api.notion.com";
-const NOTION_VERSION: &str = "2025-09-03";
-const MAX_RETRIES: u32 = 3;
-const INITIAL_BACKOFF_MS: u64 = 500;
+use crate::cassette::{Cassette, Mode, RecordedRequest, RecordedResponse};
+use crate::error::{NotionError, bail_input};
+use crate::rate_limit::RateLimiter;
+use crate::retry::{self, Attempt, Failure, RetryPolicy};
+use crate::trace::Tracer;
+
+pub const NOTION_API_BASE: &str = "https://api.notion.com";
+/// API version this CLI is built and tested against
+pub const NOTION_VERSION: &str = "2025-09-03";
+
+/// Where requests go, which API version they ask for, and how to get there.
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    pub base_url: String,
+    /// Sent as the `Notion-Version` header
+    pub api_version: String,
+    /// Proxy for every request; without it `HTTPS_PROXY`/`HTTP_PROXY` apply.
+    /// `NO_PROXY` is honored either way.
+    pub proxy: Option<String>,
+    /// PEM file with extra root certificates, e.g. for a TLS-intercepting proxy
+    pub ca_bundle: Option<PathBuf>,
+    pub connect_timeout: Option<Duration>,
+    /// Longest wait for the next chunk of a response
+    pub read_timeout: Option<Duration>,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            base_url: NOTION_API_BASE.to_string(),
+            api_version: NOTION_VERSION.to_string(),
+            proxy: None,
+            ca_bundle: None,
+            connect_timeout: None,
+            read_timeout: None,
+        }
+    }
+}
 
 pub struct NotionClient {
     client: Client,
+    /// Default headers, kept for tracing
+    headers: HeaderMap,
     base_url: String,
     dry_run: bool,
+    retry: RetryPolicy,
+    timeout: Option<Duration>,
+    limiter: Option<RateLimiter>,
+    /// Attempts made so far, kept for tests; the tracer reports them otherwise
+    #[cfg(test)]
+    attempts: Mutex<Vec<Attempt>>,
+    tracer: Option<Tracer>,
+    cassette: Option<Cassette>,
 }
 
 impl NotionClient {
     pub fn new(token: &str) -> Result<Self> {
-        Self::with_base_url(token, NOTION_API_BASE)
+        Self::with_options(token, &ClientOptions::default())
     }
 
     pub fn with_base_url(token: &str, base_url: &str) -> Result<Self> {
+        let options = ClientOptions {
+            base_url: base_url.to_string(),
+            ..ClientOptions::default()
+        };
+        Self::with_options(token, &options)
+    }
+
+    pub fn with_options(token: &str, options: &ClientOptions) -> Result<Self> {
+        let authorization = HeaderValue::from_str(&format!("Bearer {}", token))
+            .context("Invalid API token format")?;
+        Self::build(authorization, options)
+    }
+
+    /// Client for the OAuth endpoints, which authenticate the integration
+    /// itself with HTTP Basic auth instead of a bearer token.
+    pub fn oauth(client_id: &str, client_secret: &str) -> Result<Self> {
+        Self::oauth_with_options(client_id, client_secret, &ClientOptions::default())
+    }
+
+    pub fn oauth_with_base_url(
+        client_id: &str,
+        client_secret: &str,
+        base_url: &str,
+    ) -> Result<Self> {
+        let options = ClientOptions {
+            base_url: base_url.to_string(),
+            ..ClientOptions::default()
+        };
+        Self::oauth_with_options(client_id, client_secret, &options)
+    }
+
+    pub fn oauth_with_options(
+        client_id: &str,
+        client_secret: &str,
+        options: &ClientOptions,
+    ) -> Result<Self> {
+        let credentials = BASE64.encode(format!("{}:{}", client_id, client_secret));
+        let authorization = HeaderValue::from_str(&format!("Basic {}", credentials))
+            .context("Invalid OAuth client credentials")?;
+        Self::build(authorization, options)
+    }
+
+    fn build(authorization: HeaderValue, options: &ClientOptions) -> Result<Self> {
+        let base_url = validate_base_url(&options.base_url)?;
+        validate_api_version(&options.api_version)?;
+
         let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, authorization);
         headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", token))
-                .context("Invalid API token format")?,
+            "Notion-Version",
+            HeaderValue::from_str(&options.api_version).context("Invalid API version")?,
         );
-        headers.insert("Notion-Version", HeaderValue::from_static(NOTION_VERSION));
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
 
-        let client = Client::builder()
-            .default_headers(headers)
-            .build()
-            .context("Failed to build HTTP client")?;
+        let mut builder = Client::builder().default_headers(headers.clone());
+        if let Some(proxy) = &options.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| NotionError::Input(format!("Invalid proxy '{}': {}", proxy, e)))?;
+            builder = builder.proxy(proxy.no_proxy(reqwest::NoProxy::from_env()));
+        }
+        if let Some(path) = &options.ca_bundle {
+            for certificate in read_ca_bundle(path)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        if let Some(timeout) = options.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = options.read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+        let client = builder.build().context("Failed to build HTTP client")?;
 
         Ok(Self {
             client,
-            base_url: base_url.to_string(),
+            headers,
+            base_url,
             dry_run: false,
+            retry: RetryPolicy::default(),
+            timeout: None,
+            limiter: None,
+            #[cfg(test)]
+            attempts: Mutex::new(Vec::new()),
+            tracer: None,
+            cassette: None,
         })
     }
 
@@ -49,6 +164,77 @@ impl NotionClient {
         self.dry_run = dry_run;
     }
 
+    pub fn set_retry_policy(&mut self, retry: RetryPolicy) {
+        self.retry = retry;
+    }
+
+    /// Per-attempt timeout; a timed-out idempotent request is retried.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Limit requests per second across everything sent through this client
+    /// (`None` or `0` disables the limiter).
+    pub fn set_rate_limit(&mut self, rate: Option<f64>) {
+        self.limiter = rate.filter(|r| *r > 0.0).map(RateLimiter::new);
+    }
+
+    /// Log requests and responses (`-v` / `-vv`).
+    pub fn set_tracer(&mut self, tracer: Option<Tracer>) {
+        self.tracer = tracer;
+    }
+
+    /// Save responses to, or serve them from, a cassette directory.
+    pub fn set_cassette(&mut self, cassette: Option<Cassette>) {
+        self.cassette = cassette;
+    }
+
+    /// Every attempt made so far, including retried ones.
+    #[cfg(test)]
+    pub fn attempts(&self) -> Vec<Attempt> {
+        self.attempts.lock().map(|a| a.clone()).unwrap_or_default()
+    }
+
+    /// URL the user visits to authorize a public integration.
+    pub fn oauth_authorize_url(&self, client_id: &str, redirect_uri: &str, state: &str) -> String {
+        let mut url = reqwest::Url::parse(&format!("{}/v1/oauth/authorize", self.base_url))
+            .expect("base URL is valid");
+        url.query_pairs_mut()
+            .append_pair("client_id", client_id)
+            .append_pair("response_type", "code")
+            .append_pair("owner", "user")
+            .append_pair("redirect_uri", redirect_uri)
+            .append_pair("state", state);
+        url.to_string()
+    }
+
+    /// Exchange an authorization code for an access token (requires a client
+    /// built with [`NotionClient::oauth`]).
+    pub async fn oauth_token(&self, code: &str, redirect_uri: &str) -> Result<Value> {
+        let body = serde_json::json!({
+            "grant_type": "authorization_code",
+            "code": code,
+            "redirect_uri": redirect_uri,
+        });
+        self.post("/v1/oauth/token", Some(&body)).await
+    }
+
+    pub async fn oauth_introspect(&self, token: &str) -> Result<Value> {
+        self.post(
+            "/v1/oauth/introspect",
+            Some(&serde_json::json!({ "token": token })),
+        )
+        .await
+    }
+
+    pub async fn oauth_revoke(&self, token: &str) -> Result<Value> {
+        self.post(
+            "/v1/oauth/revoke",
+            Some(&serde_json::json!({ "token": token })),
+        )
+        .await
+    }
+
     pub async fn get(&self, path: &str, query: &[(&str, &str)]) -> Result<Value> {
         let url = format!("{}{}", self.base_url, path);
 
@@ -58,7 +244,7 @@ impl NotionClient {
                 if !query.is_empty() {
                     req = req.query(query);
                 }
-                req
+                Ok(req)
             },
             "GET",
             path,
@@ -70,7 +256,12 @@ impl NotionClient {
         if self.dry_run {
             return self.print_dry_run("POST", path, body);
         }
+        self.post_read(path, body).await
+    }
 
+    /// POST to an endpoint that only reads, such as a query. Sent even with
+    /// `--dry-run`, for lookups the previewed request depends on.
+    pub async fn post_read(&self, path: &str, body: Option<&Value>) -> Result<Value> {
         let url = format!("{}{}", self.base_url, path);
 
         self.send_with_retry(
@@ -79,7 +270,7 @@ impl NotionClient {
                 if let Some(body) = body {
                     req = req.json(body);
                 }
-                req
+                Ok(req)
             },
             "POST",
             path,
@@ -94,7 +285,7 @@ impl NotionClient {
 
         let url = format!("{}{}", self.base_url, path);
 
-        self.send_with_retry(|| self.client.patch(&url).json(body), "PATCH", path)
+        self.send_with_retry(|| Ok(self.client.patch(&url).json(body)), "PATCH", path)
             .await
     }
 
@@ -105,7 +296,7 @@ impl NotionClient {
 
         let url = format!("{}{}", self.base_url, path);
 
-        self.send_with_retry(|| self.client.delete(&url), "DELETE", path)
+        self.send_with_retry(|| Ok(self.client.delete(&url)), "DELETE", path)
             .await
     }
 
@@ -147,52 +338,23 @@ impl NotionClient {
         let url = format!("{}{}", self.base_url, path);
         let mime = mime_from_filename(&file_name);
 
-        let mut attempt = 0;
-        loop {
-            let file_part = multipart::Part::bytes(file_bytes.clone())
-                .file_name(file_name.clone())
-                .mime_str(&mime)
-                .context("Invalid MIME type")?;
-
-            let mut form = multipart::Form::new().part("file", file_part);
-            if let Some(pn) = part_number {
-                form = form.text("part_number", pn.to_string());
-            }
-
-            let response = self
-                .client
-                .post(&url)
-                .multipart(form)
-                .send()
-                .await
-                .with_context(|| format!("POST {}", path))?;
-
-            let status = response.status();
-
-            if status == reqwest::StatusCode::TOO_MANY_REQUESTS && attempt < MAX_RETRIES {
-                let retry_after = response
-                    .headers()
-                    .get("retry-after")
-                    .and_then(|v| v.to_str().ok())
-                    .and_then(|v| v.parse::<u64>().ok());
-
-                let wait_ms = retry_after
-                    .map(|s| s * 1000)
-                    .unwrap_or(INITIAL_BACKOFF_MS * 2u64.pow(attempt));
-
-                eprintln!(
-                    "Rate limited (429). Retrying in {}ms (attempt {}/{})",
-                    wait_ms,
-                    attempt + 1,
-                    MAX_RETRIES
-                );
-                tokio::time::sleep(Duration::from_millis(wait_ms)).await;
-                attempt += 1;
-                continue;
-            }
-
-            return self.handle_response_with_status(status, response).await;
-        }
+        self.send_with_retry(
+            || {
+                let file_part = multipart::Part::bytes(file_bytes.clone())
+                    .file_name(file_name.clone())
+                    .mime_str(&mime)
+                    .context("Invalid MIME type")?;
+
+                let mut form = multipart::Form::new().part("file", file_part);
+                if let Some(pn) = part_number {
+                    form = form.text("part_number", pn.to_string());
+                }
+                Ok(self.client.post(&url).multipart(form))
+            },
+            "POST",
+            path,
+        )
+        .await
     }
 
     fn print_dry_run<T: serde::Serialize>(
@@ -208,63 +370,209 @@ impl NotionClient {
         Ok(serde_json::json!({"dry_run": true, "method": method, "path": path}))
     }
 
+    /// Send a request, retrying transient failures according to the policy.
+    ///
+    /// `build_request` is called once per attempt since request bodies can't be reused.
     async fn send_with_retry<F>(&self, build_request: F, method: &str, path: &str) -> Result<Value>
     where
-        F: Fn() -> reqwest::RequestBuilder,
+        F: Fn() -> Result<reqwest::RequestBuilder>,
     {
-        let mut attempt = 0;
+        let mut attempt = 1;
         loop {
-            let response = build_request()
-                .send()
-                .await
-                .with_context(|| format!("{} {}", method, path))?;
+            let mut request = build_request()?;
+            if let Some(timeout) = self.timeout {
+                request = request.timeout(timeout);
+            }
+            let request = request.build().context("Failed to build request")?;
+            if let Some(tracer) = &self.tracer {
+                tracer.request(&request, &self.headers);
+            }
 
-            let status = response.status();
+            let recorded = self
+                .cassette
+                .as_ref()
+                .map(|_| RecordedRequest::from_request(&request));
+            if let (Some(cassette), Some(recorded)) = (&self.cassette, &recorded)
+                && cassette.mode() == Mode::Replay
+            {
+                let response = cassette.replay(recorded)?;
+                let status = StatusCode::from_u16(response.status)
+                    .context("Invalid status code in cassette")?;
+                return self.handle_response(
+                    status,
+                    response.request_id.as_deref(),
+                    &response.text(),
+                );
+            }
 
-            if status == reqwest::StatusCode::TOO_MANY_REQUESTS && attempt < MAX_RETRIES {
-                let retry_after = response
-                    .headers()
-                    .get("retry-after")
-                    .and_then(|v| v.to_str().ok())
-                    .and_then(|v| v.parse::<u64>().ok());
+            if let Some(limiter) = &self.limiter {
+                limiter.acquire().await;
+            }
 
-                let wait_ms = retry_after
-                    .map(|s| s * 1000)
-                    .unwrap_or(INITIAL_BACKOFF_MS * 2u64.pow(attempt));
+            let started = Instant::now();
+            let (failure, response) = match self.client.execute(request).await {
+                Ok(response) if response.status().is_success() => (None, Ok(response)),
+                Ok(response) => {
+                    let failure = Failure::Status {
+                        status: response.status(),
+                        retry_after: retry::retry_after(response.headers()),
+                    };
+                    (Some(failure), Ok(response))
+                }
+                Err(e) => (Some(Failure::from_error(&e)), Err(e)),
+            };
+            let retry_in = failure
+                .as_ref()
+                .and_then(|f| self.retry.delay(method, f, attempt));
+            self.record(Attempt {
+                method: method.to_string(),
+                path: path.to_string(),
+                number: attempt,
+                outcome: match &response {
+                    Ok(response) => response.status().to_string(),
+                    Err(_) => failure
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                },
+                elapsed: started.elapsed(),
+                retry_in,
+                request_id: response
+                    .as_ref()
+                    .ok()
+                    .and_then(|r| r.headers().get("x-request-id"))
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string),
+            });
+
+            // A Retry-After holds back every request sharing the limiter, not just this one
+            if let (
+                Some(limiter),
+                Some(Failure::Status {
+                    retry_after: Some(wait),
+                    ..
+                }),
+            ) = (&self.limiter, &failure)
+            {
+                limiter.pause(*wait);
+            }
 
+            if let (Some(failure), Some(wait)) = (&failure, retry_in) {
                 eprintln!(
-                    "Rate limited (429). Retrying in {}ms (attempt {}/{})",
-                    wait_ms,
-                    attempt + 1,
-                    MAX_RETRIES
+                    "{}. Retrying in {}ms (attempt {}/{})",
+                    failure,
+                    wait.as_millis(),
+                    attempt,
+                    self.retry.max_retries
                 );
-                tokio::time::sleep(Duration::from_millis(wait_ms)).await;
+                tokio::time::sleep(wait).await;
                 attempt += 1;
                 continue;
             }
 
-            return self.handle_response_with_status(status, response).await;
+            let response = response.map_err(|source| NotionError::Network {
+                method: method.to_string(),
+                path: path.to_string(),
+                source,
+            })?;
+            let status = response.status();
+            let request_id = response
+                .headers()
+                .get("x-request-id")
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            let text = response
+                .text()
+                .await
+                .context("Failed to read response body")?;
+            if let (Some(cassette), Some(recorded)) = (&self.cassette, recorded) {
+                let response = RecordedResponse::new(status, request_id.as_deref(), &text);
+                cassette.record(recorded, response)?;
+            }
+            return self.handle_response(status, request_id.as_deref(), &text);
+        }
+    }
+
+    fn record(&self, attempt: Attempt) {
+        if let Some(tracer) = &self.tracer {
+            tracer.attempt(&attempt);
+        }
+        #[cfg(test)]
+        if let Ok(mut attempts) = self.attempts.lock() {
+            attempts.push(attempt);
         }
     }
 
-    async fn handle_response_with_status(
+    fn handle_response(
         &self,
-        status: reqwest::StatusCode,
-        response: reqwest::Response,
+        status: StatusCode,
+        request_id: Option<&str>,
+        text: &str,
     ) -> Result<Value> {
-        let body: Value = response
-            .json()
-            .await
-            .context("Failed to parse response as JSON")?;
+        if let Some(tracer) = &self.tracer {
+            tracer.response_body(text);
+        }
 
         if !status.is_success() {
-            let message = body["message"].as_str().unwrap_or("Unknown error");
-            let code = body["code"].as_str().unwrap_or("unknown");
-            anyhow::bail!("Notion API error ({}): [{}] {}", status, code, message);
+            // Proxies and gateways may answer with HTML; keep the status either way
+            let body = serde_json::from_str(text).unwrap_or(Value::Null);
+            return Err(NotionError::from_response(status, &body, request_id).into());
         }
 
-        Ok(body)
+        serde_json::from_str(text).context("Failed to parse response as JSON")
+    }
+}
+
+/// Base URL without a trailing slash, so paths can be appended as is.
+fn validate_base_url(base_url: &str) -> Result<String> {
+    let url = reqwest::Url::parse(base_url)
+        .map_err(|e| NotionError::Input(format!("Invalid base URL '{}': {}", base_url, e)))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        bail_input!("Invalid base URL '{}': expected http or https", base_url);
+    }
+    Ok(base_url.trim_end_matches('/').to_string())
+}
+
+/// Certificates from a PEM bundle, trusted in addition to the built-in roots.
+fn read_ca_bundle(path: &Path) -> Result<Vec<reqwest::Certificate>> {
+    let pem = std::fs::read(path)
+        .with_context(|| format!("Failed to read CA bundle {}", path.display()))?;
+    let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+        .map_err(|e| NotionError::Input(format!("Invalid CA bundle {}: {}", path.display(), e)))?;
+    if certificates.is_empty() {
+        bail_input!("No certificates found in CA bundle {}", path.display());
+    }
+    Ok(certificates)
+}
+
+/// Notion versions are dates (`YYYY-MM-DD`).
+fn validate_api_version(version: &str) -> Result<()> {
+    let bytes = version.as_bytes();
+    let is_date = bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        });
+    if !is_date {
+        bail_input!(
+            "Invalid API version '{}': expected a date such as {}",
+            version,
+            NOTION_VERSION
+        );
+    }
+    Ok(())
+}
+
+/// Warning for a pinned version older than the one this CLI was built for.
+pub fn api_version_warning(version: &str) -> Option<String> {
+    // ISO dates compare correctly as strings
+    if version >= NOTION_VERSION {
+        return None;
     }
+    Some(format!(
+        "Notion-Version {} is older than {}, which this CLI was built for; some commands may not work",
+        version, NOTION_VERSION
+    ))
 }
 
 fn mime_from_filename(filename: &str) -> String {
@@ -0,0 +1,123 @@
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use crate::cli::ApiMethod;
+use crate::client::NotionClient;
+use crate::error::bail_input;
+use crate::input::parse_json;
+use crate::output::{OutputFormat, print_result};
+use crate::pagination::{ListRequest, Pagination, print_paginated};
+
+/// An arbitrary API request from `notion api`.
+pub struct Request<'a> {
+    pub method: ApiMethod,
+    pub path: &'a str,
+    pub data: Option<&'a str>,
+    pub query: &'a [(String, String)],
+}
+
+/// Send the request once and print the response.
+pub async fn send(
+    client: &NotionClient,
+    request: &Request<'_>,
+    format: &OutputFormat,
+) -> Result<()> {
+    let path = with_query(&endpoint(request.path)?, request.query);
+    let body = body(request)?;
+    let result = match request.method {
+        ApiMethod::Get => client.get(&path, &[]).await?,
+        ApiMethod::Post => client.post(&path, body.as_ref()).await?,
+        ApiMethod::Patch => {
+            client
+                .patch(&path, &body.unwrap_or_else(|| json!({})))
+                .await?
+        }
+        ApiMethod::Delete => client.delete(&path).await?,
+    };
+    print_result(&result, format)
+}
+
+/// Follow `next_cursor` and print every page as one list.
+///
+/// The cursor and page size go in the query string for GET and in the body
+/// for POST, like the endpoints themselves expect.
+pub async fn paginate(
+    client: &NotionClient,
+    request: &Request<'_>,
+    pagination: &Pagination,
+    format: &OutputFormat,
+) -> Result<()> {
+    let path = endpoint(request.path)?;
+    let body = body(request)?;
+    let list = match request.method {
+        ApiMethod::Get => request
+            .query
+            .iter()
+            .fold(ListRequest::get(path), |list, (k, v)| list.query(k, v)),
+        ApiMethod::Post => ListRequest::post(
+            with_query(&path, request.query),
+            body.unwrap_or_else(|| json!({})),
+        ),
+        ApiMethod::Patch | ApiMethod::Delete => {
+            bail_input!("--paginate only works with GET and POST")
+        }
+    };
+    let pagination = Pagination {
+        all: pagination.limit.is_none(),
+        ..pagination.clone()
+    };
+    print_paginated(client, &list, &pagination, format).await
+}
+
+/// Normalize a user-supplied path to `/v1/...`.
+pub fn endpoint(path: &str) -> Result<String> {
+    if path.contains("://") {
+        bail_input!(
+            "Pass the endpoint path (e.g. /v1/users/me), not a full URL: {}",
+            path
+        );
+    }
+    let path = path.trim_start_matches('/');
+    let path = path.strip_prefix("v1/").unwrap_or(path);
+    if path.is_empty() || path == "v1" {
+        bail_input!("Missing endpoint path (e.g. /v1/users/me)");
+    }
+    Ok(format!("/v1/{}", path))
+}
+
+/// Append URL-encoded query parameters to a path.
+pub fn with_query(path: &str, query: &[(String, String)]) -> String {
+    if query.is_empty() {
+        return path.to_string();
+    }
+    let mut url = reqwest::Url::parse("http://localhost/").expect("static URL is valid");
+    url.query_pairs_mut().extend_pairs(query);
+    let separator = if path.contains('?') { '&' } else { '?' };
+    format!("{}{}{}", path, separator, url.query().unwrap_or_default())
+}
+
+fn body(request: &Request<'_>) -> Result<Option<Value>> {
+    let Some(data) = request.data else {
+        return Ok(None);
+    };
+    if matches!(request.method, ApiMethod::Get | ApiMethod::Delete) {
+        bail_input!(
+            "--data is not allowed with {} requests",
+            method_name(request.method)
+        );
+    }
+    parse_json(data, "--data").map(Some)
+}
+
+fn method_name(method: ApiMethod) -> &'static str {
+    match method {
+        ApiMethod::Get => "GET",
+        ApiMethod::Post => "POST",
+        ApiMethod::Patch => "PATCH",
+        ApiMethod::Delete => "DELETE",
+    }
+}
+
+#[cfg(test)]
+#[path = "api_tests.rs"]
+mod tests;
@@ -0,0 +1,286 @@
+use super::*;
+use crate::error::{ErrorKind, kind};
+
+fn request<'a>(
+    method: ApiMethod,
+    path: &'a str,
+    data: Option<&'a str>,
+    query: &'a [(String, String)],
+) -> Request<'a> {
+    Request {
+        method,
+        path,
+        data,
+        query,
+    }
+}
+
+fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+    items
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_endpoint_normalizes_prefix() {
+    assert_eq!(endpoint("/v1/users/me").unwrap(), "/v1/users/me");
+    assert_eq!(endpoint("v1/users/me").unwrap(), "/v1/users/me");
+    assert_eq!(endpoint("users/me").unwrap(), "/v1/users/me");
+    assert_eq!(endpoint("/users/me").unwrap(), "/v1/users/me");
+}
+
+#[test]
+fn test_endpoint_rejects_urls_and_empty_paths() {
+    let err = endpoint("https://api.notion.com/v1/users").unwrap_err();
+    assert_eq!(kind(&err), ErrorKind::Input);
+    assert!(endpoint("/").is_err());
+    assert!(endpoint("/v1").is_err());
+}
+
+#[test]
+fn test_with_query_encodes_values() {
+    let query = pairs(&[("filter_properties", "a b"), ("x", "1&2")]);
+    assert_eq!(
+        with_query("/v1/pages/p1", &query),
+        "/v1/pages/p1?filter_properties=a+b&x=1%262"
+    );
+    assert_eq!(
+        with_query("/v1/pages/p1?a=1", &pairs(&[("b", "2")])),
+        "/v1/pages/p1?a=1&b=2"
+    );
+    assert_eq!(with_query("/v1/users", &[]), "/v1/users");
+}
+
+#[tokio::test]
+async fn test_send_get_with_query() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/v1/users")
+        .match_query(mockito::Matcher::UrlEncoded("page_size".into(), "2".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"object":"list","results":[],"has_more":false}"#)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let query = pairs(&[("page_size", "2")]);
+    let result = send(
+        &client,
+        &request(ApiMethod::Get, "users", None, &query),
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_send_post_with_data() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/comments")
+        .match_body(mockito::Matcher::Json(json!({
+            "parent": { "page_id": "p1" },
+            "rich_text": [{ "text": { "content": "Hi" } }]
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"object":"comment","id":"c1"}"#)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let data = r#"{"parent":{"page_id":"p1"},"rich_text":[{"text":{"content":"Hi"}}]}"#;
+    let result = send(
+        &client,
+        &request(ApiMethod::Post, "/v1/comments", Some(data), &[]),
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_send_patch_without_data_sends_empty_object() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("PATCH", "/v1/pages/p1")
+        .match_body(mockito::Matcher::Json(json!({})))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"object":"page","id":"p1"}"#)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let result = send(
+        &client,
+        &request(ApiMethod::Patch, "/v1/pages/p1", None, &[]),
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_send_delete() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("DELETE", "/v1/blocks/b1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"object":"block","id":"b1","in_trash":true}"#)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let result = send(
+        &client,
+        &request(ApiMethod::Delete, "blocks/b1", None, &[]),
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_send_dry_run_skips_writes() {
+    let server = mockito::Server::new_async().await;
+    let mut client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    client.set_dry_run(true);
+
+    let result = send(
+        &client,
+        &request(ApiMethod::Post, "/v1/pages", Some("{}"), &[]),
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_send_rejects_data_on_get() {
+    let client = NotionClient::with_base_url("token", "http://127.0.0.1:1").unwrap();
+    let err = send(
+        &client,
+        &request(ApiMethod::Get, "/v1/users", Some("{}"), &[]),
+        &OutputFormat::Raw,
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(err.to_string(), "--data is not allowed with GET requests");
+    assert_eq!(kind(&err), ErrorKind::Input);
+}
+
+#[tokio::test]
+async fn test_paginate_get_follows_cursor() {
+    let mut server = mockito::Server::new_async().await;
+    // Created first so the cursor request doesn't match the broader mock
+    let second = server
+        .mock("GET", "/v1/users")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "start_cursor".into(),
+            "c2".into(),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"object":"list","results":[{"id":"u2"}],"has_more":false,"next_cursor":null}"#,
+        )
+        .create_async()
+        .await;
+
+    let first = server
+        .mock("GET", "/v1/users")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "page_size".into(),
+            "100".into(),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"object":"list","results":[{"id":"u1"}],"has_more":true,"next_cursor":"c2"}"#,
+        )
+        .create_async()
+        .await;
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let result = paginate(
+        &client,
+        &request(ApiMethod::Get, "/v1/users", None, &[]),
+        &Pagination::default(),
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok());
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_paginate_post_puts_cursor_in_body() {
+    let mut server = mockito::Server::new_async().await;
+    let first = server
+        .mock("POST", "/v1/search")
+        .match_body(mockito::Matcher::Json(
+            json!({"query": "x", "page_size": 100}),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"object":"list","results":[{"id":"p1"}],"has_more":true,"next_cursor":"c2"}"#,
+        )
+        .create_async()
+        .await;
+    let second = server
+        .mock("POST", "/v1/search")
+        .match_body(mockito::Matcher::Json(
+            json!({"query": "x", "page_size": 100, "start_cursor": "c2"}),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"object":"list","results":[{"id":"p2"}],"has_more":false,"next_cursor":null}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let result = paginate(
+        &client,
+        &request(ApiMethod::Post, "search", Some(r#"{"query":"x"}"#), &[]),
+        &Pagination::default(),
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok());
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_paginate_rejects_patch() {
+    let client = NotionClient::with_base_url("token", "http://127.0.0.1:1").unwrap();
+    let err = paginate(
+        &client,
+        &request(ApiMethod::Patch, "/v1/pages/p1", None, &[]),
+        &Pagination::default(),
+        &OutputFormat::Raw,
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(kind(&err), ErrorKind::Input);
+}
@@ -1,8 +1,13 @@
 use anyhow::{Context, Result};
 use serde_json::{Value, json};
+use std::future::Future;
+use std::pin::Pin;
 
 use crate::client::NotionClient;
+use crate::input::{parse_json, read_source};
+use crate::markdown;
 use crate::output::{OutputFormat, print_result};
+use crate::pagination::{ListRequest, Pagination, paginate, print_paginated};
 
 pub async fn get(client: &NotionClient, block_id: &str, format: &OutputFormat) -> Result<()> {
     let path = format!("/v1/blocks/{}", block_id);
@@ -14,35 +19,148 @@ pub async fn get(client: &NotionClient, block_id: &str, format: &OutputFormat) -
 pub async fn children(
     client: &NotionClient,
     block_id: &str,
-    page_size: Option<u32>,
-    start_cursor: Option<&str>,
+    pagination: &Pagination,
     format: &OutputFormat,
 ) -> Result<()> {
-    let path = format!("/v1/blocks/{}/children", block_id);
-    let mut query: Vec<(&str, &str)> = Vec::new();
-    let ps_str;
-    if let Some(ps) = page_size {
-        ps_str = ps.to_string();
-        query.push(("page_size", &ps_str));
-    }
-    if let Some(cursor) = start_cursor {
-        query.push(("start_cursor", cursor));
-    }
+    let request = ListRequest::get(format!("/v1/blocks/{}/children", block_id));
+    print_paginated(client, &request, pagination, format).await
+}
 
-    let result = client.get(&path, &query).await?;
-    print_result(&result, format)?;
-    Ok(())
+/// Fetch every child of a block, recursing into blocks with `has_children`.
+///
+/// Nested children are stored under a `children` key on each block. Child
+/// pages and databases are separate documents and are not descended into.
+pub fn descendants<'a>(
+    client: &'a NotionClient,
+    block_id: &'a str,
+) -> Pin<Box<dyn Future<Output = Result<Vec<Value>>> + 'a>> {
+    Box::pin(async move {
+        let all = Pagination {
+            all: true,
+            ..Pagination::default()
+        };
+        let request = ListRequest::get(format!("/v1/blocks/{}/children", block_id));
+        let mut response = paginate(client, &request, &all, |_| Ok(())).await?;
+        let Some(Value::Array(mut blocks)) = response.get_mut("results").map(Value::take) else {
+            return Ok(Vec::new());
+        };
+
+        for block in &mut blocks {
+            let separate_document = matches!(
+                block["type"].as_str(),
+                Some("child_page" | "child_database")
+            );
+            if block["has_children"] != true || separate_document {
+                continue;
+            }
+            let id = block["id"].as_str().unwrap_or_default().to_string();
+            let children = descendants(client, &id).await?;
+            block["children"] = Value::Array(children);
+        }
+        Ok(blocks)
+    })
+}
+
+/// Most children the API accepts in one append request.
+const MAX_CHILDREN: usize = 100;
+
+/// Append a block tree, splitting it into requests the API accepts.
+///
+/// Each request carries at most 100 blocks and no nested children; deeper
+/// levels are appended once their parent has been created. Table rows stay
+/// inline because a table cannot be created empty. Returns the created
+/// top-level blocks (placeholders in dry-run mode).
+pub fn append_tree<'a>(
+    client: &'a NotionClient,
+    parent_id: &'a str,
+    blocks: Vec<Value>,
+    after: Option<&'a str>,
+) -> Pin<Box<dyn Future<Output = Result<Vec<Value>>> + 'a>> {
+    Box::pin(async move {
+        let mut nested = Vec::with_capacity(blocks.len());
+        let blocks: Vec<Value> = blocks
+            .into_iter()
+            .map(|mut block| {
+                nested.push(take_children(&mut block));
+                block
+            })
+            .collect();
+
+        let path = format!("/v1/blocks/{}/children", parent_id);
+        let mut created: Vec<Value> = Vec::with_capacity(blocks.len());
+        let mut after = after.map(String::from);
+
+        for chunk in blocks.chunks(MAX_CHILDREN) {
+            let mut body = json!({ "children": chunk });
+            if let Some(after_id) = &after {
+                body["after"] = json!(after_id);
+            }
+            let response = client.patch(&path, &body).await?;
+
+            let results = match response["results"].as_array() {
+                Some(results) if results.len() == chunk.len() => results.clone(),
+                Some(_) => anyhow::bail!(
+                    "Unexpected append response for block {}: expected {} new blocks",
+                    parent_id,
+                    chunk.len()
+                ),
+                None => (0..chunk.len())
+                    .map(|i| json!({ "id": format!("<new-block-{}>", created.len() + i + 1) }))
+                    .collect(),
+            };
+            after = results
+                .last()
+                .and_then(|b| b["id"].as_str())
+                .map(String::from);
+            created.extend(results);
+        }
+
+        for (block, children) in created.iter().zip(nested) {
+            if children.is_empty() {
+                continue;
+            }
+            let id = block["id"].as_str().unwrap_or_default().to_string();
+            append_tree(client, &id, children, None).await?;
+        }
+        Ok(created)
+    })
+}
+
+/// Detach the children that must be appended in a later request.
+fn take_children(block: &mut Value) -> Vec<Value> {
+    let kind = block["type"].as_str().unwrap_or_default().to_string();
+    let Some(data) = block.get_mut(&kind).and_then(Value::as_object_mut) else {
+        return Vec::new();
+    };
+    let Some(Value::Array(mut children)) = data.remove("children") else {
+        return Vec::new();
+    };
+
+    if kind == "table" {
+        let rest = children.split_off(children.len().min(MAX_CHILDREN));
+        data.insert("children".into(), Value::Array(children));
+        return rest;
+    }
+    children
 }
 
 pub async fn append(
     client: &NotionClient,
     block_id: &str,
-    children_json: &str,
+    children_json: Option<&str>,
+    markdown_source: Option<&str>,
     after: Option<&str>,
     format: &OutputFormat,
 ) -> Result<()> {
-    let children: Value =
-        serde_json::from_str(children_json).context("Invalid JSON for children")?;
+    if let Some(source) = markdown_source {
+        let blocks = markdown::to_blocks(&read_source(source)?);
+        let created = append_tree(client, block_id, blocks, after).await?;
+        print_result(&json!({ "object": "list", "results": created }), format)?;
+        return Ok(());
+    }
+
+    let children_json = children_json.context("Either --children or --markdown is required")?;
+    let children = parse_json(children_json, "children")?;
 
     let mut body = json!({ "children": children });
     if let Some(after_id) = after {
@@ -62,7 +180,7 @@ pub async fn update(
     archived: Option<bool>,
     format: &OutputFormat,
 ) -> Result<()> {
-    let mut body: Value = serde_json::from_str(data_json).context("Invalid JSON for block data")?;
+    let mut body = parse_json(data_json, "block data")?;
 
     if let Some(a) = archived {
         body["archived"] = json!(a);
@@ -38,8 +38,11 @@ async fn test_children() {
     let result = children(
         &client,
         "block-1",
-        Some(10),
-        Some("cursor-abc"),
+        &Pagination {
+            page_size: Some(10),
+            start_cursor: Some("cursor-abc".into()),
+            ..Default::default()
+        },
         &OutputFormat::Raw,
     )
     .await;
@@ -68,7 +71,8 @@ async fn test_append_with_after() {
     let result = append(
         &client,
         "block-1",
-        children_json,
+        Some(children_json),
+        None,
         Some("after-block-id"),
         &OutputFormat::Raw,
     )
@@ -94,7 +98,15 @@ async fn test_append_without_after() {
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
     let children_json = r#"[{"object":"block","type":"paragraph"}]"#;
-    let result = append(&client, "block-1", children_json, None, &OutputFormat::Raw).await;
+    let result = append(
+        &client,
+        "block-1",
+        Some(children_json),
+        None,
+        None,
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -130,7 +142,13 @@ async fn test_children_without_pagination() {
         .await;
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
-    let result = children(&client, "block-1", None, None, &OutputFormat::Raw).await;
+    let result = children(
+        &client,
+        "block-1",
+        &Pagination::default(),
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -152,7 +170,16 @@ async fn test_children_with_page_size_only() {
         .await;
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
-    let result = children(&client, "block-1", Some(5), None, &OutputFormat::Raw).await;
+    let result = children(
+        &client,
+        "block-1",
+        &Pagination {
+            page_size: Some(5),
+            ..Default::default()
+        },
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -222,7 +249,8 @@ async fn test_append_with_invalid_json() {
     let result = append(
         &client,
         "block-1",
-        "not valid json",
+        Some("not valid json"),
+        None,
         None,
         &OutputFormat::Raw,
     )
@@ -231,3 +259,166 @@ async fn test_append_with_invalid_json() {
     assert!(result.is_err());
     assert!(result.unwrap_err().to_string().contains("Invalid JSON"));
 }
+
+#[tokio::test]
+async fn test_descendants_recurses_into_children() {
+    let mut server = mockito::Server::new_async().await;
+    let root = server
+        .mock("GET", "/v1/blocks/page-1/children")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"results":[
+                {"id":"b-1","type":"toggle","has_children":true},
+                {"id":"b-2","type":"child_page","has_children":true},
+                {"id":"b-3","type":"paragraph","has_children":false}
+            ],"has_more":false}"#,
+        )
+        .create_async()
+        .await;
+    let nested = server
+        .mock("GET", "/v1/blocks/b-1/children")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[{"id":"b-4","type":"paragraph","has_children":false}],"has_more":false}"#)
+        .create_async()
+        .await;
+    let child_page = server
+        .mock("GET", "/v1/blocks/b-2/children")
+        .match_query(mockito::Matcher::Any)
+        .expect(0)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let blocks = descendants(&client, "page-1").await.unwrap();
+
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[0]["children"][0]["id"], "b-4");
+    assert!(blocks[1].get("children").is_none());
+    root.assert_async().await;
+    nested.assert_async().await;
+    child_page.assert_async().await;
+}
+
+fn paragraph(text: &str) -> Value {
+    json!({
+        "object": "block",
+        "type": "paragraph",
+        "paragraph": { "rich_text": [{ "type": "text", "text": { "content": text } }] }
+    })
+}
+
+fn created(ids: std::ops::Range<usize>) -> String {
+    let results: Vec<Value> = ids.map(|i| json!({ "id": format!("new-{}", i) })).collect();
+    json!({ "object": "list", "results": results }).to_string()
+}
+
+#[tokio::test]
+async fn test_append_tree_splits_into_chunks_of_100() {
+    let mut server = mockito::Server::new_async().await;
+    let first = server
+        .mock("PATCH", "/v1/blocks/page-1/children")
+        .match_body(mockito::Matcher::PartialJson(
+            json!({ "children": [paragraph("0")] }),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(created(0..100))
+        .create_async()
+        .await;
+    let second = server
+        .mock("PATCH", "/v1/blocks/page-1/children")
+        .match_body(mockito::Matcher::PartialJson(json!({ "after": "new-99" })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(created(100..150))
+        .create_async()
+        .await;
+
+    let blocks: Vec<Value> = (0..150).map(|i| paragraph(&i.to_string())).collect();
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let created = append_tree(&client, "page-1", blocks, None).await.unwrap();
+
+    assert_eq!(created.len(), 150);
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_append_tree_appends_nested_children_after_parent() {
+    let mut server = mockito::Server::new_async().await;
+    let mut item = json!({
+        "object": "block",
+        "type": "bulleted_list_item",
+        "bulleted_list_item": { "rich_text": [], "children": [paragraph("child")] }
+    });
+    let parent = server
+        .mock("PATCH", "/v1/blocks/page-1/children")
+        .match_body(mockito::Matcher::Json(json!({
+            "children": [{
+                "object": "block",
+                "type": "bulleted_list_item",
+                "bulleted_list_item": { "rich_text": [] }
+            }]
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(created(0..1))
+        .create_async()
+        .await;
+    let child = server
+        .mock("PATCH", "/v1/blocks/new-0/children")
+        .match_body(mockito::Matcher::Json(
+            json!({ "children": [paragraph("child")] }),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(created(1..2))
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let result = append_tree(&client, "page-1", vec![item.take()], None).await;
+
+    assert!(result.is_ok());
+    parent.assert_async().await;
+    child.assert_async().await;
+}
+
+#[test]
+fn test_take_children_keeps_table_rows_inline() {
+    let row = json!({ "type": "table_row", "table_row": { "cells": [] } });
+    let mut table = json!({
+        "type": "table",
+        "table": { "table_width": 1, "children": vec![row.clone(); 130] }
+    });
+
+    let rest = take_children(&mut table);
+
+    assert_eq!(table["table"]["children"].as_array().unwrap().len(), 100);
+    assert_eq!(rest.len(), 30);
+}
+
+#[tokio::test]
+async fn test_append_markdown_dry_run() {
+    let server = mockito::Server::new_async().await;
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut file, b"- a\n  - b\n").unwrap();
+
+    let mut client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    client.set_dry_run(true);
+    let result = append(
+        &client,
+        "block-1",
+        None,
+        file.path().to_str(),
+        None,
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok());
+}
@@ -3,27 +3,16 @@ use serde_json::json;
 
 use crate::client::NotionClient;
 use crate::output::{OutputFormat, print_result};
+use crate::pagination::{ListRequest, Pagination, print_paginated};
 
 pub async fn list(
     client: &NotionClient,
     block_id: &str,
-    page_size: Option<u32>,
-    start_cursor: Option<&str>,
+    pagination: &Pagination,
     format: &OutputFormat,
 ) -> Result<()> {
-    let mut query: Vec<(&str, &str)> = vec![("block_id", block_id)];
-    let ps_str;
-    if let Some(ps) = page_size {
-        ps_str = ps.to_string();
-        query.push(("page_size", &ps_str));
-    }
-    if let Some(cursor) = start_cursor {
-        query.push(("start_cursor", cursor));
-    }
-
-    let result = client.get("/v1/comments", &query).await?;
-    print_result(&result, format)?;
-    Ok(())
+    let request = ListRequest::get("/v1/comments").query("block_id", block_id);
+    print_paginated(client, &request, pagination, format).await
 }
 
 pub async fn create(
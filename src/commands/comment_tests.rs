@@ -17,7 +17,13 @@ async fn test_list() {
         .await;
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
-    let result = list(&client, "block-1", None, None, &OutputFormat::Raw).await;
+    let result = list(
+        &client,
+        "block-1",
+        &Pagination::default(),
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -61,7 +67,16 @@ async fn test_list_with_page_size() {
         .await;
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
-    let result = list(&client, "block-1", Some(10), None, &OutputFormat::Raw).await;
+    let result = list(
+        &client,
+        "block-1",
+        &Pagination {
+            page_size: Some(10),
+            ..Default::default()
+        },
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -86,8 +101,10 @@ async fn test_list_with_start_cursor() {
     let result = list(
         &client,
         "block-1",
-        None,
-        Some("cursor-abc"),
+        &Pagination {
+            start_cursor: Some("cursor-abc".into()),
+            ..Default::default()
+        },
         &OutputFormat::Raw,
     )
     .await;
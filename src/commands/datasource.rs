@@ -1,16 +1,39 @@
-use anyhow::{Context, Result};
-use serde_json::{Value, json};
+use anyhow::{Context, Result, bail};
+use indexmap::IndexMap;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::path::Path;
 
 use crate::client::NotionClient;
-use crate::output::{OutputFormat, print_result};
+use crate::commands::{page, user};
+use crate::csv::{self, Dialect};
+use crate::error::bail_input;
+use crate::filter;
+use crate::input::parse_json;
+use crate::models::{DataSource, PropertySchema};
+use crate::output::{ListPrinter, OutputFormat, print_error, print_result, print_warning};
+use crate::pagination::{ListRequest, Pagination, print_paginated_with};
+use crate::properties;
 
 pub async fn get(client: &NotionClient, ds_id: &str, format: &OutputFormat) -> Result<()> {
-    let path = format!("/v1/data_sources/{}", ds_id);
-    let result = client.get(&path, &[]).await?;
+    let result = retrieve(client, ds_id).await?;
     print_result(&result, format)?;
     Ok(())
 }
 
+/// Fetch a data source, including its property schema.
+pub async fn retrieve(client: &NotionClient, ds_id: &str) -> Result<Value> {
+    let path = format!("/v1/data_sources/{}", ds_id);
+    client.get(&path, &[]).await
+}
+
+/// CSV/TSV columns for a data source: `id`, then properties in schema order.
+pub fn schema_columns(data_source: &DataSource) -> Vec<String> {
+    std::iter::once("id".to_string())
+        .chain(data_source.properties.keys().cloned())
+        .collect()
+}
+
 pub async fn create(
     client: &NotionClient,
     parent_id: &str,
@@ -24,7 +47,7 @@ pub async fn create(
     });
 
     if let Some(pj) = properties_json {
-        let props: Value = serde_json::from_str(pj).context("Invalid JSON for properties")?;
+        let props = parse_json(pj, "properties")?;
         body["properties"] = props;
     }
 
@@ -39,46 +62,112 @@ pub async fn update(
     data_json: &str,
     format: &OutputFormat,
 ) -> Result<()> {
-    let body: Value = serde_json::from_str(data_json).context("Invalid JSON for data source")?;
+    let body = parse_json(data_json, "data source")?;
     let path = format!("/v1/data_sources/{}", ds_id);
     let result = client.patch(&path, &body).await?;
     print_result(&result, format)?;
     Ok(())
 }
 
+/// What to query for, besides pagination.
+pub struct QueryArgs<'a> {
+    /// Filter as JSON
+    pub filter: Option<&'a str>,
+    /// Filter as a [`filter`] expression
+    pub where_clause: Option<&'a str>,
+    /// Sorts as JSON
+    pub sorts: Option<&'a str>,
+    /// Sorts as `Property` or `-Property`
+    pub sort: &'a [String],
+    /// Print the body instead of sending it
+    pub explain: bool,
+}
+
 pub async fn query(
     client: &NotionClient,
     ds_id: &str,
-    filter_json: Option<&str>,
-    sorts_json: Option<&str>,
-    page_size: Option<u32>,
-    start_cursor: Option<&str>,
+    args: &QueryArgs<'_>,
+    pagination: &Pagination,
     format: &OutputFormat,
 ) -> Result<()> {
     let mut body = json!({});
 
-    if let Some(fj) = filter_json {
-        let filter: Value = serde_json::from_str(fj).context("Invalid JSON for filter")?;
+    // The schema checks --where and --sort and names the CSV/TSV columns
+    let needs_schema =
+        args.where_clause.is_some() || !args.sort.is_empty() || format.dialect().is_some();
+    let data_source = if needs_schema {
+        Some(client.retrieve_data_source(ds_id).await?)
+    } else {
+        None
+    };
+
+    if let Some(fj) = args.filter {
+        let filter = parse_json(fj, "filter")?;
         body["filter"] = filter;
     }
 
-    if let Some(sj) = sorts_json {
-        let sorts: Value = serde_json::from_str(sj).context("Invalid JSON for sorts")?;
+    if let Some(text) = args.where_clause
+        && let Some(data_source) = &data_source
+    {
+        let expr = filter::parse(text)?;
+        let people = if expr.mentions_email(&data_source.properties) {
+            user::emails(client).await?
+        } else {
+            HashMap::new()
+        };
+        body["filter"] = expr.compile(&data_source.properties, &people)?;
+    }
+
+    if let Some(sj) = args.sorts {
+        let sorts = parse_json(sj, "sorts")?;
         body["sorts"] = sorts;
     }
 
-    if let Some(ps) = page_size {
-        body["page_size"] = json!(ps);
+    if !args.sort.is_empty()
+        && let Some(data_source) = &data_source
+    {
+        body["sorts"] = sorts(args.sort, &data_source.properties)?;
     }
 
-    if let Some(cursor) = start_cursor {
-        body["start_cursor"] = json!(cursor);
+    if args.explain {
+        let format = match format {
+            OutputFormat::Raw => OutputFormat::Raw,
+            _ => OutputFormat::Json,
+        };
+        return print_result(&body, &format);
     }
 
-    let path = format!("/v1/data_sources/{}/query", ds_id);
-    let result = client.post(&path, Some(&body)).await?;
-    print_result(&result, format)?;
-    Ok(())
+    let mut printer = ListPrinter::new(format);
+    if format.dialect().is_some()
+        && let Some(data_source) = &data_source
+    {
+        printer = printer.with_columns(schema_columns(data_source));
+    }
+
+    let request = ListRequest::post(format!("/v1/data_sources/{}/query", ds_id), body);
+    print_paginated_with(client, &request, pagination, printer).await
+}
+
+/// Sorts from `--sort` arguments: a property name, ID, `created_time` or
+/// `last_edited_time`, descending with a leading `-`.
+fn sorts(specs: &[String], schema: &IndexMap<String, PropertySchema>) -> Result<Value> {
+    let mut sorts = Vec::with_capacity(specs.len());
+    for spec in specs {
+        let spec = spec.trim();
+        let (name, direction) = match spec.strip_prefix('-') {
+            Some(name) => (name, "descending"),
+            None => (spec.strip_prefix('+').unwrap_or(spec), "ascending"),
+        };
+        let sort = match properties::find_property(schema, name) {
+            Ok((name, _)) => json!({ "property": name, "direction": direction }),
+            Err(_) if matches!(name, "created_time" | "last_edited_time") => {
+                json!({ "timestamp": name, "direction": direction })
+            }
+            Err(e) => return Err(e),
+        };
+        sorts.push(sort);
+    }
+    Ok(Value::Array(sorts))
 }
 
 pub async fn templates(client: &NotionClient, ds_id: &str, format: &OutputFormat) -> Result<()> {
@@ -88,6 +177,180 @@ pub async fn templates(client: &NotionClient, ds_id: &str, format: &OutputFormat
     Ok(())
 }
 
+/// Create one page per CSV/TSV row, converting each cell with the schema.
+///
+/// Headers match property names exactly, then case-insensitively; `mappings`
+/// (`Header=Property`, or `Header=` to skip a column) override that. Rows that
+/// fail are reported and the rest are still imported.
+pub async fn import(
+    client: &NotionClient,
+    ds_id: &str,
+    file: &Path,
+    mappings: &[String],
+    format: &OutputFormat,
+) -> Result<()> {
+    let text = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let mut records = csv::parse(&text, Dialect::from_path(file))
+        .with_context(|| format!("Failed to parse {}", file.display()))?
+        .into_iter();
+    let Some(headers) = records.next() else {
+        bail_input!("{} has no header row", file.display());
+    };
+
+    let data_source = retrieve(client, ds_id).await?;
+    let schema = &data_source["properties"];
+    let columns = map_columns(&headers, schema, mappings)?;
+
+    let has_people = columns
+        .iter()
+        .flatten()
+        .any(|p| schema[p]["type"] == "people");
+    let people = if has_people {
+        user::emails(client).await?
+    } else {
+        HashMap::new()
+    };
+
+    let parent = json!({ "type": "data_source_id", "data_source_id": ds_id });
+    let mut rows = Vec::new();
+    let mut failed = 0;
+
+    for (index, record) in records.enumerate() {
+        // The header is row 1
+        let row = index + 2;
+        let outcome = match row_properties(&headers, &columns, &record, schema, &people) {
+            Ok(props) => {
+                let body = json!({ "parent": parent, "properties": props });
+                page::create_page(client, &body).await
+            }
+            Err(e) => Err(e),
+        };
+
+        match outcome {
+            Ok(result) if result["dry_run"] == true => {
+                rows.push(json!({ "row": row, "status": "valid" }));
+            }
+            Ok(result) => {
+                rows.push(json!({ "row": row, "status": "created", "id": result["id"] }));
+            }
+            Err(e) => {
+                failed += 1;
+                print_error(&format!("Row {}: {:#}", row, e));
+                rows.push(json!({ "row": row, "status": "failed", "error": format!("{:#}", e) }));
+            }
+        }
+    }
+
+    let total = rows.len();
+    let report = json!({
+        "data_source_id": ds_id,
+        "rows": total,
+        "failed": failed,
+        "results": rows,
+    });
+    print_result(&report, format)?;
+
+    if failed > 0 {
+        bail!("{} of {} rows failed to import", failed, total);
+    }
+    Ok(())
+}
+
+/// Property name for each header, or `None` for skipped columns.
+fn map_columns(
+    headers: &[String],
+    schema: &Value,
+    mappings: &[String],
+) -> Result<Vec<Option<String>>> {
+    let empty = Map::new();
+    let properties = schema.as_object().unwrap_or(&empty);
+
+    let mut explicit = HashMap::new();
+    for mapping in mappings {
+        let Some((header, property)) = mapping.split_once('=') else {
+            bail_input!("Invalid mapping '{}' (expected HEADER=PROPERTY)", mapping);
+        };
+        let (header, property) = (header.trim(), property.trim());
+        if !headers.iter().any(|h| h.trim() == header) {
+            bail_input!("Mapped column '{}' is not in the file", header);
+        }
+        if !property.is_empty() && !properties.contains_key(property) {
+            bail_input!("Data source has no property '{}'", property);
+        }
+        explicit.insert(header, property);
+    }
+
+    let columns: Vec<Option<String>> = headers
+        .iter()
+        .map(|header| {
+            let header = header.trim();
+            if let Some(property) = explicit.get(header) {
+                return (!property.is_empty()).then(|| property.to_string());
+            }
+            let found = properties
+                .keys()
+                .find(|name| name.as_str() == header)
+                .or_else(|| {
+                    properties
+                        .keys()
+                        .find(|name| name.eq_ignore_ascii_case(header))
+                });
+            if found.is_none() {
+                print_warning(&format!(
+                    "Ignoring column '{}' (no matching property)",
+                    header
+                ));
+            }
+            found.cloned()
+        })
+        .collect();
+
+    if columns.iter().all(Option::is_none) {
+        bail_input!("No column matches a property of the data source");
+    }
+    Ok(columns)
+}
+
+/// Convert one record into a `properties` object, collecting every cell error.
+fn row_properties(
+    headers: &[String],
+    columns: &[Option<String>],
+    record: &[String],
+    schema: &Value,
+    people: &HashMap<String, String>,
+) -> Result<Value> {
+    if record.len() > headers.len() {
+        bail!(
+            "{} fields, but the header has {}",
+            record.len(),
+            headers.len()
+        );
+    }
+
+    let mut props = Map::new();
+    let mut errors = Vec::new();
+    for ((header, column), cell) in headers.iter().zip(columns).zip(record) {
+        let Some(property) = column else {
+            continue;
+        };
+        if cell.trim().is_empty() {
+            continue;
+        }
+        match properties::from_text(&schema[property], cell, people) {
+            Ok(value) => {
+                props.insert(property.clone(), value);
+            }
+            Err(e) => errors.push(format!("{}: {}", header.trim(), e)),
+        }
+    }
+
+    if !errors.is_empty() {
+        bail!("{}", errors.join("; "));
+    }
+    Ok(Value::Object(props))
+}
+
 #[cfg(test)]
 #[path = "datasource_tests.rs"]
 mod tests;
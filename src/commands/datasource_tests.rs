@@ -1,6 +1,16 @@
 use super::*;
 use crate::output::OutputFormat;
 
+fn json_query<'a>(filter: Option<&'a str>, sorts: Option<&'a str>) -> QueryArgs<'a> {
+    QueryArgs {
+        filter,
+        where_clause: None,
+        sorts,
+        sort: &[],
+        explain: false,
+    }
+}
+
 #[tokio::test]
 async fn test_get() {
     let mut server = mockito::Server::new_async().await;
@@ -88,10 +98,12 @@ async fn test_query_with_all_options() {
     let result = query(
         &client,
         "ds-1",
-        Some(filter),
-        Some(sorts),
-        Some(10),
-        Some("cursor-1"),
+        &json_query(Some(filter), Some(sorts)),
+        &Pagination {
+            page_size: Some(10),
+            start_cursor: Some("cursor-1".into()),
+            ..Default::default()
+        },
         &OutputFormat::Raw,
     )
     .await;
@@ -131,7 +143,14 @@ async fn test_query_without_options() {
         .await;
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
-    let result = query(&client, "ds-1", None, None, None, None, &OutputFormat::Raw).await;
+    let result = query(
+        &client,
+        "ds-1",
+        &json_query(None, None),
+        &Pagination::default(),
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -156,10 +175,8 @@ async fn test_query_with_filter_only() {
     let result = query(
         &client,
         "ds-1",
-        Some(filter),
-        None,
-        None,
-        None,
+        &json_query(Some(filter), None),
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -206,3 +223,362 @@ async fn test_create_with_invalid_json() {
     assert!(result.is_err());
     assert!(result.unwrap_err().to_string().contains("Invalid JSON"));
 }
+
+#[test]
+fn test_schema_columns_follow_schema_order() {
+    let data_source: DataSource = serde_json::from_str(
+        r#"{"id":"ds","parent":{"type":"database_id","database_id":"db"},"properties":{"Name":{"type":"title","title":{}},"Due":{"type":"date","date":{}},"Assignee":{"type":"people","people":{}}}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        schema_columns(&data_source),
+        ["id", "Name", "Due", "Assignee"]
+    );
+}
+
+#[tokio::test]
+async fn test_query_csv_fetches_schema_for_columns() {
+    let mut server = mockito::Server::new_async().await;
+    let schema = server
+        .mock("GET", "/v1/data_sources/ds-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"object":"data_source","id":"ds-1","parent":{"type":"database_id","database_id":"db-1"},"properties":{"Name":{"type":"title"}}}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let mock = server
+        .mock("POST", "/v1/data_sources/ds-1/query")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[],"has_more":false}"#)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let result = query(
+        &client,
+        "ds-1",
+        &json_query(None, None),
+        &Pagination::default(),
+        &OutputFormat::Csv,
+    )
+    .await;
+
+    assert!(result.is_ok());
+    schema.assert_async().await;
+    mock.assert_async().await;
+}
+
+const QUERY_SCHEMA: &str = r#"{"object":"data_source","id":"ds-1","parent":{"type":"database_id","database_id":"db-1"},"properties":{
+    "Name":{"id":"title","type":"title","title":{}},
+    "Status":{"id":"st","type":"status","status":{"options":[{"name":"Todo"},{"name":"Done"}]}},
+    "Owner":{"id":"ow","type":"people","people":{}}
+}}"#;
+
+fn where_query(where_clause: &str, explain: bool) -> QueryArgs<'_> {
+    QueryArgs {
+        filter: None,
+        where_clause: Some(where_clause),
+        sorts: None,
+        sort: &[],
+        explain,
+    }
+}
+
+#[tokio::test]
+async fn test_query_with_where_compiles_filter() {
+    let mut server = mockito::Server::new_async().await;
+    let schema = server
+        .mock("GET", "/v1/data_sources/ds-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(QUERY_SCHEMA)
+        .create_async()
+        .await;
+    let users = server
+        .mock("GET", "/v1/users")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[{"id":"user-1","type":"person","person":{"email":"ada@example.com"}}],"has_more":false}"#)
+        .create_async()
+        .await;
+    let mock = server
+        .mock("POST", "/v1/data_sources/ds-1/query")
+        .match_body(mockito::Matcher::Json(json!({
+            "filter": { "and": [
+                { "property": "Status", "status": { "does_not_equal": "Done" } },
+                { "property": "Owner", "people": { "contains": "user-1" } }
+            ] }
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[],"has_more":false}"#)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let args = where_query("status != Done and Owner contains ada@example.com", false);
+    let result = query(
+        &client,
+        "ds-1",
+        &args,
+        &Pagination::default(),
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok(), "{:?}", result);
+    schema.assert_async().await;
+    users.assert_async().await;
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_query_explain_does_not_query() {
+    let mut server = mockito::Server::new_async().await;
+    let _schema = server
+        .mock("GET", "/v1/data_sources/ds-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(QUERY_SCHEMA)
+        .create_async()
+        .await;
+    let mock = server
+        .mock("POST", "/v1/data_sources/ds-1/query")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let result = query(
+        &client,
+        "ds-1",
+        &where_query("Status = Done", true),
+        &Pagination::default(),
+        &OutputFormat::Pretty,
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let err = query(
+        &client,
+        "ds-1",
+        &where_query("Status = Finished", true),
+        &Pagination::default(),
+        &OutputFormat::Pretty,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("Unknown status option 'Finished'"));
+    mock.assert_async().await;
+}
+
+#[test]
+fn test_sorts_from_names() {
+    let data_source: DataSource = serde_json::from_str(QUERY_SCHEMA).unwrap();
+    let specs = ["status", "-Name", "+created_time", "-last_edited_time"].map(String::from);
+    assert_eq!(
+        sorts(&specs, &data_source.properties).unwrap(),
+        json!([
+            { "property": "Status", "direction": "ascending" },
+            { "property": "Name", "direction": "descending" },
+            { "timestamp": "created_time", "direction": "ascending" },
+            { "timestamp": "last_edited_time", "direction": "descending" }
+        ])
+    );
+
+    let err = sorts(&["-Statu".to_string()], &data_source.properties).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown property 'Statu' (did you mean 'Status'?)"
+    );
+}
+
+#[tokio::test]
+async fn test_query_with_sort_and_where() {
+    let mut server = mockito::Server::new_async().await;
+    let schema = server
+        .mock("GET", "/v1/data_sources/ds-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(QUERY_SCHEMA)
+        .expect(1)
+        .create_async()
+        .await;
+    let mock = server
+        .mock("POST", "/v1/data_sources/ds-1/query")
+        .match_body(mockito::Matcher::Json(json!({
+            "filter": { "property": "Status", "status": { "equals": "Todo" } },
+            "sorts": [
+                { "property": "Owner", "direction": "descending" },
+                { "timestamp": "created_time", "direction": "ascending" }
+            ]
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[],"has_more":false}"#)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let sort = ["-Owner", "created_time"].map(String::from);
+    let args = QueryArgs {
+        sort: &sort,
+        ..where_query("Status = Todo", false)
+    };
+    let result = query(
+        &client,
+        "ds-1",
+        &args,
+        &Pagination::default(),
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok(), "{:?}", result);
+    schema.assert_async().await;
+    mock.assert_async().await;
+}
+
+const IMPORT_SCHEMA: &str = r#"{"object":"data_source","properties":{
+    "Name":{"type":"title"},
+    "Count":{"type":"number"},
+    "Owner":{"type":"people"}
+}}"#;
+
+fn write_csv(contents: &str) -> tempfile::NamedTempFile {
+    let mut file = tempfile::Builder::new().suffix(".csv").tempfile().unwrap();
+    std::io::Write::write_all(&mut file, contents.as_bytes()).unwrap();
+    file
+}
+
+#[test]
+fn test_map_columns_matches_and_overrides() {
+    let schema: Value = serde_json::from_str(IMPORT_SCHEMA).unwrap();
+    let schema = &schema["properties"];
+    let headers: Vec<String> = ["name", "Qty", "Notes"].map(String::from).to_vec();
+
+    let columns = map_columns(&headers, schema, &["Qty=Count".to_string()]).unwrap();
+    assert_eq!(
+        columns,
+        [Some("Name".to_string()), Some("Count".to_string()), None]
+    );
+
+    let mappings = ["name=".to_string(), "Qty=Count".to_string()];
+    let skipped = map_columns(&headers, schema, &mappings).unwrap();
+    assert_eq!(skipped[0], None);
+}
+
+#[test]
+fn test_map_columns_rejects_bad_mappings() {
+    let schema: Value = serde_json::from_str(IMPORT_SCHEMA).unwrap();
+    let schema = &schema["properties"];
+    let headers = vec!["Name".to_string()];
+
+    assert!(map_columns(&headers, schema, &["Name".to_string()]).is_err());
+    assert!(map_columns(&headers, schema, &["Missing=Name".to_string()]).is_err());
+    assert!(map_columns(&headers, schema, &["Name=Nope".to_string()]).is_err());
+    assert!(map_columns(&["Other".to_string()], schema, &[]).is_err());
+}
+
+#[tokio::test]
+async fn test_import_creates_pages() {
+    let mut server = mockito::Server::new_async().await;
+    let schema = server
+        .mock("GET", "/v1/data_sources/ds-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(IMPORT_SCHEMA)
+        .create_async()
+        .await;
+    let users = server
+        .mock("GET", "/v1/users")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"results":[{"id":"user-1","person":{"email":"ada@example.com"}}],"has_more":false}"#,
+        )
+        .create_async()
+        .await;
+    let pages = server
+        .mock("POST", "/v1/pages")
+        .match_body(mockito::Matcher::Json(json!({
+            "parent": { "type": "data_source_id", "data_source_id": "ds-1" },
+            "properties": {
+                "Name": { "title": [{ "type": "text", "text": { "content": "Launch" } }] },
+                "Count": { "number": 3 },
+                "Owner": { "people": [{ "id": "user-1" }] }
+            }
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"object":"page","id":"page-1"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let file = write_csv("Name,Count,Owner\nLaunch,3,ada@example.com\n");
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let result = import(&client, "ds-1", file.path(), &[], &OutputFormat::Raw).await;
+
+    assert!(result.is_ok());
+    schema.assert_async().await;
+    users.assert_async().await;
+    pages.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_import_reports_bad_rows_and_continues() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/v1/data_sources/ds-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(IMPORT_SCHEMA)
+        .create_async()
+        .await;
+    let pages = server
+        .mock("POST", "/v1/pages")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"object":"page","id":"page-1"}"#)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let file = write_csv("Name,Count\nA,1\nB,lots\nC,\n");
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let result = import(&client, "ds-1", file.path(), &[], &OutputFormat::Raw).await;
+
+    let err = result.unwrap_err().to_string();
+    assert_eq!(err, "1 of 3 rows failed to import");
+    pages.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_import_dry_run_does_not_create_pages() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/v1/data_sources/ds-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(IMPORT_SCHEMA)
+        .create_async()
+        .await;
+    let pages = server
+        .mock("POST", "/v1/pages")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let file = write_csv("Name,Count\nA,1\n");
+    let mut client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    client.set_dry_run(true);
+    let result = import(&client, "ds-1", file.path(), &[], &OutputFormat::Raw).await;
+
+    assert!(result.is_ok());
+    pages.assert_async().await;
+}
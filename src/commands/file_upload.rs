@@ -4,6 +4,7 @@ use std::path::Path;
 
 use crate::client::NotionClient;
 use crate::output::{OutputFormat, print_info, print_result, print_success};
+use crate::pagination::{ListRequest, Pagination, print_paginated};
 
 pub async fn create(
     client: &NotionClient,
@@ -66,26 +67,15 @@ pub async fn get(client: &NotionClient, upload_id: &str, format: &OutputFormat)
 pub async fn list(
     client: &NotionClient,
     status: Option<&str>,
-    page_size: Option<u32>,
-    start_cursor: Option<&str>,
+    pagination: &Pagination,
     format: &OutputFormat,
 ) -> Result<()> {
-    let mut query: Vec<(&str, String)> = Vec::new();
-
+    let mut request = ListRequest::get("/v1/file_uploads");
     if let Some(s) = status {
-        query.push(("status", s.to_string()));
-    }
-    if let Some(ps) = page_size {
-        query.push(("page_size", ps.to_string()));
-    }
-    if let Some(cursor) = start_cursor {
-        query.push(("start_cursor", cursor.to_string()));
+        request = request.query("status", s);
     }
 
-    let query_refs: Vec<(&str, &str)> = query.iter().map(|(k, v)| (*k, v.as_str())).collect();
-    let result = client.get("/v1/file_uploads", &query_refs).await?;
-    print_result(&result, format)?;
-    Ok(())
+    print_paginated(client, &request, pagination, format).await
 }
 
 pub async fn upload(
@@ -117,12 +107,9 @@ pub async fn upload(
     // Step 2: Send file
     print_info(&format!("Uploading file to session '{}'...", upload_id));
     let send_path = format!("/v1/file_uploads/{}/send", upload_id);
-    client.post_multipart(&send_path, file_path, None).await?;
-
-    // Step 3: Complete upload
-    print_info("Completing upload...");
-    let complete_path = format!("/v1/file_uploads/{}/complete", upload_id);
-    let result = client.post(&complete_path, None).await?;
+    // A single-part upload is finished once its file is sent; only
+    // multi-part uploads take a separate complete call
+    let result = client.post_multipart(&send_path, file_path, None).await?;
 
     print_success(&format!("File '{}' uploaded successfully", filename));
     print_result(&result, format)?;
@@ -242,8 +229,7 @@ mod tests {
         let result = list(
             &client,
             Some("upload_completed"),
-            None,
-            None,
+            &Pagination::default(),
             &OutputFormat::Raw,
         )
         .await;
@@ -253,7 +239,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_upload_orchestrates_create_send_complete() {
+    async fn test_upload_orchestrates_create_and_send() {
         let mut server = mockito::Server::new_async().await;
 
         let mock_create = server
@@ -283,7 +269,7 @@ mod tests {
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(r#"{"id":"fu-42","status":"upload_completed"}"#)
-            .expect(1)
+            .expect(0)
             .create_async()
             .await;
 
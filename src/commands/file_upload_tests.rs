@@ -108,8 +108,7 @@ async fn test_list() {
     let result = list(
         &client,
         Some("upload_completed"),
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1,51 +1,80 @@
 use anyhow::Result;
 use colored::Colorize;
-use dialoguer::{Confirm, Input};
+use dialoguer::{Confirm, Input, Select};
+
+use crate::client::{ClientOptions, NotionClient};
+use crate::config::{Config, keyring_account, validate_profile_name};
+use crate::credentials::{self, Credentials, TokenStore};
+use crate::output::{print_error, print_info, print_success, print_warning};
+
+/// Storage options offered by `notion init`, in menu order.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    Keyring,
+    Encrypted,
+    Command,
+    Plaintext,
+}
 
-use crate::client::NotionClient;
-use crate::config::Config;
-use crate::output::{print_error, print_info, print_success};
+impl Backend {
+    fn label(self) -> &'static str {
+        match self {
+            Backend::Keyring => "OS keyring (macOS Keychain / Secret Service)",
+            Backend::Encrypted => "Encrypted in the config file (passphrase)",
+            Backend::Command => "External command (e.g. `pass show notion`)",
+            Backend::Plaintext => "Plaintext in the config file",
+        }
+    }
+}
 
-pub async fn run() -> Result<()> {
+/// Prompt for a token and save it, either as the top-level token or into
+/// `[profiles.<name>]` when `profile` is given.
+pub async fn run(profile: Option<&str>, options: &ClientOptions) -> Result<()> {
     print_info("Notion CLI initialization");
 
+    if let Some(name) = profile {
+        validate_profile_name(name)?;
+        print_info(&format!("Configuring profile '{}'", name));
+    }
+
     let mut config = Config::load()?;
+    let account = keyring_account(profile);
+    let existing = match profile {
+        Some(name) => config.profiles.get(name).cloned().unwrap_or_default(),
+        None => config.credentials.clone(),
+    };
 
-    if let Some(ref token) = config.api_token
-        && !token.is_empty()
-    {
-        print_info(&format!(
-            "Existing token found ({}...)",
-            &token[..token.len().min(8)]
-        ));
+    let mut kept = None;
+    if let Some(description) = existing.describe() {
+        print_info(&format!("Existing token found ({})", description));
 
         let keep = Confirm::new()
             .with_prompt("Keep existing token?")
             .default(true)
             .interact()?;
 
-        if !keep {
-            config.api_token = None;
+        if keep {
+            kept = existing.resolve(&account)?.map(|token| (existing, token));
         }
     }
 
-    if config.api_token.is_none() {
-        print_info("You can create an integration at https://www.notion.so/my-integrations");
-        let token: String = Input::new()
-            .with_prompt("Enter your Notion API token")
-            .interact_text()?;
-
-        config.api_token = Some(token);
-    }
+    let (credentials, token) = match kept {
+        Some(kept) => kept,
+        None => prompt_credentials()?,
+    };
 
     // Test the connection
     print_info("Testing connection...");
-    let token = config.get_token()?;
-    let client = NotionClient::new(token)?;
+    let client = NotionClient::with_options(&token, options)?;
 
     verify_connection(&client).await?;
 
+    if credentials.token_store == Some(TokenStore::Keyring) {
+        credentials::keyring_set(&account, &token)?;
+    }
+
     // Save config
+    config.set_credentials(profile, credentials);
     config.save()?;
     let config_path = Config::config_path()?;
     print_success(&format!("Config saved to {}", config_path.display()));
@@ -53,6 +82,82 @@ pub async fn run() -> Result<()> {
     Ok(())
 }
 
+/// Ask where to store the token, then for the token (or the command printing it).
+fn prompt_credentials() -> Result<(Credentials, String)> {
+    let backend = select_backend(true)?;
+    if backend == Backend::Command {
+        let command: String = Input::new()
+            .with_prompt("Command that prints the token")
+            .interact_text()?;
+        let token = credentials::run_token_command(&command)?;
+        let credentials = Credentials {
+            token_command: Some(command),
+            ..Default::default()
+        };
+        return Ok((credentials, token));
+    }
+
+    print_info("You can create an integration at https://www.notion.so/my-integrations");
+    let token: String = Input::new()
+        .with_prompt("Enter your Notion API token")
+        .interact_text()?;
+
+    let credentials = protect(backend, &token)?;
+    Ok((credentials, token))
+}
+
+/// Ask where to keep a token obtained elsewhere (e.g. an OAuth exchange).
+///
+/// Keyring entries are written here; the caller saves the returned credentials.
+pub fn store_token(account: &str, token: &str) -> Result<Credentials> {
+    let backend = select_backend(false)?;
+    let credentials = protect(backend, token)?;
+    if backend == Backend::Keyring {
+        credentials::keyring_set(account, token)?;
+    }
+    Ok(credentials)
+}
+
+fn select_backend(allow_command: bool) -> Result<Backend> {
+    let keyring = credentials::keyring_available();
+    let backends: Vec<Backend> = [
+        Backend::Keyring,
+        Backend::Encrypted,
+        Backend::Command,
+        Backend::Plaintext,
+    ]
+    .into_iter()
+    .filter(|b| allow_command || *b != Backend::Command)
+    .collect();
+    let labels: Vec<&str> = backends.iter().map(|b| b.label()).collect();
+    let default = if keyring { 0 } else { 1 };
+    let choice = Select::new()
+        .with_prompt("Where should the token be stored?")
+        .items(&labels)
+        .default(default)
+        .interact()?;
+    let backend = backends[choice];
+    if backend == Backend::Keyring && !keyring {
+        print_warning("No keyring tool found (`security` or `secret-tool`); storing may fail");
+    }
+    Ok(backend)
+}
+
+/// Credentials for `token` in the chosen backend (keyring entries are not written yet).
+fn protect(backend: Backend, token: &str) -> Result<Credentials> {
+    let mut credentials = Credentials::default();
+    match backend {
+        Backend::Keyring => credentials.token_store = Some(TokenStore::Keyring),
+        Backend::Encrypted => {
+            let passphrase = credentials::passphrase(true)?;
+            credentials.encrypted_token = Some(credentials::encrypt(token, &passphrase)?);
+            credentials.token_store = Some(TokenStore::Encrypted);
+        }
+        Backend::Command | Backend::Plaintext => credentials.api_token = Some(token.to_string()),
+    }
+    Ok(credentials)
+}
+
 pub async fn verify_connection(client: &NotionClient) -> Result<()> {
     match client.get("/v1/users/me", &[]).await {
         Ok(user) => {
@@ -0,0 +1,268 @@
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::process::Command;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::client::{ClientOptions, NotionClient};
+use crate::commands::init::{store_token, verify_connection};
+use crate::config::{Config, keyring_account, validate_profile_name};
+use crate::credentials::{self, OAuthGrant, TokenStore};
+use crate::error::bail_input;
+use crate::output::{OutputFormat, print_info, print_result, print_success, print_warning};
+
+/// How long to wait for the browser to come back with an authorization code
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+
+const CALLBACK_PATH: &str = "/callback";
+
+/// Authorize a public integration in the browser and store its access token.
+pub async fn oauth(
+    profile: Option<&str>,
+    options: &ClientOptions,
+    client_id: &str,
+    client_secret: &str,
+    port: u16,
+    open_browser: bool,
+) -> Result<()> {
+    if let Some(name) = profile {
+        validate_profile_name(name)?;
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .with_context(|| format!("Failed to listen on localhost:{}", port))?;
+    let redirect_uri = format!("http://localhost:{}{}", port, CALLBACK_PATH);
+    let oauth = NotionClient::oauth_with_options(client_id, client_secret, options)?;
+    let state = random_state()?;
+    let url = oauth.oauth_authorize_url(client_id, &redirect_uri, &state);
+
+    print_info(&format!(
+        "Make sure {} is a redirect URI of your integration",
+        redirect_uri
+    ));
+    print_info(&format!("Authorize the integration at:\n  {}", url));
+    if open_browser && let Err(e) = open_url(&url) {
+        print_warning(&format!("Could not open a browser: {:#}", e));
+    }
+
+    let code = tokio::time::timeout(CALLBACK_TIMEOUT, wait_for_code(&listener, &state))
+        .await
+        .context("Timed out waiting for the OAuth redirect")??;
+    let (token, grant) = exchange(&oauth, client_id, &code, &redirect_uri).await?;
+
+    print_info("Testing connection...");
+    verify_connection(&NotionClient::with_options(&token, options)?).await?;
+
+    let mut config = Config::load()?;
+    let mut credentials = store_token(&keyring_account(profile), &token)?;
+    credentials.oauth = Some(grant);
+    config.set_credentials(profile, credentials);
+    config.save()?;
+    print_success(&format!(
+        "Config saved to {}",
+        Config::config_path()?.display()
+    ));
+    Ok(())
+}
+
+/// Show whether the stored OAuth token is still active.
+pub async fn status(
+    profile: Option<&str>,
+    options: &ClientOptions,
+    client_secret: &str,
+    format: &OutputFormat,
+) -> Result<()> {
+    let config = Config::load()?;
+    let (name, grant, token) = stored_grant(&config, profile)?;
+    let oauth = NotionClient::oauth_with_options(&grant.client_id, client_secret, options)?;
+    let mut result = oauth.oauth_introspect(&token).await?;
+    if let Value::Object(map) = &mut result {
+        map.insert("profile".into(), name.into());
+        map.insert("workspace_name".into(), grant.workspace_name.into());
+        map.insert("workspace_id".into(), grant.workspace_id.into());
+    }
+    print_result(&result, format)
+}
+
+/// Revoke the stored OAuth token and remove it from the config.
+pub async fn logout(
+    profile: Option<&str>,
+    options: &ClientOptions,
+    client_secret: &str,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let (name, grant, token) = stored_grant(&config, profile)?;
+    let name = name.map(str::to_string);
+    let keyring =
+        config.credentials_for(name.as_deref())?.1.token_store == Some(TokenStore::Keyring);
+
+    let oauth = NotionClient::oauth_with_options(&grant.client_id, client_secret, options)?;
+    oauth.oauth_revoke(&token).await?;
+
+    config.clear_credentials(name.as_deref());
+    config.save()?;
+    if keyring && let Err(e) = credentials::keyring_delete(&keyring_account(name.as_deref())) {
+        print_warning(&format!("{:#}", e));
+    }
+    match name {
+        Some(name) => print_success(&format!("Revoked and removed profile '{}'", name)),
+        None => print_success("Revoked and removed the OAuth token"),
+    }
+    Ok(())
+}
+
+/// OAuth grant and token of the selected profile (or the top-level token).
+fn stored_grant<'a>(
+    config: &'a Config,
+    profile: Option<&'a str>,
+) -> Result<(Option<&'a str>, OAuthGrant, String)> {
+    let (name, credentials) = config.credentials_for(profile)?;
+    let Some(grant) = credentials.oauth.clone() else {
+        bail_input!("The selected token was not created by `notion login --oauth`");
+    };
+    let token = credentials
+        .resolve(&keyring_account(name))?
+        .context("The OAuth access token is missing from the config")?;
+    Ok((name, grant, token))
+}
+
+/// Exchange the authorization code; returns the access token and its workspace.
+pub async fn exchange(
+    oauth: &NotionClient,
+    client_id: &str,
+    code: &str,
+    redirect_uri: &str,
+) -> Result<(String, OAuthGrant)> {
+    let response = oauth.oauth_token(code, redirect_uri).await?;
+    let token = response["access_token"]
+        .as_str()
+        .context("OAuth token response has no access_token")?
+        .to_string();
+    let text = |key: &str| response[key].as_str().map(str::to_string);
+    let grant = OAuthGrant {
+        client_id: client_id.to_string(),
+        workspace_id: text("workspace_id"),
+        workspace_name: text("workspace_name"),
+        bot_id: text("bot_id"),
+    };
+    Ok((token, grant))
+}
+
+/// Serve the redirect URI until the browser delivers the authorization code.
+pub async fn wait_for_code(listener: &TcpListener, state: &str) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .context("Failed to accept the OAuth redirect")?;
+
+        let mut buf = vec![0u8; 8192];
+        let mut len = 0;
+        while len < buf.len() && !buf[..len].windows(4).any(|w| w == b"\r\n\r\n") {
+            // A stray connection that errors shouldn't abort the login
+            match stream.read(&mut buf[len..]).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => len += n,
+            }
+        }
+        let request = String::from_utf8_lossy(&buf[..len]);
+        let target = request
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .unwrap_or("/");
+
+        let (status, message, outcome) = match parse_callback(target, state) {
+            Ok(None) => ("404 Not Found", "Not found".to_string(), None),
+            Ok(Some(code)) => (
+                "200 OK",
+                "Notion CLI is authorized. You can close this window.".to_string(),
+                Some(Ok(code)),
+            ),
+            Err(e) => (
+                "400 Bad Request",
+                format!("Authorization failed: {}", e),
+                Some(Err(e)),
+            ),
+        };
+        let body = format!(
+            "<!doctype html><title>Notion CLI</title><p>{}</p>",
+            html_escape(&message)
+        );
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        // The browser may already be gone; the code is what matters
+        let _ = stream.write_all(response.as_bytes()).await;
+        let _ = stream.shutdown().await;
+
+        if let Some(outcome) = outcome {
+            return outcome;
+        }
+    }
+}
+
+/// Authorization code from a redirect request target.
+///
+/// Returns `None` for requests to other paths (e.g. `/favicon.ico`).
+pub fn parse_callback(target: &str, state: &str) -> Result<Option<String>> {
+    let url = reqwest::Url::parse(&format!("http://localhost{}", target))
+        .context("Malformed redirect request")?;
+    if url.path() != CALLBACK_PATH {
+        return Ok(None);
+    }
+    let param = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+    };
+    if let Some(error) = param("error") {
+        bail!("Authorization was denied ({})", error);
+    }
+    if param("state").as_deref() != Some(state) {
+        bail!("OAuth state mismatch; restart `notion login --oauth`");
+    }
+    param("code")
+        .filter(|c| !c.is_empty())
+        .map(Some)
+        .context("Redirect has no authorization code")
+}
+
+fn random_state() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).context("Failed to generate the OAuth state")?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn open_url(url: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        // `cmd /C start` would split the URL at `&`
+        let mut cmd = Command::new("rundll32");
+        cmd.arg("url.dll,FileProtocolHandler");
+        cmd
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .spawn()
+        .context("Failed to launch the browser")?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "login_tests.rs"]
+mod tests;
@@ -0,0 +1,197 @@
+use super::*;
+use crate::credentials::Credentials;
+use tokio::net::TcpStream;
+
+const STATE: &str = "0123456789abcdef";
+
+#[test]
+fn test_parse_callback_returns_code() {
+    let code = parse_callback("/callback?code=abc-123&state=0123456789abcdef", STATE).unwrap();
+    assert_eq!(code.as_deref(), Some("abc-123"));
+}
+
+#[test]
+fn test_parse_callback_ignores_other_paths() {
+    assert!(parse_callback("/favicon.ico", STATE).unwrap().is_none());
+}
+
+#[test]
+fn test_parse_callback_rejects_state_mismatch() {
+    let err = parse_callback("/callback?code=abc&state=other", STATE)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("state mismatch"));
+}
+
+#[test]
+fn test_parse_callback_reports_denied_access() {
+    let err = parse_callback(
+        "/callback?error=access_denied&state=0123456789abcdef",
+        STATE,
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("access_denied"));
+}
+
+#[test]
+fn test_parse_callback_requires_code() {
+    let err = parse_callback("/callback?state=0123456789abcdef", STATE)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("no authorization code"));
+}
+
+#[test]
+fn test_random_state_is_hex_and_unique() {
+    let a = random_state().unwrap();
+    assert_eq!(a.len(), 32);
+    assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_ne!(a, random_state().unwrap());
+}
+
+#[test]
+fn test_html_escape() {
+    assert_eq!(html_escape("<b>&</b>"), "&lt;b&gt;&amp;&lt;/b&gt;");
+}
+
+async fn send(port: u16, target: &str) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target);
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn test_wait_for_code_serves_redirect() {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let browser = tokio::spawn(async move {
+        let favicon = send(port, "/favicon.ico").await;
+        let callback = send(port, "/callback?code=the-code&state=0123456789abcdef").await;
+        (favicon, callback)
+    });
+
+    let code = wait_for_code(&listener, STATE).await.unwrap();
+    assert_eq!(code, "the-code");
+
+    let (favicon, callback) = browser.await.unwrap();
+    assert!(favicon.starts_with("HTTP/1.1 404"));
+    assert!(callback.starts_with("HTTP/1.1 200"));
+    assert!(callback.contains("You can close this window"));
+}
+
+#[tokio::test]
+async fn test_wait_for_code_fails_on_denied_access() {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let browser = tokio::spawn(async move {
+        send(port, "/callback?error=access_denied&state=0123456789abcdef").await
+    });
+
+    let err = wait_for_code(&listener, STATE).await.unwrap_err();
+    assert!(err.to_string().contains("access_denied"));
+    assert!(browser.await.unwrap().starts_with("HTTP/1.1 400"));
+}
+
+#[tokio::test]
+async fn test_exchange_returns_token_and_workspace() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/oauth/token")
+        .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+            "grant_type": "authorization_code",
+            "code": "the-code",
+            "redirect_uri": "http://localhost:8765/callback"
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "access_token": "ntn_oauth_token",
+                "token_type": "bearer",
+                "bot_id": "bot-1",
+                "workspace_id": "ws-1",
+                "workspace_name": "Acme"
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    let oauth = NotionClient::oauth_with_base_url("client-1", "secret", &server.url()).unwrap();
+    let (token, grant) = exchange(
+        &oauth,
+        "client-1",
+        "the-code",
+        "http://localhost:8765/callback",
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(token, "ntn_oauth_token");
+    assert_eq!(grant.client_id, "client-1");
+    assert_eq!(grant.workspace_name.as_deref(), Some("Acme"));
+    assert_eq!(grant.workspace_id.as_deref(), Some("ws-1"));
+    assert_eq!(grant.bot_id.as_deref(), Some("bot-1"));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_exchange_reports_invalid_grant() {
+    let mut server = mockito::Server::new_async().await;
+    let _mock = server
+        .mock("POST", "/v1/oauth/token")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"error":"invalid_grant","message":"Invalid code."}"#)
+        .create_async()
+        .await;
+
+    let oauth = NotionClient::oauth_with_base_url("client-1", "secret", &server.url()).unwrap();
+    let err = exchange(&oauth, "client-1", "bad", "http://localhost:8765/callback")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid code."));
+}
+
+#[test]
+fn test_stored_grant_requires_oauth_token() {
+    let config = Config {
+        credentials: Credentials {
+            api_token: Some("ntn_internal".to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let err = stored_grant(&config, None).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("not created by `notion login --oauth`")
+    );
+    assert_eq!(crate::error::kind(&err), crate::error::ErrorKind::Input);
+}
+
+#[test]
+fn test_stored_grant_uses_selected_profile() {
+    let mut config = Config::default();
+    config.set_credentials(
+        Some("client"),
+        Credentials {
+            api_token: Some("ntn_oauth".to_string()),
+            oauth: Some(OAuthGrant {
+                client_id: "client-1".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    );
+
+    let (name, grant, token) = stored_grant(&config, Some("client")).unwrap();
+    assert_eq!(name, Some("client"));
+    assert_eq!(grant.client_id, "client-1");
+    assert_eq!(token, "ntn_oauth");
+}
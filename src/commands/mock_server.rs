@@ -0,0 +1,32 @@
+use anyhow::{Context, Result};
+use serde_json::json;
+
+use crate::mock_server::MockServer;
+use crate::output::{OutputFormat, print_info, print_result};
+
+/// Serve the in-memory mock API on localhost until interrupted.
+pub async fn run(port: u16, format: &OutputFormat) -> Result<()> {
+    let server = MockServer::start(("127.0.0.1", port)).await?;
+    let seed = server.seed();
+    print_result(
+        &json!({
+            "url": server.url(),
+            "bot_user_id": seed.bot_id,
+            "person_user_id": seed.person_id,
+            "page_id": seed.page_id,
+        }),
+        format,
+    )?;
+    print_info(&format!(
+        "Mock server listening on {} (any token works); press Ctrl-C to stop",
+        server.url()
+    ));
+    print_info(&format!(
+        "Try: NOTION_API_TOKEN=mock notion --base-url {} page get {}",
+        server.url(),
+        seed.page_id
+    ));
+    tokio::signal::ctrl_c()
+        .await
+        .context("Failed to wait for Ctrl-C")
+}
@@ -1,9 +1,13 @@
+pub mod api;
 pub mod block;
 pub mod comment;
 pub mod database;
 pub mod datasource;
 pub mod file_upload;
 pub mod init;
+pub mod login;
+pub mod mock_server;
 pub mod page;
+pub mod profile;
 pub mod search;
 pub mod user;
@@ -1,8 +1,20 @@
 use anyhow::{Context, Result};
+use indexmap::IndexMap;
 use serde_json::{Value, json};
+use std::collections::HashMap;
 
 use crate::client::NotionClient;
-use crate::output::{OutputFormat, print_result};
+use crate::commands::{block, user};
+use crate::error::{NotionError, bail_input};
+use crate::ids::parse_id;
+use crate::input::{parse_json, read_source};
+use crate::markdown;
+use crate::models::{
+    Empty, Page, Parent, PropertyConfig, PropertyContent, PropertySchema, RelationConfig,
+};
+use crate::output::{OutputFormat, print_document, print_result};
+use crate::pagination::{ListRequest, Pagination, print_paginated};
+use crate::properties::{self, AssignOp, Assignment};
 
 pub async fn get(
     client: &NotionClient,
@@ -20,16 +32,30 @@ pub async fn get(
     Ok(())
 }
 
+/// Page properties from `--properties` JSON and `--set` arguments.
+pub struct PropertyArgs<'a> {
+    pub json: Option<&'a str>,
+    pub sets: &'a [String],
+}
+
 pub async fn create(
     client: &NotionClient,
     parent_id: &str,
-    properties_json: &str,
+    properties: &PropertyArgs<'_>,
     children_json: Option<&str>,
+    markdown_source: Option<&str>,
     is_database_parent: bool,
     format: &OutputFormat,
 ) -> Result<()> {
-    let properties: Value =
-        serde_json::from_str(properties_json).context("Invalid JSON for properties")?;
+    let mut props = base_properties(properties)?;
+    if !properties.sets.is_empty() {
+        let parent = if is_database_parent {
+            Parent::DatabaseId(parent_id.to_string())
+        } else {
+            Parent::PageId(parent_id.to_string())
+        };
+        apply_sets(client, &mut props, properties.sets, &parent, None).await?;
+    }
 
     let parent = if is_database_parent {
         json!({ "database_id": parent_id })
@@ -39,30 +65,64 @@ pub async fn create(
 
     let mut body = json!({
         "parent": parent,
-        "properties": properties,
+        "properties": props,
     });
 
     if let Some(cj) = children_json {
-        let children: Value = serde_json::from_str(cj).context("Invalid JSON for children")?;
+        let children = parse_json(cj, "children")?;
         body["children"] = children;
     }
 
-    let result = client.post("/v1/pages", Some(&body)).await?;
+    // Read the content before creating anything so a bad path fails early
+    let blocks = match markdown_source {
+        Some(source) => markdown::to_blocks(&read_source(source)?),
+        None => Vec::new(),
+    };
+
+    let result = create_page(client, &body).await?;
+    if !blocks.is_empty() {
+        let page_id = result["id"].as_str().unwrap_or("<new-page>");
+        // The page exists by now; say so, or a retry would create a duplicate
+        block::append_tree(client, page_id, blocks, None)
+            .await
+            .with_context(|| match result["url"].as_str() {
+                Some(url) => format!(
+                    "Created page {} ({}) but failed to add its content",
+                    page_id, url
+                ),
+                None => format!("Created page {} but failed to add its content", page_id),
+            })?;
+    }
     print_result(&result, format)?;
     Ok(())
 }
 
+/// Create a page from a complete request body.
+pub async fn create_page(client: &NotionClient, body: &Value) -> Result<Value> {
+    client.post("/v1/pages", Some(body)).await
+}
+
 pub async fn update(
     client: &NotionClient,
     page_id: &str,
-    properties_json: &str,
+    properties: &PropertyArgs<'_>,
     archived: Option<bool>,
     format: &OutputFormat,
 ) -> Result<()> {
-    let properties: Value =
-        serde_json::from_str(properties_json).context("Invalid JSON for properties")?;
+    let mut props = base_properties(properties)?;
+    if !properties.sets.is_empty() {
+        let page = client.retrieve_page(page_id).await?;
+        apply_sets(
+            client,
+            &mut props,
+            properties.sets,
+            &page.parent,
+            Some(&page),
+        )
+        .await?;
+    }
 
-    let mut body = json!({ "properties": properties });
+    let mut body = json!({ "properties": props });
 
     if let Some(a) = archived {
         body["archived"] = json!(a);
@@ -74,6 +134,170 @@ pub async fn update(
     Ok(())
 }
 
+fn base_properties(properties: &PropertyArgs<'_>) -> Result<Value> {
+    match properties.json {
+        Some(pj) => parse_json(pj, "properties"),
+        None => Ok(json!({})),
+    }
+}
+
+/// Apply `--set` arguments on top of `properties`.
+///
+/// Values are checked against the schema of the page's parent before
+/// anything is sent: pages in a data source get its properties, other pages
+/// only have a `title`. People may be given by email and relations by page
+/// title, which costs a lookup.
+async fn apply_sets(
+    client: &NotionClient,
+    properties: &mut Value,
+    sets: &[String],
+    parent: &Parent,
+    page: Option<&Page>,
+) -> Result<()> {
+    let Some(object) = properties.as_object_mut() else {
+        bail_input!("Properties must be a JSON object to combine with --set");
+    };
+
+    let assignments = sets
+        .iter()
+        .map(|s| Assignment::parse(s))
+        .collect::<Result<Vec<_>>>()?;
+    let schema = parent_schema(client, parent).await?;
+    let mut resolved = Vec::with_capacity(assignments.len());
+    for assignment in assignments {
+        let (name, property) = properties::find_property(&schema, &assignment.property)?;
+        resolved.push((name, property, assignment));
+    }
+
+    let wants_emails = resolved.iter().any(|(_, property, assignment)| {
+        matches!(property.config, PropertyConfig::People(_)) && assignment.value.contains('@')
+    });
+    let people = if wants_emails {
+        user::emails(client).await?
+    } else {
+        HashMap::new()
+    };
+
+    for (name, property, mut assignment) in resolved {
+        if let PropertyConfig::Relation(relation) = &property.config {
+            assignment.value = relation_ids(client, relation, &assignment.value).await?;
+        }
+        // Later --set arguments for the same property build on earlier ones
+        let mut current = object
+            .get(name.as_str())
+            .and_then(|v| serde_json::from_value::<PropertyContent>(v.clone()).ok());
+        if current.is_none()
+            && let Some(page) = page
+        {
+            let listed = matches!(
+                property.config,
+                PropertyConfig::People(_) | PropertyConfig::Relation(_)
+            );
+            current = match &property.id {
+                Some(id) if listed && assignment.op != AssignOp::Set => {
+                    Some(full_list(client, &page.id, id, property.config.kind()).await?)
+                }
+                _ => page.property(name).cloned(),
+            };
+        }
+        let value = properties::assign(property, &assignment, current.as_ref(), &people)
+            .map_err(|e| NotionError::Input(format!("{}: {:#}", name, e)))?;
+        object.insert(name.clone(), value);
+    }
+    Ok(())
+}
+
+/// The whole value of a people or relation property. Page responses stop
+/// at 25 entries; the property endpoint pages through all of them.
+async fn full_list(
+    client: &NotionClient,
+    page_id: &str,
+    property_id: &str,
+    kind: &str,
+) -> Result<PropertyContent> {
+    let request = ListRequest::get(format!("/v1/pages/{}/properties/{}", page_id, property_id));
+    let items: Vec<Value> = client.list_all(&request).await?;
+    let values: Vec<Value> = items
+        .into_iter()
+        .map(|mut item| item[kind].take())
+        .collect();
+    serde_json::from_value(json!({ kind: values }))
+        .with_context(|| format!("Unexpected {} property items in response", kind))
+}
+
+/// Property schema for pages under `parent`.
+async fn parent_schema(
+    client: &NotionClient,
+    parent: &Parent,
+) -> Result<IndexMap<String, PropertySchema>> {
+    let ds_id = match parent {
+        Parent::DataSourceId(id) => id.clone(),
+        Parent::DatabaseId(id) => {
+            let database = client.retrieve_database(id).await?;
+            match database.data_sources.as_slice() {
+                [data_source] => data_source.id.clone(),
+                sources => bail_input!(
+                    "Database {} has {} data sources; use --properties to set properties",
+                    id,
+                    sources.len()
+                ),
+            }
+        }
+        _ => {
+            let title = PropertySchema {
+                id: Some("title".to_string()),
+                name: "title".to_string(),
+                description: None,
+                config: PropertyConfig::Title(Empty),
+            };
+            return Ok(IndexMap::from([("title".to_string(), title)]));
+        }
+    };
+    Ok(client.retrieve_data_source(&ds_id).await?.properties)
+}
+
+/// Comma-separated page IDs for relation values given as IDs, URLs or
+/// titles of pages in the related data source.
+async fn relation_ids(
+    client: &NotionClient,
+    relation: &RelationConfig,
+    value: &str,
+) -> Result<String> {
+    let mut ids = Vec::new();
+    let mut title_property = None;
+    for item in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        if let Ok(id) = parse_id(item) {
+            ids.push(id);
+            continue;
+        }
+        let Some(ds_id) = &relation.data_source_id else {
+            bail_input!("'{}' is not a page ID", item);
+        };
+        if title_property.is_none() {
+            let related = client.retrieve_data_source(ds_id).await?;
+            title_property = related
+                .properties
+                .into_iter()
+                .find(|(_, p)| matches!(p.config, PropertyConfig::Title(_)))
+                .map(|(name, _)| name);
+        }
+        let Some(title) = &title_property else {
+            bail_input!("Related data source {} has no title property", ds_id);
+        };
+        let query = json!({
+            "filter": { "property": title, "title": { "equals": item } },
+            "page_size": 2,
+        });
+        let pages = client.lookup_data_source(ds_id, &query).await?;
+        match pages.results.as_slice() {
+            [page] => ids.push(page.id.clone()),
+            [] => bail_input!("No page titled '{}' in the related data source", item),
+            _ => bail_input!("Several pages are titled '{}'; use the page ID", item),
+        }
+    }
+    Ok(ids.join(","))
+}
+
 pub async fn move_page(
     client: &NotionClient,
     page_id: &str,
@@ -81,11 +305,13 @@ pub async fn move_page(
     parent_id: &str,
     format: &OutputFormat,
 ) -> Result<()> {
+    // Parsed here rather than by clap: `--to` is ignored for the workspace
+    let id = || parse_id(parent_id).map_err(NotionError::Input);
     let parent = match parent_type {
-        "page" => json!({ "type": "page_id", "page_id": parent_id }),
-        "database" => json!({ "type": "database_id", "database_id": parent_id }),
+        "page" => json!({ "type": "page_id", "page_id": id()? }),
+        "database" => json!({ "type": "database_id", "database_id": id()? }),
         "workspace" => json!({ "type": "workspace" }),
-        _ => anyhow::bail!(
+        _ => bail_input!(
             "Invalid parent type: {}. Use 'page', 'database', or 'workspace'",
             parent_type
         ),
@@ -102,23 +328,38 @@ pub async fn property(
     client: &NotionClient,
     page_id: &str,
     property_id: &str,
-    page_size: Option<u32>,
-    start_cursor: Option<&str>,
+    pagination: &Pagination,
     format: &OutputFormat,
 ) -> Result<()> {
-    let path = format!("/v1/pages/{}/properties/{}", page_id, property_id);
-    let mut query: Vec<(&str, &str)> = Vec::new();
-    let ps_str;
-    if let Some(ps) = page_size {
-        ps_str = ps.to_string();
-        query.push(("page_size", &ps_str));
-    }
-    if let Some(cursor) = start_cursor {
-        query.push(("start_cursor", cursor));
+    let request = ListRequest::get(format!("/v1/pages/{}/properties/{}", page_id, property_id));
+    print_paginated(client, &request, pagination, format).await
+}
+
+/// Export a page and its whole block tree as a document.
+///
+/// The pretty format prints the document itself; the other formats wrap it
+/// in a JSON object for scripting.
+pub async fn export(
+    client: &NotionClient,
+    page_id: &str,
+    export_format: &str,
+    format: &OutputFormat,
+) -> Result<()> {
+    if export_format != "markdown" {
+        bail_input!("Invalid export format: {}. Use 'markdown'", export_format);
     }
 
-    let result = client.get(&path, &query).await?;
-    print_result(&result, format)?;
+    let page = client.get(&format!("/v1/pages/{}", page_id), &[]).await?;
+    let blocks = block::descendants(client, page_id).await?;
+    let document = markdown::page_to_markdown(&page, &blocks);
+
+    match format {
+        OutputFormat::Pretty => print_document(&document),
+        _ => print_result(
+            &json!({ "id": page["id"], "format": export_format, "content": document }),
+            format,
+        )?,
+    }
     Ok(())
 }
 
@@ -1,6 +1,13 @@
 use super::*;
 use crate::output::OutputFormat;
 
+fn json_props(json: &str) -> PropertyArgs<'_> {
+    PropertyArgs {
+        json: Some(json),
+        sets: &[],
+    }
+}
+
 #[tokio::test]
 async fn test_get() {
     let mut server = mockito::Server::new_async().await;
@@ -58,7 +65,16 @@ async fn test_create_with_page_parent() {
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
     let props = r#"{"title":[{"text":{"content":"Test"}}]}"#;
-    let result = create(&client, "parent-1", props, None, false, &OutputFormat::Raw).await;
+    let result = create(
+        &client,
+        "parent-1",
+        &json_props(props),
+        None,
+        None,
+        false,
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -81,7 +97,16 @@ async fn test_create_with_database_parent() {
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
     let props = r#"{"Name":{"title":[{"text":{"content":"Row"}}]}}"#;
-    let result = create(&client, "db-1", props, None, true, &OutputFormat::Raw).await;
+    let result = create(
+        &client,
+        "db-1",
+        &json_props(props),
+        None,
+        None,
+        true,
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -109,8 +134,9 @@ async fn test_create_with_children() {
     let result = create(
         &client,
         "parent-1",
-        props,
+        &json_props(props),
         Some(children),
+        None,
         false,
         &OutputFormat::Raw,
     )
@@ -136,7 +162,14 @@ async fn test_update_with_archived() {
         .await;
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
-    let result = update(&client, "page-1", "{}", Some(true), &OutputFormat::Raw).await;
+    let result = update(
+        &client,
+        "page-1",
+        &json_props("{}"),
+        Some(true),
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -148,7 +181,7 @@ async fn test_move_page_parent() {
     let mock = server
         .mock("POST", "/v1/pages/page-1/move")
         .match_body(mockito::Matcher::Json(json!({
-            "parent": { "type": "page_id", "page_id": "target-1" }
+            "parent": { "type": "page_id", "page_id": "2d3c4b5a-6978-4a1b-8c2d-3e4f5a6b7c8d" }
         })))
         .with_status(200)
         .with_header("content-type", "application/json")
@@ -157,7 +190,14 @@ async fn test_move_page_parent() {
         .await;
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
-    let result = move_page(&client, "page-1", "page", "target-1", &OutputFormat::Raw).await;
+    let result = move_page(
+        &client,
+        "page-1",
+        "page",
+        "2d3c4b5a69784a1b8c2d3e4f5a6b7c8d",
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -194,7 +234,14 @@ async fn test_update_without_archived() {
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
     let props = r#"{"Name":{"title":[{"text":{"content":"Updated"}}]}}"#;
-    let result = update(&client, "page-1", props, None, &OutputFormat::Raw).await;
+    let result = update(
+        &client,
+        "page-1",
+        &json_props(props),
+        None,
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -207,7 +254,8 @@ async fn test_create_with_invalid_json() {
     let result = create(
         &client,
         "parent-1",
-        "not valid json",
+        &json_props("not valid json"),
+        None,
         None,
         false,
         &OutputFormat::Raw,
@@ -238,8 +286,11 @@ async fn test_property_with_pagination() {
         &client,
         "page-1",
         "prop-1",
-        Some(25),
-        Some("cursor-xyz"),
+        &Pagination {
+            page_size: Some(25),
+            start_cursor: Some("cursor-xyz".into()),
+            ..Default::default()
+        },
         &OutputFormat::Raw,
     )
     .await;
@@ -254,7 +305,7 @@ async fn test_move_to_database_parent() {
     let mock = server
         .mock("POST", "/v1/pages/page-1/move")
         .match_body(mockito::Matcher::Json(json!({
-            "parent": { "type": "database_id", "database_id": "db-1" }
+            "parent": { "type": "database_id", "database_id": "2d3c4b5a-6978-4a1b-8c2d-3e4f5a6b7c8d" }
         })))
         .with_status(200)
         .with_header("content-type", "application/json")
@@ -263,7 +314,14 @@ async fn test_move_to_database_parent() {
         .await;
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
-    let result = move_page(&client, "page-1", "database", "db-1", &OutputFormat::Raw).await;
+    let result = move_page(
+        &client,
+        "page-1",
+        "database",
+        "https://www.notion.so/2d3c4b5a69784a1b8c2d3e4f5a6b7c8d",
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -284,8 +342,573 @@ async fn test_move_to_workspace() {
         .await;
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
-    let result = move_page(&client, "page-1", "workspace", "unused", &OutputFormat::Raw).await;
+    let result = move_page(&client, "page-1", "workspace", "_", &OutputFormat::Raw).await;
+
+    assert!(result.is_ok());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_export_markdown() {
+    let mut server = mockito::Server::new_async().await;
+    let page = server
+        .mock("GET", "/v1/pages/page-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"object":"page","id":"page-1","properties":{"Name":{"type":"title","title":[{"plain_text":"Spec"}]}}}"#)
+        .create_async()
+        .await;
+    let blocks = server
+        .mock("GET", "/v1/blocks/page-1/children")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[{"type":"paragraph","paragraph":{"rich_text":[{"plain_text":"Hi"}]}}],"has_more":false}"#)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let result = export(&client, "page-1", "markdown", &OutputFormat::Raw).await;
+
+    assert!(result.is_ok());
+    page.assert_async().await;
+    blocks.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_export_rejects_unknown_format() {
+    let client = NotionClient::with_base_url("token", "http://127.0.0.1:1").unwrap();
+    let result = export(&client, "page-1", "html", &OutputFormat::Raw).await;
+
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid export format")
+    );
+}
+
+#[tokio::test]
+async fn test_create_with_markdown_reports_page_when_append_fails() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/v1/pages")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"object":"page","id":"page-9","url":"https://www.notion.so/page-9"}"#)
+        .create_async()
+        .await;
+    server
+        .mock("PATCH", "/v1/blocks/page-9/children")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"object":"error","status":400,"code":"validation_error","message":"Bad block"}"#,
+        )
+        .create_async()
+        .await;
+
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut file, b"# Notes\n").unwrap();
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let err = create(
+        &client,
+        "parent-1",
+        &json_props("{}"),
+        None,
+        file.path().to_str(),
+        false,
+        &OutputFormat::Raw,
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "Created page page-9 (https://www.notion.so/page-9) but failed to add its content"
+    );
+    assert!(crate::error::find(&err).is_some());
+}
+
+#[tokio::test]
+async fn test_create_with_markdown_appends_after_creating_page() {
+    let mut server = mockito::Server::new_async().await;
+    let page = server
+        .mock("POST", "/v1/pages")
+        .match_body(mockito::Matcher::Json(json!({
+            "parent": { "page_id": "parent-1" },
+            "properties": {}
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"object":"page","id":"page-9"}"#)
+        .create_async()
+        .await;
+    let content = server
+        .mock("PATCH", "/v1/blocks/page-9/children")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[{"id":"b-1"}]}"#)
+        .create_async()
+        .await;
+
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut file, b"# Notes\n").unwrap();
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let result = create(
+        &client,
+        "parent-1",
+        &json_props("{}"),
+        None,
+        file.path().to_str(),
+        false,
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
+    page.assert_async().await;
+    content.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_move_to_page_rejects_invalid_target() {
+    let client = NotionClient::with_base_url("token", "http://127.0.0.1:1").unwrap();
+    let result = move_page(&client, "page-1", "page", "_", &OutputFormat::Raw).await;
+
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid Notion ID '_'")
+    );
+}
+
+fn set_props(sets: &[String]) -> PropertyArgs<'_> {
+    PropertyArgs { json: None, sets }
+}
+
+fn sets(args: &[&str]) -> Vec<String> {
+    args.iter().map(|s| s.to_string()).collect()
+}
+
+const TASKS_SCHEMA: &str = r#"{
+    "id": "ds-1",
+    "parent": { "type": "database_id", "database_id": "2d3c4b5a-6978-4a1b-8c2d-3e4f5a6b7c8d" },
+    "properties": {
+        "Name": { "id": "title", "type": "title", "title": {} },
+        "Status": {
+            "id": "st",
+            "type": "status",
+            "status": { "options": [{ "name": "Todo" }, { "name": "Done" }] }
+        },
+        "Tags": {
+            "id": "tg",
+            "type": "multi_select",
+            "multi_select": { "options": [{ "name": "urgent" }, { "name": "later" }] }
+        },
+        "Due": { "id": "du", "type": "date", "date": {} },
+        "Owner": { "id": "ow", "type": "people", "people": {} },
+        "Project": {
+            "id": "pj",
+            "type": "relation",
+            "relation": { "data_source_id": "ds-2", "type": "single_property" }
+        }
+    }
+}"#;
+
+#[tokio::test]
+async fn test_update_with_set() {
+    let mut server = mockito::Server::new_async().await;
+    let page = server
+        .mock("GET", "/v1/pages/page-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "id": "page-1",
+                "parent": { "type": "data_source_id", "data_source_id": "ds-1" },
+                "properties": {
+                    "Tags": { "id": "tg", "type": "multi_select", "multi_select": [{ "name": "later" }] }
+                }
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let schema = server
+        .mock("GET", "/v1/data_sources/ds-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(TASKS_SCHEMA)
+        .create_async()
+        .await;
+    let users = server
+        .mock("GET", "/v1/users")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"results":[{"id":"user-1","type":"person","person":{"email":"alice@example.com"}}],"has_more":false}"#,
+        )
+        .create_async()
+        .await;
+    let patch = server
+        .mock("PATCH", "/v1/pages/page-1")
+        .match_body(mockito::Matcher::Json(json!({
+            "properties": {
+                "Status": { "status": { "name": "Done" } },
+                "Tags": { "multi_select": [{ "name": "later" }, { "name": "urgent" }] },
+                "Due": { "date": { "start": "2026-11-01", "end": null } },
+                "Owner": { "people": [{ "id": "user-1" }] }
+            }
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"page-1"}"#)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let args = sets(&[
+        "Status=Done",
+        "tags+=urgent",
+        "Due=2026-11-01",
+        "Owner=@alice@example.com",
+    ]);
+    let result = update(
+        &client,
+        "page-1",
+        &set_props(&args),
+        None,
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok(), "{:?}", result);
+    page.assert_async().await;
+    schema.assert_async().await;
+    users.assert_async().await;
+    patch.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_update_add_relation_fetches_full_list() {
+    use mockito::Matcher;
+
+    let related = |range: std::ops::Range<usize>| -> Vec<Value> {
+        range
+            .map(|i| json!({ "id": format!("59833787-2cf9-4fdf-8782-{:012}", i) }))
+            .collect()
+    };
+    let items = |range: std::ops::Range<usize>| -> Vec<Value> {
+        range
+            .map(|i| json!({ "object": "property_item", "type": "relation", "relation": { "id": format!("59833787-2cf9-4fdf-8782-{:012}", i) } }))
+            .collect()
+    };
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/v1/pages/page-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "id": "page-1",
+                "parent": { "type": "data_source_id", "data_source_id": "ds-1" },
+                "properties": {
+                    "Project": { "id": "pj", "type": "relation", "relation": related(0..25), "has_more": true }
+                }
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/v1/data_sources/ds-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(TASKS_SCHEMA)
+        .create_async()
+        .await;
+    let first = server
+        .mock("GET", "/v1/pages/page-1/properties/pj")
+        .match_query(Matcher::UrlEncoded("page_size".into(), "100".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({ "object": "list", "results": items(0..25), "has_more": true, "next_cursor": "c2" })
+                .to_string(),
+        )
+        .create_async()
+        .await;
+    let second = server
+        .mock("GET", "/v1/pages/page-1/properties/pj")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("page_size".into(), "100".into()),
+            Matcher::UrlEncoded("start_cursor".into(), "c2".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({ "object": "list", "results": items(25..30), "has_more": false, "next_cursor": null })
+                .to_string(),
+        )
+        .create_async()
+        .await;
+    let mut expected = related(0..30);
+    expected.push(json!({ "id": "59833787-2cf9-4fdf-8782-e53db20768a5" }));
+    let patch = server
+        .mock("PATCH", "/v1/pages/page-1")
+        .match_body(Matcher::Json(json!({
+            "properties": { "Project": { "relation": expected } }
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"page-1"}"#)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let args = sets(&["Project+=59833787-2cf9-4fdf-8782-e53db20768a5"]);
+    let result = update(
+        &client,
+        "page-1",
+        &set_props(&args),
+        None,
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok(), "{:?}", result);
+    first.assert_async().await;
+    second.assert_async().await;
+    patch.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_create_with_set_in_database() {
+    let mut server = mockito::Server::new_async().await;
+    let database = server
+        .mock("GET", "/v1/databases/db-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"id":"db-1","parent":{"type":"workspace","workspace":true},"data_sources":[{"id":"ds-1","name":"Tasks"}]}"#,
+        )
+        .create_async()
+        .await;
+    let schema = server
+        .mock("GET", "/v1/data_sources/ds-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(TASKS_SCHEMA)
+        .create_async()
+        .await;
+    let projects = server
+        .mock("GET", "/v1/data_sources/ds-2")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"id":"ds-2","parent":{"type":"database_id","database_id":"db-2"},"properties":{"Project name":{"id":"title","type":"title","title":{}}}}"#,
+        )
+        .create_async()
+        .await;
+    let lookup = server
+        .mock("POST", "/v1/data_sources/ds-2/query")
+        .match_body(mockito::Matcher::PartialJson(json!({
+            "filter": { "property": "Project name", "title": { "equals": "Launch" } }
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"results":[{"id":"project-1","parent":{"type":"data_source_id","data_source_id":"ds-2"}}],"has_more":false}"#,
+        )
+        .create_async()
+        .await;
+    let create_mock = server
+        .mock("POST", "/v1/pages")
+        .match_body(mockito::Matcher::Json(json!({
+            "parent": { "database_id": "db-1" },
+            "properties": {
+                "Name": { "title": [{ "type": "text", "text": { "content": "Write docs" } }] },
+                "Project": { "relation": [{ "id": "project-1" }] }
+            }
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"new-page"}"#)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let args = sets(&["Name=Write docs", "Project=Launch"]);
+    let result = create(
+        &client,
+        "db-1",
+        &set_props(&args),
+        None,
+        None,
+        true,
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok(), "{:?}", result);
+    database.assert_async().await;
+    schema.assert_async().await;
+    projects.assert_async().await;
+    lookup.assert_async().await;
+    create_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_update_dry_run_looks_up_relation_title() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/v1/pages/page-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"id":"page-1","parent":{"type":"data_source_id","data_source_id":"ds-1"},"properties":{}}"#,
+        )
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/v1/data_sources/ds-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(TASKS_SCHEMA)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/v1/data_sources/ds-2")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"id":"ds-2","parent":{"type":"database_id","database_id":"db-2"},"properties":{"Project name":{"id":"title","type":"title","title":{}}}}"#,
+        )
+        .create_async()
+        .await;
+    let lookup = server
+        .mock("POST", "/v1/data_sources/ds-2/query")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"results":[{"id":"project-1","parent":{"type":"data_source_id","data_source_id":"ds-2"}}],"has_more":false}"#,
+        )
+        .create_async()
+        .await;
+    let patch = server
+        .mock("PATCH", "/v1/pages/page-1")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let mut client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    client.set_dry_run(true);
+    let args = sets(&["Project=Launch"]);
+    let result = update(
+        &client,
+        "page-1",
+        &set_props(&args),
+        None,
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok(), "{:?}", result);
+    lookup.assert_async().await;
+    patch.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_create_with_set_under_page_only_has_title() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/pages")
+        .match_body(mockito::Matcher::Json(json!({
+            "parent": { "page_id": "parent-1" },
+            "properties": {
+                "title": { "title": [{ "type": "text", "text": { "content": "Notes" } }] }
+            }
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"new-page"}"#)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let args = sets(&["title=Notes"]);
+    let result = create(
+        &client,
+        "parent-1",
+        &set_props(&args),
+        None,
+        None,
+        false,
+        &OutputFormat::Raw,
+    )
+    .await;
+
+    assert!(result.is_ok(), "{:?}", result);
     mock.assert_async().await;
 }
+
+#[tokio::test]
+async fn test_update_with_unknown_property_fails_before_patch() {
+    let mut server = mockito::Server::new_async().await;
+    let _page = server
+        .mock("GET", "/v1/pages/page-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":"page-1","parent":{"type":"data_source_id","data_source_id":"ds-1"}}"#)
+        .create_async()
+        .await;
+    let _schema = server
+        .mock("GET", "/v1/data_sources/ds-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(TASKS_SCHEMA)
+        .create_async()
+        .await;
+    let patch = server
+        .mock("PATCH", "/v1/pages/page-1")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let args = sets(&["Stauts=Done"]);
+    let err = update(
+        &client,
+        "page-1",
+        &set_props(&args),
+        None,
+        &OutputFormat::Raw,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown property 'Stauts' (did you mean 'Status'?)"
+    );
+
+    let args = sets(&["Status=Finished"]);
+    let err = update(
+        &client,
+        "page-1",
+        &set_props(&args),
+        None,
+        &OutputFormat::Raw,
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("Status: Unknown status option 'Finished'")
+    );
+    patch.assert_async().await;
+}
@@ -0,0 +1,84 @@
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::path::Path;
+
+use crate::config::{Config, keyring_account};
+use crate::credentials::{self, TokenStore};
+use crate::error::bail_input;
+use crate::output::{OutputFormat, print_document, print_result, print_success, print_warning};
+
+pub fn list(config_path: &Path, format: &OutputFormat) -> Result<()> {
+    let config = Config::load_from(config_path)?;
+    let profiles: Vec<Value> = config
+        .profiles
+        .iter()
+        .map(|(name, profile)| {
+            json!({
+                "name": name,
+                "default": config.default_profile.as_deref() == Some(name.as_str()),
+                "token": profile.describe(),
+            })
+        })
+        .collect();
+
+    match format {
+        OutputFormat::Pretty => {
+            if profiles.is_empty() {
+                print_document("No profiles configured. Run `notion init --profile <name>`.\n");
+                return Ok(());
+            }
+            let text: String = profiles
+                .iter()
+                .map(|p| {
+                    let marker = if p["default"] == true { "*" } else { " " };
+                    let token = p["token"].as_str().unwrap_or("(no token)");
+                    format!(
+                        "{} {}  {}\n",
+                        marker,
+                        p["name"].as_str().unwrap_or(""),
+                        token
+                    )
+                })
+                .collect();
+            print_document(&text);
+            Ok(())
+        }
+        _ => print_result(&json!({ "object": "list", "results": profiles }), format),
+    }
+}
+
+pub fn use_profile(config_path: &Path, name: &str) -> Result<()> {
+    let mut config = Config::load_from(config_path)?;
+    // Only check the profile is usable; resolving it could prompt or run a command
+    if config.profile_credentials(name)?.is_empty() {
+        bail_input!(
+            "Profile '{}' has no API token. Run `notion init --profile {}`.",
+            name,
+            name
+        );
+    }
+    config.default_profile = Some(name.to_string());
+    config.save_to(config_path)?;
+    print_success(&format!("Default profile set to '{}'", name));
+    Ok(())
+}
+
+pub fn remove(config_path: &Path, name: &str) -> Result<()> {
+    let mut config = Config::load_from(config_path)?;
+    let Some(removed) = config.profiles.get(name).cloned() else {
+        bail_input!("Profile '{}' not found", name);
+    };
+    config.clear_credentials(Some(name));
+    config.save_to(config_path)?;
+    if removed.token_store == Some(TokenStore::Keyring)
+        && let Err(e) = credentials::keyring_delete(&keyring_account(Some(name)))
+    {
+        print_warning(&format!("{:#}", e));
+    }
+    print_success(&format!("Removed profile '{}'", name));
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "profile_tests.rs"]
+mod tests;
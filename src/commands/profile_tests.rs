@@ -0,0 +1,130 @@
+use super::*;
+use crate::credentials::Credentials;
+
+fn write_config(dir: &Path) -> std::path::PathBuf {
+    let path = dir.join("config.toml");
+    let mut config = Config {
+        default_profile: Some("work".to_string()),
+        ..Default::default()
+    };
+    for (name, token) in [
+        ("work", "ntn_work_token"),
+        ("personal", "ntn_personal_token"),
+    ] {
+        config.profiles.insert(
+            name.to_string(),
+            Credentials {
+                api_token: Some(token.to_string()),
+                ..Default::default()
+            },
+        );
+    }
+    config.save_to(&path).unwrap();
+    path
+}
+
+#[test]
+fn test_list_profiles() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_config(dir.path());
+    assert!(list(&path, &OutputFormat::Json).is_ok());
+    assert!(list(&path, &OutputFormat::Pretty).is_ok());
+}
+
+#[test]
+fn test_list_without_config_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    assert!(list(&path, &OutputFormat::Pretty).is_ok());
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_use_profile_sets_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_config(dir.path());
+
+    use_profile(&path, "personal").unwrap();
+
+    let config = Config::load_from(&path).unwrap();
+    assert_eq!(config.default_profile.as_deref(), Some("personal"));
+    assert_eq!(config.profiles.len(), 2);
+}
+
+#[test]
+fn test_use_unknown_profile_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_config(dir.path());
+
+    let err = use_profile(&path, "missing").unwrap_err().to_string();
+    assert!(err.contains("Profile 'missing' not found"));
+
+    let config = Config::load_from(&path).unwrap();
+    assert_eq!(config.default_profile.as_deref(), Some("work"));
+}
+
+#[test]
+fn test_use_profile_does_not_resolve_token() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_config(dir.path());
+    let mut config = Config::load_from(&path).unwrap();
+    config.profiles.insert(
+        "scripted".to_string(),
+        Credentials {
+            token_command: Some("exit 1".to_string()),
+            ..Default::default()
+        },
+    );
+    config.save_to(&path).unwrap();
+
+    use_profile(&path, "scripted").unwrap();
+
+    let config = Config::load_from(&path).unwrap();
+    assert_eq!(config.default_profile.as_deref(), Some("scripted"));
+}
+
+#[test]
+fn test_use_profile_without_token_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_config(dir.path());
+    let mut config = Config::load_from(&path).unwrap();
+    config
+        .profiles
+        .insert("empty".to_string(), Credentials::default());
+    config.save_to(&path).unwrap();
+
+    let err = use_profile(&path, "empty").unwrap_err();
+    assert!(err.to_string().contains("has no API token"));
+    assert!(crate::error::find(&err).is_some());
+}
+
+#[test]
+fn test_remove_profile() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_config(dir.path());
+
+    remove(&path, "personal").unwrap();
+
+    let config = Config::load_from(&path).unwrap();
+    assert!(!config.profiles.contains_key("personal"));
+    assert_eq!(config.default_profile.as_deref(), Some("work"));
+}
+
+#[test]
+fn test_remove_default_profile_clears_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_config(dir.path());
+
+    remove(&path, "work").unwrap();
+
+    let config = Config::load_from(&path).unwrap();
+    assert!(config.default_profile.is_none());
+    assert_eq!(config.profiles.len(), 1);
+}
+
+#[test]
+fn test_remove_unknown_profile_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_config(dir.path());
+    assert!(remove(&path, "missing").is_err());
+}
@@ -2,14 +2,14 @@ use anyhow::Result;
 use serde_json::json;
 
 use crate::client::NotionClient;
-use crate::output::{OutputFormat, print_result};
+use crate::output::OutputFormat;
+use crate::pagination::{ListRequest, Pagination, print_paginated};
 
 pub async fn run(
     client: &NotionClient,
     query: &str,
     filter_type: Option<&str>,
-    page_size: Option<u32>,
-    start_cursor: Option<&str>,
+    pagination: &Pagination,
     format: &OutputFormat,
 ) -> Result<()> {
     let mut body = json!({
@@ -23,17 +23,8 @@ pub async fn run(
         });
     }
 
-    if let Some(ps) = page_size {
-        body["page_size"] = json!(ps);
-    }
-
-    if let Some(cursor) = start_cursor {
-        body["start_cursor"] = json!(cursor);
-    }
-
-    let result = client.post("/v1/search", Some(&body)).await?;
-    print_result(&result, format)?;
-    Ok(())
+    let request = ListRequest::post("/v1/search", body);
+    print_paginated(client, &request, pagination, format).await
 }
 
 #[cfg(test)]
@@ -16,7 +16,14 @@ async fn test_run_basic() {
         .await;
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
-    let result = run(&client, "my search", None, None, None, &OutputFormat::Raw).await;
+    let result = run(
+        &client,
+        "my search",
+        None,
+        &Pagination::default(),
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -42,8 +49,7 @@ async fn test_run_with_filter() {
         &client,
         "test",
         Some("page"),
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -74,8 +80,11 @@ async fn test_run_with_all_options() {
         &client,
         "test",
         Some("database"),
-        Some(5),
-        Some("cursor-xyz"),
+        &Pagination {
+            page_size: Some(5),
+            start_cursor: Some("cursor-xyz".into()),
+            ..Default::default()
+        },
         &OutputFormat::Raw,
     )
     .await;
@@ -1,7 +1,10 @@
 use anyhow::Result;
+use std::collections::HashMap;
 
 use crate::client::NotionClient;
+use crate::models::User;
 use crate::output::{OutputFormat, print_result};
+use crate::pagination::{ListRequest, Pagination, print_paginated};
 
 pub async fn me(client: &NotionClient, format: &OutputFormat) -> Result<()> {
     let result = client.get("/v1/users/me", &[]).await?;
@@ -18,23 +21,23 @@ pub async fn get(client: &NotionClient, user_id: &str, format: &OutputFormat) ->
 
 pub async fn list(
     client: &NotionClient,
-    page_size: Option<u32>,
-    start_cursor: Option<&str>,
+    pagination: &Pagination,
     format: &OutputFormat,
 ) -> Result<()> {
-    let mut query: Vec<(&str, &str)> = Vec::new();
-    let ps_str;
-    if let Some(ps) = page_size {
-        ps_str = ps.to_string();
-        query.push(("page_size", &ps_str));
-    }
-    if let Some(cursor) = start_cursor {
-        query.push(("start_cursor", cursor));
-    }
+    let request = ListRequest::get("/v1/users");
+    print_paginated(client, &request, pagination, format).await
+}
 
-    let result = client.get("/v1/users", &query).await?;
-    print_result(&result, format)?;
-    Ok(())
+/// Map every workspace member's email (lowercased) to their user ID.
+///
+/// Emails are only returned to integrations with the "read user information
+/// including email addresses" capability.
+pub async fn emails(client: &NotionClient) -> Result<HashMap<String, String>> {
+    let users: Vec<User> = client.list_all(&ListRequest::get("/v1/users")).await?;
+    Ok(users
+        .into_iter()
+        .filter_map(|u| Some((u.email()?.to_lowercase(), u.id)))
+        .collect())
 }
 
 #[cfg(test)]
@@ -53,7 +53,16 @@ async fn test_list() {
         .await;
 
     let client = NotionClient::with_base_url("token", &server.url()).unwrap();
-    let result = list(&client, Some(25), Some("cursor-1"), &OutputFormat::Raw).await;
+    let result = list(
+        &client,
+        &Pagination {
+            page_size: Some(25),
+            start_cursor: Some("cursor-1".into()),
+            ..Default::default()
+        },
+        &OutputFormat::Raw,
+    )
+    .await;
 
     assert!(result.is_ok());
     mock.assert_async().await;
@@ -1,10 +1,53 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::credentials::Credentials;
+use crate::error::{NotionError, bail_input};
+use crate::output::print_warning;
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Config {
-    pub api_token: Option<String>,
+    /// Token used when no profile is selected (single-workspace setups)
+    #[serde(flatten)]
+    pub credentials: Credentials,
+
+    /// Profile used when neither `--profile` nor `NOTION_PROFILE` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+
+    /// Requests per second for all commands (`--rate-limit` overrides it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<f64>,
+
+    /// `Notion-Version` header (`--api-version` overrides it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_version: Option<String>,
+
+    /// API endpoint, e.g. a local stand-in server (`--base-url` overrides it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+
+    /// Proxy URL for every request (`--proxy` overrides it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+
+    /// Extra PEM root certificates (`--ca-bundle` overrides it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<PathBuf>,
+
+    /// Seconds to wait for a connection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<u64>,
+
+    /// Seconds to wait for each chunk of a response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_timeout: Option<u64>,
+
+    /// Named workspaces, stored as `[profiles.<name>]` tables
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Credentials>,
 }
 
 impl Config {
@@ -19,50 +62,229 @@ impl Config {
         Ok(Self::config_dir()?.join("config.toml"))
     }
 
+    /// Load the config file, or an empty config if it doesn't exist.
     pub fn load() -> Result<Self> {
-        // First check env var
-        if let Ok(token) = std::env::var("NOTION_API_TOKEN")
-            && !token.is_empty()
-        {
-            return Ok(Config {
-                api_token: Some(token),
-            });
-        }
+        Self::load_from(&Self::config_path()?)
+    }
 
-        // Then check config file
-        let path = Self::config_path()?;
-        if path.exists() {
-            let content = std::fs::read_to_string(&path)
-                .with_context(|| format!("Failed to read config file: {}", path.display()))?;
-            let config: Config = toml::from_str(&content)
-                .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
-            return Ok(config);
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Config::default());
         }
-
-        Ok(Config::default())
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        let config: Config = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+        if config.has_plaintext_token() && is_readable_by_others(path) {
+            print_warning(&format!(
+                "{} contains an API token and is readable by other users; run `chmod 600 {}`",
+                path.display(),
+                path.display()
+            ));
+        }
+        Ok(config)
     }
 
     pub fn save(&self) -> Result<()> {
-        let dir = Self::config_dir()?;
-        std::fs::create_dir_all(&dir)
-            .with_context(|| format!("Failed to create config directory: {}", dir.display()))?;
+        self.save_to(&Self::config_path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create config directory: {}", dir.display()))?;
+        }
 
-        let path = Self::config_path()?;
         let content = toml::to_string_pretty(self).context("Failed to serialize config")?;
-        std::fs::write(&path, content)
+        write_private(path, &content)
             .with_context(|| format!("Failed to write config file: {}", path.display()))?;
 
         Ok(())
     }
 
-    pub fn get_token(&self) -> Result<&str> {
-        self.api_token
-            .as_deref()
-            .filter(|t| !t.is_empty())
-            .context(
-                "No API token configured. Run `notion init` or set NOTION_API_TOKEN environment variable.",
-            )
+    /// Top-level token, from whichever backend holds it.
+    pub fn get_token(&self) -> Result<String> {
+        self.credentials.resolve(&keyring_account(None))?.context(
+            "No API token configured. Run `notion init` or set NOTION_API_TOKEN environment variable.",
+        )
+    }
+
+    fn unknown_profile(&self, name: &str) -> anyhow::Error {
+        let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+        if known.is_empty() {
+            return NotionError::Input(format!(
+                "Profile '{}' not found. Run `notion init --profile {}` to create it.",
+                name, name
+            ))
+            .into();
+        }
+        NotionError::Input(format!(
+            "Profile '{}' not found (available: {}). Run `notion init --profile {}` to create it.",
+            name,
+            known.join(", "),
+            name
+        ))
+        .into()
+    }
+
+    /// Credentials of `profile`, or of the default profile / top-level token.
+    ///
+    /// Returns the effective profile name alongside them.
+    pub fn credentials_for<'a>(
+        &'a self,
+        profile: Option<&'a str>,
+    ) -> Result<(Option<&'a str>, &'a Credentials)> {
+        match profile
+            .filter(|p| !p.is_empty())
+            .or(self.default_profile.as_deref())
+        {
+            Some(name) => {
+                let credentials = self
+                    .profiles
+                    .get(name)
+                    .ok_or_else(|| self.unknown_profile(name))?;
+                Ok((Some(name), credentials))
+            }
+            None => Ok((None, &self.credentials)),
+        }
+    }
+
+    /// Store credentials as the top-level token, or as a profile.
+    ///
+    /// The first profile becomes the default unless a top-level token is in use.
+    pub fn set_credentials(&mut self, profile: Option<&str>, credentials: Credentials) {
+        match profile {
+            Some(name) => {
+                self.profiles.insert(name.to_string(), credentials);
+                if self.default_profile.is_none() && self.credentials.is_empty() {
+                    self.default_profile = Some(name.to_string());
+                }
+            }
+            None => self.credentials = credentials,
+        }
+    }
+
+    /// Forget the credentials of a profile (or the top-level token).
+    pub fn clear_credentials(&mut self, profile: Option<&str>) {
+        match profile {
+            Some(name) => {
+                self.profiles.remove(name);
+                if self.default_profile.as_deref() == Some(name) {
+                    self.default_profile = None;
+                }
+            }
+            None => self.credentials = Credentials::default(),
+        }
+    }
+
+    fn has_plaintext_token(&self) -> bool {
+        std::iter::once(&self.credentials)
+            .chain(self.profiles.values())
+            .any(|c| c.api_token.as_deref().is_some_and(|t| !t.is_empty()))
+    }
+
+    /// Token for this invocation; `profile` comes from `--profile` or `NOTION_PROFILE`.
+    pub fn resolve_token(&self, profile: Option<&str>) -> Result<String> {
+        let env_token = std::env::var("NOTION_API_TOKEN").ok();
+        self.select_token(env_token.as_deref(), profile)
+    }
+
+    /// Token precedence, highest first:
+    ///
+    /// 1. the selected profile (`--profile`, then `NOTION_PROFILE`)
+    /// 2. `NOTION_API_TOKEN`, when set and non-empty
+    /// 3. `default_profile`
+    /// 4. the top-level `api_token`
+    pub fn select_token(&self, env_token: Option<&str>, profile: Option<&str>) -> Result<String> {
+        if let Some(name) = profile.filter(|p| !p.is_empty()) {
+            return self.profile(name);
+        }
+        if let Some(token) = env_token.filter(|t| !t.is_empty()) {
+            return Ok(token.to_string());
+        }
+
+        match &self.default_profile {
+            Some(name) => self.profile(name),
+            None => self.get_token(),
+        }
+    }
+
+    /// Stored credentials of a named profile, without resolving the token.
+    pub fn profile_credentials(&self, name: &str) -> Result<&Credentials> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| self.unknown_profile(name))
+    }
+
+    /// Token of a named profile.
+    pub fn profile(&self, name: &str) -> Result<String> {
+        self.profile_credentials(name)?
+            .resolve(&keyring_account(Some(name)))?
+            .with_context(|| {
+                format!(
+                    "Profile '{}' has no API token. Run `notion init --profile {}`.",
+                    name, name
+                )
+            })
+    }
+}
+
+/// Keyring entry name for the top-level token or a profile.
+pub fn keyring_account(profile: Option<&str>) -> String {
+    match profile {
+        Some(name) => format!("profile.{}", name),
+        None => "default".to_string(),
+    }
+}
+
+/// Write the config readable by the owner only; it may hold a plaintext token.
+fn write_private(path: &Path, content: &str) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)?;
+        // `mode` only applies on creation; tighten files created by older versions
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        file.write_all(content.as_bytes())
+    }
+    #[cfg(not(unix))]
+    {
+        std::fs::write(path, content)
+    }
+}
+
+#[cfg(unix)]
+fn is_readable_by_others(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o044 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_readable_by_others(_path: &Path) -> bool {
+    false
+}
+
+/// Profile names are TOML table keys and shell arguments; keep them simple.
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        bail_input!(
+            "Invalid profile name '{}': use letters, digits, '-' and '_'",
+            name
+        );
     }
+    Ok(())
 }
 
 #[cfg(test)]
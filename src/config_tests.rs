@@ -1,24 +1,33 @@
 use super::*;
 use std::env;
 
+fn plain(token: &str) -> Credentials {
+    Credentials {
+        api_token: Some(token.to_string()),
+        ..Default::default()
+    }
+}
+
 #[test]
 fn test_get_token_returns_token_when_set() {
     let config = Config {
-        api_token: Some("ntn_test_abc123".to_string()),
+        credentials: plain("ntn_test_abc123"),
+        ..Default::default()
     };
     assert_eq!(config.get_token().unwrap(), "ntn_test_abc123");
 }
 
 #[test]
 fn test_get_token_errors_when_none() {
-    let config = Config { api_token: None };
+    let config = Config::default();
     assert!(config.get_token().is_err());
 }
 
 #[test]
 fn test_get_token_errors_when_empty() {
     let config = Config {
-        api_token: Some("".to_string()),
+        credentials: plain(""),
+        ..Default::default()
     };
     assert!(config.get_token().is_err());
 }
@@ -26,23 +35,27 @@ fn test_get_token_errors_when_empty() {
 #[test]
 fn test_default_config_has_no_token() {
     let config = Config::default();
-    assert!(config.api_token.is_none());
+    assert!(config.credentials.api_token.is_none());
 }
 
 #[test]
 fn test_config_serialization_roundtrip() {
     let config = Config {
-        api_token: Some("ntn_secret_token".to_string()),
+        credentials: plain("ntn_secret_token"),
+        ..Default::default()
     };
     let serialized = toml::to_string_pretty(&config).unwrap();
     let deserialized: Config = toml::from_str(&serialized).unwrap();
-    assert_eq!(deserialized.api_token.as_deref(), Some("ntn_secret_token"));
+    assert_eq!(
+        deserialized.credentials.api_token.as_deref(),
+        Some("ntn_secret_token")
+    );
 }
 
 #[test]
 fn test_config_deserialize_empty_toml() {
     let config: Config = toml::from_str("").unwrap();
-    assert!(config.api_token.is_none());
+    assert!(config.credentials.api_token.is_none());
 }
 
 #[test]
@@ -51,57 +64,18 @@ fn test_config_save_and_load_from_file() {
     let config_path = dir.path().join("config.toml");
 
     let config = Config {
-        api_token: Some("ntn_file_token".to_string()),
+        credentials: plain("ntn_file_token"),
+        ..Default::default()
     };
     let content = toml::to_string_pretty(&config).unwrap();
     std::fs::write(&config_path, &content).unwrap();
 
     let loaded_content = std::fs::read_to_string(&config_path).unwrap();
     let loaded: Config = toml::from_str(&loaded_content).unwrap();
-    assert_eq!(loaded.api_token.as_deref(), Some("ntn_file_token"));
-}
-
-#[test]
-fn test_load_prefers_env_var() {
-    // Save current value
-    let prev = env::var("NOTION_API_TOKEN").ok();
-
-    // SAFETY: test runs single-threaded via cargo test -- --test-threads=1
-    unsafe {
-        env::set_var("NOTION_API_TOKEN", "ntn_from_env");
-    }
-    let config = Config::load().unwrap();
-    assert_eq!(config.api_token.as_deref(), Some("ntn_from_env"));
-
-    // Restore
-    unsafe {
-        match prev {
-            Some(v) => env::set_var("NOTION_API_TOKEN", v),
-            None => env::remove_var("NOTION_API_TOKEN"),
-        }
-    }
-}
-
-#[test]
-fn test_load_ignores_empty_env_var() {
-    let prev = env::var("NOTION_API_TOKEN").ok();
-
-    // SAFETY: test runs single-threaded via cargo test -- --test-threads=1
-    unsafe {
-        env::set_var("NOTION_API_TOKEN", "");
-    }
-    // Should not return an empty token from env
-    let config = Config::load().unwrap();
-    if let Some(ref token) = config.api_token {
-        assert!(!token.is_empty());
-    }
-
-    unsafe {
-        match prev {
-            Some(v) => env::set_var("NOTION_API_TOKEN", v),
-            None => env::remove_var("NOTION_API_TOKEN"),
-        }
-    }
+    assert_eq!(
+        loaded.credentials.api_token.as_deref(),
+        Some("ntn_file_token")
+    );
 }
 
 #[test]
@@ -130,7 +104,8 @@ fn test_save_creates_dir_and_file() {
     let config_path = config_dir.join("config.toml");
 
     let config = Config {
-        api_token: Some("ntn_save_test".to_string()),
+        credentials: plain("ntn_save_test"),
+        ..Default::default()
     };
 
     // Manually replicate save logic with custom path
@@ -141,23 +116,184 @@ fn test_save_creates_dir_and_file() {
     // Verify via load logic
     let loaded_content = std::fs::read_to_string(&config_path).unwrap();
     let loaded: Config = toml::from_str(&loaded_content).unwrap();
-    assert_eq!(loaded.api_token.as_deref(), Some("ntn_save_test"));
+    assert_eq!(
+        loaded.credentials.api_token.as_deref(),
+        Some("ntn_save_test")
+    );
+}
+
+#[test]
+fn test_load_from_missing_file_returns_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config::load_from(&dir.path().join("config.toml")).unwrap();
+    assert!(config.credentials.api_token.is_none());
+    assert!(config.profiles.is_empty());
+}
+
+#[test]
+fn test_save_to_and_load_from_roundtrip_with_profiles() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notion-cli").join("config.toml");
+
+    let config = profiles_config();
+    config.save_to(&path).unwrap();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(content.contains("[profiles.work]"));
+    assert!(content.contains("default_profile = \"work\""));
+
+    let loaded = Config::load_from(&path).unwrap();
+    assert_eq!(loaded.default_profile.as_deref(), Some("work"));
+    assert_eq!(loaded.profile("personal").unwrap(), "ntn_personal");
+}
+
+#[test]
+fn test_deserialize_profiles_table() {
+    let config: Config = toml::from_str(
+        r#"
+api_token = "ntn_plain"
+
+[profiles.work]
+api_token = "ntn_work"
+"#,
+    )
+    .unwrap();
+    assert_eq!(config.credentials.api_token.as_deref(), Some("ntn_plain"));
+    assert_eq!(config.profile("work").unwrap(), "ntn_work");
+    assert!(config.default_profile.is_none());
+}
+
+#[test]
+fn test_serialize_plain_token_omits_profile_fields() {
+    let config = Config {
+        credentials: plain("ntn_plain"),
+        ..Default::default()
+    };
+    let serialized = toml::to_string_pretty(&config).unwrap();
+    assert_eq!(serialized.trim(), r#"api_token = "ntn_plain""#);
+}
+
+fn profiles_config() -> Config {
+    let mut config = Config {
+        credentials: plain("ntn_plain"),
+        default_profile: Some("work".to_string()),
+        ..Default::default()
+    };
+    config
+        .profiles
+        .insert("work".to_string(), plain("ntn_work"));
+    config
+        .profiles
+        .insert("personal".to_string(), plain("ntn_personal"));
+    config
+}
+
+#[test]
+fn test_select_token_selected_profile_wins_over_env_var() {
+    let config = profiles_config();
+    let token = config
+        .select_token(Some("ntn_env"), Some("personal"))
+        .unwrap();
+    assert_eq!(token, "ntn_personal");
+}
+
+#[test]
+fn test_select_token_env_var_wins_over_default_profile() {
+    let config = profiles_config();
+    let token = config.select_token(Some("ntn_env"), None).unwrap();
+    assert_eq!(token, "ntn_env");
+}
+
+#[test]
+fn test_select_token_ignores_empty_env_var() {
+    let config = profiles_config();
+    let token = config.select_token(Some(""), None).unwrap();
+    assert_eq!(token, "ntn_work");
+}
+
+#[test]
+fn test_select_token_selected_profile_wins_over_default() {
+    let config = profiles_config();
+    let token = config.select_token(None, Some("personal")).unwrap();
+    assert_eq!(token, "ntn_personal");
+}
+
+#[test]
+fn test_select_token_default_profile_wins_over_plain_token() {
+    let config = profiles_config();
+    let token = config.select_token(None, None).unwrap();
+    assert_eq!(token, "ntn_work");
+}
+
+#[test]
+fn test_select_token_falls_back_to_plain_token() {
+    let mut config = profiles_config();
+    config.default_profile = None;
+    let token = config.select_token(None, None).unwrap();
+    assert_eq!(token, "ntn_plain");
+}
+
+#[test]
+fn test_select_token_empty_profile_name_uses_default() {
+    let config = profiles_config();
+    let token = config.select_token(None, Some("")).unwrap();
+    assert_eq!(token, "ntn_work");
+}
+
+#[test]
+fn test_select_token_unknown_profile_lists_available() {
+    let config = profiles_config();
+    let err = config
+        .select_token(None, Some("missing"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Profile 'missing' not found"));
+    assert!(err.contains("available: personal, work"));
+}
+
+#[test]
+fn test_select_token_unknown_profile_does_not_fall_back() {
+    let config = Config {
+        credentials: plain("ntn_plain"),
+        ..Default::default()
+    };
+    let err = config
+        .select_token(None, Some("work"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("notion init --profile work"));
+}
+
+#[test]
+fn test_select_token_profile_without_token_errors() {
+    let mut config = profiles_config();
+    config
+        .profiles
+        .insert("empty".to_string(), Credentials::default());
+    let err = config
+        .select_token(None, Some("empty"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Profile 'empty' has no API token"));
 }
 
 #[test]
-fn test_load_falls_back_to_default_when_no_env_and_no_file() {
+fn test_select_token_errors_when_nothing_configured() {
+    let config = Config::default();
+    let err = config.select_token(None, None).unwrap_err().to_string();
+    assert!(err.contains("No API token configured"));
+}
+
+#[test]
+fn test_resolve_token_reads_env_var() {
     let prev = env::var("NOTION_API_TOKEN").ok();
 
     // SAFETY: test runs single-threaded via cargo test -- --test-threads=1
     unsafe {
-        env::remove_var("NOTION_API_TOKEN");
+        env::set_var("NOTION_API_TOKEN", "ntn_from_env");
     }
-
-    // Config::load() will check env (empty), then check config file.
-    // If the config file doesn't exist for the default path, it returns default.
-    let config = Config::load().unwrap();
-    // The result depends on whether a real config file exists, but should not panic.
-    assert!(config.api_token.is_none() || config.api_token.is_some());
+    let token = profiles_config().resolve_token(None).unwrap();
+    assert_eq!(token, "ntn_from_env");
 
     unsafe {
         match prev {
@@ -166,3 +302,168 @@ fn test_load_falls_back_to_default_when_no_env_and_no_file() {
         }
     }
 }
+
+#[test]
+fn test_validate_profile_name() {
+    assert!(validate_profile_name("work").is_ok());
+    assert!(validate_profile_name("client_a-2").is_ok());
+    assert!(validate_profile_name("").is_err());
+    assert!(validate_profile_name("my profile").is_err());
+    assert!(validate_profile_name("a.b").is_err());
+    let err = validate_profile_name("a.b").unwrap_err();
+    assert_eq!(crate::error::kind(&err), crate::error::ErrorKind::Input);
+}
+
+#[test]
+fn test_select_token_runs_profile_token_command() {
+    let mut config = profiles_config();
+    config.profiles.insert(
+        "vault".to_string(),
+        Credentials {
+            token_command: Some("echo ntn_from_vault".to_string()),
+            ..Default::default()
+        },
+    );
+    let token = config.select_token(None, Some("vault")).unwrap();
+    assert_eq!(token, "ntn_from_vault");
+}
+
+#[test]
+fn test_deserialize_top_level_token_command() {
+    let config: Config = toml::from_str(r#"token_command = "echo ntn_cmd""#).unwrap();
+    assert_eq!(config.get_token().unwrap(), "ntn_cmd");
+}
+
+#[test]
+fn test_keyring_account_names() {
+    assert_eq!(keyring_account(None), "default");
+    assert_eq!(keyring_account(Some("work")), "profile.work");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_save_to_creates_owner_only_file() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    profiles_config().save_to(&path).unwrap();
+
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_save_to_tightens_existing_file() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(&path, "").unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+    assert!(is_readable_by_others(&path));
+
+    profiles_config().save_to(&path).unwrap();
+
+    assert!(!is_readable_by_others(&path));
+}
+
+#[test]
+fn test_set_credentials_first_profile_becomes_default() {
+    let mut config = Config::default();
+    config.set_credentials(Some("work"), plain("ntn_work"));
+    config.set_credentials(Some("personal"), plain("ntn_personal"));
+    assert_eq!(config.default_profile.as_deref(), Some("work"));
+}
+
+#[test]
+fn test_set_credentials_keeps_top_level_token_as_default() {
+    let mut config = Config {
+        credentials: plain("ntn_plain"),
+        ..Default::default()
+    };
+    config.set_credentials(Some("work"), plain("ntn_work"));
+    assert!(config.default_profile.is_none());
+    assert_eq!(config.select_token(None, None).unwrap(), "ntn_plain");
+}
+
+#[test]
+fn test_clear_credentials_removes_default_profile() {
+    let mut config = profiles_config();
+    config.clear_credentials(Some("work"));
+    assert!(config.default_profile.is_none());
+    assert!(!config.profiles.contains_key("work"));
+
+    config.clear_credentials(None);
+    assert!(config.credentials.is_empty());
+}
+
+#[test]
+fn test_credentials_for_follows_default_profile() {
+    let config = profiles_config();
+    let (name, credentials) = config.credentials_for(None).unwrap();
+    assert_eq!(name, Some("work"));
+    assert_eq!(credentials.api_token.as_deref(), Some("ntn_work"));
+
+    let (name, _) = config.credentials_for(Some("personal")).unwrap();
+    assert_eq!(name, Some("personal"));
+
+    assert!(config.credentials_for(Some("missing")).is_err());
+}
+
+#[test]
+fn test_oauth_grant_roundtrip() {
+    let mut config = Config::default();
+    config.set_credentials(
+        Some("client"),
+        Credentials {
+            api_token: Some("ntn_oauth".to_string()),
+            oauth: Some(crate::credentials::OAuthGrant {
+                client_id: "client-1".to_string(),
+                workspace_name: Some("Acme".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    );
+    let serialized = toml::to_string_pretty(&config).unwrap();
+    assert!(serialized.contains("[profiles.client.oauth]"));
+
+    let loaded: Config = toml::from_str(&serialized).unwrap();
+    let grant = loaded.profiles["client"].oauth.clone().unwrap();
+    assert_eq!(grant.client_id, "client-1");
+    assert_eq!(grant.workspace_name.as_deref(), Some("Acme"));
+}
+
+#[test]
+fn test_deserialize_rate_limit() {
+    let config: Config = toml::from_str("rate_limit = 2.5").unwrap();
+    assert_eq!(config.rate_limit, Some(2.5));
+    assert!(Config::default().rate_limit.is_none());
+}
+
+#[test]
+fn test_deserialize_api_version_and_base_url() {
+    let config: Config =
+        toml::from_str("api_version = \"2022-06-28\"\nbase_url = \"http://localhost:4010\"")
+            .unwrap();
+    assert_eq!(config.api_version.as_deref(), Some("2022-06-28"));
+    assert_eq!(config.base_url.as_deref(), Some("http://localhost:4010"));
+
+    let saved = toml::to_string(&Config::default()).unwrap();
+    assert!(!saved.contains("api_version"));
+    assert!(!saved.contains("base_url"));
+}
+
+#[test]
+fn test_deserialize_network_settings() {
+    let config: Config = toml::from_str(
+        "proxy = \"http://proxy.corp:3128\"\nca_bundle = \"/etc/ssl/corp.pem\"\nconnect_timeout = 5\nread_timeout = 60",
+    )
+    .unwrap();
+    assert_eq!(config.proxy.as_deref(), Some("http://proxy.corp:3128"));
+    assert_eq!(config.ca_bundle, Some(PathBuf::from("/etc/ssl/corp.pem")));
+    assert_eq!(config.connect_timeout, Some(5));
+    assert_eq!(config.read_timeout, Some(60));
+}
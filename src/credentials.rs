@@ -0,0 +1,323 @@
+use anyhow::{Context, Result, bail};
+use argon2::Argon2;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::process::{Command, ExitStatus, Stdio};
+
+/// Keyring service name all tokens are stored under
+const SERVICE: &str = "notion-cli";
+
+/// Environment variable holding the passphrase for encrypted tokens
+pub const PASSPHRASE_ENV: &str = "NOTION_CLI_PASSPHRASE";
+
+const ENCRYPTED_PREFIX: &str = "v1:";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Where a token is kept, for the top-level config and each profile.
+///
+/// At most one source is used, in this order: `token_command`, then
+/// `token_store`, then the plaintext `api_token`.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Credentials {
+    /// Plaintext token (the `file` backend)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_token: Option<String>,
+
+    /// Shell command printing the token on stdout, e.g. `pass show notion`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_command: Option<String>,
+
+    /// Secret backend holding the token instead of `api_token`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_store: Option<TokenStore>,
+
+    /// Passphrase-encrypted token, for `token_store = "encrypted"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypted_token: Option<String>,
+
+    /// Set when the token came from `notion login --oauth`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oauth: Option<OAuthGrant>,
+}
+
+/// Public integration and workspace an OAuth access token was issued for.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct OAuthGrant {
+    pub client_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bot_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenStore {
+    /// OS secret store: macOS Keychain or Secret Service (`secret-tool`)
+    Keyring,
+    /// `encrypted_token`, unlocked with a passphrase
+    Encrypted,
+}
+
+impl Credentials {
+    pub fn is_empty(&self) -> bool {
+        self.token_command.is_none()
+            && self.token_store.is_none()
+            && self.api_token.as_deref().is_none_or(str::is_empty)
+    }
+
+    /// Short description of the backend, without revealing the token.
+    pub fn describe(&self) -> Option<String> {
+        if let Some(command) = &self.token_command {
+            return Some(format!("command: {}", command));
+        }
+        match self.token_store {
+            Some(TokenStore::Keyring) => Some("keyring".to_string()),
+            Some(TokenStore::Encrypted) => Some("encrypted".to_string()),
+            None => self
+                .api_token
+                .as_deref()
+                .filter(|t| !t.is_empty())
+                .map(|t| format!("{}...", t.chars().take(8).collect::<String>())),
+        }
+    }
+
+    /// Fetch the token from whichever backend is configured.
+    ///
+    /// `account` names the keyring entry; returns `None` when nothing is configured.
+    pub fn resolve(&self, account: &str) -> Result<Option<String>> {
+        if let Some(command) = &self.token_command {
+            return run_token_command(command).map(Some);
+        }
+        match self.token_store {
+            Some(TokenStore::Keyring) => keyring_get(account).map(Some),
+            Some(TokenStore::Encrypted) => {
+                let data = self
+                    .encrypted_token
+                    .as_deref()
+                    .context("token_store is \"encrypted\" but no encrypted_token is set")?;
+                let passphrase = passphrase(false)?;
+                decrypt(data, &passphrase).map(Some)
+            }
+            None => Ok(self.api_token.clone().filter(|t| !t.is_empty())),
+        }
+    }
+}
+
+/// Run `command` through the shell and return its trimmed stdout.
+pub fn run_token_command(command: &str) -> Result<String> {
+    let output = shell(command)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("Failed to run token_command `{}`", command))?;
+    if !output.status.success() {
+        bail!("token_command `{}` failed ({})", command, output.status);
+    }
+    let token = String::from_utf8(output.stdout)
+        .with_context(|| format!("token_command `{}` printed invalid UTF-8", command))?;
+    // Helpers like `pass` print the secret on the first line, metadata after
+    let token = token.lines().next().unwrap_or("").trim().to_string();
+    if token.is_empty() {
+        bail!("token_command `{}` printed no token", command);
+    }
+    Ok(token)
+}
+
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+pub fn keyring_available() -> bool {
+    let probe = if cfg!(target_os = "macos") {
+        Command::new("security").arg("help").output()
+    } else if cfg!(windows) {
+        return false;
+    } else {
+        Command::new("secret-tool").arg("--version").output()
+    };
+    probe.is_ok()
+}
+
+pub fn keyring_get(account: &str) -> Result<String> {
+    let output = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args(["find-generic-password", "-s", SERVICE, "-a", account, "-w"])
+            .output()
+    } else {
+        keyring_supported()?;
+        Command::new("secret-tool")
+            .args(["lookup", "service", SERVICE, "account", account])
+            .output()
+    }
+    .context("Failed to query the OS keyring")?;
+    let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || token.is_empty() {
+        bail!(
+            "No token for '{}' in the OS keyring. Run `notion init` to store one.",
+            account
+        );
+    }
+    Ok(token)
+}
+
+pub fn keyring_set(account: &str, token: &str) -> Result<()> {
+    // Both tools read the secret from stdin, keeping it out of argv
+    let status = if cfg!(target_os = "macos") {
+        // A trailing -w prompts on the terminal rather than reading stdin, so
+        // pass the whole command to security's interactive mode instead
+        let mut cmd = Command::new("security");
+        cmd.arg("-i");
+        write_stdin(cmd, &security_add_command(account, token))
+    } else {
+        keyring_supported()?;
+        let mut cmd = Command::new("secret-tool");
+        cmd.args(["store", "--label", &format!("Notion CLI ({})", account)])
+            .args(["service", SERVICE, "account", account]);
+        write_stdin(cmd, token)
+    }
+    .context("Failed to write to the OS keyring")?;
+    if !status.success() {
+        bail!("Failed to store the token in the OS keyring ({})", status);
+    }
+    Ok(())
+}
+
+/// The `security -i` line that stores `token` for `account`.
+fn security_add_command(account: &str, token: &str) -> String {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    format!(
+        "add-generic-password -U -s {} -a {} -w {}\n",
+        quote(SERVICE),
+        quote(account),
+        quote(token)
+    )
+}
+
+/// Run `cmd` with `input` on its stdin.
+fn write_stdin(mut cmd: Command, input: &str) -> Result<ExitStatus> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", cmd.get_program().to_string_lossy()))?;
+    child
+        .stdin
+        .take()
+        .context("Failed to open stdin")?
+        .write_all(input.as_bytes())?;
+    Ok(child.wait()?)
+}
+
+pub fn keyring_delete(account: &str) -> Result<()> {
+    let status = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args(["delete-generic-password", "-s", SERVICE, "-a", account])
+            .output()
+            .map(|o| o.status)
+    } else {
+        keyring_supported()?;
+        Command::new("secret-tool")
+            .args(["clear", "service", SERVICE, "account", account])
+            .status()
+    }
+    .context("Failed to update the OS keyring")?;
+    if !status.success() {
+        bail!(
+            "Failed to remove '{}' from the OS keyring ({})",
+            account,
+            status
+        );
+    }
+    Ok(())
+}
+
+fn keyring_supported() -> Result<()> {
+    if cfg!(windows) {
+        bail!(
+            "The keyring backend is not supported on this platform; use token_store = \"encrypted\" or token_command"
+        );
+    }
+    Ok(())
+}
+
+/// Passphrase from `NOTION_CLI_PASSPHRASE`, or an interactive prompt.
+pub fn passphrase(confirm: bool) -> Result<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV)
+        && !passphrase.is_empty()
+    {
+        return Ok(passphrase);
+    }
+    let mut prompt = dialoguer::Password::new().with_prompt("Token passphrase");
+    if confirm {
+        prompt = prompt.with_confirmation("Confirm passphrase", "Passphrases don't match");
+    }
+    prompt.interact().with_context(|| {
+        format!(
+            "Failed to read the token passphrase (set {} for non-interactive use)",
+            PASSPHRASE_ENV
+        )
+    })
+}
+
+/// Encrypt `token` with a key derived from `passphrase` (Argon2id + ChaCha20-Poly1305).
+pub fn encrypt(token: &str, passphrase: &str) -> Result<String> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, token.as_bytes())
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt token"))?;
+
+    let mut data = salt.to_vec();
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(data)))
+}
+
+pub fn decrypt(data: &str, passphrase: &str) -> Result<String> {
+    let encoded = data
+        .strip_prefix(ENCRYPTED_PREFIX)
+        .context("Unsupported encrypted_token format")?;
+    let bytes = BASE64
+        .decode(encoded)
+        .context("encrypted_token is not valid base64")?;
+    if bytes.len() <= SALT_LEN + NONCE_LEN {
+        bail!("encrypted_token is truncated");
+    }
+    let (salt, rest) = bytes.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow::anyhow!("Wrong passphrase or corrupted encrypted_token"))?;
+    String::from_utf8(plaintext).context("Decrypted token is not valid UTF-8")
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Failed to derive key: {}", e))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+#[path = "credentials_tests.rs"]
+mod tests;
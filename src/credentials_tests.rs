@@ -0,0 +1,195 @@
+use super::*;
+
+#[test]
+fn test_encrypt_decrypt_roundtrip() {
+    let data = encrypt("ntn_secret_token", "correct horse").unwrap();
+    assert!(data.starts_with("v1:"));
+    assert!(!data.contains("ntn_secret_token"));
+    assert_eq!(decrypt(&data, "correct horse").unwrap(), "ntn_secret_token");
+}
+
+#[test]
+fn test_encrypt_uses_fresh_salt_and_nonce() {
+    let a = encrypt("ntn_secret_token", "pass").unwrap();
+    let b = encrypt("ntn_secret_token", "pass").unwrap();
+    assert_ne!(a, b);
+}
+
+#[test]
+fn test_decrypt_wrong_passphrase() {
+    let data = encrypt("ntn_secret_token", "right").unwrap();
+    let err = decrypt(&data, "wrong").unwrap_err().to_string();
+    assert!(err.contains("Wrong passphrase"));
+}
+
+#[test]
+fn test_decrypt_rejects_unknown_format() {
+    let err = decrypt("ntn_plain", "pass").unwrap_err().to_string();
+    assert!(err.contains("Unsupported encrypted_token format"));
+}
+
+#[test]
+fn test_decrypt_rejects_truncated_data() {
+    let err = decrypt("v1:AAAA", "pass").unwrap_err().to_string();
+    assert!(err.contains("truncated"));
+}
+
+#[test]
+fn test_run_token_command_trims_output() {
+    let token = run_token_command("echo '  ntn_from_command  '").unwrap();
+    assert_eq!(token, "ntn_from_command");
+}
+
+#[test]
+fn test_run_token_command_uses_first_line() {
+    let token = run_token_command("printf 'ntn_first\\nlogin: me\\n'").unwrap();
+    assert_eq!(token, "ntn_first");
+}
+
+#[test]
+fn test_run_token_command_failure() {
+    let err = run_token_command("exit 3").unwrap_err().to_string();
+    assert!(err.contains("token_command `exit 3` failed"));
+}
+
+#[test]
+fn test_run_token_command_empty_output() {
+    let err = run_token_command("true").unwrap_err().to_string();
+    assert!(err.contains("printed no token"));
+}
+
+#[test]
+fn test_resolve_plaintext_token() {
+    let credentials = Credentials {
+        api_token: Some("ntn_plain".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        credentials.resolve("default").unwrap().as_deref(),
+        Some("ntn_plain")
+    );
+}
+
+#[test]
+fn test_resolve_nothing_configured() {
+    assert!(Credentials::default().resolve("default").unwrap().is_none());
+}
+
+#[test]
+fn test_resolve_command_wins_over_plaintext() {
+    let credentials = Credentials {
+        api_token: Some("ntn_plain".to_string()),
+        token_command: Some("echo ntn_command".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        credentials.resolve("default").unwrap().as_deref(),
+        Some("ntn_command")
+    );
+}
+
+#[test]
+fn test_resolve_encrypted_with_passphrase_env() {
+    let prev = std::env::var(PASSPHRASE_ENV).ok();
+
+    // SAFETY: test runs single-threaded via cargo test -- --test-threads=1
+    unsafe {
+        std::env::set_var(PASSPHRASE_ENV, "env passphrase");
+    }
+    let credentials = Credentials {
+        token_store: Some(TokenStore::Encrypted),
+        encrypted_token: Some(encrypt("ntn_encrypted", "env passphrase").unwrap()),
+        ..Default::default()
+    };
+    let token = credentials.resolve("default").unwrap();
+    assert_eq!(token.as_deref(), Some("ntn_encrypted"));
+
+    unsafe {
+        match prev {
+            Some(v) => std::env::set_var(PASSPHRASE_ENV, v),
+            None => std::env::remove_var(PASSPHRASE_ENV),
+        }
+    }
+}
+
+#[test]
+fn test_resolve_encrypted_without_data_errors() {
+    let credentials = Credentials {
+        token_store: Some(TokenStore::Encrypted),
+        ..Default::default()
+    };
+    let err = credentials.resolve("default").unwrap_err().to_string();
+    assert!(err.contains("no encrypted_token is set"));
+}
+
+#[test]
+fn test_describe_never_shows_full_token() {
+    let plain = Credentials {
+        api_token: Some("ntn_1234567890".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(plain.describe().as_deref(), Some("ntn_1234..."));
+
+    let keyring = Credentials {
+        token_store: Some(TokenStore::Keyring),
+        ..Default::default()
+    };
+    assert_eq!(keyring.describe().as_deref(), Some("keyring"));
+
+    let command = Credentials {
+        token_command: Some("pass show notion".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        command.describe().as_deref(),
+        Some("command: pass show notion")
+    );
+
+    assert!(Credentials::default().describe().is_none());
+}
+
+#[test]
+fn test_is_empty() {
+    assert!(Credentials::default().is_empty());
+    let empty_token = Credentials {
+        api_token: Some(String::new()),
+        ..Default::default()
+    };
+    assert!(empty_token.is_empty());
+    let keyring = Credentials {
+        token_store: Some(TokenStore::Keyring),
+        ..Default::default()
+    };
+    assert!(!keyring.is_empty());
+}
+
+#[test]
+fn test_token_store_serializes_lowercase() {
+    let credentials = Credentials {
+        token_store: Some(TokenStore::Keyring),
+        ..Default::default()
+    };
+    let serialized = toml::to_string(&credentials).unwrap();
+    assert_eq!(serialized.trim(), r#"token_store = "keyring""#);
+
+    let parsed: Credentials = toml::from_str(r#"token_store = "encrypted""#).unwrap();
+    assert_eq!(parsed.token_store, Some(TokenStore::Encrypted));
+}
+
+#[test]
+fn test_write_stdin_passes_secret_on_stdin() {
+    let cmd = shell(r#"read first; read second; test "$first-$second" = "s3cret-s3cret""#);
+    let status = write_stdin(cmd, "s3cret\ns3cret\n").unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn test_security_add_command_quotes_arguments() {
+    assert_eq!(
+        security_add_command("work \"a\"", "s3\\cret"),
+        format!(
+            "add-generic-password -U -s \"{}\" -a \"work \\\"a\\\"\" -w \"s3\\\\cret\"\n",
+            SERVICE
+        )
+    );
+}
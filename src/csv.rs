@@ -0,0 +1,157 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::error::bail_input;
+
+/// Delimited text dialects used for spreadsheet import and export.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dialect {
+    /// RFC 4180: comma separated, fields quoted when needed
+    Csv,
+    /// Tab separated, with `\t`, `\n`, `\r` and `\\` escaped
+    Tsv,
+}
+
+impl Dialect {
+    /// TSV for `.tsv`/`.tab` files, CSV otherwise.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("tsv") || ext.eq_ignore_ascii_case("tab") => {
+                Dialect::Tsv
+            }
+            _ => Dialect::Csv,
+        }
+    }
+
+    pub fn delimiter(self) -> char {
+        match self {
+            Dialect::Csv => ',',
+            Dialect::Tsv => '\t',
+        }
+    }
+}
+
+/// Format one record, including the trailing newline.
+pub fn format_record(fields: &[String], dialect: Dialect) -> String {
+    let mut line = fields
+        .iter()
+        .map(|f| escape_field(f, dialect))
+        .collect::<Vec<_>>()
+        .join(&dialect.delimiter().to_string());
+    line.push('\n');
+    line
+}
+
+fn escape_field(field: &str, dialect: Dialect) -> String {
+    match dialect {
+        Dialect::Csv => {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        }
+        Dialect::Tsv => field
+            .replace('\\', "\\\\")
+            .replace('\t', "\\t")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r"),
+    }
+}
+
+/// Parse delimited text into records, skipping blank lines.
+pub fn parse(text: &str, dialect: Dialect) -> Result<Vec<Vec<String>>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    match dialect {
+        Dialect::Csv => parse_csv(text),
+        Dialect::Tsv => Ok(text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.split('\t').map(unescape_tsv).collect())
+            .collect()),
+    }
+}
+
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    // Line where the current record began, for unterminated quotes
+    let mut record_line = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                line += 1;
+                record_line = line;
+                record.push(std::mem::take(&mut field));
+                push_record(&mut records, std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        bail_input!(
+            "Unterminated quoted field in the record starting on line {}",
+            record_line
+        );
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        push_record(&mut records, record);
+    }
+    Ok(records)
+}
+
+fn push_record(records: &mut Vec<Vec<String>>, record: Vec<String>) {
+    let blank = record.len() == 1 && record[0].trim().is_empty();
+    if !blank {
+        records.push(record);
+    }
+}
+
+fn unescape_tsv(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+#[path = "csv_tests.rs"]
+mod tests;
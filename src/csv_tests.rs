@@ -0,0 +1,96 @@
+use super::*;
+
+fn fields(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+#[test]
+fn test_csv_plain_fields() {
+    let line = format_record(&fields(&["a", "b c", "1"]), Dialect::Csv);
+    assert_eq!(line, "a,b c,1\n");
+}
+
+#[test]
+fn test_csv_quotes_special_fields() {
+    let line = format_record(&fields(&["x,y", "say \"hi\"", "two\nlines"]), Dialect::Csv);
+    assert_eq!(line, "\"x,y\",\"say \"\"hi\"\"\",\"two\nlines\"\n");
+}
+
+#[test]
+fn test_csv_empty_fields() {
+    let line = format_record(&fields(&["", "", ""]), Dialect::Csv);
+    assert_eq!(line, ",,\n");
+}
+
+#[test]
+fn test_tsv_escapes_control_characters() {
+    let line = format_record(&fields(&["a\tb", "c\nd", "e\\f", "g,h"]), Dialect::Tsv);
+    assert_eq!(line, "a\\tb\tc\\nd\te\\\\f\tg,h\n");
+}
+
+#[test]
+fn test_delimiters() {
+    assert_eq!(Dialect::Csv.delimiter(), ',');
+    assert_eq!(Dialect::Tsv.delimiter(), '\t');
+}
+
+#[test]
+fn test_dialect_from_path() {
+    assert_eq!(Dialect::from_path(Path::new("rows.csv")), Dialect::Csv);
+    assert_eq!(Dialect::from_path(Path::new("rows.TSV")), Dialect::Tsv);
+    assert_eq!(Dialect::from_path(Path::new("rows")), Dialect::Csv);
+}
+
+#[test]
+fn test_parse_csv_simple() {
+    let records = parse("Name,Count\nA,1\nB,2\n", Dialect::Csv).unwrap();
+    assert_eq!(
+        records,
+        vec![vec!["Name", "Count"], vec!["A", "1"], vec!["B", "2"]]
+    );
+}
+
+#[test]
+fn test_parse_csv_quoted_fields() {
+    let text = "Name,Notes\r\n\"Smith, J\",\"said \"\"hi\"\"\nthen left\"\r\n";
+    let records = parse(text, Dialect::Csv).unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1][0], "Smith, J");
+    assert_eq!(records[1][1], "said \"hi\"\nthen left");
+}
+
+#[test]
+fn test_parse_csv_skips_blank_lines_and_bom() {
+    let text = "\u{feff}A,B\n\n1,2\n\n";
+    let records = parse(text, Dialect::Csv).unwrap();
+    assert_eq!(records, vec![vec!["A", "B"], vec!["1", "2"]]);
+}
+
+#[test]
+fn test_parse_csv_empty_fields_and_no_trailing_newline() {
+    let records = parse("a,,c\n,,", Dialect::Csv).unwrap();
+    assert_eq!(records, vec![vec!["a", "", "c"], vec!["", "", ""]]);
+}
+
+#[test]
+fn test_parse_csv_unterminated_quote() {
+    assert!(parse("a,\"b\n", Dialect::Csv).is_err());
+    let err = parse("Name\nA\n\"open\nmore\nlines\n", Dialect::Csv).unwrap_err();
+    assert!(err.to_string().contains("starting on line 3"), "{}", err);
+}
+
+#[test]
+fn test_parse_tsv_roundtrip() {
+    let original = fields(&["a\tb", "c\nd", "e\\f"]);
+    let line = format_record(&original, Dialect::Tsv);
+    let records = parse(&line, Dialect::Tsv).unwrap();
+    assert_eq!(records, vec![original]);
+}
+
+#[test]
+fn test_parse_csv_roundtrip() {
+    let original = fields(&["x,y", "say \"hi\"", "two\nlines", ""]);
+    let line = format_record(&original, Dialect::Csv);
+    let records = parse(&line, Dialect::Csv).unwrap();
+    assert_eq!(records, vec![original]);
+}
@@ -0,0 +1,197 @@
+use clap::ValueEnum;
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+use std::fmt;
+
+/// Failures scripts may want to tell apart, each with its own exit code.
+#[derive(Debug)]
+pub enum NotionError {
+    /// The API answered with an error object
+    Api {
+        status: StatusCode,
+        code: String,
+        message: String,
+        request_id: Option<String>,
+    },
+    /// The request could not be completed (connection, timeout, TLS)
+    Network {
+        method: String,
+        path: String,
+        source: reqwest::Error,
+    },
+    /// A mistake in local input: arguments, JSON, files
+    Input(String),
+}
+
+impl fmt::Display for NotionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotionError::Api {
+                status,
+                code,
+                message,
+                ..
+            } => write!(f, "Notion API error ({}): [{}] {}", status, code, message),
+            NotionError::Network { method, path, .. } => write!(f, "{} {}", method, path),
+            NotionError::Input(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for NotionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NotionError::Network { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Error classes and their process exit codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    General,
+    Input,
+    Auth,
+    NotFound,
+    Validation,
+    RateLimited,
+    Network,
+    Server,
+    /// 409 `conflict_error`: another write to the same object won; retrying
+    /// later usually succeeds, so it is kept apart from server faults
+    Conflict,
+}
+
+impl ErrorKind {
+    pub fn exit_code(self) -> u8 {
+        match self {
+            ErrorKind::General => 1,
+            ErrorKind::Input => 2,
+            ErrorKind::Auth => 3,
+            ErrorKind::NotFound => 4,
+            ErrorKind::Validation => 5,
+            ErrorKind::RateLimited => 6,
+            ErrorKind::Network => 7,
+            ErrorKind::Server => 8,
+            ErrorKind::Conflict => 9,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorKind::General => "general",
+            ErrorKind::Input => "input",
+            ErrorKind::Auth => "auth",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::Validation => "validation",
+            ErrorKind::RateLimited => "rate_limited",
+            ErrorKind::Network => "network",
+            ErrorKind::Server => "server",
+            ErrorKind::Conflict => "conflict",
+        }
+    }
+}
+
+impl NotionError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            NotionError::Api { status, .. } => match status.as_u16() {
+                401 | 403 => ErrorKind::Auth,
+                404 => ErrorKind::NotFound,
+                429 => ErrorKind::RateLimited,
+                409 => ErrorKind::Conflict,
+                500.. => ErrorKind::Server,
+                _ => ErrorKind::Validation,
+            },
+            NotionError::Network { .. } => ErrorKind::Network,
+            NotionError::Input(_) => ErrorKind::Input,
+        }
+    }
+
+    /// Error from a non-success response; `body` is the parsed JSON, if any.
+    pub fn from_response(status: StatusCode, body: &Value, request_id: Option<&str>) -> Self {
+        // OAuth endpoints report `error` instead of `code`
+        let code = body["code"]
+            .as_str()
+            .or(body["error"].as_str())
+            .unwrap_or("unknown");
+        NotionError::Api {
+            status,
+            code: code.to_string(),
+            message: body["message"]
+                .as_str()
+                .unwrap_or("Unknown error")
+                .to_string(),
+            request_id: body["request_id"]
+                .as_str()
+                .or(request_id)
+                .map(str::to_string),
+        }
+    }
+}
+
+/// Return early with a [`NotionError::Input`], like `anyhow::bail!`.
+macro_rules! bail_input {
+    ($($arg:tt)*) => {
+        return Err($crate::error::NotionError::Input(format!($($arg)*)).into())
+    };
+}
+pub(crate) use bail_input;
+
+/// The typed error somewhere in an error's chain, if any.
+pub fn find(error: &anyhow::Error) -> Option<&NotionError> {
+    error.chain().find_map(|e| e.downcast_ref::<NotionError>())
+}
+
+pub fn kind(error: &anyhow::Error) -> ErrorKind {
+    find(error).map_or(ErrorKind::General, NotionError::kind)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ErrorFormat {
+    /// Human-readable message with its causes
+    #[default]
+    Text,
+    /// One JSON object on stderr
+    Json,
+}
+
+/// The error as a JSON object, for `--error-format json`.
+pub fn to_json(error: &anyhow::Error) -> Value {
+    let kind = kind(error);
+    let mut report = json!({
+        "kind": kind.name(),
+        "exit_code": kind.exit_code(),
+        "message": error.to_string(),
+    });
+    if let Some(NotionError::Api {
+        status,
+        code,
+        request_id,
+        ..
+    }) = find(error)
+    {
+        report["status"] = json!(status.as_u16());
+        report["code"] = json!(code);
+        report["request_id"] = json!(request_id);
+    }
+    let causes: Vec<String> = error.chain().skip(1).map(ToString::to_string).collect();
+    if !causes.is_empty() {
+        report["causes"] = json!(causes);
+    }
+    json!({ "error": report })
+}
+
+/// Print the error on stderr in the requested format and return the exit code.
+pub fn report(error: &anyhow::Error, format: ErrorFormat) -> u8 {
+    match format {
+        ErrorFormat::Text => eprintln!("Error: {:?}", error),
+        ErrorFormat::Json => eprintln!("{}", to_json(error)),
+    }
+    kind(error).exit_code()
+}
+
+#[cfg(test)]
+#[path = "error_tests.rs"]
+mod tests;
@@ -0,0 +1,158 @@
+use super::*;
+use anyhow::Context;
+
+fn api(status: u16) -> NotionError {
+    NotionError::from_response(
+        StatusCode::from_u16(status).unwrap(),
+        &json!({"code": "some_code", "message": "Some message"}),
+        None,
+    )
+}
+
+#[test]
+fn test_api_error_kinds() {
+    let cases = [
+        (400, ErrorKind::Validation),
+        (401, ErrorKind::Auth),
+        (403, ErrorKind::Auth),
+        (404, ErrorKind::NotFound),
+        (409, ErrorKind::Conflict),
+        (422, ErrorKind::Validation),
+        (429, ErrorKind::RateLimited),
+        (500, ErrorKind::Server),
+        (503, ErrorKind::Server),
+    ];
+    for (status, expected) in cases {
+        assert_eq!(api(status).kind(), expected, "status {}", status);
+    }
+}
+
+#[test]
+fn test_exit_codes_are_distinct() {
+    let kinds = [
+        ErrorKind::General,
+        ErrorKind::Input,
+        ErrorKind::Auth,
+        ErrorKind::NotFound,
+        ErrorKind::Validation,
+        ErrorKind::RateLimited,
+        ErrorKind::Network,
+        ErrorKind::Server,
+        ErrorKind::Conflict,
+    ];
+    let codes: Vec<u8> = kinds.iter().map(|k| k.exit_code()).collect();
+    assert_eq!(codes, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn test_api_error_display() {
+    assert_eq!(
+        api(404).to_string(),
+        "Notion API error (404 Not Found): [some_code] Some message"
+    );
+}
+
+#[test]
+fn test_from_response_defaults() {
+    let error = NotionError::from_response(StatusCode::BAD_GATEWAY, &Value::Null, None);
+    assert_eq!(
+        error.to_string(),
+        "Notion API error (502 Bad Gateway): [unknown] Unknown error"
+    );
+}
+
+#[test]
+fn test_from_response_oauth_error_field() {
+    let body = json!({"error": "invalid_grant"});
+    let error = NotionError::from_response(StatusCode::BAD_REQUEST, &body, None);
+    assert!(error.to_string().contains("[invalid_grant]"));
+}
+
+#[test]
+fn test_from_response_request_id_prefers_body() {
+    let body = json!({"code": "x", "request_id": "from-body"});
+    let NotionError::Api { request_id, .. } =
+        NotionError::from_response(StatusCode::BAD_REQUEST, &body, Some("from-header"))
+    else {
+        panic!("expected an API error");
+    };
+    assert_eq!(request_id.as_deref(), Some("from-body"));
+
+    let NotionError::Api { request_id, .. } =
+        NotionError::from_response(StatusCode::BAD_REQUEST, &Value::Null, Some("from-header"))
+    else {
+        panic!("expected an API error");
+    };
+    assert_eq!(request_id.as_deref(), Some("from-header"));
+}
+
+#[test]
+fn test_kind_found_under_context() {
+    let error = anyhow::Error::new(api(401)).context("Failed to load page");
+    assert_eq!(kind(&error), ErrorKind::Auth);
+
+    let result: anyhow::Result<()> = Err(NotionError::Input("bad".into()).into());
+    let error = result.context("Reading arguments").unwrap_err();
+    assert_eq!(kind(&error), ErrorKind::Input);
+}
+
+#[test]
+fn test_untyped_error_is_general() {
+    let error = anyhow::anyhow!("something else");
+    assert_eq!(kind(&error), ErrorKind::General);
+    assert_eq!(kind(&error).exit_code(), 1);
+}
+
+#[test]
+fn test_bail_input() {
+    fn check(value: u32) -> anyhow::Result<()> {
+        if value > 1 {
+            bail_input!("{} is too large", value);
+        }
+        Ok(())
+    }
+    let error = check(2).unwrap_err();
+    assert_eq!(error.to_string(), "2 is too large");
+    assert_eq!(kind(&error), ErrorKind::Input);
+}
+
+#[test]
+fn test_to_json_api_error() {
+    let body = json!({"code": "object_not_found", "message": "Could not find page.", "request_id": "req-1"});
+    let error: anyhow::Error =
+        NotionError::from_response(StatusCode::NOT_FOUND, &body, None).into();
+    let report = to_json(&error);
+    assert_eq!(report["error"]["kind"], "not_found");
+    assert_eq!(report["error"]["exit_code"], 4);
+    assert_eq!(report["error"]["status"], 404);
+    assert_eq!(report["error"]["code"], "object_not_found");
+    assert_eq!(report["error"]["request_id"], "req-1");
+    assert!(
+        report["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Could not find page.")
+    );
+    assert!(report["error"].get("causes").is_none());
+}
+
+#[test]
+fn test_to_json_lists_causes() {
+    let error = anyhow::Error::new(api(400)).context("Failed to create page");
+    let report = to_json(&error);
+    assert_eq!(report["error"]["message"], "Failed to create page");
+    assert_eq!(report["error"]["kind"], "validation");
+    assert_eq!(report["error"]["code"], "some_code");
+    let causes = report["error"]["causes"].as_array().unwrap();
+    assert_eq!(causes.len(), 1);
+    assert!(causes[0].as_str().unwrap().contains("Some message"));
+}
+
+#[test]
+fn test_to_json_general_error() {
+    let report = to_json(&anyhow::anyhow!("boom"));
+    assert_eq!(
+        report,
+        json!({"error": {"kind": "general", "exit_code": 1, "message": "boom"}})
+    );
+}
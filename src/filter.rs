@@ -0,0 +1,747 @@
+//! The `--where` expression language for data source queries.
+//!
+//! An expression such as
+//!
+//! ```text
+//! Status = "Done" and (Due < today or Tags contains infra)
+//! ```
+//!
+//! is parsed into an [`Expr`] and compiled into Notion filter JSON. The data
+//! source schema decides which filter each condition becomes: `Status` above
+//! compiles to a `status` filter, `Due` to a `date` filter and so on, so the
+//! same operators work across property types.
+
+use anyhow::{Result, bail};
+use indexmap::IndexMap;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::{NotionError, bail_input};
+use crate::ids::parse_id;
+use crate::models::{PropertyConfig, PropertySchema};
+use crate::properties::{self, civil_from_days};
+
+/// A parsed `--where` expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Condition(Condition),
+}
+
+/// One comparison, e.g. `Due < today`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    /// Property name or ID, or `created_time`/`last_edited_time`
+    pub property: String,
+    pub op: Op,
+    /// Nothing for `is empty`, several values for `in (...)`
+    pub values: Vec<Literal>,
+}
+
+/// A value as written in the expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Literal {
+    pub text: String,
+    /// Quoted values are always compared as text, e.g. `Code = "123"`
+    pub quoted: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    NotContains,
+    StartsWith,
+    EndsWith,
+    IsEmpty,
+    IsNotEmpty,
+    /// `is past_week` and the other relative date ranges
+    Within,
+    In,
+    NotIn,
+}
+
+impl Op {
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Eq => "=",
+            Op::Ne => "!=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Contains => "contains",
+            Op::NotContains => "not contains",
+            Op::StartsWith => "starts with",
+            Op::EndsWith => "ends with",
+            Op::IsEmpty => "is empty",
+            Op::IsNotEmpty => "is not empty",
+            Op::Within => "is <range>",
+            Op::In => "in",
+            Op::NotIn => "not in",
+        }
+    }
+}
+
+/// Relative date ranges for `is`, named as in the API.
+const DATE_RANGES: &[&str] = &[
+    "past_week",
+    "past_month",
+    "past_year",
+    "this_week",
+    "next_week",
+    "next_month",
+    "next_year",
+];
+
+const TEXT_OPS: &[(Op, &str)] = &[
+    (Op::Eq, "equals"),
+    (Op::Ne, "does_not_equal"),
+    (Op::Contains, "contains"),
+    (Op::NotContains, "does_not_contain"),
+    (Op::StartsWith, "starts_with"),
+    (Op::EndsWith, "ends_with"),
+    (Op::IsEmpty, "is_empty"),
+    (Op::IsNotEmpty, "is_not_empty"),
+];
+
+const NUMBER_OPS: &[(Op, &str)] = &[
+    (Op::Eq, "equals"),
+    (Op::Ne, "does_not_equal"),
+    (Op::Gt, "greater_than"),
+    (Op::Lt, "less_than"),
+    (Op::Ge, "greater_than_or_equal_to"),
+    (Op::Le, "less_than_or_equal_to"),
+    (Op::IsEmpty, "is_empty"),
+    (Op::IsNotEmpty, "is_not_empty"),
+];
+
+const CHECKBOX_OPS: &[(Op, &str)] = &[(Op::Eq, "equals"), (Op::Ne, "does_not_equal")];
+
+const OPTION_OPS: &[(Op, &str)] = &[
+    (Op::Eq, "equals"),
+    (Op::Ne, "does_not_equal"),
+    (Op::IsEmpty, "is_empty"),
+    (Op::IsNotEmpty, "is_not_empty"),
+];
+
+/// Multi-select, people and relation properties.
+const LIST_OPS: &[(Op, &str)] = &[
+    (Op::Contains, "contains"),
+    (Op::NotContains, "does_not_contain"),
+    (Op::IsEmpty, "is_empty"),
+    (Op::IsNotEmpty, "is_not_empty"),
+];
+
+const DATE_OPS: &[(Op, &str)] = &[
+    (Op::Eq, "equals"),
+    (Op::Lt, "before"),
+    (Op::Gt, "after"),
+    (Op::Le, "on_or_before"),
+    (Op::Ge, "on_or_after"),
+    (Op::IsEmpty, "is_empty"),
+    (Op::IsNotEmpty, "is_not_empty"),
+    (Op::Within, ""),
+];
+
+const FILES_OPS: &[(Op, &str)] = &[(Op::IsEmpty, "is_empty"), (Op::IsNotEmpty, "is_not_empty")];
+
+/// Parse a `--where` expression. `and` binds tighter than `or`; parentheses
+/// group.
+pub fn parse(text: &str) -> Result<Expr> {
+    let tokens = tokenize(text)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end: text.chars().count() + 1,
+    };
+    let expr = parser.or()?;
+    match parser.peek() {
+        Some(token) => parser.fail(token.column, format!("unexpected '{}'", token.text)),
+        None => Ok(expr),
+    }
+}
+
+impl Expr {
+    /// Compile to a Notion filter, looking properties up in `schema`.
+    /// People may be given by email, resolved through `people`.
+    pub fn compile(
+        &self,
+        schema: &IndexMap<String, PropertySchema>,
+        people: &HashMap<String, String>,
+    ) -> Result<Value> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        self.compile_on(schema, people, now / 86400)
+    }
+
+    /// Whether a people property is compared with an email, so workspace
+    /// users need looking up.
+    pub fn mentions_email(&self, schema: &IndexMap<String, PropertySchema>) -> bool {
+        match self {
+            Expr::And(exprs) | Expr::Or(exprs) => exprs.iter().any(|e| e.mentions_email(schema)),
+            Expr::Condition(c) => {
+                let people = properties::find_property(schema, &c.property).is_ok_and(|(_, p)| {
+                    matches!(
+                        p.config,
+                        PropertyConfig::People(_)
+                            | PropertyConfig::CreatedBy(_)
+                            | PropertyConfig::LastEditedBy(_)
+                    )
+                });
+                people && c.values.iter().any(|v| v.text.contains('@'))
+            }
+        }
+    }
+
+    /// Compile with `today` given as days since 1970-01-01 (UTC).
+    fn compile_on(
+        &self,
+        schema: &IndexMap<String, PropertySchema>,
+        people: &HashMap<String, String>,
+        today: u64,
+    ) -> Result<Value> {
+        let compile_all = |exprs: &[Expr]| {
+            exprs
+                .iter()
+                .map(|e| e.compile_on(schema, people, today))
+                .collect::<Result<Vec<_>>>()
+        };
+        match self {
+            Expr::And(exprs) => Ok(json!({ "and": compile_all(exprs)? })),
+            Expr::Or(exprs) => Ok(json!({ "or": compile_all(exprs)? })),
+            Expr::Condition(condition) => {
+                let compiler = Compiler {
+                    people,
+                    today,
+                    condition,
+                };
+                compiler.compile(schema)
+            }
+        }
+    }
+}
+
+struct Compiler<'a> {
+    people: &'a HashMap<String, String>,
+    today: u64,
+    condition: &'a Condition,
+}
+
+impl Compiler<'_> {
+    fn compile(&self, schema: &IndexMap<String, PropertySchema>) -> Result<Value> {
+        let c = self.condition;
+        let found = properties::find_property(schema, &c.property);
+        let (name, property) = match found {
+            Ok(found) => found,
+            Err(_) if matches!(c.property.as_str(), "created_time" | "last_edited_time") => {
+                return self
+                    .expand(false, |op, value| {
+                        let value = value.map(|v| v.text.as_str());
+                        let condition = self.condition(DATE_OPS, op, value, |v| self.date(v))?;
+                        Ok(json!({ "timestamp": c.property, &c.property: condition }))
+                    })
+                    .map_err(|e| input_error(&c.property, e));
+            }
+            Err(e) => return Err(e),
+        };
+        let kind = property.config.kind();
+        let several = matches!(
+            property.config,
+            PropertyConfig::MultiSelect(_)
+                | PropertyConfig::People(_)
+                | PropertyConfig::CreatedBy(_)
+                | PropertyConfig::LastEditedBy(_)
+                | PropertyConfig::Relation(_)
+        );
+        self.expand(several, |op, value| {
+            let condition = self.property_condition(property, op, value)?;
+            Ok(json!({ "property": name, kind: condition }))
+        })
+        .map_err(|e| input_error(name, e))
+    }
+
+    /// Run `build` once per value, joining the results for `in` and `not in`.
+    /// Those compare with `=`/`!=`, or `contains`/`not contains` when the
+    /// property holds `several` values.
+    fn expand(
+        &self,
+        several: bool,
+        build: impl Fn(Op, Option<&Literal>) -> Result<Value>,
+    ) -> Result<Value> {
+        let c = self.condition;
+        let (op, join) = match (c.op, several) {
+            (Op::In, false) => (Op::Eq, "or"),
+            (Op::In, true) => (Op::Contains, "or"),
+            (Op::NotIn, false) => (Op::Ne, "and"),
+            (Op::NotIn, true) => (Op::NotContains, "and"),
+            (op, _) => return build(op, c.values.first()),
+        };
+        let filters = c
+            .values
+            .iter()
+            .map(|v| build(op, Some(v)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(match filters.len() {
+            1 => filters.into_iter().next().unwrap_or_default(),
+            _ => json!({ join: filters }),
+        })
+    }
+
+    /// The `{operator: value}` object for a property of this type.
+    fn property_condition(
+        &self,
+        property: &PropertySchema,
+        op: Op,
+        literal: Option<&Literal>,
+    ) -> Result<Value> {
+        let value = literal.map(|l| l.text.as_str());
+        let options = |v: &str| -> Result<Value> {
+            properties::known_option(&serde_json::to_value(property)?, v)?;
+            Ok(json!(v))
+        };
+        match &property.config {
+            PropertyConfig::Title(_)
+            | PropertyConfig::RichText(_)
+            | PropertyConfig::Url(_)
+            | PropertyConfig::Email(_)
+            | PropertyConfig::PhoneNumber(_) => self.condition(TEXT_OPS, op, value, text),
+            PropertyConfig::Number(_) => {
+                self.condition(NUMBER_OPS, op, value, properties::parse_number)
+            }
+            PropertyConfig::UniqueId(_) => self.condition(NUMBER_OPS, op, value, unique_id),
+            PropertyConfig::Checkbox(_) => self.condition(CHECKBOX_OPS, op, value, checkbox),
+            PropertyConfig::Select(_) | PropertyConfig::Status(_) => {
+                self.condition(OPTION_OPS, op, value, options)
+            }
+            PropertyConfig::MultiSelect(_) => {
+                let ops = list_ops(op, LIST_OPS)?;
+                self.condition(ops, op, value, options)
+            }
+            PropertyConfig::Date(_)
+            | PropertyConfig::CreatedTime(_)
+            | PropertyConfig::LastEditedTime(_) => {
+                self.condition(DATE_OPS, op, value, |v| self.date(v))
+            }
+            PropertyConfig::People(_)
+            | PropertyConfig::CreatedBy(_)
+            | PropertyConfig::LastEditedBy(_) => {
+                let ops = list_ops(op, LIST_OPS)?;
+                self.condition(ops, op, value, |v| self.person(v))
+            }
+            PropertyConfig::Relation(_) => {
+                let ops = list_ops(op, LIST_OPS)?;
+                self.condition(ops, op, value, relation)
+            }
+            PropertyConfig::Files(_) => self.condition(FILES_OPS, op, value, text),
+            PropertyConfig::Formula(_) => self.formula(op, literal),
+            _ => bail!(
+                "{} properties can't be filtered with --where; use --filter",
+                property.config.kind()
+            ),
+        }
+    }
+
+    /// `{operator: value}` with the operator from `ops` and the value
+    /// converted by `convert`.
+    fn condition(
+        &self,
+        ops: &[(Op, &str)],
+        op: Op,
+        value: Option<&str>,
+        convert: impl Fn(&str) -> Result<Value>,
+    ) -> Result<Value> {
+        let Some((_, key)) = ops.iter().find(|(o, _)| *o == op) else {
+            let allowed: Vec<&str> = ops.iter().map(|(o, _)| o.symbol()).collect();
+            bail!(
+                "'{}' doesn't apply here (use {})",
+                op.symbol(),
+                allowed.join(", ")
+            );
+        };
+        Ok(match (op, value) {
+            (Op::IsEmpty | Op::IsNotEmpty, _) => json!({ *key: true }),
+            (Op::Within, Some(range)) => json!({ range: {} }),
+            (_, Some(value)) => json!({ *key: convert(value)? }),
+            (_, None) => bail!("'{}' needs a value", op.symbol()),
+        })
+    }
+
+    /// Formula results are typed by the value compared against: numbers,
+    /// `true`/`false`, dates, or text. Quoted values are always text.
+    fn formula(&self, op: Op, literal: Option<&Literal>) -> Result<Value> {
+        let value = literal.map(|l| l.text.as_str());
+        let value_kind = match value {
+            _ if op == Op::Within => "date",
+            _ if literal.is_some_and(|l| l.quoted) => "string",
+            Some(v) if properties::parse_number(v).is_ok() => "number",
+            Some("true" | "false") => "checkbox",
+            Some(v) if self.date(v).is_ok() => "date",
+            _ => "string",
+        };
+        let condition = match value_kind {
+            "number" => self.condition(NUMBER_OPS, op, value, properties::parse_number)?,
+            "checkbox" => self.condition(CHECKBOX_OPS, op, value, checkbox)?,
+            "date" => self.condition(DATE_OPS, op, value, |v| self.date(v))?,
+            _ => self.condition(TEXT_OPS, op, value, text)?,
+        };
+        Ok(json!({ value_kind: condition }))
+    }
+
+    /// An ISO 8601 date, or `today`, `yesterday`, `tomorrow` and
+    /// `today+N`/`today-N` (days, UTC).
+    fn date(&self, value: &str) -> Result<Value> {
+        if properties::is_iso_date(value) {
+            return Ok(json!(value));
+        }
+        let offset = match value.to_lowercase().as_str() {
+            "today" => Some(0),
+            "yesterday" => Some(-1),
+            "tomorrow" => Some(1),
+            v => v
+                .strip_prefix("today")
+                .filter(|n| n.starts_with(['+', '-']))
+                .and_then(|n| n.parse::<i64>().ok()),
+        };
+        let day = offset.and_then(|days| self.today.checked_add_signed(days));
+        match day {
+            Some(day) => {
+                let (year, month, day) = civil_from_days(day);
+                Ok(json!(format!("{:04}-{:02}-{:02}", year, month, day)))
+            }
+            None => bail!(
+                "'{}' is not a date (use YYYY-MM-DD, today, yesterday, tomorrow or today+N)",
+                value
+            ),
+        }
+    }
+
+    fn person(&self, value: &str) -> Result<Value> {
+        let who = value.strip_prefix('@').unwrap_or(value);
+        Ok(json!(properties::resolve_person(who, self.people)?))
+    }
+}
+
+/// Guide `=` towards `contains` on properties holding several values.
+fn list_ops(op: Op, ops: &'static [(Op, &'static str)]) -> Result<&'static [(Op, &'static str)]> {
+    match op {
+        Op::Eq | Op::Ne => bail!(
+            "'{}' doesn't apply to properties with several values (use contains or not contains)",
+            op.symbol()
+        ),
+        _ => Ok(ops),
+    }
+}
+
+fn text(value: &str) -> Result<Value> {
+    Ok(json!(value))
+}
+
+fn checkbox(value: &str) -> Result<Value> {
+    Ok(json!(properties::parse_checkbox(value)?))
+}
+
+/// The number of a unique ID, with or without its prefix (`TASK-12`).
+fn unique_id(value: &str) -> Result<Value> {
+    let number = value.rsplit('-').next().unwrap_or(value);
+    match number.parse::<u64>() {
+        Ok(n) => Ok(json!(n)),
+        Err(_) => bail!("'{}' is not a unique ID", value),
+    }
+}
+
+fn relation(value: &str) -> Result<Value> {
+    parse_id(value)
+        .map(Value::String)
+        .map_err(anyhow::Error::msg)
+}
+
+fn input_error(property: &str, error: anyhow::Error) -> anyhow::Error {
+    match crate::error::find(&error) {
+        Some(NotionError::Input(_)) => error,
+        _ => NotionError::Input(format!("{}: {:#}", property, error)).into(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Word,
+    /// A quoted string, with quotes and escapes removed
+    Quoted,
+    Symbol,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    text: String,
+    /// 1-based character position in the expression
+    column: usize,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            let mut value = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => bail_input!("Invalid --where at column {}: unclosed {}", column, c),
+                    Some('\\') if i + 1 < chars.len() => {
+                        value.push(chars[i + 1]);
+                        i += 2;
+                    }
+                    Some(q) if *q == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some(other) => {
+                        value.push(*other);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push(Token {
+                kind: TokenKind::Quoted,
+                text: value,
+                column,
+            });
+        } else if "()=,<>!".contains(c) {
+            let two: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let symbol = match two.as_str() {
+                "!=" | "<=" | ">=" | "==" => two,
+                _ if c == '!' => {
+                    bail_input!("Invalid --where at column {}: expected '!='", column)
+                }
+                _ => c.to_string(),
+            };
+            i += symbol.chars().count();
+            tokens.push(Token {
+                kind: TokenKind::Symbol,
+                text: symbol,
+                column,
+            });
+        } else {
+            let start = i;
+            while i < chars.len() && !chars[i].is_whitespace() && !"()=,<>!\"'".contains(chars[i]) {
+                i += 1;
+            }
+            tokens.push(Token {
+                kind: TokenKind::Word,
+                text: chars[start..i].iter().collect(),
+                column,
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Column just past the end, for errors at the end of input
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn column(&self) -> usize {
+        self.peek().map_or(self.end, |t| t.column)
+    }
+
+    fn fail<T>(&self, column: usize, message: impl std::fmt::Display) -> Result<T> {
+        bail_input!("Invalid --where at column {}: {}", column, message)
+    }
+
+    /// Consume a keyword (case-insensitive) if it comes next.
+    fn keyword(&mut self, word: &str) -> bool {
+        let found = self
+            .peek()
+            .is_some_and(|t| t.kind == TokenKind::Word && t.text.eq_ignore_ascii_case(word));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn symbol(&mut self, symbol: &str) -> bool {
+        let found = self
+            .peek()
+            .is_some_and(|t| t.kind == TokenKind::Symbol && t.text == symbol);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut exprs = Vec::new();
+        loop {
+            match self.and()? {
+                Expr::Or(inner) => exprs.extend(inner),
+                expr => exprs.push(expr),
+            }
+            if !self.keyword("or") {
+                return Ok(group(exprs, Expr::Or));
+            }
+        }
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut exprs = Vec::new();
+        loop {
+            match self.primary()? {
+                Expr::And(inner) => exprs.extend(inner),
+                expr => exprs.push(expr),
+            }
+            if !self.keyword("and") {
+                return Ok(group(exprs, Expr::And));
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        if self.symbol("(") {
+            let expr = self.or()?;
+            if !self.symbol(")") {
+                return self.fail(self.column(), "expected ')'");
+            }
+            return Ok(expr);
+        }
+        self.condition().map(Expr::Condition)
+    }
+
+    fn condition(&mut self) -> Result<Condition> {
+        let column = self.column();
+        let property = match self.next() {
+            Some(t) if t.kind != TokenKind::Symbol => t.text,
+            _ => return self.fail(column, "expected a property name"),
+        };
+
+        let column = self.column();
+        let op = match self.next() {
+            Some(t) if t.kind == TokenKind::Symbol => match t.text.as_str() {
+                "=" | "==" => Op::Eq,
+                "!=" => Op::Ne,
+                "<" => Op::Lt,
+                "<=" => Op::Le,
+                ">" => Op::Gt,
+                ">=" => Op::Ge,
+                _ => return self.fail(column, format!("expected an operator after {}", property)),
+            },
+            Some(t) if t.kind == TokenKind::Word => match t.text.to_lowercase().as_str() {
+                "contains" => Op::Contains,
+                "in" => Op::In,
+                "starts" if self.keyword("with") => Op::StartsWith,
+                "ends" if self.keyword("with") => Op::EndsWith,
+                "not" if self.keyword("contains") => Op::NotContains,
+                "not" if self.keyword("in") => Op::NotIn,
+                "is" if self.keyword("empty") => Op::IsEmpty,
+                "is" if self.keyword("not") => {
+                    if !self.keyword("empty") {
+                        return self.fail(self.column(), "expected 'empty' after 'is not'");
+                    }
+                    Op::IsNotEmpty
+                }
+                "is" => Op::Within,
+                _ => return self.fail(column, format!("unknown operator '{}'", t.text)),
+            },
+            _ => return self.fail(column, format!("expected an operator after {}", property)),
+        };
+
+        let values = match op {
+            Op::IsEmpty | Op::IsNotEmpty => Vec::new(),
+            Op::In | Op::NotIn => self.list()?,
+            Op::Within => {
+                let column = self.column();
+                match self.next() {
+                    Some(t) if DATE_RANGES.contains(&t.text.to_lowercase().as_str()) => {
+                        vec![Literal {
+                            text: t.text.to_lowercase(),
+                            quoted: false,
+                        }]
+                    }
+                    _ => {
+                        return self.fail(
+                            column,
+                            format!(
+                                "expected empty, not empty or one of {} after 'is'",
+                                DATE_RANGES.join(", ")
+                            ),
+                        );
+                    }
+                }
+            }
+            _ => vec![self.value(op)?],
+        };
+        Ok(Condition {
+            property,
+            op,
+            values,
+        })
+    }
+
+    fn value(&mut self, op: Op) -> Result<Literal> {
+        let column = self.column();
+        match self.next() {
+            Some(t) if t.kind != TokenKind::Symbol => Ok(Literal {
+                quoted: t.kind == TokenKind::Quoted,
+                text: t.text,
+            }),
+            _ => self.fail(column, format!("expected a value after '{}'", op.symbol())),
+        }
+    }
+
+    /// `(a, b, ...)`
+    fn list(&mut self) -> Result<Vec<Literal>> {
+        if !self.symbol("(") {
+            return self.fail(self.column(), "expected '(' to start a list");
+        }
+        let mut values = vec![self.value(Op::In)?];
+        while self.symbol(",") {
+            values.push(self.value(Op::In)?);
+        }
+        if !self.symbol(")") {
+            return self.fail(self.column(), "expected ',' or ')'");
+        }
+        Ok(values)
+    }
+}
+
+/// A single expression as itself, several as a group. Nested groups of the
+/// same kind were already merged in by the parser.
+fn group(mut exprs: Vec<Expr>, make: fn(Vec<Expr>) -> Expr) -> Expr {
+    match exprs.len() {
+        1 => exprs.remove(0),
+        _ => make(exprs),
+    }
+}
+
+#[cfg(test)]
+#[path = "filter_tests.rs"]
+mod tests;
@@ -0,0 +1,341 @@
+use super::*;
+use serde_json::json;
+
+/// 2026-10-18
+const TODAY: u64 = 20744;
+
+fn schema() -> IndexMap<String, PropertySchema> {
+    serde_json::from_value(json!({
+        "Name": { "id": "title", "type": "title", "title": {} },
+        "Status": {
+            "id": "st",
+            "type": "status",
+            "status": { "options": [{ "name": "Todo" }, { "name": "Done" }] }
+        },
+        "Priority": {
+            "id": "pr",
+            "type": "select",
+            "select": { "options": [{ "name": "High" }, { "name": "Low" }] }
+        },
+        "Tags": {
+            "id": "tg",
+            "type": "multi_select",
+            "multi_select": { "options": [{ "name": "infra" }, { "name": "docs" }] }
+        },
+        "Due": { "id": "du", "type": "date", "date": {} },
+        "Estimate": { "id": "es", "type": "number", "number": { "format": "number" } },
+        "Done?": { "id": "dn", "type": "checkbox", "checkbox": {} },
+        "Owner": { "id": "ow", "type": "people", "people": {} },
+        "Project": { "id": "pj", "type": "relation", "relation": { "data_source_id": "ds-2" } },
+        "Score": { "id": "sc", "type": "formula", "formula": { "expression": "1" } },
+        "Task ID": { "id": "ti", "type": "unique_id", "unique_id": { "prefix": "TASK" } },
+        "Total": { "id": "to", "type": "rollup", "rollup": {} }
+    }))
+    .unwrap()
+}
+
+fn compile(text: &str) -> Result<Value> {
+    let people = HashMap::from([("alice@example.com".to_string(), "user-1".to_string())]);
+    parse(text)?.compile_on(&schema(), &people, TODAY)
+}
+
+/// A condition with `values`; a leading `"` marks a quoted value.
+fn condition(property: &str, op: Op, values: &[&str]) -> Expr {
+    let literal = |v: &&str| match v.strip_prefix('"') {
+        Some(text) => Literal {
+            text: text.to_string(),
+            quoted: true,
+        },
+        None => Literal {
+            text: v.to_string(),
+            quoted: false,
+        },
+    };
+    Expr::Condition(Condition {
+        property: property.to_string(),
+        op,
+        values: values.iter().map(literal).collect(),
+    })
+}
+
+#[test]
+fn test_parse_precedence_and_groups() {
+    let expr = parse("A = 1 or B = 2 and (C = 3 or D = 4)").unwrap();
+    assert_eq!(
+        expr,
+        Expr::Or(vec![
+            condition("A", Op::Eq, &["1"]),
+            Expr::And(vec![
+                condition("B", Op::Eq, &["2"]),
+                Expr::Or(vec![
+                    condition("C", Op::Eq, &["3"]),
+                    condition("D", Op::Eq, &["4"]),
+                ]),
+            ]),
+        ])
+    );
+}
+
+#[test]
+fn test_parse_flattens_groups_of_the_same_kind() {
+    let expr = parse("A = 1 AND (B = 2 and C = 3)").unwrap();
+    assert_eq!(
+        expr,
+        Expr::And(vec![
+            condition("A", Op::Eq, &["1"]),
+            condition("B", Op::Eq, &["2"]),
+            condition("C", Op::Eq, &["3"]),
+        ])
+    );
+}
+
+#[test]
+fn test_parse_operators() {
+    let cases = [
+        ("A != x", Op::Ne),
+        ("A == x", Op::Eq),
+        ("A <= x", Op::Le),
+        ("A>x", Op::Gt),
+        ("A not contains x", Op::NotContains),
+        ("A starts with x", Op::StartsWith),
+        ("A ENDS WITH x", Op::EndsWith),
+    ];
+    for (text, op) in cases {
+        assert_eq!(parse(text).unwrap(), condition("A", op, &["x"]), "{}", text);
+    }
+    assert_eq!(
+        parse("A is not empty").unwrap(),
+        condition("A", Op::IsNotEmpty, &[])
+    );
+    assert_eq!(
+        parse("A is Next_Week").unwrap(),
+        condition("A", Op::Within, &["next_week"])
+    );
+    assert_eq!(
+        parse("A not in (x, 'y z')").unwrap(),
+        condition("A", Op::NotIn, &["x", "\"y z"])
+    );
+}
+
+#[test]
+fn test_parse_quoted_names_and_values() {
+    assert_eq!(
+        parse(r#""Due date" = 'it\'s "done"'"#).unwrap(),
+        condition("Due date", Op::Eq, &[r#""it's "done""#])
+    );
+}
+
+#[test]
+fn test_parse_errors_report_column() {
+    let cases = [
+        ("Status =", "column 9: expected a value after '='"),
+        ("Status Done", "column 8: unknown operator 'Done'"),
+        ("(A = 1", "column 7: expected ')'"),
+        ("A = 1 B = 2", "column 7: unexpected 'B'"),
+        ("A = \"open", "column 5: unclosed \""),
+        (
+            "A is soon",
+            "column 6: expected empty, not empty or one of past_week",
+        ),
+        ("A in x", "column 6: expected '(' to start a list"),
+        ("= 1", "column 1: expected a property name"),
+    ];
+    for (text, message) in cases {
+        let err = parse(text).unwrap_err();
+        assert!(err.to_string().contains(message), "{}: {}", text, err);
+        assert!(crate::error::find(&err).is_some());
+    }
+}
+
+#[test]
+fn test_compile_request_example() {
+    let filter = compile(r#"Status = "Done" and Due < today and Tags contains "infra""#).unwrap();
+    assert_eq!(
+        filter,
+        json!({ "and": [
+            { "property": "Status", "status": { "equals": "Done" } },
+            { "property": "Due", "date": { "before": "2026-10-18" } },
+            { "property": "Tags", "multi_select": { "contains": "infra" } }
+        ] })
+    );
+}
+
+#[test]
+fn test_compile_picks_operator_per_type() {
+    let cases = [
+        (
+            "name contains launch",
+            json!({ "property": "Name", "title": { "contains": "launch" } }),
+        ),
+        (
+            "Estimate >= 3",
+            json!({ "property": "Estimate", "number": { "greater_than_or_equal_to": 3 } }),
+        ),
+        (
+            "Done? = yes",
+            json!({ "property": "Done?", "checkbox": { "equals": true } }),
+        ),
+        (
+            "Priority != High",
+            json!({ "property": "Priority", "select": { "does_not_equal": "High" } }),
+        ),
+        (
+            "Due is empty",
+            json!({ "property": "Due", "date": { "is_empty": true } }),
+        ),
+        (
+            "Due is past_week",
+            json!({ "property": "Due", "date": { "past_week": {} } }),
+        ),
+        (
+            "Owner contains @alice@example.com",
+            json!({ "property": "Owner", "people": { "contains": "user-1" } }),
+        ),
+        (
+            "Project contains 59833787-2cf9-4fdf-8782-e53db20768a5",
+            json!({ "property": "Project", "relation": { "contains": "59833787-2cf9-4fdf-8782-e53db20768a5" } }),
+        ),
+        (
+            "'Task ID' = TASK-12",
+            json!({ "property": "Task ID", "unique_id": { "equals": 12 } }),
+        ),
+    ];
+    for (text, expected) in cases {
+        assert_eq!(compile(text).unwrap(), expected, "{}", text);
+    }
+}
+
+#[test]
+fn test_compile_formula_by_value() {
+    assert_eq!(
+        compile("Score > 2").unwrap(),
+        json!({ "property": "Score", "formula": { "number": { "greater_than": 2 } } })
+    );
+    assert_eq!(
+        compile("Score = true").unwrap(),
+        json!({ "property": "Score", "formula": { "checkbox": { "equals": true } } })
+    );
+    assert_eq!(
+        compile("Score <= tomorrow").unwrap(),
+        json!({ "property": "Score", "formula": { "date": { "on_or_before": "2026-10-19" } } })
+    );
+    assert_eq!(
+        compile("Score contains ok").unwrap(),
+        json!({ "property": "Score", "formula": { "string": { "contains": "ok" } } })
+    );
+}
+
+#[test]
+fn test_compile_formula_quoted_value_is_text() {
+    assert_eq!(
+        compile(r#"Score = "123""#).unwrap(),
+        json!({ "property": "Score", "formula": { "string": { "equals": "123" } } })
+    );
+    assert_eq!(
+        compile("Score in ('true', 2)").unwrap(),
+        json!({ "or": [
+            { "property": "Score", "formula": { "string": { "equals": "true" } } },
+            { "property": "Score", "formula": { "number": { "equals": 2 } } }
+        ] })
+    );
+}
+
+#[test]
+fn test_compile_dates() {
+    let start = |text: &str| compile(text).unwrap()["date"].clone();
+    assert_eq!(start("Due = yesterday"), json!({ "equals": "2026-10-17" }));
+    assert_eq!(start("Due > today+14"), json!({ "after": "2026-11-01" }));
+    assert_eq!(
+        start("Due >= today-18"),
+        json!({ "on_or_after": "2026-09-30" })
+    );
+    assert_eq!(
+        start("Due = 2026-11-01T09:00:00Z"),
+        json!({ "equals": "2026-11-01T09:00:00Z" })
+    );
+    let err = compile("Due < next tuesday").unwrap_err();
+    assert!(err.to_string().contains("unexpected 'tuesday'"));
+    let err = compile("Due < soon").unwrap_err();
+    assert!(err.to_string().starts_with("Due: 'soon' is not a date"));
+}
+
+#[test]
+fn test_compile_timestamps() {
+    assert_eq!(
+        compile("created_time > 2026-01-01 or last_edited_time is this_week").unwrap(),
+        json!({ "or": [
+            { "timestamp": "created_time", "created_time": { "after": "2026-01-01" } },
+            { "timestamp": "last_edited_time", "last_edited_time": { "this_week": {} } }
+        ] })
+    );
+}
+
+#[test]
+fn test_compile_in_lists() {
+    assert_eq!(
+        compile("Status in (Todo, Done)").unwrap(),
+        json!({ "or": [
+            { "property": "Status", "status": { "equals": "Todo" } },
+            { "property": "Status", "status": { "equals": "Done" } }
+        ] })
+    );
+    assert_eq!(
+        compile("Tags not in (infra, docs)").unwrap(),
+        json!({ "and": [
+            { "property": "Tags", "multi_select": { "does_not_contain": "infra" } },
+            { "property": "Tags", "multi_select": { "does_not_contain": "docs" } }
+        ] })
+    );
+    assert_eq!(
+        compile("Priority in (High)").unwrap(),
+        json!({ "property": "Priority", "select": { "equals": "High" } })
+    );
+}
+
+#[test]
+fn test_compile_nested_groups() {
+    let filter = compile("(Status = Done or Priority = High) and Estimate < 5").unwrap();
+    assert_eq!(
+        filter,
+        json!({ "and": [
+            { "or": [
+                { "property": "Status", "status": { "equals": "Done" } },
+                { "property": "Priority", "select": { "equals": "High" } }
+            ] },
+            { "property": "Estimate", "number": { "less_than": 5 } }
+        ] })
+    );
+}
+
+#[test]
+fn test_compile_errors_suggest() {
+    let err = compile("Stauts = Done").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown property 'Stauts' (did you mean 'Status'?)"
+    );
+    let err = compile("Status = Dne").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Status: Unknown status option 'Dne' (did you mean 'Done'?)"
+    );
+    let err = compile("Tags = infra").unwrap_err();
+    assert!(err.to_string().contains("use contains or not contains"));
+    let err = compile("Estimate contains 3").unwrap_err();
+    assert!(err.to_string().contains("(use =, !=, >, <, >=, <="));
+    let err = compile("Owner contains bob@example.com").unwrap_err();
+    assert!(err.to_string().contains("No workspace user"));
+    let err = compile("Total > 3").unwrap_err();
+    assert!(err.to_string().contains("use --filter"));
+    assert!(crate::error::find(&err).is_some());
+}
+
+#[test]
+fn test_mentions_email_only_for_people_properties() {
+    let schema = schema();
+    let mentions = |text: &str| parse(text).unwrap().mentions_email(&schema);
+    assert!(mentions("Estimate = 1 and (Owner contains x@y.z)"));
+    assert!(!mentions("Owner is not empty"));
+    assert!(!mentions("Name contains x@y.z"));
+    assert!(!mentions("Missing contains x@y.z"));
+}
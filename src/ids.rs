@@ -0,0 +1,108 @@
+/// Resolve a Notion ID from an ID or a URL copied from the Notion app.
+///
+/// Accepts dashed or undashed IDs, `Title-<id>` slugs and notion.so /
+/// notion.site / notion.com URLs. In URLs, a `#<id>` block anchor wins over
+/// a `?p=<id>` peek link, which wins over the ID at the end of the path
+/// (so a database view link resolves to the database, not `?v=`). Returns
+/// the dashed, lowercase form. Used as a clap `value_parser`.
+pub fn parse_id(input: &str) -> Result<String, String> {
+    let input = input.trim();
+    let id = if is_url(input) {
+        id_from_url(input)?
+    } else {
+        trailing_id(input).ok_or_else(|| {
+            format!(
+                "Invalid Notion ID '{}': expected 32 hex digits or a Notion URL",
+                input
+            )
+        })?
+    };
+    Ok(dashed(&id))
+}
+
+fn is_url(input: &str) -> bool {
+    input.contains("://")
+        || [
+            "notion.so/",
+            "www.notion.so/",
+            "notion.com/",
+            "www.notion.com/",
+        ]
+        .iter()
+        .any(|prefix| input.starts_with(prefix))
+        || input
+            .split_once('/')
+            .is_some_and(|(host, _)| host.to_lowercase().ends_with(".notion.site"))
+}
+
+fn id_from_url(url: &str) -> Result<String, String> {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let (host, rest) = rest.split_once('/').unwrap_or((rest, ""));
+    let host = host.to_lowercase();
+    let host = host.rsplit_once('@').map(|(_, h)| h).unwrap_or(&host);
+    let host = host.split(':').next().unwrap_or_default();
+    let is_notion = ["notion.so", "notion.site", "notion.com"]
+        .iter()
+        .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)));
+    if !is_notion {
+        return Err(format!("'{}' is not a Notion URL", url));
+    }
+
+    let (rest, fragment) = match rest.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (rest, None),
+    };
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let peek = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("p="))
+        .and_then(exact_id);
+    let from_path = path
+        .split('/')
+        .rev()
+        .find(|segment| !segment.is_empty())
+        .and_then(trailing_id);
+
+    fragment
+        .and_then(exact_id)
+        .or(peek)
+        .or(from_path)
+        .ok_or_else(|| format!("No Notion ID found in '{}'", url))
+}
+
+/// The ID at the end of a bare ID or `Title-<id>` slug.
+fn trailing_id(text: &str) -> Option<String> {
+    if let Some(id) = exact_id(text) {
+        return Some(id);
+    }
+    let (_, tail) = text.rsplit_once('-')?;
+    exact_id(tail)
+}
+
+/// A dashed or undashed ID, undashed and lowercased.
+fn exact_id(text: &str) -> Option<String> {
+    let hex: String = text.chars().filter(|c| *c != '-').collect();
+    let dashes_ok = !text.contains('-') || is_uuid_layout(text);
+    (hex.len() == 32 && dashes_ok && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| hex.to_lowercase())
+}
+
+fn is_uuid_layout(text: &str) -> bool {
+    let lengths: Vec<usize> = text.split('-').map(str::len).collect();
+    lengths == [8, 4, 4, 4, 12]
+}
+
+fn dashed(hex: &str) -> String {
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(test)]
+#[path = "ids_tests.rs"]
+mod tests;
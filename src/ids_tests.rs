@@ -0,0 +1,96 @@
+use super::*;
+
+const HEX: &str = "1a2b3c4d5e6f70819a0b1c2d3e4f5a6b";
+const DASHED: &str = "1a2b3c4d-5e6f-7081-9a0b-1c2d3e4f5a6b";
+
+#[test]
+fn test_undashed_id_is_dashed() {
+    assert_eq!(parse_id(HEX).unwrap(), DASHED);
+}
+
+#[test]
+fn test_dashed_id_is_unchanged() {
+    assert_eq!(parse_id(DASHED).unwrap(), DASHED);
+}
+
+#[test]
+fn test_uppercase_and_whitespace() {
+    assert_eq!(
+        parse_id(&format!(" {} ", HEX.to_uppercase())).unwrap(),
+        DASHED
+    );
+}
+
+#[test]
+fn test_page_url_with_slug() {
+    let url = format!("https://www.notion.so/acme/Team-Wiki-{}", HEX);
+    assert_eq!(parse_id(&url).unwrap(), DASHED);
+}
+
+#[test]
+fn test_url_without_scheme() {
+    assert_eq!(parse_id(&format!("notion.so/{}", HEX)).unwrap(), DASHED);
+    let site = format!(
+        "acme.notion.site/{}?v=ffffffffffffffffffffffffffffffff",
+        HEX
+    );
+    assert_eq!(parse_id(&site).unwrap(), DASHED);
+}
+
+#[test]
+fn test_database_view_url_uses_path_id() {
+    let url = format!(
+        "https://www.notion.so/{}?v=ffffffffffffffffffffffffffffffff",
+        HEX
+    );
+    assert_eq!(parse_id(&url).unwrap(), DASHED);
+}
+
+#[test]
+fn test_block_anchor_wins() {
+    let url = format!(
+        "https://www.notion.so/Page-ffffffffffffffffffffffffffffffff#{}",
+        HEX
+    );
+    assert_eq!(parse_id(&url).unwrap(), DASHED);
+}
+
+#[test]
+fn test_peek_link() {
+    let url = format!(
+        "https://www.notion.so/eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee?v=ffffffffffffffffffffffffffffffff&p={}&pm=s",
+        HEX
+    );
+    assert_eq!(parse_id(&url).unwrap(), DASHED);
+}
+
+#[test]
+fn test_notion_site_url() {
+    let url = format!("https://acme.notion.site/Public-Page-{}", HEX);
+    assert_eq!(parse_id(&url).unwrap(), DASHED);
+}
+
+#[test]
+fn test_rejects_other_domains() {
+    let err = parse_id(&format!("https://example.com/{}", HEX)).unwrap_err();
+    assert!(err.contains("not a Notion URL"));
+}
+
+#[test]
+fn test_rejects_malformed_ids() {
+    for bad in [
+        "page-1",
+        "1a2b3c",
+        "",
+        "zz2b3c4d5e6f70819a0b1c2d3e4f5a6b",
+        "1a2b-3c4d5e6f70819a0b1c2d3e4f5a6b",
+    ] {
+        assert!(parse_id(bad).is_err(), "{} should be rejected", bad);
+    }
+}
+
+#[test]
+fn test_rejects_url_without_id() {
+    let err = parse_id("https://www.notion.so/acme/Team-Wiki").unwrap_err();
+    assert!(err.contains("No Notion ID"));
+}
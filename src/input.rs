@@ -0,0 +1,70 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::{NotionError, bail_input};
+
+/// Set once stdin has been consumed by an argument.
+static STDIN_USED: AtomicBool = AtomicBool::new(false);
+
+/// Read text from a file, or from stdin when the path is `-`.
+pub fn read_source(source: &str) -> Result<String> {
+    if source == "-" {
+        return read_stdin();
+    }
+    std::fs::read_to_string(source).with_context(|| format!("Failed to read {}", source))
+}
+
+/// Parse a JSON argument: a literal, `@path` to read a file, or `-` for stdin.
+///
+/// `what` names the argument in error messages, which also report where the
+/// JSON came from and the line and column of the syntax error.
+pub fn parse_json(arg: &str, what: &str) -> Result<Value> {
+    let (text, origin) = if arg == "-" {
+        (read_stdin()?, "stdin".to_string())
+    } else if let Some(path) = arg.strip_prefix('@') {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {} from {}", what, path))?;
+        (text, path.to_string())
+    } else {
+        (arg.to_string(), "argument".to_string())
+    };
+
+    serde_json::from_str(&text).map_err(|e| {
+        NotionError::Input(format!(
+            "Invalid JSON for {} ({} line {}, column {}): {}",
+            what,
+            origin,
+            e.line(),
+            e.column(),
+            describe(&e)
+        ))
+        .into()
+    })
+}
+
+/// The error message without serde's trailing position.
+fn describe(error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    let suffix = format!(" at line {} column {}", error.line(), error.column());
+    message
+        .strip_suffix(&suffix)
+        .unwrap_or(&message)
+        .to_string()
+}
+
+fn read_stdin() -> Result<String> {
+    if STDIN_USED.swap(true, Ordering::SeqCst) {
+        bail_input!("Only one argument can be read from stdin (-)");
+    }
+    let mut text = String::new();
+    std::io::stdin()
+        .read_to_string(&mut text)
+        .context("Failed to read stdin")?;
+    Ok(text)
+}
+
+#[cfg(test)]
+#[path = "input_tests.rs"]
+mod tests;
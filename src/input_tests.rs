@@ -0,0 +1,71 @@
+use super::*;
+use std::io::Write;
+
+#[test]
+fn test_read_source_from_file() {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(b"# Title\n").unwrap();
+    let text = read_source(file.path().to_str().unwrap()).unwrap();
+    assert_eq!(text, "# Title\n");
+}
+
+#[test]
+fn test_read_source_missing_file() {
+    let err = read_source("/nonexistent/notes.md").unwrap_err();
+    assert!(err.to_string().contains("/nonexistent/notes.md"));
+}
+
+#[test]
+fn test_parse_json_literal() {
+    let value = parse_json(r#"{"a":1}"#, "properties").unwrap();
+    assert_eq!(value["a"], 1);
+}
+
+#[test]
+fn test_parse_json_from_file() {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(br#"[{"type":"divider","divider":{}}]"#)
+        .unwrap();
+    let arg = format!("@{}", file.path().display());
+    let value = parse_json(&arg, "children").unwrap();
+    assert_eq!(value[0]["type"], "divider");
+}
+
+#[test]
+fn test_parse_json_reports_file_and_position() {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(b"{\n  \"a\": 1,\n  \"b\": \n}\n").unwrap();
+    let path = file.path().display().to_string();
+
+    let err = parse_json(&format!("@{}", path), "filter")
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.starts_with("Invalid JSON for filter"), "{}", err);
+    assert!(
+        err.contains(&format!("{} line 4, column 1", path)),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_parse_json_reports_argument_position() {
+    let err = parse_json("{oops}", "sorts").unwrap_err().to_string();
+    assert_eq!(
+        err,
+        "Invalid JSON for sorts (argument line 1, column 2): key must be a string"
+    );
+}
+
+#[test]
+fn test_parse_json_error_is_input_kind() {
+    let err = parse_json("{oops}", "sorts").unwrap_err();
+    assert_eq!(crate::error::kind(&err), crate::error::ErrorKind::Input);
+}
+
+#[test]
+fn test_parse_json_missing_file() {
+    let err = parse_json("@/nonexistent/props.json", "properties").unwrap_err();
+    assert!(err.to_string().contains("/nonexistent/props.json"));
+}
@@ -0,0 +1,31 @@
+//! Client, typed models and command implementations behind the `notion` CLI.
+//!
+//! [`client::NotionClient`] sends raw requests (`get`, `post`, `patch`,
+//! `delete`) and returns JSON values; [`api`] adds typed methods on top that
+//! return the structs in [`models`]. The `commands` modules implement each CLI
+//! subcommand and print their results.
+
+pub mod api;
+pub mod cassette;
+pub mod cli;
+pub mod client;
+pub mod commands;
+pub mod config;
+pub mod credentials;
+pub mod csv;
+pub mod error;
+pub mod filter;
+pub mod ids;
+pub mod input;
+pub mod markdown;
+pub mod mock_server;
+pub mod models;
+pub mod output;
+pub mod pagination;
+pub mod properties;
+pub mod rate_limit;
+pub mod render;
+pub mod retry;
+pub mod trace;
+
+pub use client::NotionClient;
@@ -1,32 +1,43 @@
-mod cli;
-mod client;
-mod commands;
-mod config;
-mod output;
-
-pub use cli::*;
+use notion_cli::cli::*;
+use notion_cli::{cassette, client, commands, config, error, output, rate_limit};
 
 use anyhow::Result;
+use cassette::Cassette;
 use clap::{CommandFactory, Parser};
+use notion_cli::pagination::Pagination;
+use notion_cli::retry::RetryPolicy;
+use notion_cli::trace::Tracer;
 use output::OutputFormat;
+use std::process::ExitCode;
+use std::time::Duration;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     let cli = Cli::parse();
-    run(cli).await
+    let error_format = cli.error_format;
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => ExitCode::from(error::report(&e, error_format)),
+    }
 }
 
 pub async fn run(cli: Cli) -> Result<()> {
+    let trace_level = cli.trace_level();
     let format = if cli.raw {
         OutputFormat::Raw
     } else {
-        cli.output
+        cli.output.clone()
     };
 
-    // Commands that don't need an API client
+    // Commands that don't need the config or an API client
     match &cli.command {
-        Commands::Init => {
-            return commands::init::run().await;
+        Commands::Profile(cmd) => {
+            let path = config::Config::config_path()?;
+            return match cmd {
+                ProfileCommands::List => commands::profile::list(&path, &format),
+                ProfileCommands::Use { name } => commands::profile::use_profile(&path, name),
+                ProfileCommands::Remove { name } => commands::profile::remove(&path, name),
+            };
         }
         Commands::Completions { shell } => {
             clap_complete::generate(
@@ -37,6 +48,9 @@ pub async fn run(cli: Cli) -> Result<()> {
             );
             return Ok(());
         }
+        Commands::MockServer { port } => {
+            return commands::mock_server::run(*port, &format).await;
+        }
         Commands::Manpage => {
             let cmd = Cli::command();
             let man = clap_mangen::Man::new(cmd);
@@ -46,31 +60,124 @@ pub async fn run(cli: Cli) -> Result<()> {
         _ => {}
     }
 
-    // All other commands need an authenticated client
     let config = config::Config::load()?;
-    let token = config.get_token()?;
-    let mut notion = client::NotionClient::new(token)?;
+    let options = client_options(&cli, &config);
+
+    // Commands that set up or check credentials
+    match &cli.command {
+        Commands::Init => {
+            return commands::init::run(cli.profile.as_deref(), &options).await;
+        }
+        Commands::Login {
+            oauth,
+            status,
+            client_id,
+            client_secret,
+            port,
+            no_browser,
+        } => {
+            let profile = cli.profile.as_deref();
+            if *status {
+                let secret = require(client_secret, "--client-secret")?;
+                return commands::login::status(profile, &options, secret, &format).await;
+            }
+            if !*oauth {
+                return commands::init::run(profile, &options).await;
+            }
+            let id = require(client_id, "--client-id")?;
+            let secret = require(client_secret, "--client-secret")?;
+            return commands::login::oauth(profile, &options, id, secret, *port, !*no_browser)
+                .await;
+        }
+        Commands::Logout { client_secret } => {
+            let secret = require(client_secret, "--client-secret")?;
+            return commands::login::logout(cli.profile.as_deref(), &options, secret).await;
+        }
+        _ => {}
+    }
+
+    // All other commands need an authenticated client
+    let token = match config.resolve_token(cli.profile.as_deref()) {
+        // Replayed runs never reach the API, so CI needs no token
+        Err(_) if cli.replay.is_some() => "replay".to_string(),
+        token => token?,
+    };
+    let mut notion = client::NotionClient::with_options(&token, &options)?;
+    if let Some(warning) = client::api_version_warning(&options.api_version) {
+        output::print_warning(&warning);
+    }
     notion.set_dry_run(cli.dry_run);
+    notion.set_rate_limit(Some(
+        cli.rate_limit
+            .or(config.rate_limit)
+            .unwrap_or(rate_limit::DEFAULT_RATE),
+    ));
+    notion.set_timeout(cli.timeout.map(Duration::from_secs));
+    notion.set_retry_policy(RetryPolicy {
+        max_retries: cli.max_retries,
+        max_backoff: Duration::from_secs(cli.max_backoff),
+        ..RetryPolicy::default()
+    });
+    if trace_level > 0 {
+        let tracer = match &cli.log_file {
+            Some(path) => Tracer::to_file(trace_level, path)?,
+            None => Tracer::stderr(trace_level),
+        };
+        notion.set_tracer(Some(tracer));
+    }
+
+    let cassette = match (&cli.record, &cli.replay) {
+        (Some(dir), _) => Some(Cassette::open(dir, cassette::Mode::Record)?),
+        (None, Some(dir)) => Some(Cassette::open(dir, cassette::Mode::Replay)?),
+        (None, None) => None,
+    };
+    notion.set_cassette(cassette);
+
+    let pagination = Pagination {
+        page_size: cli.page_size,
+        start_cursor: cli.start_cursor,
+        all: cli.all,
+        limit: cli.limit.map(|l| l as usize),
+    };
 
-    run_with_client(
-        cli.command,
-        &notion,
-        cli.page_size,
-        cli.start_cursor.as_deref(),
-        &format,
-    )
-    .await
+    run_with_client(cli.command, &notion, &pagination, &format).await
+}
+
+/// Where requests go and how, from flags first and then the config file.
+fn client_options(cli: &Cli, config: &config::Config) -> client::ClientOptions {
+    let defaults = client::ClientOptions::default();
+    client::ClientOptions {
+        base_url: cli
+            .base_url
+            .clone()
+            .or(config.base_url.clone())
+            .unwrap_or(defaults.base_url),
+        api_version: cli
+            .api_version
+            .clone()
+            .or(config.api_version.clone())
+            .unwrap_or(defaults.api_version),
+        proxy: cli.proxy.clone().or(config.proxy.clone()),
+        ca_bundle: cli.ca_bundle.clone().or(config.ca_bundle.clone()),
+        connect_timeout: config.connect_timeout.map(Duration::from_secs),
+        read_timeout: config.read_timeout.map(Duration::from_secs),
+    }
 }
 
 pub async fn run_with_client(
     command: Commands,
     notion: &client::NotionClient,
-    page_size: Option<u32>,
-    start_cursor: Option<&str>,
+    pagination: &Pagination,
     format: &OutputFormat,
 ) -> Result<()> {
     match &command {
-        Commands::Init | Commands::Completions { .. } | Commands::Manpage => unreachable!(),
+        Commands::Init
+        | Commands::Login { .. }
+        | Commands::Logout { .. }
+        | Commands::Profile(_)
+        | Commands::Completions { .. }
+        | Commands::MockServer { .. }
+        | Commands::Manpage => unreachable!(),
 
         Commands::FileUpload(cmd) => match cmd {
             FileUploadCommands::Create {
@@ -101,38 +208,41 @@ pub async fn run_with_client(
             }
             FileUploadCommands::Get { id } => commands::file_upload::get(notion, id, format).await,
             FileUploadCommands::List { status } => {
-                commands::file_upload::list(
-                    notion,
-                    status.as_deref(),
-                    page_size,
-                    start_cursor,
-                    format,
-                )
-                .await
+                commands::file_upload::list(notion, status.as_deref(), pagination, format).await
             }
             FileUploadCommands::Upload { file, content_type } => {
                 commands::file_upload::upload(notion, file, content_type.as_deref(), format).await
             }
         },
 
-        Commands::Search { query, filter } => {
-            commands::search::run(
-                notion,
+        Commands::Api {
+            method,
+            path,
+            data,
+            query,
+            paginate,
+        } => {
+            let request = commands::api::Request {
+                method: *method,
+                path,
+                data: data.as_deref(),
                 query,
-                filter.as_deref(),
-                page_size,
-                start_cursor,
-                format,
-            )
-            .await
+            };
+            if *paginate {
+                commands::api::paginate(notion, &request, pagination, format).await
+            } else {
+                commands::api::send(notion, &request, format).await
+            }
+        }
+
+        Commands::Search { query, filter } => {
+            commands::search::run(notion, query, filter.as_deref(), pagination, format).await
         }
 
         Commands::User(cmd) => match cmd {
             UserCommands::Me => commands::user::me(notion, format).await,
             UserCommands::Get { id } => commands::user::get(notion, id, format).await,
-            UserCommands::List => {
-                commands::user::list(notion, page_size, start_cursor, format).await
-            }
+            UserCommands::List => commands::user::list(notion, pagination, format).await,
         },
 
         Commands::Page(cmd) => match cmd {
@@ -143,14 +253,21 @@ pub async fn run_with_client(
             PageCommands::Create {
                 parent,
                 properties,
+                set,
                 children,
+                markdown,
                 database_parent,
             } => {
+                let properties = commands::page::PropertyArgs {
+                    json: properties.as_deref(),
+                    sets: set,
+                };
                 commands::page::create(
                     notion,
                     parent,
-                    properties,
+                    &properties,
                     children.as_deref(),
+                    markdown.as_deref(),
                     *database_parent,
                     format,
                 )
@@ -159,8 +276,15 @@ pub async fn run_with_client(
             PageCommands::Update {
                 id,
                 properties,
+                set,
                 archived,
-            } => commands::page::update(notion, id, properties, *archived, format).await,
+            } => {
+                let properties = commands::page::PropertyArgs {
+                    json: properties.as_deref(),
+                    sets: set,
+                };
+                commands::page::update(notion, id, &properties, *archived, format).await
+            }
             PageCommands::Move {
                 id,
                 parent_type,
@@ -169,29 +293,34 @@ pub async fn run_with_client(
             PageCommands::Property {
                 page_id,
                 property_id,
-            } => {
-                commands::page::property(
-                    notion,
-                    page_id,
-                    property_id,
-                    page_size,
-                    start_cursor,
-                    format,
-                )
-                .await
-            }
+            } => commands::page::property(notion, page_id, property_id, pagination, format).await,
+            PageCommands::Export {
+                id,
+                format: export_format,
+            } => commands::page::export(notion, id, export_format, format).await,
         },
 
         Commands::Block(cmd) => match cmd {
             BlockCommands::Get { id } => commands::block::get(notion, id, format).await,
             BlockCommands::Children { id } => {
-                commands::block::children(notion, id, page_size, start_cursor, format).await
+                commands::block::children(notion, id, pagination, format).await
             }
             BlockCommands::Append {
                 id,
                 children,
+                markdown,
                 after,
-            } => commands::block::append(notion, id, children, after.as_deref(), format).await,
+            } => {
+                commands::block::append(
+                    notion,
+                    id,
+                    children.as_deref(),
+                    markdown.as_deref(),
+                    after.as_deref(),
+                    format,
+                )
+                .await
+            }
             BlockCommands::Update { id, data, archived } => {
                 commands::block::update(notion, id, data, *archived, format).await
             }
@@ -200,7 +329,7 @@ pub async fn run_with_client(
 
         Commands::Comment(cmd) => match cmd {
             CommentCommands::List { block_id } => {
-                commands::comment::list(notion, block_id, page_size, start_cursor, format).await
+                commands::comment::list(notion, block_id, pagination, format).await
             }
             CommentCommands::Create { page_id, text } => {
                 commands::comment::create(notion, page_id, text, format).await
@@ -224,25 +353,48 @@ pub async fn run_with_client(
             DatasourceCommands::Update { id, data } => {
                 commands::datasource::update(notion, id, data, format).await
             }
-            DatasourceCommands::Query { id, filter, sorts } => {
-                commands::datasource::query(
-                    notion,
-                    id,
-                    filter.as_deref(),
-                    sorts.as_deref(),
-                    page_size,
-                    start_cursor,
-                    format,
-                )
-                .await
+            DatasourceCommands::Query {
+                id,
+                filter,
+                where_clause,
+                sorts,
+                sort,
+                explain,
+            } => {
+                let query = commands::datasource::QueryArgs {
+                    filter: filter.as_deref(),
+                    where_clause: where_clause.as_deref(),
+                    sorts: sorts.as_deref(),
+                    sort,
+                    explain: *explain,
+                };
+                commands::datasource::query(notion, id, &query, pagination, format).await
             }
             DatasourceCommands::Templates { id } => {
                 commands::datasource::templates(notion, id, format).await
             }
+            DatasourceCommands::Import { id, file, map } => {
+                commands::datasource::import(notion, id, file, map, format).await
+            }
         },
     }
 }
 
+/// Value of an option that is only required by some modes of a command.
+fn require<'a>(value: &'a Option<String>, flag: &str) -> Result<&'a str> {
+    value.as_deref().filter(|v| !v.is_empty()).ok_or_else(|| {
+        let env = flag
+            .trim_start_matches("--")
+            .replace('-', "_")
+            .to_uppercase();
+        error::NotionError::Input(format!(
+            "{} is required (or set NOTION_OAUTH_{})",
+            flag, env
+        ))
+        .into()
+    })
+}
+
 #[cfg(test)]
 #[path = "main_tests.rs"]
 mod tests;
@@ -2,8 +2,19 @@ use super::*;
 use clap::Parser;
 use clap_complete::Shell;
 use client::NotionClient;
+use notion_cli::{mock_server, pagination};
 use std::path::PathBuf;
 
+const AFTER_ID: &str = "7f0b4c59-7a8e-4d7e-9d1e-5b3c7a1f2e90";
+const BLOCK_ID: &str = "c02fc1d3-db8b-45c5-a222-27595b15aea7";
+const DATABASE_ID: &str = "668d797c-76fa-4934-9b05-ad288df2d136";
+const DATA_SOURCE_ID: &str = "248104cd-477e-80fd-b757-000b5fa6f5b2";
+const PAGE_ID: &str = "59833787-2cf9-4fdf-8782-e53db20768a5";
+const PARENT_ID: &str = "b55c9c91-384d-452b-81db-d1ef79372b75";
+const TARGET_ID: &str = "2d3c4b5a-6978-4a1b-8c2d-3e4f5a6b7c8d";
+const UPLOAD_ID: &str = "a3f9d3e2-1abc-42de-b904-badc0ffee000";
+const USER_ID: &str = "d40e767c-d7af-4b18-a86d-55c61f1e39a4";
+
 fn parse(args: &[&str]) -> Cli {
     Cli::parse_from(args)
 }
@@ -18,6 +29,22 @@ fn test_init_command() {
     assert!(matches!(cli.command, Commands::Init));
 }
 
+#[test]
+fn test_require_returns_value() {
+    let value = Some("secret".to_string());
+    assert_eq!(require(&value, "--client-secret").unwrap(), "secret");
+}
+
+#[test]
+fn test_require_names_flag_and_env_var() {
+    let err = require(&None, "--client-secret").unwrap_err().to_string();
+    assert_eq!(
+        err,
+        "--client-secret is required (or set NOTION_OAUTH_CLIENT_SECRET)"
+    );
+    assert!(require(&Some(String::new()), "--client-id").is_err());
+}
+
 #[test]
 fn test_search_command() {
     let cli = parse(&["notion", "search", "my query"]);
@@ -54,9 +81,9 @@ fn test_user_me() {
 
 #[test]
 fn test_user_get() {
-    let cli = parse(&["notion", "user", "get", "user-123"]);
+    let cli = parse(&["notion", "user", "get", USER_ID]);
     if let Commands::User(UserCommands::Get { id }) = &cli.command {
-        assert_eq!(id, "user-123");
+        assert_eq!(id, USER_ID);
     } else {
         panic!("Expected User Get command");
     }
@@ -70,13 +97,13 @@ fn test_user_list() {
 
 #[test]
 fn test_page_get() {
-    let cli = parse(&["notion", "page", "get", "page-abc"]);
+    let cli = parse(&["notion", "page", "get", PAGE_ID]);
     if let Commands::Page(PageCommands::Get {
         id,
         filter_properties,
     }) = &cli.command
     {
-        assert_eq!(id, "page-abc");
+        assert_eq!(id, PAGE_ID);
         assert!(filter_properties.is_empty());
     } else {
         panic!("Expected Page Get command");
@@ -89,7 +116,7 @@ fn test_page_get_with_filter_properties() {
         "notion",
         "page",
         "get",
-        "page-abc",
+        PAGE_ID,
         "--filter-properties",
         "title,status",
     ]);
@@ -98,7 +125,7 @@ fn test_page_get_with_filter_properties() {
         filter_properties,
     }) = &cli.command
     {
-        assert_eq!(id, "page-abc");
+        assert_eq!(id, PAGE_ID);
         assert_eq!(filter_properties, &["title", "status"]);
     } else {
         panic!("Expected Page Get command");
@@ -112,7 +139,7 @@ fn test_page_create() {
         "page",
         "create",
         "--parent",
-        "parent-id",
+        PARENT_ID,
         "--properties",
         r#"{"Name":{"title":[{"text":{"content":"Test"}}]}}"#,
     ]);
@@ -121,10 +148,11 @@ fn test_page_create() {
         properties,
         children,
         database_parent,
+        ..
     }) = &cli.command
     {
-        assert_eq!(parent, "parent-id");
-        assert!(properties.contains("Name"));
+        assert_eq!(parent, PARENT_ID);
+        assert!(properties.as_deref().unwrap().contains("Name"));
         assert!(children.is_none());
         assert!(!database_parent);
     } else {
@@ -139,7 +167,7 @@ fn test_page_create_with_database_parent() {
         "page",
         "create",
         "--parent",
-        "db-id",
+        DATABASE_ID,
         "--properties",
         "{}",
         "--database-parent",
@@ -160,7 +188,7 @@ fn test_page_update() {
         "notion",
         "page",
         "update",
-        "page-123",
+        PAGE_ID,
         "--properties",
         "{}",
         "--archived",
@@ -170,10 +198,12 @@ fn test_page_update() {
         id,
         properties,
         archived,
+        set,
     }) = &cli.command
     {
-        assert_eq!(id, "page-123");
-        assert_eq!(properties, "{}");
+        assert_eq!(id, PAGE_ID);
+        assert_eq!(properties.as_deref(), Some("{}"));
+        assert!(set.is_empty());
         assert_eq!(*archived, Some(true));
     } else {
         panic!("Expected Page Update command");
@@ -186,11 +216,11 @@ fn test_page_move() {
         "notion",
         "page",
         "move",
-        "page-1",
+        PAGE_ID,
         "--parent-type",
         "database",
         "--to",
-        "db-2",
+        TARGET_ID,
     ]);
     if let Commands::Page(PageCommands::Move {
         id,
@@ -198,9 +228,9 @@ fn test_page_move() {
         to,
     }) = &cli.command
     {
-        assert_eq!(id, "page-1");
+        assert_eq!(id, PAGE_ID);
         assert_eq!(parent_type, "database");
-        assert_eq!(to, "db-2");
+        assert_eq!(to, TARGET_ID);
     } else {
         panic!("Expected Page Move command");
     }
@@ -208,13 +238,13 @@ fn test_page_move() {
 
 #[test]
 fn test_page_property() {
-    let cli = parse(&["notion", "page", "property", "page-1", "prop-abc"]);
+    let cli = parse(&["notion", "page", "property", PAGE_ID, "prop-abc"]);
     if let Commands::Page(PageCommands::Property {
         page_id,
         property_id,
     }) = &cli.command
     {
-        assert_eq!(page_id, "page-1");
+        assert_eq!(page_id, PAGE_ID);
         assert_eq!(property_id, "prop-abc");
     } else {
         panic!("Expected Page Property command");
@@ -223,9 +253,9 @@ fn test_page_property() {
 
 #[test]
 fn test_block_get() {
-    let cli = parse(&["notion", "block", "get", "block-1"]);
+    let cli = parse(&["notion", "block", "get", BLOCK_ID]);
     if let Commands::Block(BlockCommands::Get { id }) = &cli.command {
-        assert_eq!(id, "block-1");
+        assert_eq!(id, BLOCK_ID);
     } else {
         panic!("Expected Block Get command");
     }
@@ -233,9 +263,9 @@ fn test_block_get() {
 
 #[test]
 fn test_block_children() {
-    let cli = parse(&["notion", "block", "children", "block-1"]);
+    let cli = parse(&["notion", "block", "children", BLOCK_ID]);
     if let Commands::Block(BlockCommands::Children { id }) = &cli.command {
-        assert_eq!(id, "block-1");
+        assert_eq!(id, BLOCK_ID);
     } else {
         panic!("Expected Block Children command");
     }
@@ -247,21 +277,22 @@ fn test_block_append() {
         "notion",
         "block",
         "append",
-        "block-1",
+        BLOCK_ID,
         "--children",
         "[{}]",
         "--after",
-        "block-2",
+        AFTER_ID,
     ]);
     if let Commands::Block(BlockCommands::Append {
         id,
         children,
         after,
+        ..
     }) = &cli.command
     {
-        assert_eq!(id, "block-1");
-        assert_eq!(children, "[{}]");
-        assert_eq!(after.as_deref(), Some("block-2"));
+        assert_eq!(id, BLOCK_ID);
+        assert_eq!(children.as_deref(), Some("[{}]"));
+        assert_eq!(after.as_deref(), Some(AFTER_ID));
     } else {
         panic!("Expected Block Append command");
     }
@@ -269,9 +300,9 @@ fn test_block_append() {
 
 #[test]
 fn test_block_delete() {
-    let cli = parse(&["notion", "block", "delete", "block-1"]);
+    let cli = parse(&["notion", "block", "delete", BLOCK_ID]);
     if let Commands::Block(BlockCommands::Delete { id }) = &cli.command {
-        assert_eq!(id, "block-1");
+        assert_eq!(id, BLOCK_ID);
     } else {
         panic!("Expected Block Delete command");
     }
@@ -279,9 +310,9 @@ fn test_block_delete() {
 
 #[test]
 fn test_comment_list() {
-    let cli = parse(&["notion", "comment", "list", "--block-id", "page-1"]);
+    let cli = parse(&["notion", "comment", "list", "--block-id", PAGE_ID]);
     if let Commands::Comment(CommentCommands::List { block_id }) = &cli.command {
-        assert_eq!(block_id, "page-1");
+        assert_eq!(block_id, PAGE_ID);
     } else {
         panic!("Expected Comment List command");
     }
@@ -294,12 +325,12 @@ fn test_comment_create() {
         "comment",
         "create",
         "--page-id",
-        "page-1",
+        PAGE_ID,
         "--text",
         "Hello world",
     ]);
     if let Commands::Comment(CommentCommands::Create { page_id, text }) = &cli.command {
-        assert_eq!(page_id, "page-1");
+        assert_eq!(page_id, PAGE_ID);
         assert_eq!(text, "Hello world");
     } else {
         panic!("Expected Comment Create command");
@@ -308,9 +339,9 @@ fn test_comment_create() {
 
 #[test]
 fn test_database_get() {
-    let cli = parse(&["notion", "database", "get", "db-123"]);
+    let cli = parse(&["notion", "database", "get", DATABASE_ID]);
     if let Commands::Database(DatabaseCommands::Get { id }) = &cli.command {
-        assert_eq!(id, "db-123");
+        assert_eq!(id, DATABASE_ID);
     } else {
         panic!("Expected Database Get command");
     }
@@ -318,9 +349,9 @@ fn test_database_get() {
 
 #[test]
 fn test_datasource_get() {
-    let cli = parse(&["notion", "datasource", "get", "ds-1"]);
+    let cli = parse(&["notion", "datasource", "get", DATA_SOURCE_ID]);
     if let Commands::Datasource(DatasourceCommands::Get { id }) = &cli.command {
-        assert_eq!(id, "ds-1");
+        assert_eq!(id, DATA_SOURCE_ID);
     } else {
         panic!("Expected Datasource Get command");
     }
@@ -332,14 +363,17 @@ fn test_datasource_query_with_filter() {
         "notion",
         "datasource",
         "query",
-        "ds-1",
+        DATA_SOURCE_ID,
         "--filter",
         r#"{"property":"Status","equals":"Done"}"#,
         "--sorts",
         r#"[{"property":"Created","direction":"descending"}]"#,
     ]);
-    if let Commands::Datasource(DatasourceCommands::Query { id, filter, sorts }) = &cli.command {
-        assert_eq!(id, "ds-1");
+    if let Commands::Datasource(DatasourceCommands::Query {
+        id, filter, sorts, ..
+    }) = &cli.command
+    {
+        assert_eq!(id, DATA_SOURCE_ID);
         assert!(filter.is_some());
         assert!(sorts.is_some());
     } else {
@@ -354,7 +388,7 @@ fn test_datasource_create() {
         "datasource",
         "create",
         "--parent",
-        "page-1",
+        PAGE_ID,
         "--title",
         "My DB",
     ]);
@@ -364,7 +398,7 @@ fn test_datasource_create() {
         properties,
     }) = &cli.command
     {
-        assert_eq!(parent, "page-1");
+        assert_eq!(parent, PAGE_ID);
         assert_eq!(title, "My DB");
         assert!(properties.is_none());
     } else {
@@ -374,9 +408,9 @@ fn test_datasource_create() {
 
 #[test]
 fn test_datasource_templates() {
-    let cli = parse(&["notion", "datasource", "templates", "ds-1"]);
+    let cli = parse(&["notion", "datasource", "templates", DATA_SOURCE_ID]);
     if let Commands::Datasource(DatasourceCommands::Templates { id }) = &cli.command {
-        assert_eq!(id, "ds-1");
+        assert_eq!(id, DATA_SOURCE_ID);
     } else {
         panic!("Expected Datasource Templates command");
     }
@@ -491,7 +525,7 @@ fn test_file_upload_send() {
         "notion",
         "file-upload",
         "send",
-        "fu-1",
+        UPLOAD_ID,
         "--file",
         "/tmp/test.png",
     ]);
@@ -501,7 +535,7 @@ fn test_file_upload_send() {
         part_number,
     }) = &cli.command
     {
-        assert_eq!(id, "fu-1");
+        assert_eq!(id, UPLOAD_ID);
         assert_eq!(file, &PathBuf::from("/tmp/test.png"));
         assert!(part_number.is_none());
     } else {
@@ -515,7 +549,7 @@ fn test_file_upload_send_with_part_number() {
         "notion",
         "file-upload",
         "send",
-        "fu-1",
+        UPLOAD_ID,
         "--file",
         "/tmp/test.png",
         "--part-number",
@@ -527,7 +561,7 @@ fn test_file_upload_send_with_part_number() {
         part_number,
     }) = &cli.command
     {
-        assert_eq!(id, "fu-1");
+        assert_eq!(id, UPLOAD_ID);
         assert_eq!(file, &PathBuf::from("/tmp/test.png"));
         assert_eq!(*part_number, Some(2));
     } else {
@@ -537,9 +571,9 @@ fn test_file_upload_send_with_part_number() {
 
 #[test]
 fn test_file_upload_complete() {
-    let cli = parse(&["notion", "file-upload", "complete", "fu-1"]);
+    let cli = parse(&["notion", "file-upload", "complete", UPLOAD_ID]);
     if let Commands::FileUpload(FileUploadCommands::Complete { id }) = &cli.command {
-        assert_eq!(id, "fu-1");
+        assert_eq!(id, UPLOAD_ID);
     } else {
         panic!("Expected FileUpload Complete command");
     }
@@ -547,9 +581,9 @@ fn test_file_upload_complete() {
 
 #[test]
 fn test_file_upload_get() {
-    let cli = parse(&["notion", "file-upload", "get", "fu-1"]);
+    let cli = parse(&["notion", "file-upload", "get", UPLOAD_ID]);
     if let Commands::FileUpload(FileUploadCommands::Get { id }) = &cli.command {
-        assert_eq!(id, "fu-1");
+        assert_eq!(id, UPLOAD_ID);
     } else {
         panic!("Expected FileUpload Get command");
     }
@@ -662,8 +696,7 @@ async fn test_run_search() {
             filter: None,
         },
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -690,8 +723,11 @@ async fn test_run_search_with_filter() {
             filter: Some("page".into()),
         },
         &client,
-        Some(10),
-        Some("cursor-1"),
+        &Pagination {
+            page_size: Some(10),
+            start_cursor: Some("cursor-1".into()),
+            ..Default::default()
+        },
         &OutputFormat::Pretty,
     )
     .await;
@@ -715,8 +751,7 @@ async fn test_run_user_me() {
     let result = run_with_client(
         Commands::User(UserCommands::Me),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -742,8 +777,7 @@ async fn test_run_user_get() {
             id: "user-1".into(),
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -767,8 +801,7 @@ async fn test_run_user_list() {
     let result = run_with_client(
         Commands::User(UserCommands::List),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -795,8 +828,7 @@ async fn test_run_page_get() {
             filter_properties: vec![],
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -820,13 +852,14 @@ async fn test_run_page_create() {
     let result = run_with_client(
         Commands::Page(PageCommands::Create {
             parent: "parent-1".into(),
-            properties: r#"{"title":[{"text":{"content":"Test"}}]}"#.into(),
+            properties: Some(r#"{"title":[{"text":{"content":"Test"}}]}"#.into()),
+            set: vec![],
             children: None,
+            markdown: None,
             database_parent: false,
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -850,12 +883,12 @@ async fn test_run_page_update() {
     let result = run_with_client(
         Commands::Page(PageCommands::Update {
             id: "page-1".into(),
-            properties: "{}".into(),
+            properties: Some("{}".into()),
+            set: vec![],
             archived: Some(true),
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -880,11 +913,10 @@ async fn test_run_page_move() {
         Commands::Page(PageCommands::Move {
             id: "page-1".into(),
             parent_type: "page".into(),
-            to: "target-1".into(),
+            to: TARGET_ID.into(),
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -911,8 +943,7 @@ async fn test_run_page_property() {
             property_id: "prop-1".into(),
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -938,8 +969,7 @@ async fn test_run_block_get() {
             id: "block-1".into(),
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -965,8 +995,7 @@ async fn test_run_block_children() {
             id: "block-1".into(),
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -990,12 +1019,12 @@ async fn test_run_block_append() {
     let result = run_with_client(
         Commands::Block(BlockCommands::Append {
             id: "block-1".into(),
-            children: r#"[{"object":"block","type":"paragraph"}]"#.into(),
+            children: Some(r#"[{"object":"block","type":"paragraph"}]"#.into()),
+            markdown: None,
             after: None,
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1023,8 +1052,7 @@ async fn test_run_block_update() {
             archived: None,
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1050,8 +1078,7 @@ async fn test_run_block_delete() {
             id: "block-1".into(),
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1081,8 +1108,7 @@ async fn test_run_comment_list() {
             block_id: "block-1".into(),
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1109,8 +1135,7 @@ async fn test_run_comment_create() {
             text: "Hello".into(),
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1134,8 +1159,7 @@ async fn test_run_db_get() {
     let result = run_with_client(
         Commands::Database(DatabaseCommands::Get { id: "db-1".into() }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1159,8 +1183,7 @@ async fn test_run_ds_get() {
     let result = run_with_client(
         Commands::Datasource(DatasourceCommands::Get { id: "ds-1".into() }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1188,8 +1211,7 @@ async fn test_run_ds_create() {
             properties: None,
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1216,8 +1238,7 @@ async fn test_run_ds_update() {
             data: r#"{"title":[{"text":{"content":"Updated"}}]}"#.into(),
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1242,11 +1263,13 @@ async fn test_run_ds_query() {
         Commands::Datasource(DatasourceCommands::Query {
             id: "ds-1".into(),
             filter: None,
+            where_clause: None,
             sorts: None,
+            sort: vec![],
+            explain: false,
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1270,8 +1293,7 @@ async fn test_run_ds_templates() {
     let result = run_with_client(
         Commands::Datasource(DatasourceCommands::Templates { id: "ds-1".into() }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1301,8 +1323,7 @@ async fn test_run_file_upload_create() {
             external_url: None,
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1334,8 +1355,7 @@ async fn test_run_file_upload_send() {
             part_number: None,
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1359,8 +1379,7 @@ async fn test_run_file_upload_complete() {
     let result = run_with_client(
         Commands::FileUpload(FileUploadCommands::Complete { id: "fu-1".into() }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1384,8 +1403,7 @@ async fn test_run_file_upload_get() {
     let result = run_with_client(
         Commands::FileUpload(FileUploadCommands::Get { id: "fu-1".into() }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1409,8 +1427,7 @@ async fn test_run_file_upload_list() {
     let result = run_with_client(
         Commands::FileUpload(FileUploadCommands::List { status: None }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1441,6 +1458,7 @@ async fn test_run_file_upload_upload() {
         .with_status(200)
         .with_header(json_header().0, json_header().1)
         .with_body(r#"{"id":"fu-42","status":"upload_completed"}"#)
+        .expect(0)
         .create_async()
         .await;
 
@@ -1455,8 +1473,7 @@ async fn test_run_file_upload_upload() {
             content_type: None,
         }),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1482,8 +1499,7 @@ async fn test_run_with_raw_format() {
     let result = run_with_client(
         Commands::User(UserCommands::Me),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Raw,
     )
     .await;
@@ -1507,8 +1523,7 @@ async fn test_run_with_pretty_format() {
     let result = run_with_client(
         Commands::User(UserCommands::Me),
         &client,
-        None,
-        None,
+        &Pagination::default(),
         &OutputFormat::Pretty,
     )
     .await;
@@ -1516,3 +1531,428 @@ async fn test_run_with_pretty_format() {
     assert!(result.is_ok());
     mock.assert_async().await;
 }
+
+// --- End-to-end flows against the mock server ---
+
+async fn mock_workspace() -> (mock_server::MockServer, NotionClient) {
+    let server = mock_server::MockServer::start("127.0.0.1:0").await.unwrap();
+    let client = NotionClient::with_base_url("token", server.url()).unwrap();
+    (server, client)
+}
+
+async fn run_args(client: &NotionClient, args: &[&str]) -> Result<()> {
+    run_with_client(
+        parse(args).command,
+        client,
+        &Pagination::default(),
+        &OutputFormat::Raw,
+    )
+    .await
+}
+
+async fn first_result(client: &NotionClient, object: &str, query: &str) -> serde_json::Value {
+    let body = serde_json::json!({
+        "query": query,
+        "filter": { "property": "object", "value": object },
+    });
+    let found = client.post("/v1/search", Some(&body)).await.unwrap();
+    found["results"][0].clone()
+}
+
+#[tokio::test]
+async fn test_flow_create_page_append_blocks_query_data_source() {
+    let (server, client) = mock_workspace().await;
+    let home = server.seed().page_id.clone();
+
+    let schema = r#"{"Name":{"title":{}},"Score":{"number":{}},"Done":{"checkbox":{}}}"#;
+    run_args(
+        &client,
+        &[
+            "notion",
+            "datasource",
+            "create",
+            "--parent",
+            &home,
+            "--title",
+            "Tasks",
+            "--properties",
+            schema,
+        ],
+    )
+    .await
+    .unwrap();
+    let data_source = first_result(&client, "data_source", "Tasks").await;
+    let ds_id = data_source["id"].as_str().unwrap();
+    let database_id = data_source["parent"]["database_id"].as_str().unwrap();
+
+    for (name, score, done) in [("Write", 3, false), ("Review", 1, true), ("Ship", 2, false)] {
+        let properties = format!(
+            r#"{{"Name":{{"title":[{{"text":{{"content":"{}"}}}}]}},"Score":{{"number":{}}},"Done":{{"checkbox":{}}}}}"#,
+            name, score, done
+        );
+        run_args(
+            &client,
+            &[
+                "notion",
+                "page",
+                "create",
+                "--parent",
+                database_id,
+                "--database-parent",
+                "--properties",
+                &properties,
+            ],
+        )
+        .await
+        .unwrap();
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let notes = dir.path().join("notes.md");
+    std::fs::write(
+        &notes,
+        "# Plan\n\n- [ ] Write\n  - [ ] Outline\n\nDone soon.\n",
+    )
+    .unwrap();
+    let write = first_result(&client, "page", "Write").await;
+    let write_id = write["id"].as_str().unwrap();
+    run_args(
+        &client,
+        &[
+            "notion",
+            "block",
+            "append",
+            write_id,
+            "--markdown",
+            notes.to_str().unwrap(),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let filter = r#"{"property":"Done","checkbox":{"equals":false}}"#;
+    let sorts = r#"[{"property":"Score","direction":"ascending"}]"#;
+    run_args(
+        &client,
+        &[
+            "notion",
+            "datasource",
+            "query",
+            ds_id,
+            "--filter",
+            filter,
+            "--sorts",
+            sorts,
+        ],
+    )
+    .await
+    .unwrap();
+    run_args(&client, &["notion", "page", "export", write_id])
+        .await
+        .unwrap();
+
+    let body = serde_json::json!({
+        "filter": serde_json::from_str::<serde_json::Value>(filter).unwrap(),
+        "sorts": serde_json::from_str::<serde_json::Value>(sorts).unwrap(),
+    });
+    let open = client
+        .post(&format!("/v1/data_sources/{}/query", ds_id), Some(&body))
+        .await
+        .unwrap();
+    let names: Vec<&str> = open["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| {
+            p["properties"]["Name"]["title"][0]["plain_text"]
+                .as_str()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(names, ["Ship", "Write"]);
+
+    let blocks = commands::block::descendants(&client, write_id)
+        .await
+        .unwrap();
+    let types: Vec<&str> = blocks.iter().map(|b| b["type"].as_str().unwrap()).collect();
+    assert_eq!(types, ["heading_1", "to_do", "paragraph"]);
+    assert_eq!(
+        blocks[1]["children"][0]["to_do"]["rich_text"][0]["plain_text"],
+        "Outline"
+    );
+}
+
+#[tokio::test]
+async fn test_flow_set_properties_and_query_with_where() {
+    let (server, client) = mock_workspace().await;
+    let home = server.seed().page_id.clone();
+
+    let schema = r#"{"Name":{"title":{}},"Priority":{"select":{"options":[{"name":"High"},{"name":"Low"}]}},"Tags":{"multi_select":{"options":[{"name":"infra"},{"name":"docs"}]}},"Due":{"date":{}}}"#;
+    run_args(
+        &client,
+        &[
+            "notion",
+            "datasource",
+            "create",
+            "--parent",
+            &home,
+            "--title",
+            "Work",
+            "--properties",
+            schema,
+        ],
+    )
+    .await
+    .unwrap();
+    let data_source = first_result(&client, "data_source", "Work").await;
+    let ds_id = data_source["id"].as_str().unwrap();
+    let database_id = data_source["parent"]["database_id"].as_str().unwrap();
+
+    for (name, priority, due) in [
+        ("Upgrade", "High", "2026-01-10"),
+        ("Manual", "Low", "2026-02-01"),
+        ("Backups", "Low", "2026-01-05"),
+    ] {
+        run_args(
+            &client,
+            &[
+                "notion",
+                "page",
+                "create",
+                "--parent",
+                database_id,
+                "--database-parent",
+                "--set",
+                &format!("Name={}", name),
+                "--set",
+                &format!("priority={}", priority),
+                "--set",
+                &format!("Due={}", due),
+            ],
+        )
+        .await
+        .unwrap();
+    }
+    let manual = first_result(&client, "page", "Manual").await;
+    let manual_id = manual["id"].as_str().unwrap();
+    run_args(
+        &client,
+        &["notion", "page", "update", manual_id, "--set", "Tags+=docs"],
+    )
+    .await
+    .unwrap();
+    let err = run_args(
+        &client,
+        &["notion", "page", "update", manual_id, "--set", "Tags+=infr"],
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("did you mean 'infra'?"));
+
+    let expr = "Priority = High or (Due < 2026-01-31 and Tags not contains docs)";
+    run_args(
+        &client,
+        &[
+            "notion",
+            "datasource",
+            "query",
+            ds_id,
+            "--where",
+            expr,
+            "--explain",
+        ],
+    )
+    .await
+    .unwrap();
+    run_args(
+        &client,
+        &[
+            "notion",
+            "datasource",
+            "query",
+            ds_id,
+            "--where",
+            expr,
+            "--sort",
+            "-Due",
+            "--sort",
+            "created_time",
+        ],
+    )
+    .await
+    .unwrap();
+
+    let data_source = client.retrieve_data_source(ds_id).await.unwrap();
+    let filter = notion_cli::filter::parse(expr)
+        .unwrap()
+        .compile(&data_source.properties, &Default::default())
+        .unwrap();
+    let found = client
+        .query_data_source(ds_id, &serde_json::json!({ "filter": filter }), None)
+        .await
+        .unwrap();
+    let mut names: Vec<String> = found.results.iter().map(|p| p.title()).collect();
+    names.sort();
+    assert_eq!(names, ["Backups", "Upgrade"]);
+}
+
+#[tokio::test]
+async fn test_flow_import_update_and_trash_pages() {
+    let (server, client) = mock_workspace().await;
+    let home = server.seed().page_id.clone();
+    run_args(
+        &client,
+        &[
+            "notion",
+            "datasource",
+            "create",
+            "--parent",
+            &home,
+            "--title",
+            "People",
+            "--properties",
+            r#"{"Name":{"title":{}},"Age":{"number":{}}}"#,
+        ],
+    )
+    .await
+    .unwrap();
+    let ds_id = first_result(&client, "data_source", "People").await["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let dir = tempfile::tempdir().unwrap();
+    let csv = dir.path().join("people.csv");
+    std::fs::write(&csv, "Name,Age\nAda,36\nGrace,85\n").unwrap();
+    run_args(
+        &client,
+        &[
+            "notion",
+            "datasource",
+            "import",
+            &ds_id,
+            "--file",
+            csv.to_str().unwrap(),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let ada = first_result(&client, "page", "Ada").await;
+    let ada_id = ada["id"].as_str().unwrap();
+    assert_eq!(ada["properties"]["Age"]["number"], 36.0);
+    run_args(
+        &client,
+        &[
+            "notion",
+            "page",
+            "update",
+            ada_id,
+            "--properties",
+            r#"{"Age":{"number":37}}"#,
+        ],
+    )
+    .await
+    .unwrap();
+    run_args(
+        &client,
+        &[
+            "notion",
+            "comment",
+            "create",
+            "--page-id",
+            ada_id,
+            "--text",
+            "Updated",
+        ],
+    )
+    .await
+    .unwrap();
+    run_args(&client, &["notion", "block", "delete", ada_id])
+        .await
+        .unwrap();
+
+    let query = client
+        .post(&format!("/v1/data_sources/{}/query", ds_id), None)
+        .await
+        .unwrap();
+    let remaining = query["results"].as_array().unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(
+        remaining[0]["properties"]["Name"]["title"][0]["plain_text"],
+        "Grace"
+    );
+
+    let ada = client
+        .get(&format!("/v1/pages/{}", ada_id), &[])
+        .await
+        .unwrap();
+    assert_eq!(ada["properties"]["Age"]["number"], 37);
+    assert_eq!(ada["in_trash"], true);
+    let comments = client
+        .get("/v1/comments", &[("block_id", ada_id)])
+        .await
+        .unwrap();
+    assert_eq!(
+        comments["results"][0]["rich_text"][0]["plain_text"],
+        "Updated"
+    );
+}
+
+#[tokio::test]
+async fn test_flow_file_upload() {
+    let (_server, client) = mock_workspace().await;
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("hello.txt");
+    std::fs::write(&file, "hello world").unwrap();
+
+    run_args(
+        &client,
+        &["notion", "file-upload", "upload", file.to_str().unwrap()],
+    )
+    .await
+    .unwrap();
+
+    let uploads = client
+        .get("/v1/file_uploads", &[("status", "uploaded")])
+        .await
+        .unwrap();
+    assert_eq!(uploads["results"][0]["filename"], "hello.txt");
+    assert_eq!(uploads["results"][0]["content_length"], 11);
+}
+
+#[tokio::test]
+async fn test_flow_missing_page_maps_to_not_found_exit_code() {
+    let (_server, client) = mock_workspace().await;
+    let err = run_args(&client, &["notion", "page", "get", PAGE_ID])
+        .await
+        .unwrap_err();
+    assert_eq!(error::kind(&err), error::ErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn test_flow_paginates_with_all() {
+    let (server, client) = mock_workspace().await;
+    let home = server.seed().page_id.clone();
+    let children: Vec<serde_json::Value> = (0..5)
+        .map(|_| serde_json::json!({ "divider": {} }))
+        .collect();
+    client
+        .patch(
+            &format!("/v1/blocks/{}/children", home),
+            &serde_json::json!({ "children": children }),
+        )
+        .await
+        .unwrap();
+
+    let pagination = Pagination {
+        page_size: Some(2),
+        all: true,
+        ..Pagination::default()
+    };
+    let request = pagination::ListRequest::get(format!("/v1/blocks/{}/children", home));
+    let all = pagination::paginate(&client, &request, &pagination, |_| Ok(()))
+        .await
+        .unwrap();
+    assert_eq!(all["results"].as_array().unwrap().len(), 5);
+}
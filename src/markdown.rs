@@ -0,0 +1,955 @@
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use serde_json::{Map, Value, json};
+
+use crate::properties;
+
+/// Render a page and its block tree as a CommonMark/GFM document.
+///
+/// `blocks` are the page's children, with nested blocks stored under a
+/// `children` key (see `commands::block::descendants`).
+pub fn page_to_markdown(page: &Value, blocks: &[Value]) -> String {
+    let mut doc = String::new();
+    let title = properties::title(page);
+    if !title.is_empty() {
+        doc.push_str("# ");
+        doc.push_str(&escape(&title));
+        doc.push_str("\n\n");
+    }
+    let body = blocks_to_markdown(blocks);
+    if !body.is_empty() {
+        doc.push_str(&body);
+        doc.push('\n');
+    }
+    doc
+}
+
+/// Render a list of sibling blocks, without a trailing newline.
+pub fn blocks_to_markdown(blocks: &[Value]) -> String {
+    let mut out = String::new();
+    let mut previous: Option<&str> = None;
+    let mut number = 0;
+
+    for block in blocks {
+        let kind = block["type"].as_str().unwrap_or_default();
+        if kind == "numbered_list_item" {
+            number = if previous == Some(kind) {
+                number + 1
+            } else {
+                1
+            };
+        }
+        let Some(text) = block_to_markdown(block, number) else {
+            continue;
+        };
+        if let Some(prev) = previous {
+            out.push_str(if is_tight(prev, kind) { "\n" } else { "\n\n" });
+        }
+        out.push_str(&text);
+        previous = Some(kind);
+    }
+    out
+}
+
+/// Consecutive items of the same list are not separated by a blank line.
+fn is_tight(previous: &str, kind: &str) -> bool {
+    let bullet = |k: &str| matches!(k, "bulleted_list_item" | "to_do");
+    (previous == kind && kind == "numbered_list_item") || (bullet(previous) && bullet(kind))
+}
+
+fn block_to_markdown(block: &Value, number: usize) -> Option<String> {
+    let kind = block["type"].as_str().unwrap_or_default();
+    let data = &block[kind];
+    let empty = Vec::new();
+    let children = block["children"].as_array().unwrap_or(&empty);
+    let text = || escape_line_start(&rich_text(&data["rich_text"]));
+
+    let markdown = match kind {
+        "paragraph" => join_blocks(&text(), children),
+        "heading_1" | "heading_2" | "heading_3" => {
+            let level = &kind[kind.len() - 1..];
+            let hashes = "#".repeat(level.parse().unwrap_or(1));
+            join_blocks(
+                &format!("{} {}", hashes, rich_text(&data["rich_text"])),
+                children,
+            )
+        }
+        "bulleted_list_item" => list_item("- ", &text(), children),
+        "numbered_list_item" => list_item(&format!("{}. ", number), &text(), children),
+        "to_do" => {
+            let mark = if data["checked"] == true {
+                "[x]"
+            } else {
+                "[ ]"
+            };
+            list_item(&format!("- {} ", mark), &text(), children)
+        }
+        "toggle" => {
+            let summary = escape_html(&properties::plain_text(&data["rich_text"]));
+            let body = blocks_to_markdown(children);
+            format!(
+                "<details>\n<summary>{}</summary>\n\n{}\n\n</details>",
+                summary, body
+            )
+        }
+        "quote" => prefix_lines(&join_blocks(&text(), children), "> ", "> "),
+        "callout" => {
+            let icon = data["icon"]["emoji"].as_str().unwrap_or_default();
+            let line = match icon {
+                "" => text(),
+                icon => format!("{} {}", icon, text()),
+            };
+            prefix_lines(&join_blocks(&line, children), "> ", "> ")
+        }
+        "code" => code_block(data),
+        "equation" => format!(
+            "$$\n{}\n$$",
+            data["expression"].as_str().unwrap_or_default()
+        ),
+        "divider" => "---".to_string(),
+        "table" => table(children),
+        "image" => {
+            let alt = properties::plain_text(&data["caption"]);
+            format!("![{}]({})", escape(&alt), file_url(data))
+        }
+        "file" | "pdf" | "video" | "audio" => {
+            let caption = rich_text(&data["caption"]);
+            let label = match (caption.is_empty(), data["name"].as_str()) {
+                (false, _) => caption,
+                (true, Some(name)) => escape(name),
+                (true, None) => file_url(data).to_string(),
+            };
+            format!("[{}]({})", label, file_url(data))
+        }
+        "bookmark" | "embed" | "link_preview" => {
+            let url = data["url"].as_str().unwrap_or_default();
+            let caption = rich_text(&data["caption"]);
+            let label = if caption.is_empty() {
+                url.to_string()
+            } else {
+                caption
+            };
+            format!("[{}]({})", label, url)
+        }
+        "child_page" | "child_database" => {
+            let id = block["id"].as_str().unwrap_or_default();
+            let title = data["title"].as_str().unwrap_or_default();
+            format!("[{}]({})", escape(title), notion_url(id))
+        }
+        "link_to_page" => {
+            let kind = data["type"].as_str().unwrap_or_default();
+            let id = data[kind].as_str().unwrap_or_default();
+            format!("[{}]({})", notion_url(id), notion_url(id))
+        }
+        "column_list" | "column" | "synced_block" => blocks_to_markdown(children),
+        "table_of_contents" | "breadcrumb" => return None,
+        other => format!("<!-- unsupported block: {} -->", other),
+    };
+    // Empty paragraphs are spacing in Notion; Markdown collapses them anyway
+    (!markdown.is_empty()).then_some(markdown)
+}
+
+/// A block's own text followed by its rendered children.
+fn join_blocks(text: &str, children: &[Value]) -> String {
+    let nested = blocks_to_markdown(children);
+    match (text.is_empty(), nested.is_empty()) {
+        (_, true) => text.to_string(),
+        (true, false) => nested,
+        (false, false) => format!("{}\n\n{}", text, nested),
+    }
+}
+
+/// A list item with its children indented under the marker.
+fn list_item(marker: &str, text: &str, children: &[Value]) -> String {
+    let nested = blocks_to_markdown(children);
+    let body = if nested.is_empty() {
+        text.to_string()
+    } else {
+        format!("{}\n{}", text, nested)
+    };
+    prefix_lines(&body, marker, &" ".repeat(marker.chars().count()))
+}
+
+fn prefix_lines(text: &str, first: &str, rest: &str) -> String {
+    text.split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            let prefix = if i == 0 { first } else { rest };
+            if line.is_empty() {
+                prefix.trim_end().to_string()
+            } else {
+                format!("{}{}", prefix, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn code_block(data: &Value) -> String {
+    let code = properties::plain_text(&data["rich_text"]);
+    let language = match data["language"].as_str().unwrap_or_default() {
+        "plain text" => "",
+        other => other,
+    };
+    let fence = "`".repeat(longest_run(&code, '`').max(2) + 1);
+    format!("{}{}\n{}\n{}", fence, language, code, fence)
+}
+
+fn table(rows: &[Value]) -> String {
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            row["table_row"]["cells"]
+                .as_array()
+                .map(|cells| cells.iter().map(table_cell).collect())
+                .unwrap_or_default()
+        })
+        .collect();
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if width == 0 {
+        return String::new();
+    }
+
+    let line = |cells: &[String]| {
+        let padded: Vec<&str> = (0..width)
+            .map(|i| cells.get(i).map(String::as_str).unwrap_or(""))
+            .collect();
+        format!("| {} |", padded.join(" | "))
+    };
+
+    // GFM tables always need a header row; Notion's first row is used even
+    // when the table has no column header.
+    let mut lines = vec![line(&rows[0]), format!("|{}", " --- |".repeat(width))];
+    lines.extend(rows[1..].iter().map(|cells| line(cells)));
+    lines.join("\n")
+}
+
+fn table_cell(cell: &Value) -> String {
+    rich_text(cell).replace('|', "\\|").replace('\n', "<br>")
+}
+
+fn file_url(data: &Value) -> &str {
+    let kind = data["type"].as_str().unwrap_or_default();
+    data[kind]["url"].as_str().unwrap_or_default()
+}
+
+/// Public URL for a page or block ID.
+pub fn notion_url(id: &str) -> String {
+    format!("https://www.notion.so/{}", id.replace('-', ""))
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+struct Annotations {
+    bold: bool,
+    italic: bool,
+    strikethrough: bool,
+    code: bool,
+}
+
+struct Span {
+    text: String,
+    annotations: Annotations,
+    href: Option<String>,
+    /// Already Markdown (inline equations), never escaped or merged
+    verbatim: bool,
+}
+
+/// Convert a rich text array to inline Markdown.
+///
+/// Adjacent segments with the same formatting are merged first so that
+/// Notion's per-edit splits don't produce `**a****b**`.
+pub fn rich_text(rich_text: &Value) -> String {
+    let Some(parts) = rich_text.as_array() else {
+        return String::new();
+    };
+
+    let mut spans: Vec<Span> = Vec::new();
+    for part in parts {
+        let span = to_span(part);
+        if let Some(last) = spans.last_mut()
+            && !last.verbatim
+            && !span.verbatim
+            && last.annotations == span.annotations
+            && last.href == span.href
+        {
+            last.text.push_str(&span.text);
+            continue;
+        }
+        spans.push(span);
+    }
+
+    spans.iter().map(render_span).collect()
+}
+
+fn to_span(part: &Value) -> Span {
+    let a = &part["annotations"];
+    let annotations = Annotations {
+        bold: a["bold"] == true,
+        italic: a["italic"] == true,
+        strikethrough: a["strikethrough"] == true,
+        code: a["code"] == true,
+    };
+
+    if part["type"] == "equation" {
+        let expression = part["equation"]["expression"].as_str().unwrap_or_default();
+        return Span {
+            text: format!("${}$", expression),
+            annotations: Annotations::default(),
+            href: None,
+            verbatim: true,
+        };
+    }
+
+    let text = part["plain_text"]
+        .as_str()
+        .or(part["text"]["content"].as_str())
+        .unwrap_or_default()
+        .to_string();
+    let href = part["text"]["link"]["url"]
+        .as_str()
+        .or(part["href"].as_str())
+        .map(|url| match url.strip_prefix('/') {
+            Some(path) => format!("https://www.notion.so/{}", path),
+            None => url.to_string(),
+        });
+
+    Span {
+        text,
+        annotations,
+        href,
+        verbatim: false,
+    }
+}
+
+fn render_span(span: &Span) -> String {
+    if span.verbatim {
+        return span.text.clone();
+    }
+
+    let a = span.annotations;
+    // Emphasis markers must hug the text, so surrounding spaces stay outside.
+    let trimmed = span.text.trim();
+    if trimmed.is_empty() {
+        return span.text.clone();
+    }
+    let start = span.text.len() - span.text.trim_start().len();
+    let (leading, rest) = span.text.split_at(start);
+    let trailing = &rest[trimmed.len()..];
+
+    let mut inner = if a.code {
+        let ticks = "`".repeat(longest_run(trimmed, '`') + 1);
+        let pad = if trimmed.starts_with('`') || trimmed.ends_with('`') {
+            " "
+        } else {
+            ""
+        };
+        format!("{}{}{}{}{}", ticks, pad, trimmed, pad, ticks)
+    } else {
+        escape(trimmed).replace('\n', "\\\n")
+    };
+    if a.strikethrough {
+        inner = format!("~~{}~~", inner);
+    }
+    if a.italic {
+        inner = format!("*{}*", inner);
+    }
+    if a.bold {
+        inner = format!("**{}**", inner);
+    }
+    if let Some(href) = &span.href {
+        inner = format!(
+            "[{}]({})",
+            inner,
+            href.replace(' ', "%20").replace(')', "%29")
+        );
+    }
+    format!("{}{}{}", leading, inner, trailing)
+}
+
+/// Escape characters that would otherwise start inline Markdown syntax.
+fn escape(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let needs_escape = match c {
+            '\\' | '*' | '`' | '[' | ']' | '<' | '~' => true,
+            // Intraword underscores (snake_case) never start emphasis
+            '_' => {
+                let before = i.checked_sub(1).map(|j| chars[j]);
+                let after = chars.get(i + 1).copied();
+                !(before.is_some_and(char::is_alphanumeric)
+                    && after.is_some_and(char::is_alphanumeric))
+            }
+            _ => false,
+        };
+        if needs_escape {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Escape text that would be read as a heading, quote or list marker.
+fn escape_line_start(text: &str) -> String {
+    let starts_block = text.starts_with('#')
+        || text.starts_with('>')
+        || text.starts_with("- ")
+        || text.starts_with("+ ")
+        || text
+            .split_once(". ")
+            .is_some_and(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    if !starts_block {
+        return text.to_string();
+    }
+    match text.split_once(". ") {
+        Some((n, rest)) if n.chars().all(|c| c.is_ascii_digit()) => format!("{}\\. {}", n, rest),
+        _ => format!("\\{}", text),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn longest_run(text: &str, c: char) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for ch in text.chars() {
+        if ch == c {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    longest
+}
+
+/// Longest `content` the API accepts in a single rich text object.
+pub(crate) const MAX_TEXT_LENGTH: usize = 2000;
+
+/// Code block languages accepted by the API.
+const CODE_LANGUAGES: &[&str] = &[
+    "abap",
+    "agda",
+    "arduino",
+    "ascii art",
+    "assembly",
+    "bash",
+    "basic",
+    "bnf",
+    "c",
+    "c#",
+    "c++",
+    "clojure",
+    "coffeescript",
+    "coq",
+    "css",
+    "dart",
+    "dhall",
+    "diff",
+    "docker",
+    "ebnf",
+    "elixir",
+    "elm",
+    "erlang",
+    "f#",
+    "flow",
+    "fortran",
+    "gherkin",
+    "glsl",
+    "go",
+    "graphql",
+    "groovy",
+    "haskell",
+    "hcl",
+    "html",
+    "idris",
+    "java",
+    "javascript",
+    "json",
+    "julia",
+    "kotlin",
+    "latex",
+    "less",
+    "lisp",
+    "livescript",
+    "llvm ir",
+    "lua",
+    "makefile",
+    "markdown",
+    "markup",
+    "matlab",
+    "mathematica",
+    "mermaid",
+    "nix",
+    "notion formula",
+    "objective-c",
+    "ocaml",
+    "pascal",
+    "perl",
+    "php",
+    "plain text",
+    "powershell",
+    "prolog",
+    "protobuf",
+    "purescript",
+    "python",
+    "r",
+    "racket",
+    "reason",
+    "ruby",
+    "rust",
+    "sass",
+    "scala",
+    "scheme",
+    "scss",
+    "shell",
+    "smalltalk",
+    "solidity",
+    "sql",
+    "swift",
+    "toml",
+    "typescript",
+    "vb.net",
+    "verilog",
+    "vhdl",
+    "visual basic",
+    "webassembly",
+    "xml",
+    "yaml",
+];
+
+/// Convert a Markdown document to Notion blocks.
+///
+/// Nested blocks are placed under `<type>.children`, the shape the API
+/// accepts; `commands::block::append_tree` splits them into requests that
+/// respect the API limits.
+pub fn to_blocks(markdown: &str) -> Vec<Value> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_MATH;
+    let mut builder = BlockBuilder {
+        stack: vec![Frame::new("document")],
+        ..BlockBuilder::default()
+    };
+    for event in Parser::new_ext(markdown, options) {
+        builder.event(event);
+    }
+    builder.finish()
+}
+
+#[derive(Default)]
+struct Frame {
+    kind: &'static str,
+    rich_text: Vec<Value>,
+    children: Vec<Value>,
+    /// Extra fields of the block object (`checked`, `language`…)
+    fields: Map<String, Value>,
+    /// Blocks produced by inline Markdown (images, display math) that follow
+    /// the paragraph they appeared in
+    trailing: Vec<Value>,
+    /// Table rows collect cells here
+    cells: Vec<Value>,
+}
+
+impl Frame {
+    fn new(kind: &'static str) -> Self {
+        Frame {
+            kind,
+            ..Frame::default()
+        }
+    }
+
+    fn into_block(mut self) -> Value {
+        let mut data = std::mem::take(&mut self.fields);
+        data.insert("rich_text".into(), Value::Array(self.rich_text));
+        if !self.children.is_empty() {
+            data.insert("children".into(), Value::Array(self.children));
+        }
+        json!({ "object": "block", "type": self.kind, self.kind: data })
+    }
+}
+
+#[derive(Default)]
+struct BlockBuilder {
+    stack: Vec<Frame>,
+    bold: usize,
+    italic: usize,
+    strikethrough: usize,
+    links: Vec<Option<String>>,
+    /// Alt text of the image being parsed, with its URL
+    image: Option<(String, String)>,
+}
+
+impl BlockBuilder {
+    fn event(&mut self, event: Event) {
+        if let Some((alt, _)) = &mut self.image {
+            match event {
+                Event::End(TagEnd::Image) => self.end_image(),
+                Event::Text(text) | Event::Code(text) => alt.push_str(&text),
+                _ => {}
+            }
+            return;
+        }
+
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.text(&text, false),
+            Event::Code(code) => self.text(&code, true),
+            Event::InlineMath(expression) => self.push_rich_text(json!({
+                "type": "equation",
+                "equation": { "expression": expression.to_string() }
+            })),
+            Event::DisplayMath(expression) => {
+                let block = json!({
+                    "object": "block",
+                    "type": "equation",
+                    "equation": { "expression": expression.trim().to_string() }
+                });
+                self.top().trailing.push(block);
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                if !html.trim_start().starts_with("<!--") {
+                    self.text(html.trim_end_matches('\n'), false);
+                }
+            }
+            Event::SoftBreak => self.text(" ", false),
+            Event::HardBreak => self.text("\n", false),
+            Event::Rule => {
+                self.push_block(json!({ "object": "block", "type": "divider", "divider": {} }))
+            }
+            Event::TaskListMarker(checked) => {
+                // In loose lists the marker is inside the item's paragraph
+                let item = self
+                    .stack
+                    .iter_mut()
+                    .rev()
+                    .find(|f| matches!(f.kind, "bulleted_list_item" | "numbered_list_item"));
+                if let Some(item) = item {
+                    item.kind = "to_do";
+                    item.fields.insert("checked".into(), json!(checked));
+                }
+            }
+            Event::FootnoteReference(label) => self.text(&format!("[^{}]", label), false),
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph | Tag::HtmlBlock => self.stack.push(Frame::new("paragraph")),
+            Tag::Heading { level, .. } => self.stack.push(Frame::new(match level {
+                HeadingLevel::H1 => "heading_1",
+                HeadingLevel::H2 => "heading_2",
+                _ => "heading_3",
+            })),
+            Tag::BlockQuote(_) => self.stack.push(Frame::new("quote")),
+            Tag::CodeBlock(kind) => {
+                let info = match kind {
+                    CodeBlockKind::Fenced(info) => info.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                let mut frame = Frame::new("code");
+                frame
+                    .fields
+                    .insert("language".into(), json!(code_language(&info)));
+                self.stack.push(frame);
+            }
+            Tag::List(start) => {
+                let mut frame = Frame::new("list");
+                frame
+                    .fields
+                    .insert("ordered".into(), json!(start.is_some()));
+                self.stack.push(frame);
+            }
+            Tag::Item => {
+                let ordered = self.top().fields.get("ordered") == Some(&json!(true));
+                self.stack.push(Frame::new(if ordered {
+                    "numbered_list_item"
+                } else {
+                    "bulleted_list_item"
+                }));
+            }
+            Tag::Table(alignments) => {
+                let mut frame = Frame::new("table");
+                frame
+                    .fields
+                    .insert("table_width".into(), json!(alignments.len()));
+                self.stack.push(frame);
+            }
+            Tag::TableHead | Tag::TableRow => self.stack.push(Frame::new("table_row")),
+            Tag::TableCell => self.stack.push(Frame::new("table_cell")),
+            Tag::Emphasis => self.italic += 1,
+            Tag::Strong => self.bold += 1,
+            Tag::Strikethrough => self.strikethrough += 1,
+            Tag::Link { dest_url, .. } => {
+                let url = dest_url.to_string();
+                self.links.push(is_absolute_url(&url).then_some(url));
+            }
+            Tag::Image { dest_url, .. } => self.image = Some((String::new(), dest_url.to_string())),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph | TagEnd::HtmlBlock => {
+                let frame = self.pop();
+                self.end_paragraph(frame);
+            }
+            TagEnd::Heading(_) | TagEnd::BlockQuote(_) | TagEnd::Item => {
+                let mut frame = self.pop();
+                let trailing = std::mem::take(&mut frame.trailing);
+                self.push_block(frame.into_block());
+                for block in trailing {
+                    self.push_block(block);
+                }
+            }
+            TagEnd::CodeBlock => {
+                let mut frame = self.pop();
+                let code: String = frame
+                    .rich_text
+                    .iter()
+                    .filter_map(|t| t["text"]["content"].as_str())
+                    .collect();
+                frame.rich_text = text_objects(code.trim_end_matches('\n'), &json!({}), None);
+                self.push_block(frame.into_block());
+            }
+            TagEnd::List(_) => {
+                let list = self.pop();
+                for item in list.children {
+                    self.push_block(item);
+                }
+            }
+            TagEnd::TableCell => {
+                // Blocks can't go inside a cell; they follow the table instead
+                let mut cell = self.pop();
+                let row = self.top();
+                row.trailing.append(&mut cell.trailing);
+                row.cells.push(Value::Array(cell.rich_text));
+            }
+            TagEnd::TableHead | TagEnd::TableRow => {
+                let mut row = self.pop();
+                self.top().trailing.append(&mut row.trailing);
+                let block = json!({
+                    "object": "block",
+                    "type": "table_row",
+                    "table_row": { "cells": row.cells }
+                });
+                self.top().children.push(block);
+            }
+            TagEnd::Table => {
+                let mut table = self.pop();
+                let rows = std::mem::take(&mut table.children);
+                let trailing = std::mem::take(&mut table.trailing);
+                let block = json!({
+                    "object": "block",
+                    "type": "table",
+                    "table": {
+                        "table_width": table.fields["table_width"],
+                        "has_column_header": true,
+                        "has_row_header": false,
+                        "children": rows,
+                    }
+                });
+                self.push_block(block);
+                for block in trailing {
+                    self.push_block(block);
+                }
+            }
+            TagEnd::Emphasis => self.italic = self.italic.saturating_sub(1),
+            TagEnd::Strong => self.bold = self.bold.saturating_sub(1),
+            TagEnd::Strikethrough => self.strikethrough = self.strikethrough.saturating_sub(1),
+            TagEnd::Link => {
+                self.links.pop();
+            }
+            _ => {}
+        }
+    }
+
+    /// The first paragraph of a list item or quote is its own text; later
+    /// paragraphs become children.
+    fn end_paragraph(&mut self, mut frame: Frame) {
+        let trailing = std::mem::take(&mut frame.trailing);
+        let has_text = frame
+            .rich_text
+            .iter()
+            .any(|t| t["type"] != "text" || t["text"]["content"].as_str() != Some(""));
+
+        if has_text {
+            let parent = self.top();
+            let absorbs = matches!(
+                parent.kind,
+                "bulleted_list_item" | "numbered_list_item" | "to_do" | "quote"
+            );
+            if absorbs && parent.rich_text.is_empty() && parent.children.is_empty() {
+                parent.rich_text = frame.rich_text;
+            } else {
+                self.push_block(frame.into_block());
+            }
+        }
+        for block in trailing {
+            self.push_block(block);
+        }
+    }
+
+    fn end_image(&mut self) {
+        let Some((alt, url)) = self.image.take() else {
+            return;
+        };
+        if !is_absolute_url(&url) {
+            // Local images would need a file upload; keep the reference as text
+            self.text(&format!("![{}]({})", alt, url), false);
+            return;
+        }
+        let caption = text_objects(&alt, &json!({}), None);
+        let block = json!({
+            "object": "block",
+            "type": "image",
+            "image": { "type": "external", "external": { "url": url }, "caption": caption }
+        });
+        self.top().trailing.push(block);
+    }
+
+    fn text(&mut self, text: &str, code: bool) {
+        if self.top().kind == "code" {
+            self.top()
+                .rich_text
+                .extend(text_objects(text, &json!({}), None));
+            return;
+        }
+        let annotations = json!({
+            "bold": self.bold > 0,
+            "italic": self.italic > 0,
+            "strikethrough": self.strikethrough > 0,
+            "underline": false,
+            "code": code,
+            "color": "default",
+        });
+        let link = self.links.last().cloned().flatten();
+        for object in text_objects(text, &annotations, link.as_deref()) {
+            self.push_rich_text(object);
+        }
+    }
+
+    /// Append a rich text object, merging it into the previous one when the
+    /// formatting is the same.
+    fn push_rich_text(&mut self, object: Value) {
+        let rich_text = &mut self.top().rich_text;
+        if let Some(last) = rich_text.last_mut()
+            && last["type"] == "text"
+            && object["type"] == "text"
+            && last["annotations"] == object["annotations"]
+            && last["text"]["link"] == object["text"]["link"]
+        {
+            let merged = format!(
+                "{}{}",
+                last["text"]["content"].as_str().unwrap_or_default(),
+                object["text"]["content"].as_str().unwrap_or_default()
+            );
+            if merged.chars().count() <= MAX_TEXT_LENGTH {
+                last["text"]["content"] = json!(merged);
+                return;
+            }
+        }
+        rich_text.push(object);
+    }
+
+    fn push_block(&mut self, block: Value) {
+        self.top().children.push(block);
+    }
+
+    /// The innermost open frame; the document frame is never popped.
+    fn top(&mut self) -> &mut Frame {
+        if self.stack.is_empty() {
+            self.stack.push(Frame::new("document"));
+        }
+        self.stack.last_mut().expect("stack is not empty")
+    }
+
+    fn pop(&mut self) -> Frame {
+        match self.stack.len() {
+            0 | 1 => Frame::default(),
+            _ => self.stack.pop().unwrap_or_default(),
+        }
+    }
+
+    fn finish(mut self) -> Vec<Value> {
+        self.stack
+            .drain(..)
+            .next()
+            .map(|document| document.children)
+            .unwrap_or_default()
+    }
+}
+
+/// Text objects for `text`, split to stay under the API's length limit.
+fn text_objects(text: &str, annotations: &Value, link: Option<&str>) -> Vec<Value> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(MAX_TEXT_LENGTH)
+        .map(|chunk| {
+            let mut object = json!({
+                "type": "text",
+                "text": {
+                    "content": chunk.iter().collect::<String>(),
+                    "link": link.map(|url| json!({ "url": url })),
+                },
+            });
+            if annotations.as_object().is_some_and(|a| !a.is_empty()) {
+                object["annotations"] = annotations.clone();
+            }
+            object
+        })
+        .collect()
+}
+
+fn is_absolute_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://") || url.starts_with("mailto:")
+}
+
+/// Map a fenced code block's info string to a Notion language.
+fn code_language(info: &str) -> &'static str {
+    let name = info
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let name = match name.as_str() {
+        "" | "text" | "txt" | "plain" => "plain text",
+        "js" | "jsx" | "mjs" => "javascript",
+        "ts" | "tsx" => "typescript",
+        "py" => "python",
+        "rs" => "rust",
+        "sh" | "zsh" | "console" => "shell",
+        "yml" => "yaml",
+        "md" => "markdown",
+        "rb" => "ruby",
+        "cpp" | "cc" | "hpp" => "c++",
+        "cs" | "csharp" => "c#",
+        "fs" | "fsharp" => "f#",
+        "kt" => "kotlin",
+        "dockerfile" => "docker",
+        "tf" => "hcl",
+        "proto" => "protobuf",
+        "golang" => "go",
+        "objc" => "objective-c",
+        "ps1" | "pwsh" => "powershell",
+        "tex" => "latex",
+        "wasm" => "webassembly",
+        other => other,
+    };
+    CODE_LANGUAGES
+        .iter()
+        .find(|l| **l == name)
+        .copied()
+        .unwrap_or("plain text")
+}
+
+#[cfg(test)]
+#[path = "markdown_tests.rs"]
+mod tests;
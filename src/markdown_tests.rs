@@ -0,0 +1,374 @@
+use super::*;
+use serde_json::json;
+
+fn text(content: &str) -> Value {
+    json!({ "type": "text", "plain_text": content, "text": { "content": content } })
+}
+
+fn styled(content: &str, annotations: Value) -> Value {
+    json!({
+        "type": "text",
+        "plain_text": content,
+        "text": { "content": content },
+        "annotations": annotations
+    })
+}
+
+fn block(kind: &str, content: &str) -> Value {
+    json!({ "type": kind, kind: { "rich_text": [text(content)] } })
+}
+
+fn with_children(mut block: Value, children: Vec<Value>) -> Value {
+    block["has_children"] = json!(true);
+    block["children"] = Value::Array(children);
+    block
+}
+
+#[test]
+fn test_rich_text_annotations() {
+    let rt = json!([
+        text("Use "),
+        styled("bold ", json!({ "bold": true })),
+        styled("code", json!({ "code": true })),
+        text(" and "),
+        styled("gone", json!({ "strikethrough": true, "italic": true })),
+    ]);
+    assert_eq!(rich_text(&rt), "Use **bold** `code` and *~~gone~~*");
+}
+
+#[test]
+fn test_rich_text_merges_adjacent_spans() {
+    let rt = json!([
+        styled("a", json!({ "bold": true })),
+        styled("b", json!({ "bold": true })),
+    ]);
+    assert_eq!(rich_text(&rt), "**ab**");
+}
+
+#[test]
+fn test_rich_text_links_and_mentions() {
+    let rt = json!([
+        { "type": "text", "plain_text": "docs", "text": { "content": "docs", "link": { "url": "https://x.dev" } } },
+        text(" "),
+        { "type": "mention", "plain_text": "Roadmap", "href": "https://www.notion.so/abc", "mention": { "type": "page" } },
+        text(" "),
+        { "type": "text", "plain_text": "here", "text": { "content": "here", "link": { "url": "/abc123" } } },
+    ]);
+    assert_eq!(
+        rich_text(&rt),
+        "[docs](https://x.dev) [Roadmap](https://www.notion.so/abc) [here](https://www.notion.so/abc123)"
+    );
+}
+
+#[test]
+fn test_rich_text_equation_and_escaping() {
+    let rt = json!([
+        text("x*y_ snake_case [1] "),
+        { "type": "equation", "plain_text": "e=mc^2", "equation": { "expression": "e=mc^2" } },
+    ]);
+    assert_eq!(rich_text(&rt), "x\\*y\\_ snake_case \\[1\\] $e=mc^2$");
+}
+
+#[test]
+fn test_rich_text_code_with_backticks() {
+    let rt = json!([styled("a`b", json!({ "code": true }))]);
+    assert_eq!(rich_text(&rt), "``a`b``");
+}
+
+#[test]
+fn test_headings_and_paragraphs() {
+    let blocks = vec![
+        block("heading_1", "Title"),
+        block("paragraph", "Intro"),
+        block("heading_3", "Small"),
+        block("paragraph", "# not a heading"),
+    ];
+    assert_eq!(
+        blocks_to_markdown(&blocks),
+        "# Title\n\nIntro\n\n### Small\n\n\\# not a heading"
+    );
+}
+
+#[test]
+fn test_nested_lists() {
+    let blocks = vec![
+        with_children(
+            block("bulleted_list_item", "One"),
+            vec![block("bulleted_list_item", "Nested")],
+        ),
+        block("bulleted_list_item", "Two"),
+        block("paragraph", "Break"),
+        block("numbered_list_item", "First"),
+        with_children(
+            block("numbered_list_item", "Second"),
+            vec![block("numbered_list_item", "Inner")],
+        ),
+    ];
+    assert_eq!(
+        blocks_to_markdown(&blocks),
+        "- One\n  - Nested\n- Two\n\nBreak\n\n1. First\n2. Second\n   1. Inner"
+    );
+}
+
+#[test]
+fn test_to_do() {
+    let mut done = block("to_do", "Ship");
+    done["to_do"]["checked"] = json!(true);
+    let blocks = vec![done, block("to_do", "Test")];
+    assert_eq!(blocks_to_markdown(&blocks), "- [x] Ship\n- [ ] Test");
+}
+
+#[test]
+fn test_toggle_quote_and_callout() {
+    let mut callout = block("callout", "Heads up");
+    callout["callout"]["icon"] = json!({ "type": "emoji", "emoji": "💡" });
+    let blocks = vec![
+        with_children(
+            block("toggle", "More <info>"),
+            vec![block("paragraph", "Hidden")],
+        ),
+        with_children(block("quote", "Quoted"), vec![block("paragraph", "Nested")]),
+        callout,
+    ];
+    assert_eq!(
+        blocks_to_markdown(&blocks),
+        "<details>\n<summary>More &lt;info&gt;</summary>\n\nHidden\n\n</details>\n\n\
+         > Quoted\n>\n> Nested\n\n\
+         > 💡 Heads up"
+    );
+}
+
+#[test]
+fn test_code_equation_and_divider() {
+    let code = json!({
+        "type": "code",
+        "code": { "rich_text": [text("fn main() {}\n```")], "language": "rust" }
+    });
+    let plain = json!({
+        "type": "code",
+        "code": { "rich_text": [text("x")], "language": "plain text" }
+    });
+    let equation = json!({ "type": "equation", "equation": { "expression": "a^2" } });
+    let divider = json!({ "type": "divider", "divider": {} });
+    assert_eq!(
+        blocks_to_markdown(&[code, plain, equation, divider]),
+        "````rust\nfn main() {}\n```\n````\n\n```\nx\n```\n\n$$\na^2\n$$\n\n---"
+    );
+}
+
+#[test]
+fn test_table() {
+    let row = |a: &str, b: &str| json!({ "type": "table_row", "table_row": { "cells": [[text(a)], [text(b)]] } });
+    let table = with_children(
+        json!({ "type": "table", "table": { "table_width": 2 } }),
+        vec![row("Name", "Value"), row("a|b", "1")],
+    );
+    assert_eq!(
+        blocks_to_markdown(&[table]),
+        "| Name | Value |\n| --- | --- |\n| a\\|b | 1 |"
+    );
+}
+
+#[test]
+fn test_media_and_links() {
+    let image = json!({
+        "type": "image",
+        "image": { "type": "external", "external": { "url": "https://x.dev/a.png" }, "caption": [text("Diagram")] }
+    });
+    let file = json!({
+        "type": "file",
+        "file": { "type": "file", "file": { "url": "https://s3/f.pdf" }, "caption": [], "name": "spec.pdf" }
+    });
+    let child = json!({ "type": "child_page", "id": "ab-cd", "child_page": { "title": "Sub" } });
+    assert_eq!(
+        blocks_to_markdown(&[image, file, child]),
+        "![Diagram](https://x.dev/a.png)\n\n[spec.pdf](https://s3/f.pdf)\n\n[Sub](https://www.notion.so/abcd)"
+    );
+}
+
+#[test]
+fn test_unsupported_and_skipped_blocks() {
+    let toc = json!({ "type": "table_of_contents", "table_of_contents": {} });
+    let unknown = json!({ "type": "ai_block", "ai_block": {} });
+    assert_eq!(
+        blocks_to_markdown(&[toc, unknown]),
+        "<!-- unsupported block: ai_block -->"
+    );
+}
+
+#[test]
+fn test_page_to_markdown_adds_title() {
+    let page = json!({
+        "object": "page",
+        "properties": { "Name": { "type": "title", "title": [text("Spec")] } }
+    });
+    assert_eq!(
+        page_to_markdown(&page, &[block("paragraph", "Body")]),
+        "# Spec\n\nBody\n"
+    );
+}
+
+#[test]
+fn test_empty_paragraphs_are_skipped() {
+    let empty = json!({ "type": "paragraph", "paragraph": { "rich_text": [] } });
+    let blocks = vec![block("paragraph", "A"), empty, block("paragraph", "B")];
+    assert_eq!(blocks_to_markdown(&blocks), "A\n\nB");
+}
+
+fn content(block: &Value) -> String {
+    let kind = block["type"].as_str().unwrap();
+    block[kind]["rich_text"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["text"]["content"].as_str().unwrap_or_default())
+        .collect()
+}
+
+#[test]
+fn test_to_blocks_headings_and_paragraphs() {
+    let blocks = to_blocks("# One\n\n## Two\n\n#### Four\n\nSome text\nwrapped.\n");
+    let kinds: Vec<&str> = blocks.iter().map(|b| b["type"].as_str().unwrap()).collect();
+    assert_eq!(kinds, ["heading_1", "heading_2", "heading_3", "paragraph"]);
+    assert_eq!(content(&blocks[3]), "Some text wrapped.");
+}
+
+#[test]
+fn test_to_blocks_inline_annotations() {
+    let blocks =
+        to_blocks("Plain **bold** *it* `code` ~~old~~ [link](https://x.dev) [rel](./a.md)");
+    let rt = blocks[0]["paragraph"]["rich_text"].as_array().unwrap();
+    let find = |text: &str| {
+        rt.iter()
+            .find(|t| t["text"]["content"] == text)
+            .unwrap_or_else(|| panic!("no span {:?} in {:?}", text, rt))
+    };
+    assert_eq!(find("bold")["annotations"]["bold"], true);
+    assert_eq!(find("it")["annotations"]["italic"], true);
+    assert_eq!(find("code")["annotations"]["code"], true);
+    assert_eq!(find("old")["annotations"]["strikethrough"], true);
+    assert_eq!(find("link")["text"]["link"]["url"], "https://x.dev");
+    // Relative links can't be resolved by Notion and stay plain text
+    assert!(find(" rel")["text"]["link"].is_null());
+}
+
+#[test]
+fn test_to_blocks_nested_lists_and_tasks() {
+    let blocks = to_blocks("- a\n  - b\n    1. c\n- [x] done\n- [ ] todo\n");
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[0]["type"], "bulleted_list_item");
+    let b = &blocks[0]["bulleted_list_item"]["children"][0];
+    assert_eq!(content(b), "b");
+    let c = &b["bulleted_list_item"]["children"][0];
+    assert_eq!(c["type"], "numbered_list_item");
+    assert_eq!(blocks[1]["type"], "to_do");
+    assert_eq!(blocks[1]["to_do"]["checked"], true);
+    assert_eq!(content(&blocks[1]), "done");
+    assert_eq!(blocks[2]["to_do"]["checked"], false);
+}
+
+#[test]
+fn test_to_blocks_loose_list_item_paragraphs() {
+    let blocks = to_blocks("- first\n\n  second\n\n- next\n");
+    assert_eq!(content(&blocks[0]), "first");
+    assert_eq!(
+        content(&blocks[0]["bulleted_list_item"]["children"][0]),
+        "second"
+    );
+    assert_eq!(content(&blocks[1]), "next");
+}
+
+#[test]
+fn test_to_blocks_code_block_language() {
+    let blocks = to_blocks("```ts\nconst a = 1;\n```\n\n```weird\nx\n```\n\n    indented\n");
+    assert_eq!(blocks[0]["code"]["language"], "typescript");
+    assert_eq!(content(&blocks[0]), "const a = 1;");
+    assert_eq!(blocks[1]["code"]["language"], "plain text");
+    assert_eq!(content(&blocks[2]), "indented");
+}
+
+#[test]
+fn test_to_blocks_splits_long_text() {
+    let long = "x".repeat(4500);
+    let blocks = to_blocks(&long);
+    let rt = blocks[0]["paragraph"]["rich_text"].as_array().unwrap();
+    assert_eq!(rt.len(), 3);
+    assert_eq!(rt[0]["text"]["content"].as_str().unwrap().len(), 2000);
+}
+
+#[test]
+fn test_to_blocks_quote_table_rule_and_image() {
+    let md = "> quoted\n>\n> more\n\n| A | B |\n|---|---|\n| 1 | 2 |\n\n---\n\n![Diagram](https://x.dev/a.png)\n";
+    let blocks = to_blocks(md);
+    let kinds: Vec<&str> = blocks.iter().map(|b| b["type"].as_str().unwrap()).collect();
+    assert_eq!(kinds, ["quote", "table", "divider", "image"]);
+
+    assert_eq!(content(&blocks[0]), "quoted");
+    assert_eq!(content(&blocks[0]["quote"]["children"][0]), "more");
+
+    let table = &blocks[1]["table"];
+    assert_eq!(table["table_width"], 2);
+    assert_eq!(table["has_column_header"], true);
+    assert_eq!(table["children"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        table["children"][1]["table_row"]["cells"][1][0]["text"]["content"],
+        "2"
+    );
+
+    assert_eq!(blocks[3]["image"]["external"]["url"], "https://x.dev/a.png");
+    assert_eq!(
+        blocks[3]["image"]["caption"][0]["text"]["content"],
+        "Diagram"
+    );
+}
+
+#[test]
+fn test_to_blocks_local_image_stays_text() {
+    let blocks = to_blocks("![logo](./logo.png)");
+    assert_eq!(blocks[0]["type"], "paragraph");
+    assert_eq!(content(&blocks[0]), "![logo](./logo.png)");
+}
+
+#[test]
+fn test_to_blocks_image_in_heading() {
+    let blocks = to_blocks("# Logo ![x](https://x.dev/a.png)\n\nAfter");
+    let kinds: Vec<&str> = blocks.iter().map(|b| b["type"].as_str().unwrap()).collect();
+    assert_eq!(kinds, ["heading_1", "image", "paragraph"]);
+    assert_eq!(content(&blocks[0]), "Logo ");
+    assert_eq!(blocks[1]["image"]["external"]["url"], "https://x.dev/a.png");
+}
+
+#[test]
+fn test_to_blocks_image_in_table_cell_follows_table() {
+    let md = "| A | B |\n|---|---|\n| 1 | ![x](https://x.dev/a.png) |\n\nAfter";
+    let blocks = to_blocks(md);
+    let kinds: Vec<&str> = blocks.iter().map(|b| b["type"].as_str().unwrap()).collect();
+    assert_eq!(kinds, ["table", "image", "paragraph"]);
+    assert_eq!(blocks[1]["image"]["external"]["url"], "https://x.dev/a.png");
+}
+
+#[test]
+fn test_to_blocks_math() {
+    let blocks = to_blocks("Inline $x^2$ here\n\n$$\na+b\n$$\n");
+    let rt = blocks[0]["paragraph"]["rich_text"].as_array().unwrap();
+    assert_eq!(rt[1]["equation"]["expression"], "x^2");
+    assert_eq!(blocks[1]["type"], "equation");
+    assert_eq!(blocks[1]["equation"]["expression"], "a+b");
+}
+
+#[test]
+fn test_markdown_roundtrip() {
+    let md = "# Title\n\nText with **bold** and `code`.\n\n- one\n  - two\n- [x] done\n\n> quote\n\n```rust\nfn main() {}\n```";
+    let blocks = to_blocks(md);
+    // Rendering expects children next to the block, as the API returns them
+    let tree: Vec<Value> = blocks.into_iter().map(lift_children).collect();
+    assert_eq!(blocks_to_markdown(&tree), md);
+}
+
+fn lift_children(mut block: Value) -> Value {
+    let kind = block["type"].as_str().unwrap().to_string();
+    if let Some(Value::Array(children)) = block[&kind].as_object_mut().unwrap().remove("children") {
+        block["children"] = Value::Array(children.into_iter().map(lift_children).collect());
+    }
+    block
+}
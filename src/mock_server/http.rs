@@ -0,0 +1,267 @@
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Largest request body accepted, matching the API's 20 MB upload limit.
+const MAX_BODY: usize = 20 * 1024 * 1024;
+
+/// A parsed HTTP/1.1 request.
+#[derive(Debug)]
+pub struct Request {
+    pub method: String,
+    /// Percent-decoded path
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// A JSON response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+/// One part of a `multipart/form-data` body.
+#[derive(Debug, PartialEq)]
+pub struct FormPart {
+    pub name: String,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub data: Vec<u8>,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Parts of a `multipart/form-data` body; empty for any other body.
+    pub fn form_parts(&self) -> Vec<FormPart> {
+        let boundary = self
+            .header("content-type")
+            .filter(|ct| ct.starts_with("multipart/form-data"))
+            .and_then(|ct| {
+                ct.split(';')
+                    .find_map(|param| param.trim().strip_prefix("boundary="))
+            })
+            .map(|b| b.trim_matches('"').to_string());
+        match boundary {
+            Some(boundary) => parse_form(&self.body, &boundary),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Read the next request on a connection; `None` once the client hangs up.
+pub async fn read_request<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<Request>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        bail!("Malformed request line: {:?}", line.trim_end());
+    };
+    let method = method.to_string();
+    let url = reqwest::Url::parse(&format!("http://localhost{}", target))
+        .with_context(|| format!("Malformed request target: {}", target))?;
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            bail!("Connection closed in the middle of the headers");
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let mut request = Request {
+        method,
+        path: percent_decode(url.path()),
+        query: url
+            .query_pairs()
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect(),
+        headers,
+        body: Vec::new(),
+    };
+    let chunked = request
+        .header("transfer-encoding")
+        .is_some_and(|te| te.eq_ignore_ascii_case("chunked"));
+    request.body = if chunked {
+        read_chunked(reader).await?
+    } else {
+        let length: usize = request
+            .header("content-length")
+            .map(str::parse)
+            .transpose()
+            .context("Invalid Content-Length")?
+            .unwrap_or(0);
+        if length > MAX_BODY {
+            bail!("Request body of {} bytes is too large", length);
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).await?;
+        body
+    };
+    Ok(Some(request))
+}
+
+async fn read_chunked<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        let size = line.trim().split(';').next().unwrap_or_default();
+        let size = usize::from_str_radix(size, 16)
+            .with_context(|| format!("Invalid chunk size: {:?}", line.trim()))?;
+        if size == 0 {
+            // Skip trailers up to the blank line
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+                    return Ok(body);
+                }
+            }
+        }
+        if body.len() + size > MAX_BODY {
+            bail!("Request body is too large");
+        }
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..]).await?;
+        let mut crlf = [0; 2];
+        reader.read_exact(&mut crlf).await?;
+    }
+}
+
+pub async fn write_response<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    response: &Response,
+    request_id: &str,
+) -> Result<()> {
+    let body = response.body.to_string();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\ncontent-type: application/json; charset=utf-8\r\ncontent-length: {}\r\nx-request-id: {}\r\n\r\n",
+        response.status,
+        reason(response.status),
+        body.len(),
+        request_id
+    );
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(body.as_bytes()).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        409 => "Conflict",
+        _ => "Unknown",
+    }
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn parse_form(body: &[u8], boundary: &str) -> Vec<FormPart> {
+    let delimiter = format!("--{}", boundary);
+    split(body, delimiter.as_bytes())
+        .into_iter()
+        .skip(1)
+        .filter_map(|section| {
+            // Each section is "\r\n<headers>\r\n\r\n<data>\r\n"; the last is "--\r\n"
+            let section = section.strip_prefix(b"\r\n")?;
+            let split_at = find(section, b"\r\n\r\n")?;
+            let head = String::from_utf8_lossy(&section[..split_at]);
+            let data = &section[split_at + 4..];
+            let data = data.strip_suffix(b"\r\n").unwrap_or(data);
+
+            let mut part = FormPart {
+                name: String::new(),
+                filename: None,
+                content_type: None,
+                data: data.to_vec(),
+            };
+            for line in head.lines() {
+                let Some((name, value)) = line.split_once(':') else {
+                    continue;
+                };
+                if name.eq_ignore_ascii_case("content-type") {
+                    part.content_type = Some(value.trim().to_string());
+                } else if name.eq_ignore_ascii_case("content-disposition") {
+                    for param in value.split(';').map(str::trim) {
+                        if let Some(v) = param.strip_prefix("name=") {
+                            part.name = v.trim_matches('"').to_string();
+                        } else if let Some(v) = param.strip_prefix("filename=") {
+                            part.filename = Some(v.trim_matches('"').to_string());
+                        }
+                    }
+                }
+            }
+            Some(part)
+        })
+        .collect()
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn split<'a>(mut bytes: &'a [u8], delimiter: &[u8]) -> Vec<&'a [u8]> {
+    let mut pieces = Vec::new();
+    while let Some(i) = find(bytes, delimiter) {
+        pieces.push(&bytes[..i]);
+        bytes = &bytes[i + delimiter.len()..];
+    }
+    pieces.push(bytes);
+    pieces
+}
+
+#[cfg(test)]
+#[path = "http_tests.rs"]
+mod tests;
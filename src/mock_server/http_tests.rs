@@ -0,0 +1,108 @@
+use super::*;
+use serde_json::json;
+
+async fn parse(raw: &[u8]) -> Option<Request> {
+    let mut reader = tokio::io::BufReader::new(raw);
+    read_request(&mut reader).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_read_request_with_content_length() {
+    let raw = b"POST /v1/data_sources/ds%201/query?page_size=5&q=a%20b HTTP/1.1\r\nHost: x\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n{}";
+    let request = parse(raw).await.unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/v1/data_sources/ds 1/query");
+    assert_eq!(request.query("page_size"), Some("5"));
+    assert_eq!(request.query("q"), Some("a b"));
+    assert_eq!(request.header("content-type"), Some("application/json"));
+    assert_eq!(request.body, b"{}");
+}
+
+#[tokio::test]
+async fn test_read_request_with_chunked_body() {
+    let raw = b"PATCH /v1/pages/p HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n{\"a\"\r\n4;ext=1\r\n:1}\n\r\n0\r\n\r\n";
+    let request = parse(raw).await.unwrap();
+    assert_eq!(request.body, b"{\"a\":1}\n");
+}
+
+#[tokio::test]
+async fn test_read_request_returns_none_at_eof() {
+    assert!(parse(b"").await.is_none());
+}
+
+#[tokio::test]
+async fn test_read_request_reads_keep_alive_requests_in_turn() {
+    let raw = b"GET /v1/users/me HTTP/1.1\r\n\r\nGET /v1/users HTTP/1.1\r\n\r\n";
+    let mut reader = tokio::io::BufReader::new(&raw[..]);
+    let first = read_request(&mut reader).await.unwrap().unwrap();
+    let second = read_request(&mut reader).await.unwrap().unwrap();
+    assert_eq!(first.path, "/v1/users/me");
+    assert_eq!(second.path, "/v1/users");
+    assert!(read_request(&mut reader).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_read_request_rejects_malformed_request_line() {
+    let mut reader = tokio::io::BufReader::new(&b"nonsense\r\n\r\n"[..]);
+    assert!(read_request(&mut reader).await.is_err());
+}
+
+#[tokio::test]
+async fn test_write_response() {
+    let mut out = Vec::new();
+    let response = Response {
+        status: 404,
+        body: json!({"object": "error"}),
+    };
+    write_response(&mut out, &response, "req-1").await.unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    assert!(text.contains("content-length: 18\r\n"));
+    assert!(text.contains("x-request-id: req-1\r\n"));
+    assert!(text.ends_with("\r\n\r\n{\"object\":\"error\"}"));
+}
+
+#[test]
+fn test_form_parts() {
+    let body = b"--XYZ\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\nContent-Type: text/plain\r\n\r\nhello\r\n--XYZ\r\nContent-Disposition: form-data; name=\"part_number\"\r\n\r\n2\r\n--XYZ--\r\n";
+    let request = Request {
+        method: "POST".into(),
+        path: "/v1/file_uploads/u/send".into(),
+        query: Vec::new(),
+        headers: vec![(
+            "Content-Type".into(),
+            "multipart/form-data; boundary=XYZ".into(),
+        )],
+        body: body.to_vec(),
+    };
+    let parts = request.form_parts();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(
+        parts[0],
+        FormPart {
+            name: "file".into(),
+            filename: Some("a.txt".into()),
+            content_type: Some("text/plain".into()),
+            data: b"hello".to_vec(),
+        }
+    );
+    assert_eq!(parts[1].name, "part_number");
+    assert_eq!(parts[1].data, b"2");
+}
+
+#[test]
+fn test_form_parts_empty_for_json_body() {
+    let request = Request {
+        method: "POST".into(),
+        path: "/".into(),
+        query: Vec::new(),
+        headers: vec![("content-type".into(), "application/json".into())],
+        body: b"{}".to_vec(),
+    };
+    assert!(request.form_parts().is_empty());
+}
+
+#[test]
+fn test_percent_decode_keeps_invalid_escapes() {
+    assert_eq!(percent_decode("a%20b%zz%"), "a b%zz%");
+}
@@ -0,0 +1,109 @@
+//! A local, in-memory stand-in for the Notion API.
+//!
+//! Serves a subset of the API over plain HTTP on localhost: users, pages,
+//! blocks with children, databases, data sources with query filters and
+//! sorts, search, comments and file uploads. Point a client at it with
+//! `NotionClient::with_base_url` (or `--base-url`) to run whole flows
+//! without a network or a workspace.
+
+mod http;
+mod query;
+mod store;
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::BufReader;
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::task::JoinHandle;
+
+pub use store::Seed;
+use store::Store;
+
+/// A running mock server; it stops when dropped.
+pub struct MockServer {
+    url: String,
+    seed: Seed,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Listen on `addr` (port 0 picks a free port) and serve in the background.
+    pub async fn start(addr: impl ToSocketAddrs) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .context("Failed to start the mock server")?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let store = Store::new(&url);
+        let seed = store.seed().clone();
+        let task = tokio::spawn(accept(listener, Arc::new(Mutex::new(store))));
+        Ok(Self { url, seed, task })
+    }
+
+    /// Base URL to pass to the client, e.g. `http://127.0.0.1:4010`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// IDs of the users and page the server starts with.
+    pub fn seed(&self) -> &Seed {
+        &self.seed
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn accept(listener: TcpListener, store: Arc<Mutex<Store>>) {
+    let requests = Arc::new(AtomicU64::new(0));
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        tokio::spawn(serve(stream, store.clone(), requests.clone()));
+    }
+}
+
+/// Answer requests on one keep-alive connection until the client hangs up.
+async fn serve(stream: TcpStream, store: Arc<Mutex<Store>>, requests: Arc<AtomicU64>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    loop {
+        let number = requests.fetch_add(1, Ordering::Relaxed) + 1;
+        let request_id = format!("6d6f636b-0000-4000-9000-{:012x}", number);
+        let (mut response, keep_open) = match http::read_request(&mut reader).await {
+            Ok(Some(request)) => {
+                let mut store = store.lock().unwrap_or_else(|e| e.into_inner());
+                (store.handle(&request), true)
+            }
+            Ok(None) => return,
+            Err(e) => {
+                let response = http::Response {
+                    status: 400,
+                    body: json!({
+                        "object": "error",
+                        "status": 400,
+                        "code": "invalid_request",
+                        "message": e.to_string(),
+                    }),
+                };
+                (response, false)
+            }
+        };
+        if response.body["object"] == "error" {
+            response.body["request_id"] = json!(request_id);
+        }
+        let written = http::write_response(&mut writer, &response, &request_id).await;
+        if written.is_err() || !keep_open {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "mod_tests.rs"]
+mod tests;
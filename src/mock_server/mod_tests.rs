@@ -0,0 +1,59 @@
+use super::*;
+use crate::client::NotionClient;
+use crate::error::{ErrorKind, NotionError, find};
+
+#[tokio::test]
+async fn test_serves_client_requests_over_http() {
+    let server = MockServer::start("127.0.0.1:0").await.unwrap();
+    assert!(server.url().starts_with("http://127.0.0.1:"));
+    let client = NotionClient::with_base_url("any-token", server.url()).unwrap();
+
+    let me = client.get("/v1/users/me", &[]).await.unwrap();
+    assert_eq!(me["id"], server.seed().bot_id.as_str());
+
+    // Several requests share a keep-alive connection
+    let path = format!("/v1/pages/{}", server.seed().page_id);
+    for _ in 0..3 {
+        let page = client.get(&path, &[]).await.unwrap();
+        assert_eq!(page["id"], server.seed().page_id.as_str());
+    }
+}
+
+#[tokio::test]
+async fn test_errors_become_api_errors_with_request_id() {
+    let server = MockServer::start("127.0.0.1:0").await.unwrap();
+    let client = NotionClient::with_base_url("any-token", server.url()).unwrap();
+
+    let err = client
+        .get("/v1/blocks/00000000000000000000000000000000", &[])
+        .await
+        .unwrap_err();
+    let error = find(&err).unwrap();
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+    let NotionError::Api { request_id, .. } = error else {
+        panic!("expected an API error, got {:?}", error);
+    };
+    assert!(request_id.as_deref().unwrap().starts_with("6d6f636b-"));
+}
+
+#[tokio::test]
+async fn test_each_server_has_its_own_workspace() {
+    let first = MockServer::start("127.0.0.1:0").await.unwrap();
+    let second = MockServer::start("127.0.0.1:0").await.unwrap();
+    let client = NotionClient::with_base_url("any-token", first.url()).unwrap();
+    let body = serde_json::json!({
+        "children": [{ "divider": {} }],
+    });
+    let path = format!("/v1/blocks/{}/children", first.seed().page_id);
+    client.patch(&path, &body).await.unwrap();
+
+    let other = NotionClient::with_base_url("any-token", second.url()).unwrap();
+    let children = other
+        .get(
+            &format!("/v1/blocks/{}/children", second.seed().page_id),
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(children["results"], serde_json::json!([]));
+}
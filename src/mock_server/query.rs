@@ -0,0 +1,315 @@
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// A property or timestamp value reduced to something filters can compare.
+#[derive(Debug, Clone, PartialEq)]
+enum Field {
+    Text(String),
+    Number(Option<f64>),
+    Bool(bool),
+    /// Select, multi-select, people, relation and files values
+    Names(Vec<String>),
+    /// ISO 8601 date or date-time
+    Date(Option<String>),
+}
+
+/// Whether a page passes a data source query filter.
+///
+/// Supports `and`/`or` compounds, property filters for the common property
+/// types and `created_time`/`last_edited_time` timestamp filters. Errors are
+/// messages for a `validation_error` response.
+pub fn matches(page: &Value, filter: &Value) -> Result<bool, String> {
+    if let Some(filters) = filter.get("and") {
+        for f in filters.as_array().ok_or("filter.and should be an array")? {
+            if !matches(page, f)? {
+                return Ok(false);
+            }
+        }
+        return Ok(true);
+    }
+    if let Some(filters) = filter.get("or") {
+        for f in filters.as_array().ok_or("filter.or should be an array")? {
+            if matches(page, f)? {
+                return Ok(true);
+            }
+        }
+        return Ok(false);
+    }
+
+    let (field, key) = if let Some(timestamp) = filter["timestamp"].as_str() {
+        (timestamp_field(page, timestamp)?, timestamp)
+    } else if let Some(name) = filter["property"].as_str() {
+        let property = property(page, name)?;
+        let key = filter
+            .as_object()
+            .and_then(|f| f.keys().find(|k| *k != "property"))
+            .ok_or_else(|| format!("Filter for property \"{}\" has no condition", name))?;
+        (field(property), key.as_str())
+    } else {
+        return Err("Filter needs a property, timestamp, and or or".to_string());
+    };
+
+    let condition = filter[key]
+        .as_object()
+        .and_then(|c| c.iter().next())
+        .ok_or_else(|| format!("filter.{} should be an object with one condition", key))?;
+    test(&field, condition.0, condition.1)
+}
+
+/// Order pages by a list of property or timestamp sorts.
+///
+/// Empty values sort last in both directions, like the API.
+pub fn sort(pages: &mut [Value], sorts: &[Value]) -> Result<(), String> {
+    let mut keys = Vec::with_capacity(sorts.len());
+    for sort in sorts {
+        let descending = match sort["direction"].as_str() {
+            Some("ascending") | None => false,
+            Some("descending") => true,
+            Some(other) => return Err(format!("Invalid sort direction: {}", other)),
+        };
+        let key = match (sort["property"].as_str(), sort["timestamp"].as_str()) {
+            (Some(name), _) => SortKey::Property(name.to_string()),
+            (None, Some(timestamp)) => SortKey::Timestamp(timestamp.to_string()),
+            (None, None) => return Err("Each sort needs a property or timestamp".to_string()),
+        };
+        keys.push((key, descending));
+    }
+
+    // Check every key up front so sorting itself can't fail
+    for page in pages.iter() {
+        for (key, _) in &keys {
+            key.field(page)?;
+        }
+    }
+    pages.sort_by(|a, b| {
+        keys.iter()
+            .map(|(key, descending)| {
+                let (Ok(a), Ok(b)) = (key.field(a), key.field(b)) else {
+                    return Ordering::Equal;
+                };
+                compare(&a, &b, *descending)
+            })
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+    Ok(())
+}
+
+enum SortKey {
+    Property(String),
+    Timestamp(String),
+}
+
+impl SortKey {
+    fn field(&self, page: &Value) -> Result<Field, String> {
+        match self {
+            SortKey::Property(name) => property(page, name).map(field),
+            SortKey::Timestamp(timestamp) => timestamp_field(page, timestamp),
+        }
+    }
+}
+
+/// Plain text of a rich text array.
+pub fn plain_text(rich_text: &Value) -> String {
+    rich_text
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item["plain_text"].as_str())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Look a property up by name, then by ID.
+fn property<'a>(page: &'a Value, name: &str) -> Result<&'a Value, String> {
+    let properties = &page["properties"];
+    properties
+        .get(name)
+        .or_else(|| {
+            properties
+                .as_object()?
+                .values()
+                .find(|p| p["id"].as_str() == Some(name))
+        })
+        .ok_or_else(|| format!("Could not find property with name or id: {}", name))
+}
+
+fn timestamp_field(page: &Value, timestamp: &str) -> Result<Field, String> {
+    match timestamp {
+        "created_time" | "last_edited_time" => {
+            Ok(Field::Date(page[timestamp].as_str().map(String::from)))
+        }
+        other => Err(format!("Invalid timestamp: {}", other)),
+    }
+}
+
+fn field(property: &Value) -> Field {
+    let kind = property["type"].as_str().unwrap_or_default();
+    let value = &property[kind];
+    let names = |items: &Value, key: &str| {
+        items
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item[key].as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    match kind {
+        "title" | "rich_text" => Field::Text(plain_text(value)),
+        "url" | "email" | "phone_number" => Field::Text(value.as_str().unwrap_or("").to_string()),
+        "number" => Field::Number(value.as_f64()),
+        "checkbox" => Field::Bool(value.as_bool().unwrap_or(false)),
+        "select" | "status" => Field::Names(
+            value["name"]
+                .as_str()
+                .map(String::from)
+                .into_iter()
+                .collect(),
+        ),
+        "multi_select" | "files" => Field::Names(names(value, "name")),
+        "people" | "relation" => Field::Names(names(value, "id")),
+        "date" => Field::Date(value["start"].as_str().map(String::from)),
+        "created_time" | "last_edited_time" => Field::Date(value.as_str().map(String::from)),
+        _ => Field::Text(String::new()),
+    }
+}
+
+fn test(field: &Field, condition: &str, operand: &Value) -> Result<bool, String> {
+    let unsupported = || Err(format!("Unsupported filter condition: {}", condition));
+    match field {
+        Field::Text(text) => {
+            if let Some(result) = emptiness(text.is_empty(), condition) {
+                return Ok(result);
+            }
+            let operand = operand
+                .as_str()
+                .ok_or_else(|| format!("Condition {} expects a string", condition))?;
+            match condition {
+                "equals" => Ok(text == operand),
+                "does_not_equal" => Ok(text != operand),
+                "contains" => Ok(text.to_lowercase().contains(&operand.to_lowercase())),
+                "does_not_contain" => Ok(!text.to_lowercase().contains(&operand.to_lowercase())),
+                "starts_with" => Ok(text.to_lowercase().starts_with(&operand.to_lowercase())),
+                "ends_with" => Ok(text.to_lowercase().ends_with(&operand.to_lowercase())),
+                _ => unsupported(),
+            }
+        }
+        Field::Number(number) => {
+            if let Some(result) = emptiness(number.is_none(), condition) {
+                return Ok(result);
+            }
+            let operand = operand
+                .as_f64()
+                .ok_or_else(|| format!("Condition {} expects a number", condition))?;
+            let Some(number) = *number else {
+                return Ok(condition == "does_not_equal");
+            };
+            match condition {
+                "equals" => Ok(number == operand),
+                "does_not_equal" => Ok(number != operand),
+                "greater_than" => Ok(number > operand),
+                "less_than" => Ok(number < operand),
+                "greater_than_or_equal_to" => Ok(number >= operand),
+                "less_than_or_equal_to" => Ok(number <= operand),
+                _ => unsupported(),
+            }
+        }
+        Field::Bool(value) => {
+            let operand = operand
+                .as_bool()
+                .ok_or_else(|| format!("Condition {} expects a boolean", condition))?;
+            match condition {
+                "equals" => Ok(*value == operand),
+                "does_not_equal" => Ok(*value != operand),
+                _ => unsupported(),
+            }
+        }
+        Field::Names(names) => {
+            if let Some(result) = emptiness(names.is_empty(), condition) {
+                return Ok(result);
+            }
+            let operand = operand
+                .as_str()
+                .ok_or_else(|| format!("Condition {} expects a string", condition))?;
+            let found = names.iter().any(|n| n == operand);
+            match condition {
+                "equals" | "contains" => Ok(found),
+                "does_not_equal" | "does_not_contain" => Ok(!found),
+                _ => unsupported(),
+            }
+        }
+        Field::Date(date) => {
+            if let Some(result) = emptiness(date.is_none(), condition) {
+                return Ok(result);
+            }
+            let operand = operand
+                .as_str()
+                .ok_or_else(|| format!("Condition {} expects a date string", condition))?;
+            let Some(date) = date else {
+                return Ok(false);
+            };
+            let order = compare_dates(date, operand);
+            match condition {
+                "equals" => Ok(order.is_eq()),
+                "before" => Ok(order.is_lt()),
+                "after" => Ok(order.is_gt()),
+                "on_or_before" => Ok(order.is_le()),
+                "on_or_after" => Ok(order.is_ge()),
+                _ => unsupported(),
+            }
+        }
+    }
+}
+
+/// Result of an `is_empty`/`is_not_empty` condition, if that's what it is.
+fn emptiness(empty: bool, condition: &str) -> Option<bool> {
+    match condition {
+        "is_empty" => Some(empty),
+        "is_not_empty" => Some(!empty),
+        _ => None,
+    }
+}
+
+/// Compare dates, by day only when either side has no time.
+fn compare_dates(a: &str, b: &str) -> Ordering {
+    if a.len() == 10 || b.len() == 10 {
+        let day = |d: &str| d.chars().take(10).collect::<String>();
+        return day(a).cmp(&day(b));
+    }
+    a.cmp(b)
+}
+
+fn compare(a: &Field, b: &Field, descending: bool) -> Ordering {
+    let (a_empty, b_empty) = (is_empty(a), is_empty(b));
+    if a_empty || b_empty {
+        return a_empty.cmp(&b_empty);
+    }
+    let order = match (a, b) {
+        (Field::Text(a), Field::Text(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+        (Field::Number(a), Field::Number(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        (Field::Bool(a), Field::Bool(b)) => a.cmp(b),
+        (Field::Names(a), Field::Names(b)) => a.cmp(b),
+        (Field::Date(Some(a)), Field::Date(Some(b))) => compare_dates(a, b),
+        _ => Ordering::Equal,
+    };
+    if descending { order.reverse() } else { order }
+}
+
+fn is_empty(field: &Field) -> bool {
+    match field {
+        Field::Text(text) => text.is_empty(),
+        Field::Number(number) => number.is_none(),
+        Field::Bool(_) => false,
+        Field::Names(names) => names.is_empty(),
+        Field::Date(date) => date.is_none(),
+    }
+}
+
+#[cfg(test)]
+#[path = "query_tests.rs"]
+mod tests;
@@ -0,0 +1,221 @@
+use super::*;
+use serde_json::json;
+
+fn page(name: &str, score: Option<f64>, done: bool, tags: &[&str], due: Option<&str>) -> Value {
+    json!({
+        "created_time": "2025-01-01T00:00:01.000Z",
+        "properties": {
+            "Name": {"id": "title", "type": "title", "title": [{"plain_text": name}]},
+            "Score": {"id": "p1", "type": "number", "number": score},
+            "Done": {"id": "p2", "type": "checkbox", "checkbox": done},
+            "Tags": {"id": "p3", "type": "multi_select", "multi_select": tags.iter().map(|t| json!({"name": t})).collect::<Vec<_>>()},
+            "Status": {"id": "p4", "type": "status", "status": {"name": "Open"}},
+            "Due": {"id": "p5", "type": "date", "date": due.map(|d| json!({"start": d}))},
+        }
+    })
+}
+
+fn sample() -> Value {
+    page(
+        "Write docs",
+        Some(3.0),
+        false,
+        &["docs"],
+        Some("2025-02-01"),
+    )
+}
+
+#[test]
+fn test_text_conditions() {
+    let page = sample();
+    let cases = [
+        (json!({"equals": "Write docs"}), true),
+        (json!({"does_not_equal": "Write docs"}), false),
+        (json!({"contains": "DOCS"}), true),
+        (json!({"does_not_contain": "code"}), true),
+        (json!({"starts_with": "write"}), true),
+        (json!({"ends_with": "code"}), false),
+        (json!({"is_empty": true}), false),
+        (json!({"is_not_empty": true}), true),
+    ];
+    for (condition, expected) in cases {
+        let filter = json!({"property": "Name", "title": condition});
+        assert_eq!(matches(&page, &filter), Ok(expected), "{}", filter);
+    }
+}
+
+#[test]
+fn test_number_conditions() {
+    let page = sample();
+    let cases = [
+        (json!({"equals": 3}), true),
+        (json!({"greater_than": 3}), false),
+        (json!({"greater_than_or_equal_to": 3}), true),
+        (json!({"less_than": 4}), true),
+        (json!({"less_than_or_equal_to": 2}), false),
+        (json!({"does_not_equal": 3}), false),
+    ];
+    for (condition, expected) in cases {
+        let filter = json!({"property": "Score", "number": condition});
+        assert_eq!(matches(&page, &filter), Ok(expected), "{}", filter);
+    }
+}
+
+#[test]
+fn test_empty_number_only_matches_is_empty() {
+    let page = page("x", None, false, &[], None);
+    let filter = |c: Value| json!({"property": "Score", "number": c});
+    assert_eq!(matches(&page, &filter(json!({"is_empty": true}))), Ok(true));
+    assert_eq!(
+        matches(&page, &filter(json!({"greater_than": 0}))),
+        Ok(false)
+    );
+}
+
+#[test]
+fn test_checkbox_select_and_multi_select() {
+    let page = sample();
+    let checks = [
+        (
+            json!({"property": "Done", "checkbox": {"equals": false}}),
+            true,
+        ),
+        (
+            json!({"property": "Status", "status": {"equals": "Open"}}),
+            true,
+        ),
+        (
+            json!({"property": "Status", "status": {"does_not_equal": "Open"}}),
+            false,
+        ),
+        (
+            json!({"property": "Tags", "multi_select": {"contains": "docs"}}),
+            true,
+        ),
+        (
+            json!({"property": "Tags", "multi_select": {"does_not_contain": "docs"}}),
+            false,
+        ),
+        (
+            json!({"property": "Tags", "multi_select": {"is_empty": true}}),
+            false,
+        ),
+    ];
+    for (filter, expected) in checks {
+        assert_eq!(matches(&page, &filter), Ok(expected), "{}", filter);
+    }
+}
+
+#[test]
+fn test_date_conditions_compare_by_day_for_dates() {
+    let page = sample();
+    let checks = [
+        (json!({"equals": "2025-02-01T10:00:00.000Z"}), true),
+        (json!({"before": "2025-02-02"}), true),
+        (json!({"after": "2025-02-01"}), false),
+        (json!({"on_or_after": "2025-02-01"}), true),
+        (json!({"on_or_before": "2025-01-31"}), false),
+    ];
+    for (condition, expected) in checks {
+        let filter = json!({"property": "Due", "date": condition});
+        assert_eq!(matches(&page, &filter), Ok(expected), "{}", filter);
+    }
+}
+
+#[test]
+fn test_timestamp_filter() {
+    let filter = json!({"timestamp": "created_time", "created_time": {"after": "2024-12-31"}});
+    assert_eq!(matches(&sample(), &filter), Ok(true));
+}
+
+#[test]
+fn test_compound_filters() {
+    let page = sample();
+    let and = json!({"and": [
+        {"property": "Score", "number": {"greater_than": 1}},
+        {"property": "Done", "checkbox": {"equals": true}},
+    ]});
+    let or = json!({"or": [
+        {"property": "Score", "number": {"greater_than": 1}},
+        {"property": "Done", "checkbox": {"equals": true}},
+    ]});
+    assert_eq!(matches(&page, &and), Ok(false));
+    assert_eq!(matches(&page, &or), Ok(true));
+}
+
+#[test]
+fn test_property_found_by_id() {
+    let filter = json!({"property": "p1", "number": {"equals": 3}});
+    assert_eq!(matches(&sample(), &filter), Ok(true));
+}
+
+#[test]
+fn test_filter_errors() {
+    let page = sample();
+    let unknown = matches(&page, &json!({"property": "Nope", "number": {"equals": 1}}));
+    assert_eq!(
+        unknown.unwrap_err(),
+        "Could not find property with name or id: Nope"
+    );
+    let condition = matches(&page, &json!({"property": "Score", "number": {"near": 1}}));
+    assert_eq!(condition.unwrap_err(), "Unsupported filter condition: near");
+    let operand = matches(
+        &page,
+        &json!({"property": "Score", "number": {"equals": "3"}}),
+    );
+    assert!(operand.unwrap_err().contains("expects a number"));
+    assert!(matches(&page, &json!({})).is_err());
+}
+
+#[test]
+fn test_sort_by_property_with_empty_values_last() {
+    let mut pages = vec![
+        page("b", Some(2.0), false, &[], None),
+        page("empty", None, false, &[], None),
+        page("a", Some(5.0), false, &[], None),
+    ];
+    let names = |pages: &[Value]| -> Vec<String> {
+        pages
+            .iter()
+            .map(|p| plain_text(&p["properties"]["Name"]["title"]))
+            .collect()
+    };
+
+    sort(
+        &mut pages,
+        &[json!({"property": "Score", "direction": "descending"})],
+    )
+    .unwrap();
+    assert_eq!(names(&pages), ["a", "b", "empty"]);
+
+    sort(&mut pages, &[json!({"property": "Score"})]).unwrap();
+    assert_eq!(names(&pages), ["b", "a", "empty"]);
+}
+
+#[test]
+fn test_sort_falls_back_to_later_keys() {
+    let mut pages = vec![
+        page("b", Some(1.0), false, &[], None),
+        page("a", Some(1.0), false, &[], None),
+    ];
+    let sorts = [
+        json!({"property": "Score", "direction": "ascending"}),
+        json!({"property": "Name", "direction": "ascending"}),
+    ];
+    sort(&mut pages, &sorts).unwrap();
+    assert_eq!(plain_text(&pages[0]["properties"]["Name"]["title"]), "a");
+}
+
+#[test]
+fn test_sort_errors() {
+    let mut pages = vec![sample()];
+    assert!(sort(&mut pages, &[json!({"property": "Nope"})]).is_err());
+    assert!(
+        sort(
+            &mut pages,
+            &[json!({"property": "Score", "direction": "up"})]
+        )
+        .is_err()
+    );
+    assert!(sort(&mut pages, &[json!({})]).is_err());
+}
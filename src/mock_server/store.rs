@@ -0,0 +1,1560 @@
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, HashMap};
+
+use super::http::{Request, Response};
+use super::query;
+use crate::properties::civil_from_days;
+
+/// Largest page size list endpoints accept, and the most children per append.
+const MAX_PAGE_SIZE: usize = 100;
+
+/// Days from 1970-01-01 to 2025-01-01, where the logical clock starts.
+const EPOCH_DAYS: u64 = 20089;
+
+/// IDs of the objects every server starts with.
+#[derive(Debug, Clone)]
+pub struct Seed {
+    /// The integration's bot user, returned by `/v1/users/me`
+    pub bot_id: String,
+    pub person_id: String,
+    /// A top-level page to create content under
+    pub page_id: String,
+}
+
+/// In-memory workspace behind the mock server.
+///
+/// Users, pages, blocks, databases, data sources and comments share one ID
+/// space like they do in Notion. Timestamps come from a logical clock that
+/// starts at 2025-01-01T00:00:00Z and ticks one second per write, so
+/// creation order and `created_time` always agree.
+pub struct Store {
+    base_url: String,
+    clock: u64,
+    ids: u64,
+    seed: Seed,
+    objects: HashMap<String, Value>,
+    /// Every object ID in creation order
+    created: Vec<String>,
+    /// Ordered child block IDs of pages and blocks
+    children: HashMap<String, Vec<String>>,
+    uploads: HashMap<String, Upload>,
+}
+
+struct Upload {
+    mode: String,
+    /// Bytes received per part number
+    parts: BTreeMap<u32, usize>,
+    object: Value,
+}
+
+/// Where a list response starts and how many items it holds.
+struct Paging {
+    start: usize,
+    size: usize,
+}
+
+type Outcome = Result<Value, Response>;
+
+impl Store {
+    /// A workspace with a bot user, a person and one top-level page.
+    pub fn new(base_url: &str) -> Self {
+        let mut store = Store {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            clock: 0,
+            ids: 0,
+            seed: Seed {
+                bot_id: String::new(),
+                person_id: String::new(),
+                page_id: String::new(),
+            },
+            objects: HashMap::new(),
+            created: Vec::new(),
+            children: HashMap::new(),
+            uploads: HashMap::new(),
+        };
+
+        let bot_id = store.new_id();
+        store.insert(json!({
+            "object": "user",
+            "id": bot_id,
+            "name": "Mock Integration",
+            "avatar_url": null,
+            "type": "bot",
+            "bot": {
+                "owner": { "type": "workspace", "workspace": true },
+                "workspace_name": "Mock Workspace",
+            },
+        }));
+        store.seed.bot_id = bot_id;
+
+        let person_id = store.new_id();
+        store.insert(json!({
+            "object": "user",
+            "id": person_id,
+            "name": "Ada Lovelace",
+            "avatar_url": null,
+            "type": "person",
+            "person": { "email": "ada@example.com" },
+        }));
+        store.seed.person_id = person_id;
+
+        let page = store.create_page(&json!({
+            "parent": { "workspace": true },
+            "properties": { "title": { "title": [{ "text": { "content": "Home" } }] } },
+        }));
+        store.seed.page_id = page
+            .ok()
+            .and_then(|p| p["id"].as_str().map(String::from))
+            .unwrap_or_default();
+        store
+    }
+
+    pub fn seed(&self) -> &Seed {
+        &self.seed
+    }
+
+    /// Answer one API request.
+    pub fn handle(&mut self, request: &Request) -> Response {
+        match self.route(request) {
+            Ok(body) => Response { status: 200, body },
+            Err(response) => response,
+        }
+    }
+
+    fn route(&mut self, request: &Request) -> Outcome {
+        authorize(request)?;
+        let body = json_body(request)?;
+        let Some(path) = request.path.strip_prefix("/v1/") else {
+            return Err(invalid_url());
+        };
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let id = segments.get(1).map(|s| normalize_id(s)).unwrap_or_default();
+
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["users", "me"]) => self.find(&self.seed.bot_id, "user").cloned(),
+            ("GET", ["users"]) => {
+                let users = self.all("user").into_iter().cloned().collect();
+                list(users, "user", Paging::from_query(request)?)
+            }
+            ("GET", ["users", _]) => self.find(&id, "user").cloned(),
+
+            ("POST", ["pages"]) => self.create_page(&body),
+            ("GET", ["pages", _]) => self.find(&id, "page").cloned(),
+            ("PATCH", ["pages", _]) => self.update_page(&id, &body),
+            ("POST", ["pages", _, "move"]) => self.move_page(&id, &body),
+            ("GET", ["pages", _, "properties", property]) => {
+                self.page_property(&id, property, Paging::from_query(request)?)
+            }
+
+            ("GET", ["blocks", _]) => self.block_view(&id).ok_or_else(|| not_found("block", &id)),
+            ("PATCH", ["blocks", _]) => self.update_block(&id, &body),
+            ("DELETE", ["blocks", _]) => self.update_block(&id, &json!({ "in_trash": true })),
+            ("GET", ["blocks", _, "children"]) => {
+                self.list_children(&id, Paging::from_query(request)?)
+            }
+            ("PATCH", ["blocks", _, "children"]) => self.append_children(&id, &body),
+
+            ("GET", ["comments"]) => self.list_comments(request),
+            ("POST", ["comments"]) => self.create_comment(&body),
+
+            ("GET", ["databases", _]) => self.database_view(&id),
+
+            ("POST", ["data_sources"]) => self.create_data_source(&body),
+            ("GET", ["data_sources", _]) => self.find(&id, "data_source").cloned(),
+            ("PATCH", ["data_sources", _]) => self.update_data_source(&id, &body),
+            ("POST", ["data_sources", _, "query"]) => self.query_data_source(&id, &body),
+            ("GET", ["data_sources", _, "templates"]) => {
+                self.find(&id, "data_source")?;
+                Ok(json!({ "templates": [], "has_more": false, "next_cursor": null }))
+            }
+
+            ("POST", ["search"]) => self.search(&body),
+
+            ("POST", ["file_uploads"]) => self.create_upload(&body),
+            ("GET", ["file_uploads"]) => self.list_uploads(request),
+            ("GET", ["file_uploads", _]) => self.upload(&id).map(|u| u.object.clone()),
+            ("POST", ["file_uploads", _, "send"]) => self.send_upload(&id, request),
+            ("POST", ["file_uploads", _, "complete"]) => self.complete_upload(&id),
+
+            _ => Err(invalid_url()),
+        }
+    }
+
+    // --- Pages ---
+
+    fn create_page(&mut self, body: &Value) -> Outcome {
+        let (parent, schema) = self.page_parent(&body["parent"])?;
+        let now = self.tick();
+        let id = self.new_id();
+        let mut page = json!({
+            "object": "page",
+            "id": id,
+            "created_time": now,
+            "last_edited_time": now,
+            "created_by": self.bot_ref(),
+            "last_edited_by": self.bot_ref(),
+            "cover": body.get("cover").cloned().unwrap_or(Value::Null),
+            "icon": body.get("icon").cloned().unwrap_or(Value::Null),
+            "parent": parent,
+            "archived": false,
+            "in_trash": false,
+            "properties": {},
+            "url": format!("https://www.notion.so/{}", id.replace('-', "")),
+            "public_url": null,
+        });
+        let properties = match &schema {
+            Some(schema) => {
+                let mut properties = empty_properties(schema);
+                set_properties(&mut properties, &body["properties"], schema)?;
+                properties
+            }
+            None => title_property(&body["properties"])?,
+        };
+        page["properties"] = Value::Object(properties);
+        stamp(&mut page);
+
+        if let Some(parent_id) = page["parent"]["page_id"].as_str() {
+            self.children
+                .entry(parent_id.to_string())
+                .or_default()
+                .push(id.clone());
+        }
+        self.insert(page);
+
+        if let Some(children) = body.get("children") {
+            self.append_children(&id, &json!({ "children": children }))?;
+        }
+        self.find(&id, "page").cloned()
+    }
+
+    /// A page's `parent` object and, for data source pages, the schema.
+    fn page_parent(&self, parent: &Value) -> Result<(Value, Option<Value>), Response> {
+        if let Some(page_id) = parent["page_id"].as_str() {
+            let page_id = normalize_id(page_id);
+            self.find(&page_id, "page")?;
+            return Ok((json!({ "type": "page_id", "page_id": page_id }), None));
+        }
+        let data_source_id = match (
+            parent["data_source_id"].as_str(),
+            parent["database_id"].as_str(),
+        ) {
+            (Some(id), _) => normalize_id(id),
+            (None, Some(database_id)) => {
+                let database_id = normalize_id(database_id);
+                self.find(&database_id, "database")?;
+                self.data_sources_of(&database_id)
+                    .first()
+                    .and_then(|ds| ds["id"].as_str().map(String::from))
+                    .ok_or_else(|| {
+                        validation(format!("Database {} has no data source", database_id))
+                    })?
+            }
+            (None, None) if parent["workspace"] == true || parent["type"] == "workspace" => {
+                return Ok((json!({ "type": "workspace", "workspace": true }), None));
+            }
+            (None, None) => return Err(validation("body.parent should be defined")),
+        };
+        let data_source = self.find(&data_source_id, "data_source")?;
+        let parent = json!({
+            "type": "data_source_id",
+            "data_source_id": data_source_id,
+            "database_id": data_source["parent"]["database_id"],
+        });
+        Ok((parent, Some(data_source["properties"].clone())))
+    }
+
+    fn update_page(&mut self, id: &str, body: &Value) -> Outcome {
+        let schema = self.schema_of(self.find(id, "page")?);
+        let now = self.tick();
+        let page = self.find_mut(id, "page")?;
+        let trash = trash_flag(body);
+        if page["in_trash"] == true && trash != Some(false) {
+            return Err(validation(
+                "Can't edit block that is archived. You must unarchive the block before editing.",
+            ));
+        }
+
+        if let Some(properties) = body.get("properties").filter(|p| !p.is_null()) {
+            let mut current = page["properties"].as_object().cloned().unwrap_or_default();
+            match &schema {
+                Some(schema) => set_properties(&mut current, properties, schema)?,
+                None if properties.as_object().is_some_and(|p| p.is_empty()) => {}
+                None => current.extend(title_property(properties)?),
+            }
+            page["properties"] = Value::Object(current);
+        }
+        for key in ["icon", "cover"] {
+            if let Some(value) = body.get(key) {
+                page[key] = value.clone();
+            }
+        }
+        if let Some(trash) = trash {
+            page["archived"] = json!(trash);
+            page["in_trash"] = json!(trash);
+        }
+        page["last_edited_time"] = json!(now);
+        stamp(page);
+        Ok(page.clone())
+    }
+
+    fn move_page(&mut self, id: &str, body: &Value) -> Outcome {
+        self.find(id, "page")?;
+        let (parent, _) = self.page_parent(&body["parent"])?;
+        for siblings in self.children.values_mut() {
+            siblings.retain(|child| child != id);
+        }
+        if let Some(parent_id) = parent["page_id"].as_str() {
+            self.children
+                .entry(parent_id.to_string())
+                .or_default()
+                .push(id.to_string());
+        }
+        let now = self.tick();
+        let page = self.find_mut(id, "page")?;
+        page["parent"] = parent;
+        page["last_edited_time"] = json!(now);
+        Ok(page.clone())
+    }
+
+    /// A property item, or a list of them for array-valued properties.
+    fn page_property(&self, id: &str, property_id: &str, paging: Paging) -> Outcome {
+        let page = self.find(id, "page")?;
+        let property = page["properties"]
+            .as_object()
+            .and_then(|properties| {
+                properties
+                    .values()
+                    .find(|p| p["id"] == property_id)
+                    .or_else(|| properties.get(property_id))
+            })
+            .ok_or_else(|| {
+                validation(format!("Could not find property with id: {}", property_id))
+            })?;
+        let kind = property["type"].as_str().unwrap_or_default();
+        let item = |value: &Value| {
+            json!({
+                "object": "property_item",
+                "id": property["id"],
+                "type": kind,
+                kind: value,
+            })
+        };
+        if !matches!(kind, "title" | "rich_text" | "people" | "relation") {
+            return Ok(item(&property[kind]));
+        }
+        let items = property[kind]
+            .as_array()
+            .map(|values| values.iter().map(item).collect())
+            .unwrap_or_default();
+        let mut response = list(items, "property_item", paging)?;
+        response["property_item"] = json!({
+            "id": property["id"],
+            "next_url": null,
+            "type": kind,
+            kind: {},
+        });
+        Ok(response)
+    }
+
+    /// Schema of a page's data source; `None` for pages under pages.
+    fn schema_of(&self, page: &Value) -> Option<Value> {
+        let data_source_id = page["parent"]["data_source_id"].as_str()?;
+        self.objects
+            .get(data_source_id)
+            .map(|ds| ds["properties"].clone())
+    }
+
+    // --- Blocks ---
+
+    fn append_children(&mut self, parent_id: &str, body: &Value) -> Outcome {
+        let parent = self
+            .objects
+            .get(parent_id)
+            .filter(|o| matches!(o["object"].as_str(), Some("page" | "block")))
+            .ok_or_else(|| not_found("block", parent_id))?;
+        if parent["in_trash"] == true {
+            return Err(validation(
+                "Can't edit block that is archived. You must unarchive the block before editing.",
+            ));
+        }
+        let parent_ref = match parent["object"].as_str() {
+            Some("page") => json!({ "type": "page_id", "page_id": parent_id }),
+            _ => json!({ "type": "block_id", "block_id": parent_id }),
+        };
+        let blocks = body["children"].as_array().ok_or_else(|| {
+            validation("body.children should be defined, instead was `undefined`.")
+        })?;
+        if blocks.len() > MAX_PAGE_SIZE {
+            return Err(validation(format!(
+                "body.children.length should be ≤ `{}`, instead was `{}`.",
+                MAX_PAGE_SIZE,
+                blocks.len()
+            )));
+        }
+
+        let siblings = self.children.get(parent_id).cloned().unwrap_or_default();
+        let mut position = match body["after"].as_str() {
+            Some(after) => {
+                let after = normalize_id(after);
+                let index = siblings
+                    .iter()
+                    .position(|child| *child == after)
+                    .ok_or_else(|| {
+                        validation(format!("Block {} is not a child of {}", after, parent_id))
+                    })?;
+                index + 1
+            }
+            None => siblings.len(),
+        };
+
+        // Validate everything before creating anything
+        for block in blocks {
+            block_parts(block)?;
+        }
+        let mut created = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let id = self.create_block(&parent_ref, block)?;
+            self.children
+                .entry(parent_id.to_string())
+                .or_default()
+                .insert(position, id.clone());
+            position += 1;
+            created.push(self.block_view(&id).unwrap_or_default());
+        }
+        self.touch(parent_id);
+        list(
+            created,
+            "block",
+            Paging {
+                start: 0,
+                size: usize::MAX,
+            },
+        )
+    }
+
+    fn create_block(&mut self, parent: &Value, block: &Value) -> Result<String, Response> {
+        let (kind, mut content) = block_parts(block)?;
+        let children = content.remove("children");
+        normalize_content(&mut content);
+
+        let now = self.tick();
+        let id = self.new_id();
+        self.insert(json!({
+            "object": "block",
+            "id": id,
+            "parent": parent,
+            "created_time": now,
+            "last_edited_time": now,
+            "created_by": self.bot_ref(),
+            "last_edited_by": self.bot_ref(),
+            "has_children": false,
+            "archived": false,
+            "in_trash": false,
+            "type": kind,
+            kind: content,
+        }));
+
+        let child_parent = json!({ "type": "block_id", "block_id": id });
+        for child in children
+            .as_ref()
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let child_id = self.create_block(&child_parent, child)?;
+            self.children.entry(id.clone()).or_default().push(child_id);
+        }
+        Ok(id)
+    }
+
+    fn update_block(&mut self, id: &str, body: &Value) -> Outcome {
+        let object = self.objects.get(id).map(|o| o["object"].clone());
+        match object.as_ref().and_then(Value::as_str) {
+            Some("page") => {
+                let trash = trash_flag(body).unwrap_or(false);
+                self.update_page(id, &json!({ "in_trash": trash }))?;
+                return self.block_view(id).ok_or_else(|| not_found("block", id));
+            }
+            Some("block") => {}
+            _ => return Err(not_found("block", id)),
+        }
+
+        let now = self.tick();
+        let block = self.find_mut(id, "block")?;
+        let trash = trash_flag(body);
+        if block["in_trash"] == true && trash != Some(false) {
+            return Err(validation(
+                "Can't edit block that is archived. You must unarchive the block before editing.",
+            ));
+        }
+        let kind = block["type"].as_str().unwrap_or_default().to_string();
+        for (key, value) in body.as_object().into_iter().flatten() {
+            if *key == kind {
+                let mut changes = value.as_object().cloned().unwrap_or_default();
+                normalize_content(&mut changes);
+                if let Some(content) = block[&kind].as_object_mut() {
+                    content.extend(changes);
+                }
+            } else if value.is_object() {
+                return Err(validation(format!(
+                    "Block type {} does not match the existing type {}",
+                    key, kind
+                )));
+            }
+        }
+        if let Some(trash) = trash {
+            block["archived"] = json!(trash);
+            block["in_trash"] = json!(trash);
+        }
+        block["last_edited_time"] = json!(now);
+        self.block_view(id).ok_or_else(|| not_found("block", id))
+    }
+
+    fn list_children(&self, id: &str, paging: Paging) -> Outcome {
+        if self.block_view(id).is_none() {
+            return Err(not_found("block", id));
+        }
+        let blocks = self
+            .live_children(id)
+            .filter_map(|child| self.block_view(child))
+            .collect();
+        list(blocks, "block", paging)
+    }
+
+    /// A page, database or block as the block endpoints show it.
+    fn block_view(&self, id: &str) -> Option<Value> {
+        let object = self.objects.get(id)?;
+        let has_children = self.live_children(id).next().is_some();
+        let mut view = match object["object"].as_str()? {
+            "block" => object.clone(),
+            "page" => {
+                let mut view = child_view(object, "child_page");
+                view["child_page"] = json!({ "title": page_title(object) });
+                view
+            }
+            "database" => {
+                let mut view = child_view(object, "child_database");
+                view["child_database"] = json!({ "title": query::plain_text(&object["title"]) });
+                view
+            }
+            _ => return None,
+        };
+        view["has_children"] = json!(has_children);
+        Some(view)
+    }
+
+    fn live_children<'a>(&'a self, id: &str) -> impl Iterator<Item = &'a String> + 'a {
+        self.children.get(id).into_iter().flatten().filter(|child| {
+            self.objects
+                .get(*child)
+                .is_some_and(|o| o["in_trash"] != true)
+        })
+    }
+
+    // --- Comments ---
+
+    fn create_comment(&mut self, body: &Value) -> Outcome {
+        let (parent, discussion_id) = if let Some(discussion) = body["discussion_id"].as_str() {
+            let first = self
+                .all("comment")
+                .into_iter()
+                .find(|c| c["discussion_id"] == discussion)
+                .ok_or_else(|| not_found("discussion", discussion))?;
+            (first["parent"].clone(), discussion.to_string())
+        } else {
+            let (kind, parent_id) = match (
+                body["parent"]["page_id"].as_str(),
+                body["parent"]["block_id"].as_str(),
+            ) {
+                (Some(id), _) => ("page_id", normalize_id(id)),
+                (None, Some(id)) => ("block_id", normalize_id(id)),
+                (None, None) => {
+                    return Err(validation(
+                        "body.parent or body.discussion_id should be defined",
+                    ));
+                }
+            };
+            let object = if kind == "page_id" { "page" } else { "block" };
+            self.find(&parent_id, object)?;
+            (json!({ "type": kind, kind: parent_id }), self.new_id())
+        };
+        let rich_text = body["rich_text"]
+            .as_array()
+            .ok_or_else(|| validation("body.rich_text should be defined"))?;
+
+        let now = self.tick();
+        let id = self.new_id();
+        let comment = json!({
+            "object": "comment",
+            "id": id,
+            "parent": parent,
+            "discussion_id": discussion_id,
+            "created_time": now,
+            "last_edited_time": now,
+            "created_by": self.bot_ref(),
+            "rich_text": normalize_rich_text(&Value::Array(rich_text.clone())),
+        });
+        self.insert(comment.clone());
+        Ok(comment)
+    }
+
+    fn list_comments(&self, request: &Request) -> Outcome {
+        let block_id = request
+            .query("block_id")
+            .map(normalize_id)
+            .ok_or_else(|| validation("block_id should be defined"))?;
+        if self.block_view(&block_id).is_none() {
+            return Err(not_found("block", &block_id));
+        }
+        let comments = self
+            .all("comment")
+            .into_iter()
+            .filter(|c| {
+                let parent = &c["parent"];
+                parent["page_id"] == block_id.as_str() || parent["block_id"] == block_id.as_str()
+            })
+            .cloned()
+            .collect();
+        list(comments, "comment", Paging::from_query(request)?)
+    }
+
+    // --- Databases and data sources ---
+
+    fn database_view(&self, id: &str) -> Outcome {
+        let mut database = self.find(id, "database")?.clone();
+        database["data_sources"] = self
+            .data_sources_of(id)
+            .iter()
+            .map(|ds| json!({ "id": ds["id"], "name": query::plain_text(&ds["title"]) }))
+            .collect();
+        Ok(database)
+    }
+
+    fn data_sources_of(&self, database_id: &str) -> Vec<&Value> {
+        self.all("data_source")
+            .into_iter()
+            .filter(|ds| ds["parent"]["database_id"] == database_id)
+            .collect()
+    }
+
+    /// Create a data source in a database, or a new database under a page.
+    fn create_data_source(&mut self, body: &Value) -> Outcome {
+        let title = normalize_rich_text(&body["title"]);
+        let database_id = match (
+            body["parent"]["database_id"].as_str(),
+            body["parent"]["page_id"].as_str(),
+        ) {
+            (Some(id), _) => {
+                let id = normalize_id(id);
+                self.find(&id, "database")?;
+                id
+            }
+            (None, Some(page_id)) => {
+                let page_id = normalize_id(page_id);
+                self.find(&page_id, "page")?;
+                let now = self.tick();
+                let id = self.new_id();
+                self.insert(json!({
+                    "object": "database",
+                    "id": id,
+                    "title": title.clone(),
+                    "description": [],
+                    "parent": { "type": "page_id", "page_id": page_id },
+                    "is_inline": false,
+                    "in_trash": false,
+                    "created_time": now,
+                    "last_edited_time": now,
+                    "icon": null,
+                    "cover": null,
+                    "url": format!("https://www.notion.so/{}", id.replace('-', "")),
+                    "public_url": null,
+                }));
+                self.children.entry(page_id).or_default().push(id.clone());
+                id
+            }
+            (None, None) => return Err(validation("body.parent should be defined")),
+        };
+
+        let mut properties = Map::new();
+        for (name, definition) in body["properties"].as_object().into_iter().flatten() {
+            let schema = self.property_schema(name, definition, None)?;
+            properties.insert(name.clone(), schema);
+        }
+        if !properties.values().any(|p| p["type"] == "title") {
+            let schema = self.property_schema("Name", &json!({ "title": {} }), None)?;
+            properties.insert("Name".to_string(), schema);
+        }
+
+        let now = self.tick();
+        let id = self.new_id();
+        let database_parent = self.find(&database_id, "database")?["parent"].clone();
+        let data_source = json!({
+            "object": "data_source",
+            "id": id,
+            "created_time": now,
+            "last_edited_time": now,
+            "created_by": self.bot_ref(),
+            "last_edited_by": self.bot_ref(),
+            "title": title,
+            "description": [],
+            "properties": properties,
+            "parent": { "type": "database_id", "database_id": database_id },
+            "database_parent": database_parent,
+            "archived": false,
+            "in_trash": false,
+            "icon": null,
+            "cover": null,
+            "url": format!("https://www.notion.so/{}", id.replace('-', "")),
+            "public_url": null,
+        });
+        self.insert(data_source.clone());
+        Ok(data_source)
+    }
+
+    /// Update a data source; pages follow renamed, removed and retyped properties.
+    fn update_data_source(&mut self, id: &str, body: &Value) -> Outcome {
+        let mut schema = self.find(id, "data_source")?["properties"]
+            .as_object()
+            .cloned()
+            .unwrap_or_default();
+        // (old name, new name, reset values) for each changed property
+        let mut changes: Vec<(Option<String>, Option<String>, bool)> = Vec::new();
+        for (name, definition) in body["properties"].as_object().into_iter().flatten() {
+            let key = schema
+                .iter()
+                .find(|(k, p)| *k == name || p["id"] == name.as_str())
+                .map(|(k, _)| k.clone());
+            if definition.is_null() {
+                let key = key.ok_or_else(|| missing_property(name))?;
+                schema.shift_remove(&key);
+                changes.push((Some(key), None, false));
+                continue;
+            }
+            let existing = key.as_ref().and_then(|k| schema.get(k)).cloned();
+            let property = self.property_schema(name, definition, existing.as_ref())?;
+            let new_name = property["name"].as_str().unwrap_or(name).to_string();
+            let retyped = existing
+                .as_ref()
+                .is_none_or(|e| e["type"] != property["type"]);
+            if let Some(key) = &key {
+                schema.shift_remove(key);
+            }
+            schema.insert(new_name.clone(), property);
+            changes.push((key, Some(new_name), retyped));
+        }
+
+        let now = self.tick();
+        let page_ids: Vec<String> = self
+            .all("page")
+            .into_iter()
+            .filter(|p| p["parent"]["data_source_id"] == id)
+            .filter_map(|p| p["id"].as_str().map(String::from))
+            .collect();
+        for page_id in page_ids {
+            let Some(properties) = self
+                .objects
+                .get_mut(&page_id)
+                .and_then(|p| p["properties"].as_object_mut())
+            else {
+                continue;
+            };
+            for (old, new, reset) in &changes {
+                let value = old.as_ref().and_then(|old| properties.shift_remove(old));
+                let Some(new) = new else {
+                    continue;
+                };
+                let value = match value {
+                    Some(value) if !reset => value,
+                    _ => empty_property(&schema[new.as_str()]),
+                };
+                properties.insert(new.clone(), value);
+            }
+        }
+
+        let data_source = self.find_mut(id, "data_source")?;
+        data_source["properties"] = Value::Object(schema);
+        for key in ["title", "description"] {
+            if let Some(value) = body.get(key) {
+                data_source[key] = normalize_rich_text(value);
+            }
+        }
+        if let Some(trash) = trash_flag(body) {
+            data_source["archived"] = json!(trash);
+            data_source["in_trash"] = json!(trash);
+        }
+        data_source["last_edited_time"] = json!(now);
+        Ok(data_source.clone())
+    }
+
+    /// A schema entry from a create/update definition such as `{"number": {}}`.
+    fn property_schema(
+        &mut self,
+        name: &str,
+        definition: &Value,
+        existing: Option<&Value>,
+    ) -> Outcome {
+        let kind = definition["type"].as_str().map(String::from).or_else(|| {
+            definition
+                .as_object()?
+                .keys()
+                .find(|k| !matches!(k.as_str(), "name" | "description" | "id" | "type"))
+                .cloned()
+        });
+        let mut property = match (kind, existing) {
+            (Some(kind), _) => {
+                let mut config = definition[&kind].clone();
+                if !config.is_object() {
+                    config = json!({});
+                }
+                if let Some(options) = config["options"].as_array_mut() {
+                    for option in options {
+                        if option["id"].is_null() {
+                            option["id"] = json!(self.short_id());
+                        }
+                        if option["color"].is_null() {
+                            option["color"] = json!("default");
+                        }
+                    }
+                }
+                let id = match existing {
+                    Some(existing) => existing["id"].clone(),
+                    None if kind == "title" => json!("title"),
+                    None => json!(self.short_id()),
+                };
+                json!({ "id": id, "name": name, "type": kind, kind: config })
+            }
+            (None, Some(existing)) => existing.clone(),
+            (None, None) => {
+                return Err(validation(format!("Property \"{}\" needs a type", name)));
+            }
+        };
+        if let Some(new_name) = definition["name"].as_str() {
+            property["name"] = json!(new_name);
+        }
+        Ok(property)
+    }
+
+    fn query_data_source(&self, id: &str, body: &Value) -> Outcome {
+        self.find(id, "data_source")?;
+        let mut pages = Vec::new();
+        for page in self.all("page") {
+            if page["parent"]["data_source_id"] != id || page["in_trash"] == true {
+                continue;
+            }
+            let matched = match body.get("filter").filter(|f| !f.is_null()) {
+                Some(filter) => query::matches(page, filter).map_err(validation)?,
+                None => true,
+            };
+            if matched {
+                pages.push(page.clone());
+            }
+        }
+        if let Some(sorts) = body["sorts"].as_array() {
+            query::sort(&mut pages, sorts).map_err(validation)?;
+        }
+        list(pages, "page_or_data_source", Paging::from_body(body)?)
+    }
+
+    // --- Search ---
+
+    fn search(&self, body: &Value) -> Outcome {
+        let object = match &body["filter"] {
+            Value::Null => None,
+            filter if filter["property"] == "object" => match filter["value"].as_str() {
+                Some(value @ ("page" | "data_source")) => Some(value),
+                _ => {
+                    return Err(validation(
+                        "body.filter.value should be `\"page\"` or `\"data_source\"`",
+                    ));
+                }
+            },
+            _ => return Err(validation("body.filter.property should be `\"object\"`")),
+        };
+        let text = body["query"].as_str().unwrap_or_default().to_lowercase();
+        let mut results: Vec<Value> = self
+            .created
+            .iter()
+            .filter_map(|id| self.objects.get(id))
+            .filter(|o| match object {
+                Some(object) => o["object"] == object,
+                None => matches!(o["object"].as_str(), Some("page" | "data_source")),
+            })
+            .filter(|o| o["in_trash"] != true)
+            .filter(|o| {
+                let title = match o["object"].as_str() {
+                    Some("page") => page_title(o),
+                    _ => query::plain_text(&o["title"]),
+                };
+                title.to_lowercase().contains(&text)
+            })
+            .cloned()
+            .collect();
+        if let Some(sort) = body.get("sort").filter(|s| !s.is_null()) {
+            query::sort(&mut results, std::slice::from_ref(sort)).map_err(validation)?;
+        }
+        list(results, "page_or_data_source", Paging::from_body(body)?)
+    }
+
+    // --- File uploads ---
+
+    fn create_upload(&mut self, body: &Value) -> Outcome {
+        let mode = body["mode"].as_str().unwrap_or("single_part").to_string();
+        let now = self.tick();
+        let id = self.new_id();
+        let mut object = json!({
+            "object": "file_upload",
+            "id": id,
+            "created_time": now,
+            "last_edited_time": now,
+            "expiry_time": null,
+            "upload_url": format!("{}/v1/file_uploads/{}/send", self.base_url, id),
+            "archived": false,
+            "status": "pending",
+            "filename": body["filename"],
+            "content_type": body["content_type"],
+            "content_length": null,
+        });
+        match mode.as_str() {
+            "single_part" => {}
+            "multi_part" => {
+                let parts = body["number_of_parts"]
+                    .as_u64()
+                    .filter(|n| (1..=1000).contains(n))
+                    .ok_or_else(|| {
+                        validation("body.number_of_parts should be between 1 and 1000")
+                    })?;
+                if body["filename"].is_null() {
+                    return Err(validation(
+                        "body.filename should be defined for multi_part uploads",
+                    ));
+                }
+                object["number_of_parts"] = json!({ "total": parts, "sent": 0 });
+            }
+            "external_url" => {
+                let url = body["external_url"].as_str().ok_or_else(|| {
+                    validation("body.external_url should be defined for external_url uploads")
+                })?;
+                object["status"] = json!("uploaded");
+                object["filename"] = body["filename"]
+                    .as_str()
+                    .or(url.rsplit('/').next())
+                    .map_or(Value::Null, |name| json!(name));
+                remove_key(&mut object, "upload_url");
+            }
+            other => {
+                return Err(validation(format!(
+                    "body.mode should be one of `single_part`, `multi_part` or `external_url`, instead was `{}`.",
+                    other
+                )));
+            }
+        }
+        self.created.push(id.clone());
+        self.uploads.insert(
+            id,
+            Upload {
+                mode,
+                parts: BTreeMap::new(),
+                object: object.clone(),
+            },
+        );
+        Ok(object)
+    }
+
+    fn upload(&self, id: &str) -> Result<&Upload, Response> {
+        self.uploads
+            .get(id)
+            .ok_or_else(|| not_found("file upload", id))
+    }
+
+    fn list_uploads(&self, request: &Request) -> Outcome {
+        let status = request.query("status");
+        if let Some(status) =
+            status.filter(|s| !matches!(*s, "pending" | "uploaded" | "expired" | "failed"))
+        {
+            return Err(validation(format!("Invalid status filter: {}", status)));
+        }
+        let uploads = self
+            .created
+            .iter()
+            .filter_map(|id| self.uploads.get(id))
+            .map(|u| &u.object)
+            .filter(|o| status.is_none_or(|s| o["status"] == s))
+            .cloned()
+            .collect();
+        list(uploads, "file_upload", Paging::from_query(request)?)
+    }
+
+    fn send_upload(&mut self, id: &str, request: &Request) -> Outcome {
+        let parts = request.form_parts();
+        let now = self.tick();
+        let upload = self
+            .uploads
+            .get_mut(id)
+            .ok_or_else(|| not_found("file upload", id))?;
+        if upload.object["status"] != "pending" {
+            return Err(validation(format!(
+                "File upload {} is {}, not pending",
+                id,
+                upload.object["status"].as_str().unwrap_or_default()
+            )));
+        }
+        let file = parts
+            .iter()
+            .find(|p| p.name == "file")
+            .ok_or_else(|| validation("body.file should be defined"))?;
+        let part_number = match parts.iter().find(|p| p.name == "part_number") {
+            Some(part) => Some(
+                String::from_utf8_lossy(&part.data)
+                    .trim()
+                    .parse::<u32>()
+                    .map_err(|_| validation("body.part_number should be a number"))?,
+            ),
+            None => None,
+        };
+
+        let object = &mut upload.object;
+        if object["filename"].is_null() {
+            object["filename"] = json!(file.filename);
+        }
+        if object["content_type"].is_null() {
+            object["content_type"] = json!(file.content_type);
+        }
+        object["last_edited_time"] = json!(now);
+        match (upload.mode.as_str(), part_number) {
+            ("single_part", None) => {
+                object["status"] = json!("uploaded");
+                object["content_length"] = json!(file.data.len());
+                remove_key(object, "upload_url");
+            }
+            ("single_part", Some(_)) => {
+                return Err(validation(
+                    "part_number is only allowed for multi_part uploads",
+                ));
+            }
+            ("multi_part", Some(number)) => {
+                let total = object["number_of_parts"]["total"].as_u64().unwrap_or(0);
+                if number == 0 || u64::from(number) > total {
+                    return Err(validation(format!(
+                        "part_number should be between 1 and {}",
+                        total
+                    )));
+                }
+                upload.parts.insert(number, file.data.len());
+                object["number_of_parts"]["sent"] = json!(upload.parts.len());
+            }
+            ("multi_part", None) => {
+                return Err(validation("part_number is required for multi_part uploads"));
+            }
+            (mode, _) => {
+                return Err(validation(format!(
+                    "Files can't be sent to {} uploads",
+                    mode
+                )));
+            }
+        }
+        Ok(upload.object.clone())
+    }
+
+    fn complete_upload(&mut self, id: &str) -> Outcome {
+        let now = self.tick();
+        let upload = self
+            .uploads
+            .get_mut(id)
+            .ok_or_else(|| not_found("file upload", id))?;
+        if upload.mode != "multi_part" {
+            return Err(validation("Only multi_part uploads can be completed"));
+        }
+        if upload.object["status"] != "pending" {
+            return Err(validation(format!("File upload {} is not pending", id)));
+        }
+        let total = upload.object["number_of_parts"]["total"]
+            .as_u64()
+            .unwrap_or(0);
+        if upload.parts.len() as u64 != total {
+            return Err(validation(format!(
+                "Expected {} parts but received {}",
+                total,
+                upload.parts.len()
+            )));
+        }
+        let object = &mut upload.object;
+        object["status"] = json!("uploaded");
+        object["content_length"] = json!(upload.parts.values().sum::<usize>());
+        object["last_edited_time"] = json!(now);
+        remove_key(object, "upload_url");
+        Ok(upload.object.clone())
+    }
+
+    // --- Storage helpers ---
+
+    fn new_id(&mut self) -> String {
+        self.ids += 1;
+        format!("{:08x}-0000-4000-8000-{:012x}", 0x6d6f636b, self.ids)
+    }
+
+    /// A short schema or option ID, like the API's `%3AUPp`.
+    fn short_id(&mut self) -> String {
+        self.ids += 1;
+        format!("p{:x}", self.ids)
+    }
+
+    fn tick(&mut self) -> String {
+        self.clock += 1;
+        timestamp(self.clock)
+    }
+
+    fn bot_ref(&self) -> Value {
+        json!({ "object": "user", "id": self.seed.bot_id })
+    }
+
+    fn insert(&mut self, object: Value) {
+        let id = object["id"].as_str().unwrap_or_default().to_string();
+        self.created.push(id.clone());
+        self.objects.insert(id, object);
+    }
+
+    fn all(&self, object: &str) -> Vec<&Value> {
+        self.created
+            .iter()
+            .filter_map(|id| self.objects.get(id))
+            .filter(|o| o["object"] == object)
+            .collect()
+    }
+
+    fn find(&self, id: &str, object: &str) -> Result<&Value, Response> {
+        self.objects
+            .get(id)
+            .filter(|o| o["object"] == object)
+            .ok_or_else(|| not_found(&object.replace('_', " "), id))
+    }
+
+    fn find_mut(&mut self, id: &str, object: &str) -> Result<&mut Value, Response> {
+        self.objects
+            .get_mut(id)
+            .filter(|o| o["object"] == object)
+            .ok_or_else(|| not_found(&object.replace('_', " "), id))
+    }
+
+    fn touch(&mut self, id: &str) {
+        let now = timestamp(self.clock);
+        if let Some(object) = self.objects.get_mut(id) {
+            object["last_edited_time"] = json!(now);
+        }
+    }
+}
+
+impl Paging {
+    fn new(cursor: Option<&str>, size: Option<u64>) -> Result<Self, Response> {
+        let start = match cursor {
+            Some(cursor) => cursor
+                .parse()
+                .map_err(|_| validation("start_cursor should be a valid cursor"))?,
+            None => 0,
+        };
+        let size = size.unwrap_or(MAX_PAGE_SIZE as u64);
+        if size == 0 || size > MAX_PAGE_SIZE as u64 {
+            return Err(validation(format!(
+                "page_size should be between 1 and {}, instead was `{}`.",
+                MAX_PAGE_SIZE, size
+            )));
+        }
+        Ok(Paging {
+            start,
+            size: size as usize,
+        })
+    }
+
+    fn from_query(request: &Request) -> Result<Self, Response> {
+        let size = request
+            .query("page_size")
+            .map(|s| {
+                s.parse()
+                    .map_err(|_| validation("page_size should be a number"))
+            })
+            .transpose()?;
+        Paging::new(request.query("start_cursor"), size)
+    }
+
+    fn from_body(body: &Value) -> Result<Self, Response> {
+        Paging::new(body["start_cursor"].as_str(), body["page_size"].as_u64())
+    }
+}
+
+/// A list response holding one page of `items`. Cursors are item offsets.
+fn list(items: Vec<Value>, kind: &str, paging: Paging) -> Outcome {
+    let end = paging.start.saturating_add(paging.size).min(items.len());
+    let has_more = end < items.len();
+    let results: Vec<Value> = items
+        .into_iter()
+        .skip(paging.start)
+        .take(end.saturating_sub(paging.start))
+        .collect();
+    Ok(json!({
+        "object": "list",
+        "results": results,
+        "next_cursor": if has_more { json!(end.to_string()) } else { Value::Null },
+        "has_more": has_more,
+        "type": kind,
+        kind: {},
+    }))
+}
+
+fn authorize(request: &Request) -> Result<(), Response> {
+    let token = request
+        .header("authorization")
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .filter(|t| !t.trim().is_empty());
+    if token.is_none() {
+        return Err(error(401, "unauthorized", "API token is invalid."));
+    }
+    if request.header("notion-version").is_none() {
+        return Err(error(
+            400,
+            "missing_version",
+            "Notion-Version header failed validation: Notion-Version header should be defined, instead was `undefined`.",
+        ));
+    }
+    Ok(())
+}
+
+fn json_body(request: &Request) -> Result<Value, Response> {
+    let multipart = request
+        .header("content-type")
+        .is_some_and(|ct| ct.starts_with("multipart/"));
+    if multipart || request.body.is_empty() {
+        return Ok(json!({}));
+    }
+    serde_json::from_slice(&request.body)
+        .map_err(|_| error(400, "invalid_json", "Error parsing JSON body."))
+}
+
+fn error(status: u16, code: &str, message: impl Into<String>) -> Response {
+    Response {
+        status,
+        body: json!({
+            "object": "error",
+            "status": status,
+            "code": code,
+            "message": message.into(),
+        }),
+    }
+}
+
+fn validation(message: impl Into<String>) -> Response {
+    error(400, "validation_error", message)
+}
+
+fn not_found(object: &str, id: &str) -> Response {
+    error(
+        404,
+        "object_not_found",
+        format!(
+            "Could not find {} with ID: {}. Make sure the relevant pages and databases are shared with your integration.",
+            object, id
+        ),
+    )
+}
+
+fn invalid_url() -> Response {
+    error(400, "invalid_request_url", "Invalid request URL.")
+}
+
+fn missing_property(name: &str) -> Response {
+    validation(format!("{} is not a property that exists.", name))
+}
+
+/// The dashed, lowercase form of a 32-digit ID; anything else is unchanged.
+fn normalize_id(id: &str) -> String {
+    let hex: String = id.chars().filter(|c| *c != '-').collect();
+    if hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return id.to_string();
+    }
+    let hex = hex.to_lowercase();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+fn remove_key(object: &mut Value, key: &str) {
+    if let Some(object) = object.as_object_mut() {
+        object.shift_remove(key);
+    }
+}
+
+/// `archived` or `in_trash` from an update body; the API treats them alike.
+fn trash_flag(body: &Value) -> Option<bool> {
+    body["in_trash"].as_bool().or(body["archived"].as_bool())
+}
+
+fn page_title(page: &Value) -> String {
+    page["properties"]
+        .as_object()
+        .and_then(|properties| properties.values().find(|p| p["type"] == "title"))
+        .map(|title| query::plain_text(&title["title"]))
+        .unwrap_or_default()
+}
+
+/// The block fields shared by `child_page` and `child_database` views.
+fn child_view(object: &Value, kind: &str) -> Value {
+    json!({
+        "object": "block",
+        "id": object["id"],
+        "parent": object["parent"],
+        "created_time": object["created_time"],
+        "last_edited_time": object["last_edited_time"],
+        "has_children": false,
+        "archived": object["in_trash"],
+        "in_trash": object["in_trash"],
+        "type": kind,
+    })
+}
+
+/// A block's type and its type-specific content.
+fn block_parts(block: &Value) -> Result<(String, Map<String, Value>), Response> {
+    let kind = block["type"].as_str().map(String::from).or_else(|| {
+        block
+            .as_object()?
+            .keys()
+            .find(|k| !matches!(k.as_str(), "object" | "type"))
+            .cloned()
+    });
+    let Some(kind) = kind else {
+        return Err(validation("Block type should be defined"));
+    };
+    if matches!(kind.as_str(), "child_page" | "child_database") {
+        return Err(validation(format!(
+            "{} blocks can't be appended; create a page or data source instead",
+            kind
+        )));
+    }
+    let content = block[&kind]
+        .as_object()
+        .cloned()
+        .ok_or_else(|| validation(format!("body.children[].{} should be an object", kind)))?;
+    Ok((kind, content))
+}
+
+/// Fill in rich text defaults in block content, including table cells.
+fn normalize_content(content: &mut Map<String, Value>) {
+    for key in ["rich_text", "caption"] {
+        if let Some(value) = content.get_mut(key) {
+            *value = normalize_rich_text(value);
+        }
+    }
+    if let Some(Value::Array(cells)) = content.get_mut("cells") {
+        for cell in cells {
+            *cell = normalize_rich_text(cell);
+        }
+    }
+}
+
+/// Rich text with the `plain_text`, `href` and `annotations` the API adds.
+fn normalize_rich_text(value: &Value) -> Value {
+    let Some(items) = value.as_array() else {
+        return json!([]);
+    };
+    let items = items
+        .iter()
+        .map(|item| {
+            let mut item = item.clone();
+            let kind = item["type"].as_str().map(String::from).unwrap_or_else(|| {
+                ["mention", "equation"]
+                    .into_iter()
+                    .find(|k| item.get(*k).is_some())
+                    .unwrap_or("text")
+                    .to_string()
+            });
+            item["type"] = json!(kind);
+            match kind.as_str() {
+                "text" => {
+                    let link = item["text"]["link"].clone();
+                    let content = item["text"]["content"].as_str().unwrap_or("").to_string();
+                    item["text"] = json!({ "content": content, "link": link });
+                    item["plain_text"] = item["text"]["content"].clone();
+                    item["href"] = link["url"].clone();
+                }
+                "equation" => {
+                    item["plain_text"] = item["equation"]["expression"].clone();
+                    item["href"] = Value::Null;
+                }
+                _ => {
+                    if item["plain_text"].is_null() {
+                        item["plain_text"] = json!("");
+                    }
+                    if item.get("href").is_none() {
+                        item["href"] = Value::Null;
+                    }
+                }
+            }
+            let mut annotations = json!({
+                "bold": false,
+                "italic": false,
+                "strikethrough": false,
+                "underline": false,
+                "code": false,
+                "color": "default",
+            });
+            for (key, value) in item["annotations"].as_object().into_iter().flatten() {
+                annotations[key] = value.clone();
+            }
+            item["annotations"] = annotations;
+            item
+        })
+        .collect();
+    Value::Array(items)
+}
+
+/// Every schema property with an empty value.
+fn empty_properties(schema: &Value) -> Map<String, Value> {
+    schema
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(name, property)| (name.clone(), empty_property(property)))
+        .collect()
+}
+
+fn empty_property(schema: &Value) -> Value {
+    let kind = schema["type"].as_str().unwrap_or_default();
+    let value = match kind {
+        "title" | "rich_text" | "multi_select" | "people" | "relation" | "files" => json!([]),
+        "checkbox" => json!(false),
+        _ => Value::Null,
+    };
+    json!({ "id": schema["id"], "type": kind, kind: value })
+}
+
+/// Apply input values such as `{"Score": {"number": 3}}` to page properties.
+fn set_properties(
+    properties: &mut Map<String, Value>,
+    input: &Value,
+    schema: &Value,
+) -> Result<(), Response> {
+    for (name, value) in input.as_object().into_iter().flatten() {
+        let (key, property) = schema
+            .as_object()
+            .and_then(|schema| {
+                schema
+                    .iter()
+                    .find(|(k, p)| *k == name || p["id"] == name.as_str())
+            })
+            .ok_or_else(|| missing_property(name))?;
+        let kind = property["type"].as_str().unwrap_or_default();
+        if matches!(
+            kind,
+            "created_time"
+                | "last_edited_time"
+                | "created_by"
+                | "last_edited_by"
+                | "formula"
+                | "rollup"
+                | "unique_id"
+        ) {
+            return Err(validation(format!(
+                "{} is a computed property and can't be set.",
+                name
+            )));
+        }
+        let Some(value) = value.get(kind) else {
+            return Err(validation(format!("{} is expected to be {}.", name, kind)));
+        };
+        properties.insert(
+            key.clone(),
+            json!({ "id": property["id"], "type": kind, kind: property_value(kind, value) }),
+        );
+    }
+    Ok(())
+}
+
+/// The title of a page whose parent is a page or the workspace.
+fn title_property(input: &Value) -> Result<Map<String, Value>, Response> {
+    let mut properties = Map::new();
+    let title = match input.as_object() {
+        Some(input) => match input.iter().find(|(_, v)| v.get("title").is_some()) {
+            Some((_, value)) => normalize_rich_text(&value["title"]),
+            None if input.is_empty() => json!([]),
+            None => {
+                return Err(validation(
+                    "Pages whose parent is a page can only have a title property.",
+                ));
+            }
+        },
+        None => json!([]),
+    };
+    properties.insert(
+        "title".to_string(),
+        json!({ "id": "title", "type": "title", "title": title }),
+    );
+    Ok(properties)
+}
+
+fn property_value(kind: &str, value: &Value) -> Value {
+    let with_color = |option: &Value| {
+        let mut option = option.clone();
+        if option["color"].is_null() {
+            option["color"] = json!("default");
+        }
+        option
+    };
+    match kind {
+        "title" | "rich_text" => normalize_rich_text(value),
+        "select" | "status" if value.is_object() => with_color(value),
+        "multi_select" => Value::Array(
+            value
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(with_color)
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+/// Copy timestamps and authors into the computed properties that show them.
+fn stamp(page: &mut Value) {
+    let (created, edited) = (
+        page["created_time"].clone(),
+        page["last_edited_time"].clone(),
+    );
+    let (creator, editor) = (page["created_by"].clone(), page["last_edited_by"].clone());
+    for property in page["properties"]
+        .as_object_mut()
+        .into_iter()
+        .flat_map(|p| p.values_mut())
+    {
+        let kind = property["type"].as_str().unwrap_or_default().to_string();
+        let value = match kind.as_str() {
+            "created_time" => created.clone(),
+            "last_edited_time" => edited.clone(),
+            "created_by" => creator.clone(),
+            "last_edited_by" => editor.clone(),
+            _ => continue,
+        };
+        property[&kind] = value;
+    }
+}
+
+/// A logical clock reading as an API timestamp.
+fn timestamp(seconds: u64) -> String {
+    let (year, month, day) = civil_from_days(EPOCH_DAYS + seconds / 86400);
+    let secs = seconds % 86400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.000Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+#[path = "store_tests.rs"]
+mod tests;
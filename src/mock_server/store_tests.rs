@@ -0,0 +1,939 @@
+use super::*;
+
+fn request(method: &str, target: &str, body: Option<Value>) -> Request {
+    let url = reqwest::Url::parse(&format!("http://localhost{}", target)).unwrap();
+    Request {
+        method: method.to_string(),
+        path: url.path().to_string(),
+        query: url
+            .query_pairs()
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect(),
+        headers: vec![
+            ("Authorization".into(), "Bearer secret".into()),
+            ("Notion-Version".into(), "2025-09-03".into()),
+        ],
+        body: body.map(|b| b.to_string().into_bytes()).unwrap_or_default(),
+    }
+}
+
+fn call(store: &mut Store, method: &str, target: &str, body: Option<Value>) -> Value {
+    let response = store.handle(&request(method, target, body));
+    assert_eq!(
+        response.status, 200,
+        "{} {}: {}",
+        method, target, response.body
+    );
+    response.body
+}
+
+fn fail(store: &mut Store, method: &str, target: &str, body: Option<Value>) -> Response {
+    let response = store.handle(&request(method, target, body));
+    assert_ne!(response.status, 200, "{} {} succeeded", method, target);
+    response
+}
+
+fn title(text: &str) -> Value {
+    json!([{ "text": { "content": text } }])
+}
+
+/// A data source under the seeded page with a title, number and select.
+fn tasks(store: &mut Store) -> String {
+    let page_id = store.seed().page_id.clone();
+    let ds = call(
+        store,
+        "POST",
+        "/v1/data_sources",
+        Some(json!({
+            "parent": { "page_id": page_id },
+            "title": title("Tasks"),
+            "properties": {
+                "Name": { "title": {} },
+                "Score": { "number": {} },
+                "Stage": { "select": { "options": [{ "name": "Todo" }] } },
+            },
+        })),
+    );
+    ds["id"].as_str().unwrap().to_string()
+}
+
+fn add_task(store: &mut Store, ds: &str, name: &str, score: f64) -> Value {
+    call(
+        store,
+        "POST",
+        "/v1/pages",
+        Some(json!({
+            "parent": { "data_source_id": ds },
+            "properties": {
+                "Name": { "title": title(name) },
+                "Score": { "number": score },
+            },
+        })),
+    )
+}
+
+fn names(list: &Value) -> Vec<String> {
+    list["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(page_title)
+        .collect()
+}
+
+#[test]
+fn test_seeded_users_and_page() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let seed = store.seed().clone();
+    let me = call(&mut store, "GET", "/v1/users/me", None);
+    assert_eq!(me["id"], seed.bot_id.as_str());
+    assert_eq!(me["type"], "bot");
+
+    let users = call(&mut store, "GET", "/v1/users", None);
+    assert_eq!(users["results"].as_array().unwrap().len(), 2);
+    assert_eq!(users["type"], "user");
+
+    let person = call(
+        &mut store,
+        "GET",
+        &format!("/v1/users/{}", seed.person_id),
+        None,
+    );
+    assert_eq!(person["person"]["email"], "ada@example.com");
+
+    let page = call(
+        &mut store,
+        "GET",
+        &format!("/v1/pages/{}", seed.page_id),
+        None,
+    );
+    assert_eq!(page_title(&page), "Home");
+    assert_eq!(page["parent"]["type"], "workspace");
+}
+
+#[test]
+fn test_requests_need_token_and_version() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let mut unauthenticated = request("GET", "/v1/users/me", None);
+    unauthenticated.headers.remove(0);
+    let response = store.handle(&unauthenticated);
+    assert_eq!(response.status, 401);
+    assert_eq!(response.body["code"], "unauthorized");
+
+    let mut unversioned = request("GET", "/v1/users/me", None);
+    unversioned.headers.remove(1);
+    assert_eq!(store.handle(&unversioned).body["code"], "missing_version");
+}
+
+#[test]
+fn test_unknown_route_and_bad_json() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let response = fail(&mut store, "GET", "/v1/nothing", None);
+    assert_eq!(response.body["code"], "invalid_request_url");
+
+    let mut bad = request("POST", "/v1/search", None);
+    bad.body = b"{oops".to_vec();
+    assert_eq!(store.handle(&bad).body["code"], "invalid_json");
+}
+
+#[test]
+fn test_missing_object_is_404() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let response = fail(
+        &mut store,
+        "GET",
+        "/v1/pages/00000000000000000000000000000000",
+        None,
+    );
+    assert_eq!(response.status, 404);
+    assert_eq!(response.body["code"], "object_not_found");
+    assert!(
+        response.body["message"]
+            .as_str()
+            .unwrap()
+            .contains("page with ID: 00000000-0000-0000-0000-000000000000")
+    );
+}
+
+#[test]
+fn test_ids_accepted_without_dashes() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let undashed = store.seed().page_id.replace('-', "").to_uppercase();
+    let page = call(&mut store, "GET", &format!("/v1/pages/{}", undashed), None);
+    assert_eq!(page["id"], store.seed().page_id.as_str());
+}
+
+#[test]
+fn test_create_page_under_page_adds_child_page_block() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let parent = store.seed().page_id.clone();
+    let page = call(
+        &mut store,
+        "POST",
+        "/v1/pages",
+        Some(json!({
+            "parent": { "page_id": parent },
+            "properties": { "title": { "title": title("Notes") } },
+            "children": [{ "paragraph": { "rich_text": title("Body") } }],
+        })),
+    );
+    assert_eq!(
+        page["properties"]["title"]["title"][0]["plain_text"],
+        "Notes"
+    );
+    assert_eq!(page["created_time"], "2025-01-01T00:00:02.000Z");
+
+    let children = call(
+        &mut store,
+        "GET",
+        &format!("/v1/blocks/{}/children", parent),
+        None,
+    );
+    assert_eq!(children["results"][0]["type"], "child_page");
+    assert_eq!(children["results"][0]["child_page"]["title"], "Notes");
+    assert_eq!(children["results"][0]["has_children"], true);
+
+    let id = page["id"].as_str().unwrap();
+    let body = call(
+        &mut store,
+        "GET",
+        &format!("/v1/blocks/{}/children", id),
+        None,
+    );
+    assert_eq!(
+        body["results"][0]["paragraph"]["rich_text"][0]["plain_text"],
+        "Body"
+    );
+}
+
+#[test]
+fn test_page_under_page_only_takes_a_title() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let parent = store.seed().page_id.clone();
+    let response = fail(
+        &mut store,
+        "POST",
+        "/v1/pages",
+        Some(json!({
+            "parent": { "page_id": parent },
+            "properties": { "Score": { "number": 1 } },
+        })),
+    );
+    assert_eq!(response.body["code"], "validation_error");
+    fail(
+        &mut store,
+        "POST",
+        "/v1/pages",
+        Some(json!({ "properties": {} })),
+    );
+}
+
+#[test]
+fn test_update_page_and_trash() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let ds = tasks(&mut store);
+    let page = add_task(&mut store, &ds, "One", 1.0);
+    let path = format!("/v1/pages/{}", page["id"].as_str().unwrap());
+
+    let updated = call(
+        &mut store,
+        "PATCH",
+        &path,
+        Some(json!({ "properties": { "Stage": { "select": { "name": "Todo" } } } })),
+    );
+    assert_eq!(updated["properties"]["Stage"]["select"]["name"], "Todo");
+    assert_eq!(updated["properties"]["Stage"]["select"]["color"], "default");
+    assert_eq!(
+        updated["properties"]["Name"]["title"][0]["plain_text"],
+        "One"
+    );
+    assert_ne!(updated["last_edited_time"], updated["created_time"]);
+
+    let trashed = call(
+        &mut store,
+        "PATCH",
+        &path,
+        Some(json!({ "archived": true })),
+    );
+    assert_eq!(trashed["in_trash"], true);
+    let response = fail(
+        &mut store,
+        "PATCH",
+        &path,
+        Some(json!({ "properties": { "Score": { "number": 2 } } })),
+    );
+    assert!(
+        response.body["message"]
+            .as_str()
+            .unwrap()
+            .contains("archived")
+    );
+    call(
+        &mut store,
+        "PATCH",
+        &path,
+        Some(json!({ "in_trash": false })),
+    );
+}
+
+#[test]
+fn test_page_property_errors() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let ds = tasks(&mut store);
+    let page_path = "/v1/pages";
+    let response = fail(
+        &mut store,
+        "POST",
+        page_path,
+        Some(json!({
+            "parent": { "data_source_id": ds },
+            "properties": { "Missing": { "number": 1 } },
+        })),
+    );
+    assert_eq!(
+        response.body["message"],
+        "Missing is not a property that exists."
+    );
+    let response = fail(
+        &mut store,
+        "POST",
+        page_path,
+        Some(json!({
+            "parent": { "data_source_id": ds },
+            "properties": { "Score": { "rich_text": [] } },
+        })),
+    );
+    assert_eq!(response.body["message"], "Score is expected to be number.");
+}
+
+#[test]
+fn test_page_property_item() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let ds = tasks(&mut store);
+    let page = add_task(&mut store, &ds, "One", 4.0);
+    let id = page["id"].as_str().unwrap();
+    let score_id = page["properties"]["Score"]["id"].as_str().unwrap();
+
+    let score = call(
+        &mut store,
+        "GET",
+        &format!("/v1/pages/{}/properties/{}", id, score_id),
+        None,
+    );
+    assert_eq!(score["object"], "property_item");
+    assert_eq!(score["number"], 4.0);
+
+    let title = call(
+        &mut store,
+        "GET",
+        &format!("/v1/pages/{}/properties/title", id),
+        None,
+    );
+    assert_eq!(title["object"], "list");
+    assert_eq!(title["results"][0]["title"]["plain_text"], "One");
+    assert_eq!(title["property_item"]["type"], "title");
+}
+
+#[test]
+fn test_move_page() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let home = store.seed().page_id.clone();
+    let create = |store: &mut Store, name: &str| {
+        let page = call(
+            store,
+            "POST",
+            "/v1/pages",
+            Some(json!({
+                "parent": { "page_id": home },
+                "properties": { "title": { "title": title(name) } },
+            })),
+        );
+        page["id"].as_str().unwrap().to_string()
+    };
+    let a = create(&mut store, "A");
+    let b = create(&mut store, "B");
+
+    let moved = call(
+        &mut store,
+        "POST",
+        &format!("/v1/pages/{}/move", b),
+        Some(json!({ "parent": { "type": "page_id", "page_id": a } })),
+    );
+    assert_eq!(moved["parent"]["page_id"], a.as_str());
+    let home_children = call(
+        &mut store,
+        "GET",
+        &format!("/v1/blocks/{}/children", home),
+        None,
+    );
+    assert_eq!(home_children["results"].as_array().unwrap().len(), 1);
+    let a_children = call(
+        &mut store,
+        "GET",
+        &format!("/v1/blocks/{}/children", a),
+        None,
+    );
+    assert_eq!(a_children["results"][0]["id"], b.as_str());
+}
+
+#[test]
+fn test_append_children_nested_and_after() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let page = store.seed().page_id.clone();
+    let path = format!("/v1/blocks/{}/children", page);
+    let created = call(
+        &mut store,
+        "PATCH",
+        &path,
+        Some(json!({ "children": [
+            { "type": "heading_1", "heading_1": { "rich_text": title("Title") } },
+            { "bulleted_list_item": {
+                "rich_text": title("Parent"),
+                "children": [{ "paragraph": { "rich_text": title("Nested") } }],
+            } },
+        ] })),
+    );
+    let results = created["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["parent"]["page_id"], page.as_str());
+    assert_eq!(results[1]["has_children"], true);
+    assert!(results[1]["bulleted_list_item"].get("children").is_none());
+    let heading = results[0]["id"].as_str().unwrap().to_string();
+    let item = results[1]["id"].as_str().unwrap().to_string();
+
+    let nested = call(
+        &mut store,
+        "GET",
+        &format!("/v1/blocks/{}/children", item),
+        None,
+    );
+    assert_eq!(nested["results"][0]["parent"]["block_id"], item.as_str());
+    assert_eq!(
+        nested["results"][0]["paragraph"]["rich_text"][0]["annotations"]["bold"],
+        false
+    );
+
+    call(
+        &mut store,
+        "PATCH",
+        &path,
+        Some(json!({
+            "after": heading,
+            "children": [{ "divider": {} }],
+        })),
+    );
+    let children = call(&mut store, "GET", &path, None);
+    let types: Vec<&str> = children["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b["type"].as_str().unwrap())
+        .collect();
+    assert_eq!(types, ["heading_1", "divider", "bulleted_list_item"]);
+}
+
+#[test]
+fn test_append_validation() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let path = format!("/v1/blocks/{}/children", store.seed().page_id);
+    let too_many: Vec<Value> = (0..101).map(|_| json!({ "divider": {} })).collect();
+    let response = fail(
+        &mut store,
+        "PATCH",
+        &path,
+        Some(json!({ "children": too_many })),
+    );
+    assert!(
+        response.body["message"]
+            .as_str()
+            .unwrap()
+            .contains("≤ `100`")
+    );
+
+    fail(&mut store, "PATCH", &path, Some(json!({})));
+    fail(
+        &mut store,
+        "PATCH",
+        &path,
+        Some(json!({ "children": [{ "divider": {} }], "after": "missing" })),
+    );
+    fail(
+        &mut store,
+        "PATCH",
+        &path,
+        Some(json!({ "children": [{ "child_page": { "title": "x" } }] })),
+    );
+    // Nothing was created by the failed requests
+    assert!(
+        call(&mut store, "GET", &path, None)["results"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_update_and_delete_block() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let page = store.seed().page_id.clone();
+    let created = call(
+        &mut store,
+        "PATCH",
+        &format!("/v1/blocks/{}/children", page),
+        Some(json!({ "children": [{ "to_do": { "rich_text": title("Task") } }] })),
+    );
+    let path = format!(
+        "/v1/blocks/{}",
+        created["results"][0]["id"].as_str().unwrap()
+    );
+
+    let updated = call(
+        &mut store,
+        "PATCH",
+        &path,
+        Some(json!({ "to_do": { "checked": true } })),
+    );
+    assert_eq!(updated["to_do"]["checked"], true);
+    assert_eq!(updated["to_do"]["rich_text"][0]["plain_text"], "Task");
+    let response = fail(&mut store, "PATCH", &path, Some(json!({ "paragraph": {} })));
+    assert!(
+        response.body["message"]
+            .as_str()
+            .unwrap()
+            .contains("does not match")
+    );
+
+    let deleted = call(&mut store, "DELETE", &path, None);
+    assert_eq!(deleted["in_trash"], true);
+    // Trashed blocks can still be retrieved but are no longer listed
+    assert_eq!(call(&mut store, "GET", &path, None)["archived"], true);
+    let children = call(
+        &mut store,
+        "GET",
+        &format!("/v1/blocks/{}/children", page),
+        None,
+    );
+    assert!(children["results"].as_array().unwrap().is_empty());
+    fail(&mut store, "DELETE", &path, None);
+}
+
+#[test]
+fn test_delete_block_trashes_page() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let ds = tasks(&mut store);
+    let page = add_task(&mut store, &ds, "Gone", 1.0);
+    let id = page["id"].as_str().unwrap();
+    let block = call(&mut store, "DELETE", &format!("/v1/blocks/{}", id), None);
+    assert_eq!(block["type"], "child_page");
+    assert_eq!(block["in_trash"], true);
+    let query = call(
+        &mut store,
+        "POST",
+        &format!("/v1/data_sources/{}/query", ds),
+        None,
+    );
+    assert!(query["results"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_list_pagination() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let page = store.seed().page_id.clone();
+    let path = format!("/v1/blocks/{}/children", page);
+    let blocks: Vec<Value> = (0..5).map(|_| json!({ "divider": {} })).collect();
+    call(
+        &mut store,
+        "PATCH",
+        &path,
+        Some(json!({ "children": blocks })),
+    );
+
+    let first = call(&mut store, "GET", &format!("{}?page_size=2", path), None);
+    assert_eq!(first["results"].as_array().unwrap().len(), 2);
+    assert_eq!(first["has_more"], true);
+    assert_eq!(first["type"], "block");
+    let cursor = first["next_cursor"].as_str().unwrap();
+
+    let rest = call(
+        &mut store,
+        "GET",
+        &format!("{}?start_cursor={}", path, cursor),
+        None,
+    );
+    assert_eq!(rest["results"].as_array().unwrap().len(), 3);
+    assert_eq!(rest["has_more"], false);
+    assert!(rest["next_cursor"].is_null());
+
+    fail(&mut store, "GET", &format!("{}?page_size=101", path), None);
+    fail(
+        &mut store,
+        "GET",
+        &format!("{}?start_cursor=nope", path),
+        None,
+    );
+}
+
+#[test]
+fn test_data_source_create_and_database() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let ds_id = tasks(&mut store);
+    let ds = call(
+        &mut store,
+        "GET",
+        &format!("/v1/data_sources/{}", ds_id),
+        None,
+    );
+    assert_eq!(ds["title"][0]["plain_text"], "Tasks");
+    assert_eq!(ds["properties"]["Name"]["id"], "title");
+    assert_eq!(ds["properties"]["Score"]["type"], "number");
+    assert_eq!(
+        ds["properties"]["Stage"]["select"]["options"][0]["color"],
+        "default"
+    );
+    assert!(ds["properties"]["Stage"]["select"]["options"][0]["id"].is_string());
+
+    let database_id = ds["parent"]["database_id"].as_str().unwrap();
+    let database = call(
+        &mut store,
+        "GET",
+        &format!("/v1/databases/{}", database_id),
+        None,
+    );
+    assert_eq!(database["data_sources"][0]["id"], ds_id.as_str());
+    assert_eq!(database["data_sources"][0]["name"], "Tasks");
+
+    let page = store.seed().page_id.clone();
+    let children = call(
+        &mut store,
+        "GET",
+        &format!("/v1/blocks/{}/children", page),
+        None,
+    );
+    assert_eq!(children["results"][0]["type"], "child_database");
+
+    // Pages can name the database when it has a data source
+    let created = call(
+        &mut store,
+        "POST",
+        "/v1/pages",
+        Some(json!({
+            "parent": { "database_id": database_id },
+            "properties": { "Name": { "title": title("Via database") } },
+        })),
+    );
+    assert_eq!(created["parent"]["data_source_id"], ds_id.as_str());
+    assert!(created["properties"]["Score"]["number"].is_null());
+}
+
+#[test]
+fn test_data_source_gets_a_default_title_property() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let page = store.seed().page_id.clone();
+    let ds = call(
+        &mut store,
+        "POST",
+        "/v1/data_sources",
+        Some(json!({ "parent": { "page_id": page }, "title": title("Empty") })),
+    );
+    assert_eq!(ds["properties"]["Name"]["type"], "title");
+}
+
+#[test]
+fn test_update_data_source_updates_pages() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let ds = tasks(&mut store);
+    let page = add_task(&mut store, &ds, "One", 3.0);
+    let updated = call(
+        &mut store,
+        "PATCH",
+        &format!("/v1/data_sources/{}", ds),
+        Some(json!({
+            "title": title("Renamed"),
+            "properties": {
+                "Score": { "name": "Points" },
+                "Stage": null,
+                "Done": { "checkbox": {} },
+            },
+        })),
+    );
+    assert_eq!(updated["title"][0]["plain_text"], "Renamed");
+    let properties = updated["properties"].as_object().unwrap();
+    assert!(properties.contains_key("Points"));
+    assert!(!properties.contains_key("Score"));
+    assert!(!properties.contains_key("Stage"));
+    assert_eq!(properties["Done"]["type"], "checkbox");
+
+    let page = call(
+        &mut store,
+        "GET",
+        &format!("/v1/pages/{}", page["id"].as_str().unwrap()),
+        None,
+    );
+    assert_eq!(page["properties"]["Points"]["number"], 3.0);
+    assert_eq!(page["properties"]["Done"]["checkbox"], false);
+    assert!(page["properties"].get("Stage").is_none());
+
+    fail(
+        &mut store,
+        "PATCH",
+        &format!("/v1/data_sources/{}", ds),
+        Some(json!({ "properties": { "Nope": null } })),
+    );
+}
+
+#[test]
+fn test_query_filters_sorts_and_paginates() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let ds = tasks(&mut store);
+    for (name, score) in [("c", 3.0), ("a", 1.0), ("b", 2.0), ("d", 4.0)] {
+        add_task(&mut store, &ds, name, score);
+    }
+    let path = format!("/v1/data_sources/{}/query", ds);
+
+    let all = call(&mut store, "POST", &path, None);
+    assert_eq!(names(&all), ["c", "a", "b", "d"]);
+
+    let body = json!({
+        "filter": { "property": "Score", "number": { "greater_than": 1 } },
+        "sorts": [{ "property": "Name", "direction": "ascending" }],
+        "page_size": 2,
+    });
+    let first = call(&mut store, "POST", &path, Some(body.clone()));
+    assert_eq!(names(&first), ["b", "c"]);
+    assert_eq!(first["type"], "page_or_data_source");
+
+    let mut next = body;
+    next["start_cursor"] = first["next_cursor"].clone();
+    assert_eq!(names(&call(&mut store, "POST", &path, Some(next))), ["d"]);
+
+    let response = fail(
+        &mut store,
+        "POST",
+        &path,
+        Some(json!({ "filter": { "property": "Nope", "number": { "equals": 1 } } })),
+    );
+    assert_eq!(response.body["code"], "validation_error");
+}
+
+#[test]
+fn test_templates() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let ds = tasks(&mut store);
+    let templates = call(
+        &mut store,
+        "GET",
+        &format!("/v1/data_sources/{}/templates", ds),
+        None,
+    );
+    assert_eq!(templates["templates"], json!([]));
+}
+
+#[test]
+fn test_search() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let ds = tasks(&mut store);
+    add_task(&mut store, &ds, "Task page", 1.0);
+
+    let everything = call(
+        &mut store,
+        "POST",
+        "/v1/search",
+        Some(json!({ "query": "TASK" })),
+    );
+    let objects: Vec<&str> = everything["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["object"].as_str().unwrap())
+        .collect();
+    assert_eq!(objects, ["data_source", "page"]);
+
+    let pages = call(
+        &mut store,
+        "POST",
+        "/v1/search",
+        Some(json!({ "filter": { "property": "object", "value": "page" } })),
+    );
+    assert_eq!(names(&pages), ["Home", "Task page"]);
+
+    let sorted = call(
+        &mut store,
+        "POST",
+        "/v1/search",
+        Some(json!({
+            "filter": { "property": "object", "value": "page" },
+            "sort": { "timestamp": "last_edited_time", "direction": "descending" },
+        })),
+    );
+    assert_eq!(names(&sorted), ["Task page", "Home"]);
+
+    fail(
+        &mut store,
+        "POST",
+        "/v1/search",
+        Some(json!({ "filter": { "property": "object", "value": "block" } })),
+    );
+}
+
+#[test]
+fn test_comments() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let page = store.seed().page_id.clone();
+    let first = call(
+        &mut store,
+        "POST",
+        "/v1/comments",
+        Some(json!({ "parent": { "page_id": page }, "rich_text": title("First") })),
+    );
+    assert_eq!(first["rich_text"][0]["plain_text"], "First");
+    call(
+        &mut store,
+        "POST",
+        "/v1/comments",
+        Some(json!({ "discussion_id": first["discussion_id"], "rich_text": title("Reply") })),
+    );
+
+    let comments = call(
+        &mut store,
+        "GET",
+        &format!("/v1/comments?block_id={}", page),
+        None,
+    );
+    let results = comments["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[1]["discussion_id"], first["discussion_id"]);
+
+    fail(&mut store, "GET", "/v1/comments", None);
+    fail(
+        &mut store,
+        "POST",
+        "/v1/comments",
+        Some(json!({ "parent": { "page_id": page } })),
+    );
+}
+
+fn multipart(target: &str, data: &[u8], part_number: Option<u32>) -> Request {
+    let mut body = b"--B\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\nContent-Type: text/plain\r\n\r\n".to_vec();
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\r\n");
+    if let Some(n) = part_number {
+        body.extend_from_slice(
+            format!(
+                "--B\r\nContent-Disposition: form-data; name=\"part_number\"\r\n\r\n{}\r\n",
+                n
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(b"--B--\r\n");
+    let mut request = request("POST", target, None);
+    request.headers.push((
+        "Content-Type".into(),
+        "multipart/form-data; boundary=B".into(),
+    ));
+    request.body = body;
+    request
+}
+
+#[test]
+fn test_single_part_upload() {
+    let mut store = Store::new("http://127.0.0.1:9");
+    let upload = call(&mut store, "POST", "/v1/file_uploads", Some(json!({})));
+    let id = upload["id"].as_str().unwrap().to_string();
+    assert_eq!(upload["status"], "pending");
+    assert_eq!(
+        upload["upload_url"],
+        format!("http://127.0.0.1:9/v1/file_uploads/{}/send", id)
+    );
+
+    let send = format!("/v1/file_uploads/{}/send", id);
+    let sent = store.handle(&multipart(&send, b"hello", None));
+    assert_eq!(sent.status, 200, "{}", sent.body);
+    assert_eq!(sent.body["status"], "uploaded");
+    assert_eq!(sent.body["filename"], "a.txt");
+    assert_eq!(sent.body["content_type"], "text/plain");
+    assert_eq!(sent.body["content_length"], 5);
+    assert!(sent.body.get("upload_url").is_none());
+
+    assert_eq!(store.handle(&multipart(&send, b"again", None)).status, 400);
+    fail(
+        &mut store,
+        "POST",
+        &format!("/v1/file_uploads/{}/complete", id),
+        None,
+    );
+}
+
+#[test]
+fn test_multi_part_upload() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let upload = call(
+        &mut store,
+        "POST",
+        "/v1/file_uploads",
+        Some(json!({ "mode": "multi_part", "number_of_parts": 2, "filename": "big.bin" })),
+    );
+    let id = upload["id"].as_str().unwrap().to_string();
+    let send = format!("/v1/file_uploads/{}/send", id);
+    let complete = format!("/v1/file_uploads/{}/complete", id);
+
+    assert_eq!(store.handle(&multipart(&send, b"abc", None)).status, 400);
+    assert_eq!(store.handle(&multipart(&send, b"abc", Some(3))).status, 400);
+    let part = store.handle(&multipart(&send, b"abc", Some(1)));
+    assert_eq!(part.body["number_of_parts"]["sent"], 1);
+    assert_eq!(part.body["status"], "pending");
+    let response = fail(&mut store, "POST", &complete, None);
+    assert_eq!(response.body["message"], "Expected 2 parts but received 1");
+
+    store.handle(&multipart(&send, b"de", Some(2)));
+    let done = call(&mut store, "POST", &complete, None);
+    assert_eq!(done["status"], "uploaded");
+    assert_eq!(done["content_length"], 5);
+    assert_eq!(done["filename"], "big.bin");
+}
+
+#[test]
+fn test_upload_modes_and_listing() {
+    let mut store = Store::new("http://127.0.0.1:1");
+    let external = call(
+        &mut store,
+        "POST",
+        "/v1/file_uploads",
+        Some(json!({ "mode": "external_url", "external_url": "https://example.com/cat.png" })),
+    );
+    assert_eq!(external["status"], "uploaded");
+    assert_eq!(external["filename"], "cat.png");
+    call(&mut store, "POST", "/v1/file_uploads", Some(json!({})));
+
+    let pending = call(&mut store, "GET", "/v1/file_uploads?status=pending", None);
+    assert_eq!(pending["results"].as_array().unwrap().len(), 1);
+    let all = call(&mut store, "GET", "/v1/file_uploads", None);
+    assert_eq!(all["results"].as_array().unwrap().len(), 2);
+    let id = external["id"].as_str().unwrap();
+    assert_eq!(
+        call(&mut store, "GET", &format!("/v1/file_uploads/{}", id), None)["id"],
+        id
+    );
+
+    fail(&mut store, "GET", "/v1/file_uploads?status=lost", None);
+    fail(
+        &mut store,
+        "POST",
+        "/v1/file_uploads",
+        Some(json!({ "mode": "carrier_pigeon" })),
+    );
+    fail(
+        &mut store,
+        "POST",
+        "/v1/file_uploads",
+        Some(json!({ "mode": "multi_part", "filename": "x" })),
+    );
+}
+
+#[test]
+fn test_timestamp() {
+    assert_eq!(timestamp(0), "2025-01-01T00:00:00.000Z");
+    assert_eq!(timestamp(61), "2025-01-01T00:01:01.000Z");
+    assert_eq!(timestamp(59 * 86400), "2025-03-01T00:00:00.000Z");
+    assert_eq!(timestamp(365 * 86400 + 3600), "2026-01-01T01:00:00.000Z");
+}
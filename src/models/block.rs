@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{Empty, Equation, FileObject, Parent, RichText, User, plain_text};
+
+/// A block. Fields the API fills in are skipped when empty, so blocks built
+/// with [`Block::new`] serialize as valid `children` for append requests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Block {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Parent>,
+    #[serde(flatten)]
+    pub content: BlockContent,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub has_children: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_edited_time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<User>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_edited_by: Option<User>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub in_trash: bool,
+}
+
+impl Block {
+    pub fn new(content: BlockContent) -> Self {
+        Self {
+            id: String::new(),
+            parent: None,
+            content,
+            has_children: false,
+            created_time: None,
+            last_edited_time: None,
+            created_by: None,
+            last_edited_by: None,
+            archived: false,
+            in_trash: false,
+        }
+    }
+
+    /// The block's own rich text (or title), without its children.
+    pub fn text(&self) -> String {
+        match &self.content {
+            BlockContent::Paragraph(b)
+            | BlockContent::BulletedListItem(b)
+            | BlockContent::NumberedListItem(b)
+            | BlockContent::Quote(b)
+            | BlockContent::Toggle(b) => plain_text(&b.rich_text),
+            BlockContent::Heading1(h) | BlockContent::Heading2(h) | BlockContent::Heading3(h) => {
+                plain_text(&h.rich_text)
+            }
+            BlockContent::ToDo(t) => plain_text(&t.rich_text),
+            BlockContent::Callout(c) => plain_text(&c.rich_text),
+            BlockContent::Code(c) => plain_text(&c.rich_text),
+            BlockContent::Equation(e) => e.expression.clone(),
+            BlockContent::ChildPage(c) | BlockContent::ChildDatabase(c) => c.title.clone(),
+            BlockContent::Bookmark(b) | BlockContent::Embed(b) => b.url.clone(),
+            _ => String::new(),
+        }
+    }
+}
+
+tagged_enum! {
+    /// A block's type and its type-specific payload.
+    pub enum BlockContent {
+        Paragraph(TextBlock) = "paragraph",
+        Heading1(Heading) = "heading_1",
+        Heading2(Heading) = "heading_2",
+        Heading3(Heading) = "heading_3",
+        BulletedListItem(TextBlock) = "bulleted_list_item",
+        NumberedListItem(TextBlock) = "numbered_list_item",
+        Quote(TextBlock) = "quote",
+        Toggle(TextBlock) = "toggle",
+        ToDo(ToDo) = "to_do",
+        Callout(Callout) = "callout",
+        Code(Code) = "code",
+        Equation(Equation) = "equation",
+        Divider(Empty) = "divider",
+        Breadcrumb(Empty) = "breadcrumb",
+        TableOfContents(Value) = "table_of_contents",
+        Image(FileObject) = "image",
+        Video(FileObject) = "video",
+        Audio(FileObject) = "audio",
+        File(FileObject) = "file",
+        Pdf(FileObject) = "pdf",
+        Bookmark(Bookmark) = "bookmark",
+        Embed(Bookmark) = "embed",
+        ChildPage(ChildTitle) = "child_page",
+        ChildDatabase(ChildTitle) = "child_database",
+        Table(Table) = "table",
+        TableRow(TableRow) = "table_row",
+        ColumnList(Value) = "column_list",
+        Column(Value) = "column",
+    }
+}
+
+/// Payload of paragraphs, list items, quotes and toggles.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct TextBlock {
+    #[serde(default)]
+    pub rich_text: Vec<RichText>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Nested blocks, in requests only
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Block>,
+}
+
+impl TextBlock {
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self {
+            rich_text: vec![RichText::plain(text)],
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Heading {
+    #[serde(default)]
+    pub rich_text: Vec<RichText>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_toggleable: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Block>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ToDo {
+    #[serde(default)]
+    pub rich_text: Vec<RichText>,
+    #[serde(default)]
+    pub checked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Block>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Callout {
+    #[serde(default)]
+    pub rich_text: Vec<RichText>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Block>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Code {
+    #[serde(default)]
+    pub rich_text: Vec<RichText>,
+    /// One of the languages Notion highlights, e.g. `rust` or `plain text`
+    pub language: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub caption: Vec<RichText>,
+}
+
+/// Payload of bookmarks and embeds.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub caption: Vec<RichText>,
+}
+
+/// Payload of child page and child database blocks.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ChildTitle {
+    pub title: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Table {
+    pub table_width: u32,
+    #[serde(default)]
+    pub has_column_header: bool,
+    #[serde(default)]
+    pub has_row_header: bool,
+    /// Table rows, in requests only
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Block>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct TableRow {
+    /// Each cell's rich text, left to right
+    pub cells: Vec<Vec<RichText>>,
+}
+
+#[cfg(test)]
+#[path = "block_tests.rs"]
+mod tests;
@@ -0,0 +1,96 @@
+use super::*;
+use crate::models::FileSource;
+use serde_json::json;
+
+#[test]
+fn test_deserialize_api_block() {
+    let block: Block = serde_json::from_value(json!({
+        "object": "block",
+        "id": "b1",
+        "parent": { "type": "page_id", "page_id": "p1" },
+        "created_time": "2025-01-01T00:00:00.000Z",
+        "has_children": true,
+        "in_trash": false,
+        "type": "to_do",
+        "to_do": {
+            "rich_text": [{ "type": "text", "text": { "content": "Ship it" }, "plain_text": "Ship it" }],
+            "checked": true,
+            "color": "default",
+        },
+    }))
+    .unwrap();
+    assert_eq!(block.id, "b1");
+    assert!(block.has_children);
+    assert_eq!(block.content.kind(), "to_do");
+    assert!(matches!(&block.content, BlockContent::ToDo(t) if t.checked));
+    assert_eq!(block.text(), "Ship it");
+}
+
+#[test]
+fn test_block_types() {
+    let cases = [
+        (
+            json!({ "type": "heading_2", "heading_2": { "rich_text": [{ "text": { "content": "Intro" } }], "is_toggleable": false } }),
+            "Intro",
+        ),
+        (
+            json!({ "type": "code", "code": { "rich_text": [{ "text": { "content": "fn main() {}" } }], "language": "rust" } }),
+            "fn main() {}",
+        ),
+        (
+            json!({ "type": "child_page", "child_page": { "title": "Notes" } }),
+            "Notes",
+        ),
+        (
+            json!({ "type": "bookmark", "bookmark": { "url": "https://example.com", "caption": [] } }),
+            "https://example.com",
+        ),
+        (json!({ "type": "divider", "divider": {} }), ""),
+    ];
+    for (json, text) in cases {
+        let block: Block = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(block.text(), text, "{}", json);
+    }
+}
+
+#[test]
+fn test_file_blocks() {
+    let block: Block = serde_json::from_value(json!({
+        "type": "image",
+        "image": {
+            "type": "file",
+            "file": { "url": "https://files.example.com/a.png", "expiry_time": "2025-01-01T01:00:00.000Z" },
+            "caption": [],
+        },
+    }))
+    .unwrap();
+    let BlockContent::Image(image) = &block.content else {
+        panic!("expected an image, got {:?}", block.content);
+    };
+    assert!(matches!(&image.source, FileSource::File(f) if f.expiry_time.is_some()));
+}
+
+#[test]
+fn test_unknown_block_kept_as_other() {
+    let json = json!({ "type": "synced_block", "synced_block": { "synced_from": null } });
+    let block: Block = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(block.content.kind(), "synced_block");
+    assert_eq!(serde_json::to_value(&block).unwrap(), json);
+}
+
+#[test]
+fn test_new_block_serializes_as_request_child() {
+    let mut paragraph = TextBlock::plain("Parent");
+    paragraph.children = vec![Block::new(BlockContent::Divider(Empty))];
+    let block = Block::new(BlockContent::Paragraph(paragraph));
+    assert_eq!(
+        serde_json::to_value(&block).unwrap(),
+        json!({
+            "type": "paragraph",
+            "paragraph": {
+                "rich_text": [{ "type": "text", "text": { "content": "Parent" } }],
+                "children": [{ "type": "divider", "divider": {} }],
+            },
+        })
+    );
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Parent, RichText, User, plain_text};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: String,
+    pub parent: Parent,
+    /// Thread the comment belongs to; reply with it to stay in the thread
+    pub discussion_id: String,
+    #[serde(default)]
+    pub rich_text: Vec<RichText>,
+    #[serde(default)]
+    pub created_time: Option<String>,
+    #[serde(default)]
+    pub last_edited_time: Option<String>,
+    #[serde(default)]
+    pub created_by: Option<User>,
+}
+
+impl Comment {
+    pub fn text(&self) -> String {
+        plain_text(&self.rich_text)
+    }
+}
@@ -0,0 +1,187 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{Empty, Parent, RichText, SelectOption, User, plain_text};
+
+/// A database: a container for one or more data sources.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Database {
+    pub id: String,
+    #[serde(default)]
+    pub title: Vec<RichText>,
+    #[serde(default)]
+    pub description: Vec<RichText>,
+    pub parent: Parent,
+    #[serde(default)]
+    pub data_sources: Vec<DataSourceRef>,
+    #[serde(default)]
+    pub is_inline: bool,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub created_time: Option<String>,
+    #[serde(default)]
+    pub last_edited_time: Option<String>,
+    #[serde(default)]
+    pub in_trash: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataSourceRef {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+}
+
+/// A data source and its property schema.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataSource {
+    pub id: String,
+    #[serde(default)]
+    pub title: Vec<RichText>,
+    #[serde(default)]
+    pub description: Vec<RichText>,
+    /// Property schema by name, in schema order
+    #[serde(default)]
+    pub properties: IndexMap<String, PropertySchema>,
+    pub parent: Parent,
+    #[serde(default)]
+    pub database_parent: Option<Parent>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub created_time: Option<String>,
+    #[serde(default)]
+    pub last_edited_time: Option<String>,
+    #[serde(default)]
+    pub created_by: Option<User>,
+    #[serde(default)]
+    pub last_edited_by: Option<User>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub in_trash: bool,
+}
+
+impl DataSource {
+    pub fn title_text(&self) -> String {
+        plain_text(&self.title)
+    }
+
+    /// Schema of a property, looked up by name, then by ID.
+    pub fn property(&self, name_or_id: &str) -> Option<&PropertySchema> {
+        self.properties.get(name_or_id).or_else(|| {
+            self.properties
+                .values()
+                .find(|p| p.id.as_deref() == Some(name_or_id))
+        })
+    }
+}
+
+/// One property of a data source schema.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertySchema {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(flatten)]
+    pub config: PropertyConfig,
+}
+
+tagged_enum! {
+    /// A property's type and its type-specific configuration.
+    pub enum PropertyConfig {
+        Title(Empty) = "title",
+        RichText(Empty) = "rich_text",
+        Number(NumberConfig) = "number",
+        Select(SelectConfig) = "select",
+        MultiSelect(SelectConfig) = "multi_select",
+        Status(StatusConfig) = "status",
+        Date(Empty) = "date",
+        People(Empty) = "people",
+        Files(Empty) = "files",
+        Checkbox(Empty) = "checkbox",
+        Url(Empty) = "url",
+        Email(Empty) = "email",
+        PhoneNumber(Empty) = "phone_number",
+        Formula(FormulaConfig) = "formula",
+        Relation(RelationConfig) = "relation",
+        /// Rollup configuration, kept as JSON
+        Rollup(Value) = "rollup",
+        CreatedTime(Empty) = "created_time",
+        CreatedBy(Empty) = "created_by",
+        LastEditedTime(Empty) = "last_edited_time",
+        LastEditedBy(Empty) = "last_edited_by",
+        /// Prefix configuration, kept as JSON
+        UniqueId(Value) = "unique_id",
+    }
+}
+
+impl PropertyConfig {
+    /// Options of a select, multi-select or status property.
+    pub fn options(&self) -> &[SelectOption] {
+        match self {
+            PropertyConfig::Select(c) | PropertyConfig::MultiSelect(c) => &c.options,
+            PropertyConfig::Status(c) => &c.options,
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct NumberConfig {
+    /// Display format, e.g. `number`, `percent` or `dollar`
+    #[serde(default)]
+    pub format: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SelectConfig {
+    #[serde(default)]
+    pub options: Vec<SelectOption>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct StatusConfig {
+    #[serde(default)]
+    pub options: Vec<SelectOption>,
+    #[serde(default)]
+    pub groups: Vec<StatusGroup>,
+}
+
+/// A status group such as "To-do" and the options in it.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct StatusGroup {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub option_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct FormulaConfig {
+    pub expression: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct RelationConfig {
+    /// The related data source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_source_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database_id: Option<String>,
+    /// `single_property` or `dual_property`
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+}
+
+#[cfg(test)]
+#[path = "data_source_tests.rs"]
+mod tests;
@@ -0,0 +1,104 @@
+use super::*;
+use serde_json::json;
+
+fn sample() -> DataSource {
+    serde_json::from_value(json!({
+        "object": "data_source",
+        "id": "ds1",
+        "title": [{ "type": "text", "text": { "content": "Tasks" }, "plain_text": "Tasks" }],
+        "parent": { "type": "database_id", "database_id": "db1" },
+        "database_parent": { "type": "page_id", "page_id": "p1" },
+        "properties": {
+            "Name": { "id": "title", "name": "Name", "type": "title", "title": {} },
+            "Status": {
+                "id": "s", "name": "Status", "type": "status",
+                "status": {
+                    "options": [{ "id": "o1", "name": "Not started", "color": "default" }, { "id": "o2", "name": "Done", "color": "green" }],
+                    "groups": [{ "id": "g1", "name": "Complete", "color": "green", "option_ids": ["o2"] }],
+                },
+            },
+            "Tags": { "id": "t", "name": "Tags", "type": "multi_select", "multi_select": { "options": [{ "name": "infra" }] } },
+            "Estimate": { "id": "e", "name": "Estimate", "type": "number", "number": { "format": "number" } },
+            "Blocked by": { "id": "r", "name": "Blocked by", "type": "relation", "relation": { "data_source_id": "ds1", "type": "single_property", "single_property": {} } },
+            "Total": { "id": "f", "name": "Total", "type": "formula", "formula": { "expression": "prop(\"Estimate\") * 2" } },
+            "Place": { "id": "l", "name": "Place", "type": "place", "place": {} },
+        },
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_schema_by_name_and_id() {
+    let data_source = sample();
+    assert_eq!(data_source.title_text(), "Tasks");
+    assert_eq!(
+        data_source.database_parent,
+        Some(Parent::PageId("p1".into()))
+    );
+    let status = data_source.property("Status").unwrap();
+    assert_eq!(status.config.kind(), "status");
+    assert_eq!(data_source.property("s"), Some(status));
+    assert!(data_source.property("Nope").is_none());
+}
+
+#[test]
+fn test_property_configs() {
+    let data_source = sample();
+    let options = |name: &str| -> Vec<String> {
+        data_source.properties[name]
+            .config
+            .options()
+            .iter()
+            .map(|o| o.name.clone())
+            .collect()
+    };
+    assert_eq!(options("Status"), ["Not started", "Done"]);
+    assert_eq!(options("Tags"), ["infra"]);
+    assert!(options("Name").is_empty());
+
+    let PropertyConfig::Status(status) = &data_source.properties["Status"].config else {
+        panic!("expected a status");
+    };
+    assert_eq!(status.groups[0].option_ids, ["o2"]);
+    let PropertyConfig::Relation(relation) = &data_source.properties["Blocked by"].config else {
+        panic!("expected a relation");
+    };
+    assert_eq!(relation.data_source_id.as_deref(), Some("ds1"));
+    assert_eq!(relation.kind.as_deref(), Some("single_property"));
+    assert!(matches!(
+        &data_source.properties["Total"].config,
+        PropertyConfig::Formula(f) if f.expression.starts_with("prop")
+    ));
+    assert_eq!(data_source.properties["Place"].config.kind(), "place");
+}
+
+#[test]
+fn test_schema_serializes_as_request_properties() {
+    let schema = PropertySchema {
+        id: None,
+        name: String::new(),
+        description: None,
+        config: PropertyConfig::Select(SelectConfig {
+            options: vec![SelectOption::named("High")],
+        }),
+    };
+    assert_eq!(
+        serde_json::to_value(&schema).unwrap(),
+        json!({ "type": "select", "select": { "options": [{ "name": "High" }] } })
+    );
+}
+
+#[test]
+fn test_database_lists_data_sources() {
+    let database: Database = serde_json::from_value(json!({
+        "object": "database",
+        "id": "db1",
+        "title": [],
+        "parent": { "type": "page_id", "page_id": "p1" },
+        "data_sources": [{ "id": "ds1", "name": "Tasks" }],
+        "is_inline": true,
+    }))
+    .unwrap();
+    assert_eq!(database.data_sources[0].name, "Tasks");
+    assert!(database.is_inline);
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileUpload {
+    pub id: String,
+    pub status: FileUploadStatus,
+    #[serde(default)]
+    pub filename: Option<String>,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub content_length: Option<u64>,
+    /// Where to send the file contents, while the upload is pending
+    #[serde(default)]
+    pub upload_url: Option<String>,
+    /// Where to complete a multi-part upload
+    #[serde(default)]
+    pub complete_url: Option<String>,
+    #[serde(default)]
+    pub expiry_time: Option<String>,
+    #[serde(default)]
+    pub number_of_parts: Option<Parts>,
+    #[serde(default)]
+    pub created_time: Option<String>,
+    #[serde(default)]
+    pub last_edited_time: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileUploadStatus {
+    Pending,
+    Uploaded,
+    Expired,
+    Failed,
+    /// A status added to the API after this crate
+    #[serde(other)]
+    Unknown,
+}
+
+/// Progress of a multi-part upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Parts {
+    pub total: u32,
+    pub sent: u32,
+}
@@ -0,0 +1,172 @@
+//! Serde models for the objects the Notion API returns and accepts.
+//!
+//! Notion tags most variants with a `type` field and nests the payload under
+//! a key of the same name (`{"type": "paragraph", "paragraph": {...}}`).
+//! Those become enums whose variants hold the payload, with an `Other`
+//! variant that keeps types this crate doesn't model as raw JSON, so new API
+//! additions never break deserialization.
+
+use serde::de::{self, Deserializer, IgnoredAny};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Define an enum for a `{"type": tag, tag: payload}` object.
+macro_rules! tagged_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident($payload:ty) = $tag:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant($payload),)*
+            /// A type this crate doesn't model, with its raw payload
+            Other { kind: String, value: serde_json::Value },
+        }
+
+        impl $name {
+            /// The `type` tag, e.g. `"paragraph"`.
+            pub fn kind(&self) -> &str {
+                match self {
+                    $(Self::$variant(_) => $tag,)*
+                    Self::Other { kind, .. } => kind,
+                }
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use serde::ser::Error;
+                let payload = match self {
+                    $(Self::$variant(payload) => {
+                        serde_json::to_value(payload).map_err(S::Error::custom)?
+                    })*
+                    Self::Other { value, .. } => value.clone(),
+                };
+                $crate::models::serialize_tagged(self.kind(), payload, serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                use serde::de::Error;
+                let (kind, value) = $crate::models::deserialize_tagged(deserializer)?;
+                match kind.as_str() {
+                    $($tag => serde_json::from_value(value)
+                        .map(Self::$variant)
+                        .map_err(|e| D::Error::custom(format!("invalid {}: {}", $tag, e))),)*
+                    _ => Ok(Self::Other { kind, value }),
+                }
+            }
+        }
+    };
+}
+
+mod block;
+mod comment;
+mod data_source;
+mod file_upload;
+mod page;
+mod rich_text;
+mod user;
+
+pub use block::{
+    Block, BlockContent, Bookmark, Callout, ChildTitle, Code, Heading, Table, TableRow, TextBlock,
+    ToDo,
+};
+pub use comment::Comment;
+pub use data_source::{
+    DataSource, DataSourceRef, Database, FormulaConfig, NumberConfig, PropertyConfig,
+    PropertySchema, RelationConfig, SelectConfig, StatusConfig, StatusGroup,
+};
+pub use file_upload::{FileUpload, FileUploadStatus, Parts};
+pub use page::{
+    DateRange, ExternalFile, FileObject, FileSource, FileUploadRef, FormulaResult, HostedFile,
+    Page, PropertyContent, PropertyValue, Relation, SelectOption, UniqueId,
+};
+pub use rich_text::{Annotations, Equation, Link, RichText, RichTextContent, Text, plain_text};
+pub use user::{Person, User};
+
+/// One page of a paginated list endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct List<T> {
+    pub results: Vec<T>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+tagged_enum! {
+    /// Where a page, block, data source or comment lives.
+    pub enum Parent {
+        PageId(String) = "page_id",
+        DatabaseId(String) = "database_id",
+        DataSourceId(String) = "data_source_id",
+        BlockId(String) = "block_id",
+        Workspace(bool) = "workspace",
+    }
+}
+
+/// A search hit: search returns pages and data sources.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "object", rename_all = "snake_case")]
+pub enum SearchResult {
+    Page(Page),
+    DataSource(DataSource),
+}
+
+/// A payload that is always `{}`, such as a divider or a checkbox schema.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Empty;
+
+impl Serialize for Empty {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Map::new().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Empty {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        IgnoredAny::deserialize(deserializer).map(|_| Empty)
+    }
+}
+
+#[doc(hidden)]
+pub fn serialize_tagged<S: Serializer>(
+    kind: &str,
+    payload: Value,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut object = Map::new();
+    object.insert("type".to_string(), Value::String(kind.to_string()));
+    object.insert(kind.to_string(), payload);
+    object.serialize(serializer)
+}
+
+/// Split a tagged object into its tag and payload.
+///
+/// Objects written without a `type` (`{"select": {...}}`, as the API
+/// accepts in requests) are tagged by their only key. A missing payload
+/// becomes `null`.
+#[doc(hidden)]
+pub fn deserialize_tagged<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<(String, Value), D::Error> {
+    let mut object = Map::<String, Value>::deserialize(deserializer)?;
+    let kind = match object.shift_remove("type") {
+        Some(Value::String(kind)) => kind,
+        Some(_) => return Err(de::Error::custom("`type` should be a string")),
+        None if object.len() == 1 => object.keys().next().cloned().unwrap_or_default(),
+        None => return Err(de::Error::missing_field("type")),
+    };
+    let value = object.shift_remove(&kind).unwrap_or(Value::Null);
+    Ok((kind, value))
+}
+
+#[cfg(test)]
+#[path = "mod_tests.rs"]
+mod tests;
@@ -0,0 +1,69 @@
+use super::*;
+use serde_json::json;
+
+#[test]
+fn test_parent_round_trips() {
+    let json = json!({ "type": "page_id", "page_id": "p1" });
+    let parent: Parent = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(parent, Parent::PageId("p1".into()));
+    assert_eq!(parent.kind(), "page_id");
+    assert_eq!(serde_json::to_value(&parent).unwrap(), json);
+}
+
+#[test]
+fn test_tag_inferred_from_only_key() {
+    let parent: Parent = serde_json::from_value(json!({ "workspace": true })).unwrap();
+    assert_eq!(parent, Parent::Workspace(true));
+}
+
+#[test]
+fn test_unknown_type_kept_as_other() {
+    let json = json!({ "type": "agent_id", "agent_id": { "id": "a1" } });
+    let parent: Parent = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(
+        parent,
+        Parent::Other {
+            kind: "agent_id".into(),
+            value: json!({ "id": "a1" }),
+        }
+    );
+    assert_eq!(serde_json::to_value(&parent).unwrap(), json);
+}
+
+#[test]
+fn test_tagged_errors() {
+    let missing = serde_json::from_value::<Parent>(json!({ "a": 1, "b": 2 }));
+    assert!(missing.unwrap_err().to_string().contains("type"));
+    let invalid = serde_json::from_value::<Parent>(json!({ "type": "page_id", "page_id": 1 }));
+    assert!(invalid.unwrap_err().to_string().contains("invalid page_id"));
+}
+
+#[test]
+fn test_empty_accepts_anything_and_writes_an_object() {
+    let empty: Empty = serde_json::from_value(json!({ "ignored": [1] })).unwrap();
+    assert_eq!(serde_json::to_value(empty).unwrap(), json!({}));
+}
+
+#[test]
+fn test_list_defaults() {
+    let list: List<User> = serde_json::from_value(json!({ "results": [{ "id": "u1" }] })).unwrap();
+    assert_eq!(list.results[0].id, "u1");
+    assert_eq!(list.next_cursor, None);
+    assert!(!list.has_more);
+}
+
+#[test]
+fn test_search_results_by_object() {
+    let json = json!({
+        "results": [
+            { "object": "page", "id": "p1", "parent": { "type": "workspace", "workspace": true } },
+            { "object": "data_source", "id": "ds1", "parent": { "type": "database_id", "database_id": "db1" } },
+        ],
+        "next_cursor": "c2",
+        "has_more": true,
+    });
+    let list: List<SearchResult> = serde_json::from_value(json).unwrap();
+    assert!(matches!(&list.results[0], SearchResult::Page(p) if p.id == "p1"));
+    assert!(matches!(&list.results[1], SearchResult::DataSource(d) if d.id == "ds1"));
+    assert_eq!(list.next_cursor.as_deref(), Some("c2"));
+}
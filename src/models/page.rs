@@ -0,0 +1,199 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{Parent, RichText, User, plain_text};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page {
+    pub id: String,
+    pub parent: Parent,
+    /// Property values by name, in the order the API returned them
+    #[serde(default)]
+    pub properties: IndexMap<String, PropertyValue>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub public_url: Option<String>,
+    #[serde(default)]
+    pub icon: Option<Value>,
+    #[serde(default)]
+    pub cover: Option<Value>,
+    #[serde(default)]
+    pub created_time: Option<String>,
+    #[serde(default)]
+    pub last_edited_time: Option<String>,
+    #[serde(default)]
+    pub created_by: Option<User>,
+    #[serde(default)]
+    pub last_edited_by: Option<User>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub in_trash: bool,
+}
+
+impl Page {
+    /// Plain text of the title property.
+    pub fn title(&self) -> String {
+        self.properties
+            .values()
+            .find_map(|p| match &p.value {
+                PropertyContent::Title(title) => Some(plain_text(title)),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Value of a property, looked up by name, then by ID.
+    pub fn property(&self, name_or_id: &str) -> Option<&PropertyContent> {
+        self.properties
+            .get(name_or_id)
+            .or_else(|| {
+                self.properties
+                    .values()
+                    .find(|p| p.id.as_deref() == Some(name_or_id))
+            })
+            .map(|p| &p.value)
+    }
+}
+
+/// A page property value with its property ID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyValue {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(flatten)]
+    pub value: PropertyContent,
+}
+
+impl From<PropertyContent> for PropertyValue {
+    fn from(value: PropertyContent) -> Self {
+        Self { id: None, value }
+    }
+}
+
+tagged_enum! {
+    /// A property's type and its value.
+    pub enum PropertyContent {
+        Title(Vec<RichText>) = "title",
+        RichText(Vec<RichText>) = "rich_text",
+        Number(Option<f64>) = "number",
+        Select(Option<SelectOption>) = "select",
+        MultiSelect(Vec<SelectOption>) = "multi_select",
+        Status(Option<SelectOption>) = "status",
+        Date(Option<DateRange>) = "date",
+        People(Vec<User>) = "people",
+        Files(Vec<FileObject>) = "files",
+        Checkbox(bool) = "checkbox",
+        Url(Option<String>) = "url",
+        Email(Option<String>) = "email",
+        PhoneNumber(Option<String>) = "phone_number",
+        Relation(Vec<Relation>) = "relation",
+        Formula(FormulaResult) = "formula",
+        /// Rollup results come in many shapes, kept as JSON
+        Rollup(Value) = "rollup",
+        CreatedTime(String) = "created_time",
+        CreatedBy(User) = "created_by",
+        LastEditedTime(String) = "last_edited_time",
+        LastEditedBy(User) = "last_edited_by",
+        UniqueId(UniqueId) = "unique_id",
+    }
+}
+
+/// A select, multi-select or status option. Requests may name an option
+/// by `name` alone.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SelectOption {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+impl SelectOption {
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// A date or date-time, optionally with an end.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DateRange {
+    pub start: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Relation {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UniqueId {
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub number: Option<u64>,
+}
+
+tagged_enum! {
+    /// The computed value of a formula property.
+    pub enum FormulaResult {
+        String(Option<String>) = "string",
+        Number(Option<f64>) = "number",
+        Boolean(Option<bool>) = "boolean",
+        Date(Option<DateRange>) = "date",
+    }
+}
+
+/// A file in a files property or a file, image, video, audio or PDF block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileObject {
+    #[serde(flatten)]
+    pub source: FileSource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub caption: Vec<RichText>,
+}
+
+tagged_enum! {
+    /// Where a file is hosted.
+    pub enum FileSource {
+        /// Hosted by Notion; `url` is temporary and `expiry_time` says until when
+        File(HostedFile) = "file",
+        External(ExternalFile) = "external",
+        /// Attaches a completed file upload, in requests
+        FileUpload(FileUploadRef) = "file_upload",
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostedFile {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiry_time: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalFile {
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileUploadRef {
+    pub id: String,
+}
+
+#[cfg(test)]
+#[path = "page_tests.rs"]
+mod tests;
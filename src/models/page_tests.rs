@@ -0,0 +1,108 @@
+use super::*;
+use serde_json::json;
+
+fn sample() -> Page {
+    serde_json::from_value(json!({
+        "object": "page",
+        "id": "p1",
+        "parent": { "type": "data_source_id", "data_source_id": "ds1", "database_id": "db1" },
+        "created_by": { "object": "user", "id": "u1" },
+        "properties": {
+            "Name": { "id": "title", "type": "title", "title": [{ "type": "text", "text": { "content": "Launch" }, "plain_text": "Launch" }] },
+            "Score": { "id": "a%3Ab", "type": "number", "number": 3.5 },
+            "Status": { "id": "s", "type": "status", "status": { "id": "o1", "name": "Done", "color": "green" } },
+            "Tags": { "id": "t", "type": "multi_select", "multi_select": [{ "name": "infra" }, { "name": "urgent" }] },
+            "Due": { "id": "d", "type": "date", "date": { "start": "2026-11-01", "end": null, "time_zone": null } },
+            "Owner": { "id": "o", "type": "people", "people": [{ "object": "user", "id": "u2", "name": "Ada", "type": "person", "person": { "email": "ada@example.com" } }] },
+            "Done": { "id": "c", "type": "checkbox", "checkbox": true },
+            "Link": { "id": "l", "type": "url", "url": null },
+            "Blocks": { "id": "r", "type": "relation", "relation": [{ "id": "p2" }], "has_more": false },
+            "Total": { "id": "f", "type": "formula", "formula": { "type": "number", "number": 7 } },
+            "Key": { "id": "k", "type": "unique_id", "unique_id": { "prefix": "ENG", "number": 42 } },
+            "Attachments": { "id": "x", "type": "files", "files": [{ "name": "spec.pdf", "type": "external", "external": { "url": "https://example.com/spec.pdf" } }] },
+            "Vote": { "id": "v", "type": "button", "button": {} },
+        },
+        "url": "https://www.notion.so/p1",
+        "in_trash": false,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_page_property_values() {
+    let page = sample();
+    assert_eq!(page.title(), "Launch");
+    assert_eq!(page.parent, Parent::DataSourceId("ds1".into()));
+    assert_eq!(
+        page.property("Score"),
+        Some(&PropertyContent::Number(Some(3.5)))
+    );
+    assert_eq!(page.property("a%3Ab"), page.property("Score"));
+    let Some(PropertyContent::Status(Some(status))) = page.property("Status") else {
+        panic!("expected a status");
+    };
+    assert_eq!(status.name, "Done");
+    let Some(PropertyContent::MultiSelect(tags)) = page.property("Tags") else {
+        panic!("expected tags");
+    };
+    assert_eq!(tags.len(), 2);
+    let Some(PropertyContent::People(people)) = page.property("Owner") else {
+        panic!("expected people");
+    };
+    assert_eq!(people[0].email(), Some("ada@example.com"));
+    assert_eq!(page.property("Link"), Some(&PropertyContent::Url(None)));
+    assert_eq!(
+        page.property("Total"),
+        Some(&PropertyContent::Formula(FormulaResult::Number(Some(7.0))))
+    );
+    let Some(PropertyContent::Files(files)) = page.property("Attachments") else {
+        panic!("expected files");
+    };
+    assert!(matches!(&files[0].source, FileSource::External(f) if f.url.ends_with("spec.pdf")));
+    assert_eq!(page.property("Vote").unwrap().kind(), "button");
+    assert_eq!(page.property("Missing"), None);
+}
+
+#[test]
+fn test_properties_keep_api_order() {
+    let names: Vec<_> = sample().properties.keys().cloned().collect();
+    assert_eq!(names[..3], ["Name", "Score", "Status"]);
+}
+
+#[test]
+fn test_property_values_serialize_as_request_payloads() {
+    let properties = [
+        (
+            PropertyContent::Select(Some(SelectOption::named("Done"))),
+            json!({ "type": "select", "select": { "name": "Done" } }),
+        ),
+        (
+            PropertyContent::Date(Some(DateRange {
+                start: "2026-11-01".into(),
+                ..DateRange::default()
+            })),
+            json!({ "type": "date", "date": { "start": "2026-11-01" } }),
+        ),
+        (
+            PropertyContent::People(vec![User::with_id("u1")]),
+            json!({ "type": "people", "people": [{ "id": "u1" }] }),
+        ),
+        (
+            PropertyContent::Number(None),
+            json!({ "type": "number", "number": null }),
+        ),
+    ];
+    for (value, expected) in properties {
+        let value = PropertyValue::from(value);
+        assert_eq!(serde_json::to_value(&value).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_property_value_round_trips_with_id() {
+    let json = json!({ "id": "c", "type": "checkbox", "checkbox": true });
+    let value: PropertyValue = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(value.id.as_deref(), Some("c"));
+    assert_eq!(value.value, PropertyContent::Checkbox(true));
+    assert_eq!(serde_json::to_value(&value).unwrap(), json);
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A run of styled text, a mention or an inline equation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichText {
+    #[serde(flatten)]
+    pub content: RichTextContent,
+    #[serde(default, skip_serializing_if = "Annotations::is_plain")]
+    pub annotations: Annotations,
+    /// Filled in by the API; ignored in requests
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub plain_text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub href: Option<String>,
+}
+
+impl RichText {
+    /// Unstyled text, as used in requests.
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self {
+            content: RichTextContent::Text(Text {
+                content: text.into(),
+                link: None,
+            }),
+            annotations: Annotations::default(),
+            plain_text: String::new(),
+            href: None,
+        }
+    }
+
+    /// The text as displayed, falling back to the content for text runs
+    /// built locally.
+    pub fn text(&self) -> &str {
+        match &self.content {
+            RichTextContent::Text(text) if self.plain_text.is_empty() => &text.content,
+            RichTextContent::Equation(equation) if self.plain_text.is_empty() => {
+                &equation.expression
+            }
+            _ => &self.plain_text,
+        }
+    }
+}
+
+tagged_enum! {
+    /// What a rich text run holds.
+    pub enum RichTextContent {
+        Text(Text) = "text",
+        /// Mentions of users, pages, dates and links, kept as JSON
+        Mention(Value) = "mention",
+        Equation(Equation) = "equation",
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Text {
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<Link>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    pub url: String,
+}
+
+/// A KaTeX expression, inline or as a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Equation {
+    pub expression: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Annotations {
+    pub bold: bool,
+    pub italic: bool,
+    pub strikethrough: bool,
+    pub underline: bool,
+    pub code: bool,
+    pub color: String,
+}
+
+impl Annotations {
+    /// No styling at all, so requests can leave annotations out.
+    pub fn is_plain(&self) -> bool {
+        !(self.bold || self.italic || self.strikethrough || self.underline || self.code)
+            && (self.color.is_empty() || self.color == "default")
+    }
+}
+
+/// Concatenate the displayed text of a rich text array.
+pub fn plain_text(rich_text: &[RichText]) -> String {
+    rich_text.iter().map(RichText::text).collect()
+}
+
+#[cfg(test)]
+#[path = "rich_text_tests.rs"]
+mod tests;
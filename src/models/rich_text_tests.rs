@@ -0,0 +1,52 @@
+use super::*;
+use serde_json::json;
+
+#[test]
+fn test_deserialize_api_rich_text() {
+    let json = json!([
+        {
+            "type": "text",
+            "text": { "content": "Docs", "link": { "url": "https://example.com" } },
+            "annotations": { "bold": true, "italic": false, "strikethrough": false, "underline": false, "code": false, "color": "default" },
+            "plain_text": "Docs",
+            "href": "https://example.com",
+        },
+        { "type": "equation", "equation": { "expression": "x^2" }, "plain_text": "x^2" },
+        { "type": "mention", "mention": { "type": "date", "date": { "start": "2025-01-01" } }, "plain_text": "2025-01-01" },
+    ]);
+    let rich_text: Vec<RichText> = serde_json::from_value(json).unwrap();
+    assert!(rich_text[0].annotations.bold);
+    let RichTextContent::Text(text) = &rich_text[0].content else {
+        panic!("expected text, got {:?}", rich_text[0].content);
+    };
+    assert_eq!(text.link.as_ref().unwrap().url, "https://example.com");
+    assert!(matches!(rich_text[2].content, RichTextContent::Mention(_)));
+    assert_eq!(plain_text(&rich_text), "Docsx^22025-01-01");
+}
+
+#[test]
+fn test_plain_serializes_without_read_only_fields() {
+    let text = RichText::plain("Hello");
+    assert_eq!(text.text(), "Hello");
+    assert_eq!(
+        serde_json::to_value(&text).unwrap(),
+        json!({ "type": "text", "text": { "content": "Hello" } })
+    );
+}
+
+#[test]
+fn test_text_falls_back_to_content() {
+    let text: RichText = serde_json::from_value(json!({ "text": { "content": "Hi" } })).unwrap();
+    assert_eq!(text.plain_text, "");
+    assert_eq!(text.text(), "Hi");
+}
+
+#[test]
+fn test_annotations_is_plain() {
+    assert!(Annotations::default().is_plain());
+    let colored = Annotations {
+        color: "red".into(),
+        ..Annotations::default()
+    };
+    assert!(!colored.is_plain());
+}
@@ -1,6 +1,10 @@
 use anyhow::Result;
 use colored::Colorize;
 use serde_json::Value;
+use std::io::Write;
+
+use crate::csv::{Dialect, format_record};
+use crate::{properties, render};
 
 #[derive(Clone, Debug, Default)]
 pub enum OutputFormat {
@@ -8,6 +12,32 @@ pub enum OutputFormat {
     Pretty,
     Json,
     Raw,
+    /// One compact JSON object per line for each list item
+    Ndjson,
+    /// Comma-separated rows with page properties flattened into columns
+    Csv,
+    /// Tab-separated rows with page properties flattened into columns
+    Tsv,
+}
+
+impl OutputFormat {
+    /// Whether list items are written as each page arrives rather than as a
+    /// single document once every page has been fetched.
+    pub fn streams_items(&self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Ndjson | OutputFormat::Csv | OutputFormat::Tsv
+        )
+    }
+
+    /// Delimited dialect for spreadsheet formats.
+    pub fn dialect(&self) -> Option<Dialect> {
+        match self {
+            OutputFormat::Csv => Some(Dialect::Csv),
+            OutputFormat::Tsv => Some(Dialect::Tsv),
+            _ => None,
+        }
+    }
 }
 
 impl std::str::FromStr for OutputFormat {
@@ -18,6 +48,9 @@ impl std::str::FromStr for OutputFormat {
             "pretty" => Ok(OutputFormat::Pretty),
             "json" => Ok(OutputFormat::Json),
             "raw" => Ok(OutputFormat::Raw),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            "csv" => Ok(OutputFormat::Csv),
+            "tsv" => Ok(OutputFormat::Tsv),
             _ => Err(format!("Unknown output format: {}", s)),
         }
     }
@@ -28,13 +61,164 @@ pub fn print_result(value: &Value, format: &OutputFormat) -> Result<()> {
         OutputFormat::Raw => {
             println!("{}", serde_json::to_string(value)?);
         }
-        OutputFormat::Json | OutputFormat::Pretty => {
+        OutputFormat::Pretty => match render::render(value) {
+            Some(text) => print!("{}", text),
+            None => println!("{}", serde_json::to_string_pretty(value)?),
+        },
+        OutputFormat::Json => {
             println!("{}", serde_json::to_string_pretty(value)?);
         }
+        OutputFormat::Ndjson => {
+            write_ndjson(&mut std::io::stdout().lock(), value)?;
+        }
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let items = match value.get("results").and_then(Value::as_array) {
+                Some(items) => items.as_slice(),
+                None => std::slice::from_ref(value),
+            };
+            ListPrinter::new(format).print_items(items)?;
+        }
     }
     Ok(())
 }
 
+/// Prints list results page by page.
+///
+/// Streaming formats write each page as soon as it arrives; the others wait
+/// for `finish` and print the merged envelope. For CSV/TSV the columns are
+/// either given up front (a data source schema) or taken from the first item.
+pub struct ListPrinter<'a> {
+    format: &'a OutputFormat,
+    columns: Option<Vec<String>>,
+    header_written: bool,
+    streamed: bool,
+}
+
+impl<'a> ListPrinter<'a> {
+    pub fn new(format: &'a OutputFormat) -> Self {
+        Self {
+            format,
+            columns: None,
+            header_written: false,
+            streamed: false,
+        }
+    }
+
+    /// Use a fixed column order for CSV/TSV output.
+    pub fn with_columns(mut self, columns: Vec<String>) -> Self {
+        self.columns = Some(columns);
+        self
+    }
+
+    /// Whether pages are printed as they arrive rather than by `finish`.
+    pub fn streams(&self) -> bool {
+        self.format.streams_items()
+    }
+
+    /// Print one page of items (streaming formats only).
+    pub fn print_items(&mut self, items: &[Value]) -> Result<()> {
+        if !self.format.streams_items() {
+            return Ok(());
+        }
+        self.streamed = true;
+        self.write_items(&mut std::io::stdout().lock(), items)
+    }
+
+    /// Print the merged result unless it has already been streamed.
+    pub fn finish(self, result: &Value) -> Result<()> {
+        if self.streamed {
+            return Ok(());
+        }
+        print_result(result, self.format)
+    }
+
+    fn write_items(&mut self, out: &mut impl Write, items: &[Value]) -> Result<()> {
+        match self.format.dialect() {
+            Some(dialect) => self.write_rows(out, items, dialect),
+            None => write_ndjson_items(out, items),
+        }
+    }
+
+    fn write_rows(
+        &mut self,
+        out: &mut impl Write,
+        items: &[Value],
+        dialect: Dialect,
+    ) -> Result<()> {
+        if self.columns.is_none()
+            && let Some(first) = items.first()
+        {
+            self.columns = Some(columns_for(first));
+        }
+        let Some(columns) = &self.columns else {
+            return Ok(());
+        };
+
+        if !self.header_written {
+            out.write_all(format_record(columns, dialect).as_bytes())?;
+            self.header_written = true;
+        }
+        for item in items {
+            let row: Vec<String> = columns.iter().map(|c| cell_text(item, c)).collect();
+            out.write_all(format_record(&row, dialect).as_bytes())?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// Columns for an item: `id` plus every property for pages, top-level keys otherwise.
+fn columns_for(item: &Value) -> Vec<String> {
+    match item["properties"].as_object() {
+        Some(props) if item["object"] == "page" => std::iter::once("id".to_string())
+            .chain(props.keys().cloned())
+            .collect(),
+        _ => item
+            .as_object()
+            .map(|o| o.keys().cloned().collect())
+            .unwrap_or_default(),
+    }
+}
+
+fn cell_text(item: &Value, column: &str) -> String {
+    if item["object"] == "page" && column != "id" {
+        return item["properties"]
+            .get(column)
+            .map(properties::to_text)
+            .unwrap_or_default();
+    }
+    match &item[column] {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Write each element of `results`, or the bare object for single resources.
+fn write_ndjson(out: &mut impl Write, value: &Value) -> Result<()> {
+    match value.get("results").and_then(Value::as_array) {
+        Some(items) => write_ndjson_items(out, items),
+        None => {
+            writeln!(out, "{}", serde_json::to_string(value)?)?;
+            out.flush()?;
+            Ok(())
+        }
+    }
+}
+
+fn write_ndjson_items(out: &mut impl Write, items: &[Value]) -> Result<()> {
+    for item in items {
+        writeln!(out, "{}", serde_json::to_string(item)?)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Print a rendered document (e.g. Markdown) as-is.
+pub fn print_document(text: &str) {
+    print!("{}", text);
+}
+
 pub fn print_success(msg: &str) {
     println!("{} {}", "✓".green().bold(), msg);
 }
@@ -43,6 +227,10 @@ pub fn print_error(msg: &str) {
     eprintln!("{} {}", "✗".red().bold(), msg);
 }
 
+pub fn print_warning(msg: &str) {
+    eprintln!("{} {}", "!".yellow().bold(), msg);
+}
+
 pub fn print_info(msg: &str) {
     println!("{} {}", "→".blue().bold(), msg);
 }
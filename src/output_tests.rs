@@ -106,3 +106,154 @@ fn test_print_success_does_not_panic() {
 fn test_print_info_does_not_panic() {
     print_info("test info message");
 }
+
+#[test]
+fn test_output_format_parse_ndjson() {
+    let format: OutputFormat = "ndjson".parse().unwrap();
+    assert!(matches!(format, OutputFormat::Ndjson));
+    assert!(format.streams_items());
+    assert!(!OutputFormat::Raw.streams_items());
+}
+
+#[test]
+fn test_write_ndjson_one_line_per_result() {
+    let value = json!({
+        "object": "list",
+        "results": [{"id": "a", "nested": {"x": 1}}, {"id": "b"}],
+        "has_more": false
+    });
+    let mut out = Vec::new();
+    write_ndjson(&mut out, &value).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(
+        text,
+        "{\"id\":\"a\",\"nested\":{\"x\":1}}\n{\"id\":\"b\"}\n"
+    );
+}
+
+#[test]
+fn test_write_ndjson_single_resource() {
+    let value = json!({"object": "page", "id": "p1"});
+    let mut out = Vec::new();
+    write_ndjson(&mut out, &value).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(text.lines().count(), 1);
+    assert_eq!(
+        serde_json::from_str::<Value>(text.trim()).unwrap()["id"],
+        "p1"
+    );
+}
+
+#[test]
+fn test_write_ndjson_empty_results() {
+    let value = json!({"results": [], "has_more": false});
+    let mut out = Vec::new();
+    write_ndjson(&mut out, &value).unwrap();
+    assert!(out.is_empty());
+}
+
+#[test]
+fn test_print_result_ndjson_format() {
+    let value = json!({"results": [{"id": "a"}]});
+    let result = print_result(&value, &OutputFormat::Ndjson);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_list_printer_ignores_items_for_non_streaming_formats() {
+    let items = vec![json!({"id": "a"})];
+    let mut printer = ListPrinter::new(&OutputFormat::Json);
+    assert!(printer.print_items(&items).is_ok());
+    assert!(!printer.streamed);
+    assert!(printer.finish(&json!({"results": items})).is_ok());
+}
+
+#[test]
+fn test_output_format_parse_csv_tsv() {
+    let format: OutputFormat = "csv".parse().unwrap();
+    assert!(matches!(format, OutputFormat::Csv));
+    assert_eq!(format.dialect(), Some(Dialect::Csv));
+    let format: OutputFormat = "TSV".parse().unwrap();
+    assert!(matches!(format, OutputFormat::Tsv));
+    assert!(format.streams_items());
+    assert_eq!(OutputFormat::Json.dialect(), None);
+}
+
+fn page(id: &str, name: &str, status: &str) -> Value {
+    json!({
+        "object": "page",
+        "id": id,
+        "properties": {
+            "Name": { "type": "title", "title": [{ "plain_text": name }] },
+            "Status": { "type": "status", "status": { "name": status } }
+        }
+    })
+}
+
+#[test]
+fn test_list_printer_csv_flattens_page_properties() {
+    let format = OutputFormat::Csv;
+    let mut printer = ListPrinter::new(&format);
+    let mut out = Vec::new();
+    printer
+        .write_items(&mut out, &[page("p1", "Write, docs", "Done")])
+        .unwrap();
+    printer
+        .write_items(&mut out, &[page("p2", "Ship", "In progress")])
+        .unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(
+        text,
+        "id,Name,Status\np1,\"Write, docs\",Done\np2,Ship,In progress\n"
+    );
+}
+
+#[test]
+fn test_list_printer_uses_given_columns() {
+    let format = OutputFormat::Tsv;
+    let mut printer = ListPrinter::new(&format).with_columns(vec![
+        "Status".into(),
+        "Missing".into(),
+        "id".into(),
+    ]);
+    let mut out = Vec::new();
+    printer
+        .write_items(&mut out, &[page("p1", "A", "Done")])
+        .unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(text, "Status\tMissing\tid\nDone\t\tp1\n");
+}
+
+#[test]
+fn test_list_printer_header_without_rows() {
+    let format = OutputFormat::Csv;
+    let mut printer = ListPrinter::new(&format).with_columns(vec!["id".into(), "Name".into()]);
+    let mut out = Vec::new();
+    printer.write_items(&mut out, &[]).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "id,Name\n");
+}
+
+#[test]
+fn test_list_printer_csv_non_page_objects() {
+    let format = OutputFormat::Csv;
+    let mut printer = ListPrinter::new(&format);
+    let mut out = Vec::new();
+    printer
+        .write_items(
+            &mut out,
+            &[json!({"object": "user", "id": "u1", "person": {"email": "a@b.c"}})],
+        )
+        .unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(
+        text,
+        "object,id,person\nuser,u1,\"{\"\"email\"\":\"\"a@b.c\"\"}\"\n"
+    );
+}
+
+#[test]
+fn test_print_result_csv_format() {
+    let value = json!({"results": [page("p1", "A", "Done")]});
+    assert!(print_result(&value, &OutputFormat::Csv).is_ok());
+    assert!(print_result(&page("p1", "A", "Done"), &OutputFormat::Tsv).is_ok());
+}
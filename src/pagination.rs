@@ -0,0 +1,219 @@
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use crate::client::NotionClient;
+use crate::output::{ListPrinter, OutputFormat};
+
+/// Largest `page_size` accepted by the Notion API.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Pagination options shared by every list endpoint.
+#[derive(Clone, Debug, Default)]
+pub struct Pagination {
+    /// Number of items requested per page
+    pub page_size: Option<u32>,
+    /// Cursor to start from
+    pub start_cursor: Option<String>,
+    /// Follow `next_cursor` until `has_more` is false
+    pub all: bool,
+    /// Stop after this many items in total (follows cursors as needed)
+    pub limit: Option<usize>,
+}
+
+impl Pagination {
+    fn follows_cursor(&self) -> bool {
+        self.all || self.limit.is_some()
+    }
+
+    fn remaining(&self, fetched: usize) -> Option<usize> {
+        self.limit.map(|limit| limit.saturating_sub(fetched))
+    }
+
+    /// Page size to request once `fetched` items have already been collected.
+    ///
+    /// When following cursors without an explicit size we ask for the maximum
+    /// to keep the number of round-trips down, and a `--limit` shrinks the last
+    /// request so the returned `next_cursor` stays accurate.
+    fn request_size(&self, fetched: usize) -> Option<u32> {
+        let size = match (self.page_size, self.follows_cursor()) {
+            (Some(ps), _) => Some(ps),
+            (None, true) => Some(MAX_PAGE_SIZE),
+            (None, false) => None,
+        };
+
+        match (size, self.remaining(fetched)) {
+            (Some(size), Some(remaining)) => {
+                let remaining = u32::try_from(remaining).unwrap_or(MAX_PAGE_SIZE);
+                Some(size.min(remaining).min(MAX_PAGE_SIZE))
+            }
+            (size, _) => size,
+        }
+    }
+}
+
+/// A paginated list request: the cursor and page size are added per page.
+pub enum ListRequest {
+    /// Pagination passed as query parameters
+    Get {
+        path: String,
+        query: Vec<(String, String)>,
+    },
+    /// Pagination passed in the JSON body
+    Post { path: String, body: Value },
+}
+
+impl ListRequest {
+    pub fn get(path: impl Into<String>) -> Self {
+        ListRequest::Get {
+            path: path.into(),
+            query: Vec::new(),
+        }
+    }
+
+    pub fn post(path: impl Into<String>, body: Value) -> Self {
+        ListRequest::Post {
+            path: path.into(),
+            body,
+        }
+    }
+
+    /// Add a query parameter (GET requests only).
+    pub fn query(mut self, key: &str, value: &str) -> Self {
+        if let ListRequest::Get { ref mut query, .. } = self {
+            query.push((key.to_string(), value.to_string()));
+        }
+        self
+    }
+
+    async fn send(
+        &self,
+        client: &NotionClient,
+        page_size: Option<u32>,
+        cursor: Option<&str>,
+    ) -> Result<Value> {
+        match self {
+            ListRequest::Get { path, query } => {
+                let ps_str = page_size.map(|ps| ps.to_string());
+                let mut params: Vec<(&str, &str)> = query
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), v.as_str()))
+                    .collect();
+                if let Some(ref ps) = ps_str {
+                    params.push(("page_size", ps));
+                }
+                if let Some(c) = cursor {
+                    params.push(("start_cursor", c));
+                }
+                client.get(path, &params).await
+            }
+            ListRequest::Post { path, body } => {
+                let mut body = body.clone();
+                if let Some(ps) = page_size {
+                    body["page_size"] = json!(ps);
+                }
+                if let Some(c) = cursor {
+                    body["start_cursor"] = json!(c);
+                }
+                client.post(path, Some(&body)).await
+            }
+        }
+    }
+}
+
+/// Fetch a list endpoint, following `next_cursor` when requested.
+///
+/// `on_page` is called with the items of each page as soon as it arrives.
+/// The returned value is the last response envelope with `results` replaced
+/// by every collected item. Responses without a `results` array (a single
+/// property item, a dry-run preview) are returned untouched.
+pub async fn paginate<F>(
+    client: &NotionClient,
+    request: &ListRequest,
+    pagination: &Pagination,
+    on_page: F,
+) -> Result<Value>
+where
+    F: FnMut(&[Value]) -> Result<()>,
+{
+    fetch_pages(client, request, pagination, true, on_page).await
+}
+
+/// [`paginate`], optionally dropping each page once `on_page` has seen it.
+///
+/// Without `keep` the returned envelope has an empty `results` array, so
+/// streamed listings use memory for one page at a time.
+async fn fetch_pages<F>(
+    client: &NotionClient,
+    request: &ListRequest,
+    pagination: &Pagination,
+    keep: bool,
+    mut on_page: F,
+) -> Result<Value>
+where
+    F: FnMut(&[Value]) -> Result<()>,
+{
+    let mut cursor = pagination.start_cursor.clone();
+    let mut collected: Vec<Value> = Vec::new();
+    let mut fetched = 0;
+
+    loop {
+        let page_size = pagination.request_size(fetched);
+        let mut response = request.send(client, page_size, cursor.as_deref()).await?;
+
+        let Some(Value::Array(mut items)) = response.get_mut("results").map(Value::take) else {
+            return Ok(response);
+        };
+
+        if let Some(remaining) = pagination.remaining(fetched) {
+            items.truncate(remaining);
+        }
+        on_page(&items)?;
+        fetched += items.len();
+        if keep {
+            collected.extend(items);
+        }
+
+        let next_cursor = response["next_cursor"].as_str().map(String::from);
+        let has_more = response["has_more"].as_bool().unwrap_or(false);
+        let limit_reached = pagination.remaining(fetched) == Some(0);
+
+        if !pagination.follows_cursor() || !has_more || next_cursor.is_none() || limit_reached {
+            response["results"] = Value::Array(collected);
+            return Ok(response);
+        }
+
+        cursor = next_cursor;
+    }
+}
+
+/// Fetch a list endpoint and print the result.
+///
+/// Streaming formats print each page as soon as it arrives; the others print
+/// the merged envelope once the last page has been fetched.
+pub async fn print_paginated(
+    client: &NotionClient,
+    request: &ListRequest,
+    pagination: &Pagination,
+    format: &OutputFormat,
+) -> Result<()> {
+    print_paginated_with(client, request, pagination, ListPrinter::new(format)).await
+}
+
+/// Like [`print_paginated`], with a preconfigured printer (e.g. CSV columns).
+pub async fn print_paginated_with(
+    client: &NotionClient,
+    request: &ListRequest,
+    pagination: &Pagination,
+    mut printer: ListPrinter<'_>,
+) -> Result<()> {
+    let keep = !printer.streams();
+    let result = fetch_pages(client, request, pagination, keep, |items| {
+        printer.print_items(items)
+    })
+    .await?;
+    printer.finish(&result)
+}
+
+#[cfg(test)]
+#[path = "pagination_tests.rs"]
+mod tests;
@@ -0,0 +1,347 @@
+use super::*;
+
+fn all() -> Pagination {
+    Pagination {
+        all: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_request_size_defaults_to_none() {
+    let pagination = Pagination::default();
+    assert_eq!(pagination.request_size(0), None);
+}
+
+#[test]
+fn test_request_size_uses_explicit_page_size() {
+    let pagination = Pagination {
+        page_size: Some(25),
+        ..Default::default()
+    };
+    assert_eq!(pagination.request_size(0), Some(25));
+}
+
+#[test]
+fn test_request_size_uses_max_when_following_cursors() {
+    assert_eq!(all().request_size(0), Some(MAX_PAGE_SIZE));
+}
+
+#[test]
+fn test_request_size_shrinks_to_remaining_limit() {
+    let pagination = Pagination {
+        limit: Some(130),
+        ..Default::default()
+    };
+    assert_eq!(pagination.request_size(0), Some(100));
+    assert_eq!(pagination.request_size(100), Some(30));
+}
+
+#[test]
+fn test_request_size_limit_and_page_size() {
+    let pagination = Pagination {
+        page_size: Some(10),
+        limit: Some(15),
+        ..Default::default()
+    };
+    assert_eq!(pagination.request_size(0), Some(10));
+    assert_eq!(pagination.request_size(10), Some(5));
+}
+
+#[test]
+fn test_list_request_query_ignored_for_post() {
+    let request = ListRequest::post("/v1/search", json!({})).query("a", "b");
+    assert!(matches!(request, ListRequest::Post { .. }));
+}
+
+#[tokio::test]
+async fn test_paginate_single_page_without_all() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/v1/users")
+        .match_query(mockito::Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"object":"list","results":[{"id":"u1"}],"has_more":true,"next_cursor":"c2"}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let request = ListRequest::get("/v1/users");
+    let result = paginate(&client, &request, &Pagination::default(), |_| Ok(()))
+        .await
+        .unwrap();
+
+    assert_eq!(result["results"].as_array().unwrap().len(), 1);
+    assert_eq!(result["has_more"], true);
+    assert_eq!(result["next_cursor"], "c2");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_paginate_all_follows_cursor_on_get() {
+    let mut server = mockito::Server::new_async().await;
+    let first = server
+        .mock("GET", "/v1/blocks/b1/children")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "page_size".into(),
+            "100".into(),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"object":"list","results":[{"id":"a"},{"id":"b"}],"has_more":true,"next_cursor":"c2"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let second = server
+        .mock("GET", "/v1/blocks/b1/children")
+        .match_query(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::UrlEncoded("page_size".into(), "100".into()),
+            mockito::Matcher::UrlEncoded("start_cursor".into(), "c2".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"object":"list","results":[{"id":"c"}],"has_more":false,"next_cursor":null}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let request = ListRequest::get("/v1/blocks/b1/children");
+    let mut pages = Vec::new();
+    let result = paginate(&client, &request, &all(), |items| {
+        pages.push(items.len());
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    let ids: Vec<&str> = result["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, ["a", "b", "c"]);
+    assert_eq!(pages, [2, 1]);
+    assert_eq!(result["has_more"], false);
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_paginate_all_follows_cursor_on_post() {
+    let mut server = mockito::Server::new_async().await;
+    let first = server
+        .mock("POST", "/v1/search")
+        .match_body(mockito::Matcher::Json(json!({
+            "query": "x",
+            "page_size": 100
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[{"id":"a"}],"has_more":true,"next_cursor":"c2"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let second = server
+        .mock("POST", "/v1/search")
+        .match_body(mockito::Matcher::Json(json!({
+            "query": "x",
+            "page_size": 100,
+            "start_cursor": "c2"
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[{"id":"b"}],"has_more":false,"next_cursor":null}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let request = ListRequest::post("/v1/search", json!({ "query": "x" }));
+    let result = paginate(&client, &request, &all(), |_| Ok(()))
+        .await
+        .unwrap();
+
+    assert_eq!(result["results"].as_array().unwrap().len(), 2);
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_paginate_stops_at_limit() {
+    let mut server = mockito::Server::new_async().await;
+    let first = server
+        .mock("GET", "/v1/users")
+        .match_query(mockito::Matcher::UrlEncoded("page_size".into(), "2".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[{"id":"a"},{"id":"b"}],"has_more":true,"next_cursor":"c2"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let second = server
+        .mock("GET", "/v1/users")
+        .match_query(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::UrlEncoded("page_size".into(), "1".into()),
+            mockito::Matcher::UrlEncoded("start_cursor".into(), "c2".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[{"id":"c"}],"has_more":true,"next_cursor":"c3"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let request = ListRequest::get("/v1/users");
+    let pagination = Pagination {
+        page_size: Some(2),
+        limit: Some(3),
+        ..Default::default()
+    };
+    let result = paginate(&client, &request, &pagination, |_| Ok(()))
+        .await
+        .unwrap();
+
+    assert_eq!(result["results"].as_array().unwrap().len(), 3);
+    assert_eq!(result["has_more"], true);
+    assert_eq!(result["next_cursor"], "c3");
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_fetch_pages_without_keep_streams_and_counts() {
+    let mut server = mockito::Server::new_async().await;
+    let first = server
+        .mock("GET", "/v1/users")
+        .match_query(mockito::Matcher::UrlEncoded("page_size".into(), "2".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[{"id":"a"},{"id":"b"}],"has_more":true,"next_cursor":"c2"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let second = server
+        .mock("GET", "/v1/users")
+        .match_query(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::UrlEncoded("page_size".into(), "1".into()),
+            mockito::Matcher::UrlEncoded("start_cursor".into(), "c2".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[{"id":"c"},{"id":"d"}],"has_more":true,"next_cursor":"c3"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let request = ListRequest::get("/v1/users");
+    let pagination = Pagination {
+        page_size: Some(2),
+        limit: Some(3),
+        ..Default::default()
+    };
+    let mut seen = Vec::new();
+    let result = fetch_pages(&client, &request, &pagination, false, |items| {
+        seen.extend(items.iter().map(|i| i["id"].as_str().unwrap().to_string()));
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(seen, ["a", "b", "c"]);
+    assert_eq!(result["results"], json!([]));
+    assert_eq!(result["next_cursor"], "c3");
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_paginate_returns_non_list_response_untouched() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/v1/pages/p1/properties/title")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"object":"property_item","type":"number","number":3}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let request = ListRequest::get("/v1/pages/p1/properties/title");
+    let mut called = false;
+    let result = paginate(&client, &request, &all(), |_| {
+        called = true;
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(result["number"], 3);
+    assert!(!called);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_paginate_propagates_callback_error() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/v1/users")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[{"id":"a"}],"has_more":false}"#)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let request = ListRequest::get("/v1/users");
+    let result = paginate(&client, &request, &all(), |_| anyhow::bail!("boom")).await;
+
+    assert_eq!(result.unwrap_err().to_string(), "boom");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_print_paginated_ndjson_streams_every_page() {
+    let mut server = mockito::Server::new_async().await;
+    let first = server
+        .mock("POST", "/v1/data_sources/ds-1/query")
+        .match_body(mockito::Matcher::Json(json!({ "page_size": 100 })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[{"id":"a"}],"has_more":true,"next_cursor":"c2"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let second = server
+        .mock("POST", "/v1/data_sources/ds-1/query")
+        .match_body(mockito::Matcher::Json(json!({
+            "page_size": 100,
+            "start_cursor": "c2"
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":[{"id":"b"}],"has_more":false,"next_cursor":null}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = NotionClient::with_base_url("token", &server.url()).unwrap();
+    let request = ListRequest::post("/v1/data_sources/ds-1/query", json!({}));
+    let result = print_paginated(&client, &request, &all(), &OutputFormat::Ndjson).await;
+
+    assert!(result.is_ok());
+    first.assert_async().await;
+    second.assert_async().await;
+}
@@ -0,0 +1,464 @@
+use anyhow::{Result, bail};
+use indexmap::IndexMap;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+use crate::error::bail_input;
+use crate::markdown::MAX_TEXT_LENGTH;
+use crate::models::{PropertyConfig, PropertyContent, PropertySchema};
+
+/// Concatenate the `plain_text` of a rich text array.
+pub fn plain_text(rich_text: &Value) -> String {
+    rich_text
+        .as_array()
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|p| p["plain_text"].as_str().or(p["text"]["content"].as_str()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Title of a page, database or data source.
+pub fn title(object: &Value) -> String {
+    if let Some(title) = object.get("title") {
+        return plain_text(title);
+    }
+
+    object["properties"]
+        .as_object()
+        .and_then(|props| props.values().find(|p| p["type"] == "title"))
+        .map(|p| plain_text(&p["title"]))
+        .unwrap_or_default()
+}
+
+/// Flatten a page property value into plain text.
+///
+/// Multi-valued properties are joined with `", "`, dates are rendered as ISO
+/// `start` or `start/end`, and people fall back to their ID when the name is
+/// not shared with the integration.
+pub fn to_text(property: &Value) -> String {
+    let kind = property["type"].as_str().unwrap_or_default();
+    typed_value_text(kind, &property[kind])
+}
+
+fn typed_value_text(kind: &str, value: &Value) -> String {
+    match kind {
+        "title" | "rich_text" => plain_text(value),
+        "number" => scalar_text(value),
+        "select" | "status" => value["name"].as_str().unwrap_or_default().to_string(),
+        "multi_select" => join(value, |o| o["name"].as_str().map(String::from)),
+        "date" => date_text(value),
+        "people" => join(value, |u| Some(user_text(u))),
+        "relation" => join(value, |r| r["id"].as_str().map(String::from)),
+        "files" => join(value, |f| {
+            f["name"]
+                .as_str()
+                .or(f["file"]["url"].as_str())
+                .or(f["external"]["url"].as_str())
+                .map(String::from)
+        }),
+        "checkbox" | "url" | "email" | "phone_number" | "created_time" | "last_edited_time" => {
+            scalar_text(value)
+        }
+        "created_by" | "last_edited_by" => user_text(value),
+        "formula" => {
+            let inner = value["type"].as_str().unwrap_or_default();
+            typed_value_text(inner, &value[inner])
+        }
+        "rollup" => match value["type"].as_str().unwrap_or_default() {
+            "array" => join(&value["array"], |item| Some(to_text(item))),
+            inner => typed_value_text(inner, &value[inner]),
+        },
+        "unique_id" => match (value["prefix"].as_str(), value["number"].as_i64()) {
+            (Some(prefix), Some(n)) => format!("{}-{}", prefix, n),
+            (None, Some(n)) => n.to_string(),
+            _ => String::new(),
+        },
+        "verification" => value["state"].as_str().unwrap_or_default().to_string(),
+        _ => scalar_text(value),
+    }
+}
+
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn date_text(date: &Value) -> String {
+    match (date["start"].as_str(), date["end"].as_str()) {
+        (Some(start), Some(end)) => format!("{}/{}", start, end),
+        (Some(start), None) => start.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// A user's name, or their ID when the name is not available.
+pub fn user_text(user: &Value) -> String {
+    user["name"]
+        .as_str()
+        .or(user["id"].as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn join(values: &Value, f: impl Fn(&Value) -> Option<String>) -> String {
+    values
+        .as_array()
+        .map(|items| items.iter().filter_map(&f).collect::<Vec<_>>().join(", "))
+        .unwrap_or_default()
+}
+
+/// Build a page property payload from text, guided by the property schema.
+///
+/// This is the inverse of [`to_text`]: multi-valued cells are comma
+/// separated, dates are `start` or `start/end`, and people are given by email
+/// (looked up in `people`, lowercase email to user ID) or by user ID. An empty
+/// string clears the property.
+pub fn from_text(schema: &Value, text: &str, people: &HashMap<String, String>) -> Result<Value> {
+    let kind = schema["type"].as_str().unwrap_or_default();
+    let text = text.trim();
+    let value = match kind {
+        "title" | "rich_text" => {
+            // Long cells become several text objects, each within the API limit
+            let chars: Vec<char> = text.chars().collect();
+            json!(
+                chars
+                    .chunks(MAX_TEXT_LENGTH)
+                    .map(|chunk| {
+                        let content: String = chunk.iter().collect();
+                        json!({ "type": "text", "text": { "content": content } })
+                    })
+                    .collect::<Vec<_>>()
+            )
+        }
+        "number" => match text {
+            "" => Value::Null,
+            _ => parse_number(text)?,
+        },
+        "select" => match text {
+            "" => Value::Null,
+            _ => json!({ "name": text }),
+        },
+        "status" => match text {
+            "" => Value::Null,
+            _ => json!({ "name": known_option(schema, text)? }),
+        },
+        "multi_select" => json!(
+            split_list(text)
+                .map(|name| json!({ "name": name }))
+                .collect::<Vec<_>>()
+        ),
+        "date" => match text {
+            "" => Value::Null,
+            _ => parse_date(text)?,
+        },
+        "checkbox" => json!(parse_checkbox(text)?),
+        "url" | "phone_number" => match text {
+            "" => Value::Null,
+            _ => json!(text),
+        },
+        "email" => match text {
+            "" => Value::Null,
+            _ if !text.contains('@') => bail!("'{}' is not an email address", text),
+            _ => json!(text),
+        },
+        "people" => json!(
+            split_list(text)
+                .map(|who| resolve_person(who, people).map(|id| json!({ "id": id })))
+                .collect::<Result<Vec<_>>>()?
+        ),
+        "relation" => json!(
+            split_list(text)
+                .map(|id| json!({ "id": id }))
+                .collect::<Vec<_>>()
+        ),
+        "files" => json!(
+            split_list(text)
+                .map(|url| json!({ "name": url, "type": "external", "external": { "url": url } }))
+                .collect::<Vec<_>>()
+        ),
+        "formula" | "rollup" | "created_time" | "created_by" | "last_edited_time"
+        | "last_edited_by" | "unique_id" | "verification" | "button" => {
+            bail!("{} properties are read-only", kind)
+        }
+        "" => bail!("Property schema has no type"),
+        other => bail!("Unsupported property type: {}", other),
+    };
+    Ok(json!({ kind: value }))
+}
+
+fn split_list(text: &str) -> impl Iterator<Item = &str> {
+    text.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+pub(crate) fn parse_number(text: &str) -> Result<Value> {
+    if let Ok(n) = text.parse::<i64>() {
+        return Ok(json!(n));
+    }
+    match text.parse::<f64>() {
+        Ok(n) if n.is_finite() => Ok(json!(n)),
+        _ => bail!("'{}' is not a number", text),
+    }
+}
+
+pub(crate) fn parse_checkbox(text: &str) -> Result<bool> {
+    match text.to_lowercase().as_str() {
+        "true" | "yes" | "y" | "1" | "x" | "checked" => Ok(true),
+        "false" | "no" | "n" | "0" | "" | "unchecked" => Ok(false),
+        _ => bail!("'{}' is not a checkbox value (use true/false)", text),
+    }
+}
+
+fn parse_date(text: &str) -> Result<Value> {
+    let (start, end) = match text.split_once('/') {
+        Some((start, end)) => (start.trim(), Some(end.trim())),
+        None => (text, None),
+    };
+    for part in std::iter::once(start).chain(end) {
+        if !is_iso_date(part) {
+            bail!("'{}' is not an ISO 8601 date (YYYY-MM-DD)", part);
+        }
+    }
+    Ok(json!({ "start": start, "end": end }))
+}
+
+pub(crate) fn is_iso_date(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() >= 10
+        && bytes[..10].iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+        && (bytes.len() == 10 || bytes[10] == b'T')
+}
+
+/// Calendar date of a day count since 1970-01-01 (Howard Hinnant's algorithm).
+pub(crate) fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+pub(crate) fn known_option<'a>(schema: &Value, name: &'a str) -> Result<&'a str> {
+    let kind = schema["type"].as_str().unwrap_or_default();
+    let Some(options) = schema[kind]["options"].as_array() else {
+        return Ok(name);
+    };
+    let names: Vec<&str> = options.iter().filter_map(|o| o["name"].as_str()).collect();
+    if names.contains(&name) {
+        return Ok(name);
+    }
+    match suggest(name, names.iter().copied()) {
+        Some(close) => bail!(
+            "Unknown {} option '{}' (did you mean '{}'?)",
+            kind,
+            name,
+            close
+        ),
+        None => bail!(
+            "Unknown {} option '{}' (expected one of: {})",
+            kind,
+            name,
+            names.join(", ")
+        ),
+    }
+}
+
+pub(crate) fn resolve_person(who: &str, people: &HashMap<String, String>) -> Result<String> {
+    if who.contains('@') {
+        return match people.get(&who.to_lowercase()) {
+            Some(id) => Ok(id.clone()),
+            None => bail!("No workspace user with email '{}'", who),
+        };
+    }
+    Ok(who.to_string())
+}
+
+/// How a `--set` argument changes a property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignOp {
+    /// `Name=value`
+    Set,
+    /// `Name+=value`
+    Add,
+    /// `Name-=value`
+    Remove,
+}
+
+/// A `--set` argument, e.g. `Status=Done` or `Tags+=urgent`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    pub property: String,
+    pub op: AssignOp,
+    pub value: String,
+}
+
+impl Assignment {
+    pub fn parse(text: &str) -> Result<Self> {
+        let Some((name, value)) = text.split_once('=') else {
+            bail_input!("Invalid --set '{}' (expected NAME=VALUE)", text);
+        };
+        let (name, op) = if let Some(name) = name.strip_suffix('+') {
+            (name, AssignOp::Add)
+        } else if let Some(name) = name.strip_suffix('-') {
+            (name, AssignOp::Remove)
+        } else {
+            (name, AssignOp::Set)
+        };
+        if name.trim().is_empty() {
+            bail_input!("Invalid --set '{}' (missing property name)", text);
+        }
+        Ok(Self {
+            property: name.trim().to_string(),
+            op,
+            value: value.trim().to_string(),
+        })
+    }
+}
+
+/// Look a property up by name (exactly, then ignoring case) or ID.
+pub fn find_property<'a>(
+    schema: &'a IndexMap<String, PropertySchema>,
+    name: &str,
+) -> Result<(&'a String, &'a PropertySchema)> {
+    if let Some(found) = schema.get_key_value(name) {
+        return Ok(found);
+    }
+    let mut folded = schema.iter().filter(|(n, _)| n.eq_ignore_ascii_case(name));
+    if let (Some(found), None) = (folded.next(), folded.next()) {
+        return Ok(found);
+    }
+    if let Some(found) = schema.iter().find(|(_, p)| p.id.as_deref() == Some(name)) {
+        return Ok(found);
+    }
+    match suggest(name, schema.keys().map(String::as_str)) {
+        Some(close) => bail_input!("Unknown property '{}' (did you mean '{}'?)", name, close),
+        None => bail_input!(
+            "Unknown property '{}' (properties: {})",
+            name,
+            schema.keys().cloned().collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+/// The candidate closest to a misspelled name, if any is close enough.
+pub fn suggest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let name = name.to_lowercase();
+    let threshold = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|c| (edit_distance(&name, &c.to_lowercase()), c))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, c)| c)
+}
+
+/// Levenshtein distance, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substituted = previous + usize::from(ca != *cb);
+            previous = row[j + 1];
+            row[j + 1] = substituted.min(previous + 1).min(row[j] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Build a property payload (`{type: value}`) for a `--set` argument.
+///
+/// Values are parsed like [`from_text`], except that select and
+/// multi-select options must already exist and people may be written as
+/// `@email`. `+=` and `-=` add to or remove from the `current` value of a
+/// multi-select, people or relation property; relations must already be
+/// IDs.
+pub fn assign(
+    schema: &PropertySchema,
+    assignment: &Assignment,
+    current: Option<&PropertyContent>,
+    people: &HashMap<String, String>,
+) -> Result<Value> {
+    let kind = schema.config.kind();
+    let json_schema = serde_json::to_value(schema)?;
+    let text = match &schema.config {
+        PropertyConfig::Select(_) if !assignment.value.is_empty() => {
+            known_option(&json_schema, &assignment.value)?;
+            assignment.value.clone()
+        }
+        PropertyConfig::MultiSelect(_) => {
+            for name in split_list(&assignment.value) {
+                known_option(&json_schema, name)?;
+            }
+            assignment.value.clone()
+        }
+        PropertyConfig::People(_) => split_list(&assignment.value)
+            .map(|who| match who.strip_prefix('@') {
+                Some(email) if email.contains('@') => email,
+                _ => who,
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+        _ => assignment.value.clone(),
+    };
+    let payload = from_text(&json_schema, &text, people)?;
+    if assignment.op == AssignOp::Set {
+        return Ok(payload);
+    }
+
+    let (key, existing): (&str, Vec<String>) = match current {
+        Some(PropertyContent::MultiSelect(options)) => {
+            ("name", options.iter().map(|o| o.name.clone()).collect())
+        }
+        Some(PropertyContent::People(users)) => {
+            ("id", users.iter().map(|u| u.id.clone()).collect())
+        }
+        Some(PropertyContent::Relation(pages)) => {
+            ("id", pages.iter().map(|p| p.id.clone()).collect())
+        }
+        _ => match kind {
+            "multi_select" => ("name", Vec::new()),
+            "people" | "relation" => ("id", Vec::new()),
+            _ => bail!(
+                "+= and -= only apply to multi_select, people and relation properties, not {}",
+                kind
+            ),
+        },
+    };
+    let mut items = existing;
+    for item in payload[kind].as_array().into_iter().flatten() {
+        let item = item[key].as_str().unwrap_or_default().to_string();
+        let position = items.iter().position(|i| *i == item);
+        match (assignment.op, position) {
+            (AssignOp::Add, None) => items.push(item),
+            (AssignOp::Remove, Some(index)) => {
+                items.remove(index);
+            }
+            (AssignOp::Remove, None) => bail!("'{}' is not set", item),
+            _ => {}
+        }
+    }
+    let items: Vec<Value> = items.into_iter().map(|i| json!({ key: i })).collect();
+    Ok(json!({ kind: items }))
+}
+
+#[cfg(test)]
+#[path = "properties_tests.rs"]
+mod tests;
@@ -0,0 +1,471 @@
+use super::*;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn text(content: &str) -> Value {
+    json!([{ "type": "text", "plain_text": content, "text": { "content": content } }])
+}
+
+#[test]
+fn test_plain_text_concatenates_parts() {
+    let rt = json!([
+        { "plain_text": "Hello " },
+        { "plain_text": "world" }
+    ]);
+    assert_eq!(plain_text(&rt), "Hello world");
+}
+
+#[test]
+fn test_plain_text_falls_back_to_text_content() {
+    let rt = json!([{ "type": "text", "text": { "content": "draft" } }]);
+    assert_eq!(plain_text(&rt), "draft");
+}
+
+#[test]
+fn test_plain_text_non_array_is_empty() {
+    assert_eq!(plain_text(&json!(null)), "");
+}
+
+#[test]
+fn test_title_of_page() {
+    let page = json!({
+        "object": "page",
+        "properties": {
+            "Status": { "type": "status", "status": { "name": "Done" } },
+            "Name": { "type": "title", "title": text("Roadmap") }
+        }
+    });
+    assert_eq!(title(&page), "Roadmap");
+}
+
+#[test]
+fn test_title_of_data_source() {
+    let ds = json!({ "object": "data_source", "title": text("Tasks") });
+    assert_eq!(title(&ds), "Tasks");
+}
+
+#[test]
+fn test_to_text_title_and_rich_text() {
+    assert_eq!(
+        to_text(&json!({ "type": "title", "title": text("A") })),
+        "A"
+    );
+    assert_eq!(
+        to_text(&json!({ "type": "rich_text", "rich_text": text("B") })),
+        "B"
+    );
+}
+
+#[test]
+fn test_to_text_select_status_multi_select() {
+    assert_eq!(
+        to_text(&json!({ "type": "select", "select": { "name": "High" } })),
+        "High"
+    );
+    assert_eq!(to_text(&json!({ "type": "select", "select": null })), "");
+    assert_eq!(
+        to_text(&json!({ "type": "status", "status": { "name": "Done" } })),
+        "Done"
+    );
+    assert_eq!(
+        to_text(&json!({
+            "type": "multi_select",
+            "multi_select": [{ "name": "infra" }, { "name": "urgent" }]
+        })),
+        "infra, urgent"
+    );
+}
+
+#[test]
+fn test_to_text_number_and_checkbox() {
+    assert_eq!(to_text(&json!({ "type": "number", "number": 4.5 })), "4.5");
+    assert_eq!(to_text(&json!({ "type": "number", "number": null })), "");
+    assert_eq!(
+        to_text(&json!({ "type": "checkbox", "checkbox": true })),
+        "true"
+    );
+}
+
+#[test]
+fn test_to_text_date() {
+    assert_eq!(
+        to_text(&json!({ "type": "date", "date": { "start": "2026-01-02", "end": null } })),
+        "2026-01-02"
+    );
+    assert_eq!(
+        to_text(&json!({
+            "type": "date",
+            "date": { "start": "2026-01-02", "end": "2026-01-05" }
+        })),
+        "2026-01-02/2026-01-05"
+    );
+    assert_eq!(to_text(&json!({ "type": "date", "date": null })), "");
+}
+
+#[test]
+fn test_to_text_people_falls_back_to_id() {
+    let prop = json!({
+        "type": "people",
+        "people": [{ "id": "u1", "name": "Alice" }, { "id": "u2" }]
+    });
+    assert_eq!(to_text(&prop), "Alice, u2");
+}
+
+#[test]
+fn test_to_text_relation_ids() {
+    let prop = json!({
+        "type": "relation",
+        "relation": [{ "id": "r1" }, { "id": "r2" }]
+    });
+    assert_eq!(to_text(&prop), "r1, r2");
+}
+
+#[test]
+fn test_to_text_formula() {
+    let prop = json!({ "type": "formula", "formula": { "type": "number", "number": 42 } });
+    assert_eq!(to_text(&prop), "42");
+    let prop = json!({ "type": "formula", "formula": { "type": "boolean", "boolean": false } });
+    assert_eq!(to_text(&prop), "false");
+}
+
+#[test]
+fn test_to_text_rollup() {
+    let prop = json!({ "type": "rollup", "rollup": { "type": "number", "number": 7 } });
+    assert_eq!(to_text(&prop), "7");
+    let prop = json!({
+        "type": "rollup",
+        "rollup": {
+            "type": "array",
+            "array": [
+                { "type": "title", "title": text("x") },
+                { "type": "title", "title": text("y") }
+            ]
+        }
+    });
+    assert_eq!(to_text(&prop), "x, y");
+}
+
+#[test]
+fn test_to_text_files_and_unique_id() {
+    let prop = json!({
+        "type": "files",
+        "files": [{ "name": "a.pdf" }, { "external": { "url": "https://x/b.png" } }]
+    });
+    assert_eq!(to_text(&prop), "a.pdf, https://x/b.png");
+    let prop = json!({ "type": "unique_id", "unique_id": { "prefix": "TASK", "number": 12 } });
+    assert_eq!(to_text(&prop), "TASK-12");
+}
+
+#[test]
+fn test_to_text_created_by() {
+    let prop = json!({ "type": "created_by", "created_by": { "id": "u9", "object": "user" } });
+    assert_eq!(to_text(&prop), "u9");
+}
+
+fn schema(kind: &str) -> Value {
+    json!({ "type": kind })
+}
+
+fn no_people() -> HashMap<String, String> {
+    HashMap::new()
+}
+
+#[test]
+fn test_from_text_title_and_rich_text() {
+    let value = from_text(&schema("title"), " Launch ", &no_people()).unwrap();
+    assert_eq!(
+        value,
+        json!({ "title": [{ "type": "text", "text": { "content": "Launch" } }] })
+    );
+    let cleared = from_text(&schema("rich_text"), "", &no_people()).unwrap();
+    assert_eq!(cleared, json!({ "rich_text": [] }));
+}
+
+#[test]
+fn test_from_text_splits_long_text() {
+    let long = "é".repeat(4500);
+    let value = from_text(&schema("rich_text"), &long, &no_people()).unwrap();
+    let lengths: Vec<usize> = value["rich_text"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["text"]["content"].as_str().unwrap().chars().count())
+        .collect();
+    assert_eq!(lengths, [2000, 2000, 500]);
+}
+
+#[test]
+fn test_from_text_number() {
+    assert_eq!(
+        from_text(&schema("number"), "42", &no_people()).unwrap(),
+        json!({ "number": 42 })
+    );
+    assert_eq!(
+        from_text(&schema("number"), "-1.5", &no_people()).unwrap(),
+        json!({ "number": -1.5 })
+    );
+    let err = from_text(&schema("number"), "12x", &no_people()).unwrap_err();
+    assert!(err.to_string().contains("not a number"));
+}
+
+#[test]
+fn test_from_text_select_and_multi_select() {
+    assert_eq!(
+        from_text(&schema("select"), "High", &no_people()).unwrap(),
+        json!({ "select": { "name": "High" } })
+    );
+    assert_eq!(
+        from_text(&schema("multi_select"), "a, b,,c", &no_people()).unwrap(),
+        json!({ "multi_select": [{ "name": "a" }, { "name": "b" }, { "name": "c" }] })
+    );
+}
+
+#[test]
+fn test_from_text_status_must_exist() {
+    let status = json!({
+        "type": "status",
+        "status": { "options": [{ "name": "Todo" }, { "name": "Done" }] }
+    });
+    assert_eq!(
+        from_text(&status, "Done", &no_people()).unwrap(),
+        json!({ "status": { "name": "Done" } })
+    );
+    let err = from_text(&status, "Later", &no_people()).unwrap_err();
+    assert!(err.to_string().contains("Todo, Done"));
+}
+
+#[test]
+fn test_from_text_date() {
+    assert_eq!(
+        from_text(&schema("date"), "2024-01-15", &no_people()).unwrap(),
+        json!({ "date": { "start": "2024-01-15", "end": null } })
+    );
+    assert_eq!(
+        from_text(&schema("date"), "2024-01-15/2024-01-20", &no_people()).unwrap(),
+        json!({ "date": { "start": "2024-01-15", "end": "2024-01-20" } })
+    );
+    assert_eq!(
+        from_text(&schema("date"), "2024-01-15T09:30:00Z", &no_people()).unwrap()["date"]["start"],
+        "2024-01-15T09:30:00Z"
+    );
+    assert!(from_text(&schema("date"), "15/01/2024", &no_people()).is_err());
+}
+
+#[test]
+fn test_from_text_checkbox() {
+    for yes in ["true", "Yes", "1", "x"] {
+        assert_eq!(
+            from_text(&schema("checkbox"), yes, &no_people()).unwrap(),
+            json!({ "checkbox": true })
+        );
+    }
+    assert_eq!(
+        from_text(&schema("checkbox"), "no", &no_people()).unwrap(),
+        json!({ "checkbox": false })
+    );
+    assert!(from_text(&schema("checkbox"), "maybe", &no_people()).is_err());
+}
+
+#[test]
+fn test_from_text_url_and_email() {
+    assert_eq!(
+        from_text(&schema("url"), "https://example.com", &no_people()).unwrap(),
+        json!({ "url": "https://example.com" })
+    );
+    assert_eq!(
+        from_text(&schema("email"), "a@b.co", &no_people()).unwrap(),
+        json!({ "email": "a@b.co" })
+    );
+    assert!(from_text(&schema("email"), "nobody", &no_people()).is_err());
+}
+
+#[test]
+fn test_from_text_people_by_email_or_id() {
+    let people = HashMap::from([("ada@example.com".to_string(), "user-1".to_string())]);
+    assert_eq!(
+        from_text(&schema("people"), "Ada@Example.com, user-2", &people).unwrap(),
+        json!({ "people": [{ "id": "user-1" }, { "id": "user-2" }] })
+    );
+    let err = from_text(&schema("people"), "bob@example.com", &people).unwrap_err();
+    assert!(err.to_string().contains("bob@example.com"));
+}
+
+#[test]
+fn test_from_text_relation() {
+    assert_eq!(
+        from_text(&schema("relation"), "page-1, page-2", &no_people()).unwrap(),
+        json!({ "relation": [{ "id": "page-1" }, { "id": "page-2" }] })
+    );
+}
+
+#[test]
+fn test_from_text_read_only() {
+    let err = from_text(&schema("formula"), "3", &no_people()).unwrap_err();
+    assert!(err.to_string().contains("read-only"));
+}
+
+#[test]
+fn test_from_text_roundtrips_to_text() {
+    let value = from_text(&schema("multi_select"), "a, b", &no_people()).unwrap();
+    let property = json!({ "type": "multi_select", "multi_select": value["multi_select"] });
+    assert_eq!(to_text(&property), "a, b");
+}
+
+#[test]
+fn test_assignment_parse() {
+    let set = Assignment::parse("Status = Done").unwrap();
+    assert_eq!(set.property, "Status");
+    assert_eq!(set.op, AssignOp::Set);
+    assert_eq!(set.value, "Done");
+
+    let add = Assignment::parse("Tags+=urgent").unwrap();
+    assert_eq!((add.property.as_str(), add.op), ("Tags", AssignOp::Add));
+    let remove = Assignment::parse("Tags-=old").unwrap();
+    assert_eq!(remove.op, AssignOp::Remove);
+
+    let formula = Assignment::parse("Note=a=b").unwrap();
+    assert_eq!(formula.value, "a=b");
+    let cleared = Assignment::parse("Due=").unwrap();
+    assert_eq!(cleared.value, "");
+}
+
+#[test]
+fn test_assignment_parse_errors() {
+    let err = Assignment::parse("Status").unwrap_err();
+    assert!(err.to_string().contains("expected NAME=VALUE"));
+    let err = Assignment::parse("+=x").unwrap_err();
+    assert!(err.to_string().contains("missing property name"));
+}
+
+fn data_source_schema() -> IndexMap<String, PropertySchema> {
+    serde_json::from_value(json!({
+        "Name": { "id": "title", "type": "title", "title": {} },
+        "Status": {
+            "id": "st",
+            "type": "status",
+            "status": { "options": [{ "name": "Todo" }, { "name": "Done" }] }
+        },
+        "Priority": {
+            "id": "pr",
+            "type": "select",
+            "select": { "options": [{ "name": "High" }, { "name": "Low" }] }
+        },
+        "Tags": {
+            "id": "tg",
+            "type": "multi_select",
+            "multi_select": { "options": [{ "name": "urgent" }, { "name": "later" }] }
+        },
+        "Owner": { "id": "ow", "type": "people", "people": {} },
+        "Due": { "id": "du", "type": "date", "date": {} },
+        "Estimate": { "id": "es", "type": "number", "number": { "format": "number" } },
+        "Blocked by": { "id": "rl", "type": "relation", "relation": { "data_source_id": "ds-2" } }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_find_property_by_name_case_or_id() {
+    let schema = data_source_schema();
+    assert_eq!(find_property(&schema, "Status").unwrap().0, "Status");
+    assert_eq!(find_property(&schema, "status").unwrap().0, "Status");
+    assert_eq!(find_property(&schema, "du").unwrap().0, "Due");
+}
+
+#[test]
+fn test_find_property_suggests_close_names() {
+    let schema = data_source_schema();
+    let err = find_property(&schema, "Stauts").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown property 'Stauts' (did you mean 'Status'?)"
+    );
+    let err = find_property(&schema, "Color").unwrap_err();
+    assert!(err.to_string().contains("(properties: Name, Status,"));
+    assert!(err.downcast_ref::<crate::error::NotionError>().is_some());
+}
+
+#[test]
+fn test_suggest() {
+    let names = ["Status", "Priority", "Due"];
+    assert_eq!(suggest("priorty", names), Some("Priority"));
+    assert_eq!(suggest("Du", names), Some("Due"));
+    assert_eq!(suggest("Owner", names), None);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+}
+
+fn assign_to(name: &str, text: &str, current: Option<&PropertyContent>) -> Result<Value> {
+    let schema = data_source_schema();
+    let assignment = Assignment::parse(&format!("{}{}", name, text)).unwrap();
+    let people = HashMap::from([("alice@example.com".to_string(), "user-1".to_string())]);
+    assign(&schema[name], &assignment, current, &people)
+}
+
+#[test]
+fn test_assign_set() {
+    assert_eq!(
+        assign_to("Status", "=Done", None).unwrap(),
+        json!({ "status": { "name": "Done" } })
+    );
+    assert_eq!(
+        assign_to("Due", "=2026-11-01", None).unwrap(),
+        json!({ "date": { "start": "2026-11-01", "end": null } })
+    );
+    assert_eq!(
+        assign_to("Estimate", "=3", None).unwrap(),
+        json!({ "number": 3 })
+    );
+    assert_eq!(
+        assign_to("Priority", "=", None).unwrap(),
+        json!({ "select": null })
+    );
+    assert_eq!(
+        assign_to("Owner", "=@alice@example.com", None).unwrap(),
+        json!({ "people": [{ "id": "user-1" }] })
+    );
+}
+
+#[test]
+fn test_assign_rejects_unknown_options() {
+    let err = assign_to("Priority", "=Hgh", None).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown select option 'Hgh' (did you mean 'High'?)"
+    );
+    let err = assign_to("Tags", "+=blocked", None).unwrap_err();
+    assert!(err.to_string().contains("expected one of: urgent, later"));
+    let err = assign_to("Status", "=Dne", None).unwrap_err();
+    assert!(err.to_string().contains("did you mean 'Done'?"));
+}
+
+#[test]
+fn test_assign_adds_and_removes() {
+    let current: PropertyContent = serde_json::from_value(
+        json!({ "type": "multi_select", "multi_select": [{ "name": "later" }] }),
+    )
+    .unwrap();
+    assert_eq!(
+        assign_to("Tags", "+=urgent", Some(&current)).unwrap(),
+        json!({ "multi_select": [{ "name": "later" }, { "name": "urgent" }] })
+    );
+    assert_eq!(
+        assign_to("Tags", "+=later", Some(&current)).unwrap(),
+        json!({ "multi_select": [{ "name": "later" }] })
+    );
+    assert_eq!(
+        assign_to("Tags", "-=later", Some(&current)).unwrap(),
+        json!({ "multi_select": [] })
+    );
+    let err = assign_to("Tags", "-=urgent", Some(&current)).unwrap_err();
+    assert_eq!(err.to_string(), "'urgent' is not set");
+
+    assert_eq!(
+        assign_to("Blocked by", "+=page-9", None).unwrap(),
+        json!({ "relation": [{ "id": "page-9" }] })
+    );
+}
+
+#[test]
+fn test_assign_add_needs_a_list_property() {
+    let err = assign_to("Estimate", "+=1", None).unwrap_err();
+    assert!(err.to_string().contains("not number"));
+}
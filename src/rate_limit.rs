@@ -0,0 +1,89 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Notion's documented average rate limit per integration
+pub const DEFAULT_RATE: f64 = 3.0;
+
+/// Token bucket shared by every request made through one client.
+///
+/// Tokens refill continuously at `rate` per second up to `burst`. A
+/// `Retry-After` from the server pauses the whole bucket, so concurrent
+/// requests wait for the same deadline instead of each backing off alone.
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    state: Mutex<State>,
+}
+
+struct State {
+    tokens: f64,
+    updated: Instant,
+    paused_until: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// `rate` requests per second; bursts of up to `rate` (at least one) are allowed.
+    pub fn new(rate: f64) -> Self {
+        let burst = rate.ceil().max(1.0);
+        Self {
+            rate,
+            burst,
+            state: Mutex::new(State {
+                tokens: burst,
+                updated: Instant::now(),
+                paused_until: None,
+            }),
+        }
+    }
+
+    /// Wait until a request may be sent, then take a token.
+    pub async fn acquire(&self) {
+        loop {
+            match self.try_acquire(Instant::now()) {
+                None => return,
+                Some(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+
+    /// Take a token at `now`, or return how long to wait before trying again.
+    fn try_acquire(&self, now: Instant) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(until) = state.paused_until {
+            if until > now {
+                return Some(until - now);
+            }
+            state.paused_until = None;
+            state.updated = now;
+        }
+
+        let elapsed = now.saturating_duration_since(state.updated).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate).min(self.burst);
+        state.updated = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            return None;
+        }
+        Some(Duration::from_secs_f64((1.0 - state.tokens) / self.rate))
+    }
+
+    /// Hold every request for `wait`, e.g. after a `Retry-After` response.
+    pub fn pause(&self, wait: Duration) {
+        self.pause_at(Instant::now(), wait);
+    }
+
+    fn pause_at(&self, now: Instant, wait: Duration) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let until = now + wait;
+        if state.paused_until.is_none_or(|current| current < until) {
+            state.paused_until = Some(until);
+        }
+        // Resume with an empty bucket rather than a burst
+        state.tokens = 0.0;
+    }
+}
+
+#[cfg(test)]
+#[path = "rate_limit_tests.rs"]
+mod tests;
@@ -0,0 +1,83 @@
+use super::*;
+
+#[test]
+fn test_burst_then_wait() {
+    let limiter = RateLimiter::new(3.0);
+    let now = Instant::now();
+    assert!(limiter.try_acquire(now).is_none());
+    assert!(limiter.try_acquire(now).is_none());
+    assert!(limiter.try_acquire(now).is_none());
+
+    let wait = limiter.try_acquire(now).unwrap();
+    assert!(wait > Duration::from_millis(330) && wait <= Duration::from_millis(334));
+}
+
+#[test]
+fn test_tokens_refill_over_time() {
+    let limiter = RateLimiter::new(2.0);
+    let now = Instant::now();
+    assert!(limiter.try_acquire(now).is_none());
+    assert!(limiter.try_acquire(now).is_none());
+    assert!(limiter.try_acquire(now).is_some());
+
+    let later = now + Duration::from_millis(500);
+    assert!(limiter.try_acquire(later).is_none());
+    assert!(limiter.try_acquire(later).is_some());
+}
+
+#[test]
+fn test_refill_is_capped_at_burst() {
+    let limiter = RateLimiter::new(2.0);
+    let later = Instant::now() + Duration::from_secs(60);
+    assert!(limiter.try_acquire(later).is_none());
+    assert!(limiter.try_acquire(later).is_none());
+    assert!(limiter.try_acquire(later).is_some());
+}
+
+#[test]
+fn test_fractional_rate_allows_one_request() {
+    let limiter = RateLimiter::new(0.5);
+    let now = Instant::now();
+    assert!(limiter.try_acquire(now).is_none());
+    assert_eq!(limiter.try_acquire(now), Some(Duration::from_secs(2)));
+}
+
+#[test]
+fn test_pause_blocks_everyone_until_deadline() {
+    let limiter = RateLimiter::new(3.0);
+    let now = Instant::now();
+    limiter.pause_at(now, Duration::from_secs(2));
+
+    assert_eq!(limiter.try_acquire(now), Some(Duration::from_secs(2)));
+    let halfway = now + Duration::from_secs(1);
+    assert_eq!(limiter.try_acquire(halfway), Some(Duration::from_secs(1)));
+
+    // After the pause the bucket starts empty, so requests trickle back at the rate
+    let after = now + Duration::from_secs(2);
+    assert!(limiter.try_acquire(after).is_some());
+    assert!(
+        limiter
+            .try_acquire(after + Duration::from_millis(334))
+            .is_none()
+    );
+}
+
+#[test]
+fn test_pause_keeps_the_later_deadline() {
+    let limiter = RateLimiter::new(3.0);
+    let now = Instant::now();
+    limiter.pause_at(now, Duration::from_secs(5));
+    limiter.pause_at(now, Duration::from_secs(1));
+    assert_eq!(limiter.try_acquire(now), Some(Duration::from_secs(5)));
+}
+
+#[tokio::test]
+async fn test_acquire_waits_for_a_token() {
+    let limiter = RateLimiter::new(20.0);
+    let started = Instant::now();
+    for _ in 0..22 {
+        limiter.acquire().await;
+    }
+    // 20 from the burst, then two more at 50ms each
+    assert!(started.elapsed() >= Duration::from_millis(90));
+}
@@ -0,0 +1,465 @@
+use colored::{ColoredString, Colorize};
+use serde_json::Value;
+
+use crate::properties::{plain_text, title, to_text, user_text};
+
+/// Longest cell rendered in a table before it is truncated.
+const MAX_CELL_WIDTH: usize = 60;
+
+/// Render a Notion API response as human-readable text.
+///
+/// Returns `None` for shapes that are not recognized so the caller can fall
+/// back to indented JSON.
+pub fn render(value: &Value) -> Option<String> {
+    match value["object"].as_str()? {
+        "list" => render_list(value),
+        "page" => Some(render_page(value)),
+        "database" => Some(render_database(value)),
+        "data_source" => Some(render_data_source(value)),
+        "block" => Some(render_block(value)),
+        "user" => Some(render_user(value)),
+        "comment" => Some(render_comment(value)),
+        "file_upload" => Some(render_file_upload(value)),
+        "property_item" => Some(format!("{}\n", to_text(value))),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Style {
+    Plain,
+    Title,
+    Id,
+}
+
+impl Style {
+    fn apply(self, text: &str) -> ColoredString {
+        match self {
+            Style::Plain => text.normal(),
+            Style::Title => text.bold(),
+            Style::Id => text.dimmed(),
+        }
+    }
+}
+
+struct Table {
+    columns: Vec<(&'static str, Style)>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn new(columns: Vec<(&'static str, Style)>) -> Self {
+        Self {
+            columns,
+            rows: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, row: Vec<String>) {
+        self.rows
+            .push(row.into_iter().map(|c| truncate(&c)).collect());
+    }
+
+    fn render(&self) -> String {
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, (header, _))| {
+                self.rows
+                    .iter()
+                    .map(|r| r[i].chars().count())
+                    .chain(std::iter::once(header.len()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let last = self.columns.len() - 1;
+        let mut out = String::new();
+
+        for (i, (header, _)) in self.columns.iter().enumerate() {
+            let cell = if i == last {
+                header.to_string()
+            } else {
+                pad(header, widths[i])
+            };
+            out.push_str(&cell.bold().to_string());
+        }
+        out.push('\n');
+
+        for row in &self.rows {
+            for (i, (_, style)) in self.columns.iter().enumerate() {
+                let cell = if i == last {
+                    row[i].clone()
+                } else {
+                    pad(&row[i], widths[i])
+                };
+                out.push_str(&style.apply(&cell).to_string());
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn pad(text: &str, width: usize) -> String {
+    let fill = width.saturating_sub(text.chars().count()) + 2;
+    format!("{}{}", text, " ".repeat(fill))
+}
+
+fn truncate(text: &str) -> String {
+    let text = text.replace(['\n', '\t'], " ");
+    if text.chars().count() <= MAX_CELL_WIDTH {
+        return text;
+    }
+    let mut short: String = text.chars().take(MAX_CELL_WIDTH - 1).collect();
+    short.push('…');
+    short
+}
+
+/// A bold heading followed by aligned `label  value` rows.
+fn details(heading: &str, fields: &[(&str, String)]) -> String {
+    let width = fields.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+    let mut out = format!("{}\n", heading.bold());
+    for (label, value) in fields {
+        if value.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("  {}{}\n", pad(label, width).dimmed(), value));
+    }
+    out
+}
+
+fn str_field(value: &Value, key: &str) -> String {
+    value[key].as_str().unwrap_or_default().to_string()
+}
+
+fn yes_if(flag: &Value) -> String {
+    if flag.as_bool().unwrap_or(false) {
+        "yes".to_string()
+    } else {
+        String::new()
+    }
+}
+
+fn or_untitled(text: String) -> String {
+    if text.is_empty() {
+        "Untitled".to_string()
+    } else {
+        text
+    }
+}
+
+fn parent_text(parent: &Value) -> String {
+    let kind = parent["type"].as_str().unwrap_or_default();
+    match &parent[kind] {
+        Value::String(id) => format!("{} {}", kind.trim_end_matches("_id"), id),
+        _ => kind.to_string(),
+    }
+}
+
+fn render_list(value: &Value) -> Option<String> {
+    let items = value["results"].as_array()?;
+    let mut out = match items.first().and_then(|i| i["object"].as_str()) {
+        None => String::new(),
+        Some(kind) => {
+            if items.iter().any(|i| !same_family(&i["object"], kind)) {
+                return None;
+            }
+            list_table(kind, items)?.render()
+        }
+    };
+
+    let count = items.len();
+    let mut footer = format!("{} result{}", count, if count == 1 { "" } else { "s" });
+    if value["has_more"].as_bool().unwrap_or(false) {
+        footer.push_str(" (more available");
+        if let Some(cursor) = value["next_cursor"].as_str() {
+            footer.push_str(&format!(", next cursor: {}", cursor));
+        }
+        footer.push(')');
+    }
+    out.push_str(&format!("{}\n", footer.dimmed()));
+    Some(out)
+}
+
+/// Pages, databases and data sources share a table (search mixes them).
+fn same_family(object: &Value, kind: &str) -> bool {
+    let titled = ["page", "database", "data_source"];
+    let object = object.as_str().unwrap_or_default();
+    object == kind || (titled.contains(&object) && titled.contains(&kind))
+}
+
+fn list_table(kind: &str, items: &[Value]) -> Option<Table> {
+    let table = match kind {
+        "page" | "database" | "data_source" => {
+            let mut t = Table::new(vec![
+                ("TYPE", Style::Plain),
+                ("TITLE", Style::Title),
+                ("ID", Style::Id),
+                ("LAST EDITED", Style::Plain),
+            ]);
+            for item in items {
+                t.push(vec![
+                    str_field(item, "object"),
+                    or_untitled(title(item)),
+                    str_field(item, "id"),
+                    str_field(item, "last_edited_time"),
+                ]);
+            }
+            t
+        }
+        "block" => {
+            let mut t = Table::new(vec![
+                ("TYPE", Style::Plain),
+                ("TEXT", Style::Title),
+                ("ID", Style::Id),
+                ("CHILDREN", Style::Plain),
+            ]);
+            for item in items {
+                t.push(vec![
+                    str_field(item, "type"),
+                    block_text(item),
+                    str_field(item, "id"),
+                    yes_if(&item["has_children"]),
+                ]);
+            }
+            t
+        }
+        "user" => {
+            let mut t = Table::new(vec![
+                ("NAME", Style::Title),
+                ("TYPE", Style::Plain),
+                ("EMAIL", Style::Plain),
+                ("ID", Style::Id),
+            ]);
+            for item in items {
+                t.push(vec![
+                    str_field(item, "name"),
+                    str_field(item, "type"),
+                    item["person"]["email"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    str_field(item, "id"),
+                ]);
+            }
+            t
+        }
+        "comment" => {
+            let mut t = Table::new(vec![
+                ("AUTHOR", Style::Plain),
+                ("TEXT", Style::Title),
+                ("CREATED", Style::Plain),
+                ("ID", Style::Id),
+            ]);
+            for item in items {
+                t.push(vec![
+                    user_text(&item["created_by"]),
+                    plain_text(&item["rich_text"]),
+                    str_field(item, "created_time"),
+                    str_field(item, "id"),
+                ]);
+            }
+            t
+        }
+        "file_upload" => {
+            let mut t = Table::new(vec![
+                ("FILENAME", Style::Title),
+                ("STATUS", Style::Plain),
+                ("CREATED", Style::Plain),
+                ("ID", Style::Id),
+            ]);
+            for item in items {
+                t.push(vec![
+                    str_field(item, "filename"),
+                    str_field(item, "status"),
+                    str_field(item, "created_time"),
+                    str_field(item, "id"),
+                ]);
+            }
+            t
+        }
+        "property_item" => {
+            let mut t = Table::new(vec![("VALUE", Style::Plain)]);
+            for item in items {
+                t.push(vec![to_text(item)]);
+            }
+            t
+        }
+        _ => return None,
+    };
+    Some(table)
+}
+
+fn render_page(page: &Value) -> String {
+    let mut out = details(
+        &or_untitled(title(page)),
+        &[
+            ("ID", str_field(page, "id")),
+            ("URL", str_field(page, "url")),
+            ("Parent", parent_text(&page["parent"])),
+            ("Created", str_field(page, "created_time")),
+            ("Last edited", str_field(page, "last_edited_time")),
+            ("Archived", yes_if(&page["archived"])),
+        ],
+    );
+
+    if let Some(props) = page["properties"].as_object()
+        && !props.is_empty()
+    {
+        let mut table = Table::new(vec![("PROPERTY", Style::Plain), ("VALUE", Style::Plain)]);
+        for (name, prop) in props {
+            table.push(vec![name.clone(), to_text(prop)]);
+        }
+        out.push('\n');
+        out.push_str(&table.render());
+    }
+    out
+}
+
+fn render_database(db: &Value) -> String {
+    let mut out = details(
+        &or_untitled(title(db)),
+        &[
+            ("ID", str_field(db, "id")),
+            ("URL", str_field(db, "url")),
+            ("Parent", parent_text(&db["parent"])),
+            ("Last edited", str_field(db, "last_edited_time")),
+        ],
+    );
+
+    if let Some(sources) = db["data_sources"].as_array()
+        && !sources.is_empty()
+    {
+        let mut table = Table::new(vec![("DATA SOURCE", Style::Title), ("ID", Style::Id)]);
+        for source in sources {
+            table.push(vec![str_field(source, "name"), str_field(source, "id")]);
+        }
+        out.push('\n');
+        out.push_str(&table.render());
+    }
+    out
+}
+
+fn render_data_source(ds: &Value) -> String {
+    let mut out = details(
+        &or_untitled(title(ds)),
+        &[
+            ("ID", str_field(ds, "id")),
+            ("Parent", parent_text(&ds["parent"])),
+            ("Last edited", str_field(ds, "last_edited_time")),
+        ],
+    );
+
+    if let Some(props) = ds["properties"].as_object()
+        && !props.is_empty()
+    {
+        let mut table = Table::new(vec![
+            ("PROPERTY", Style::Title),
+            ("TYPE", Style::Plain),
+            ("OPTIONS", Style::Plain),
+        ]);
+        for (name, prop) in props {
+            let kind = str_field(prop, "type");
+            let options = prop[&kind]["options"]
+                .as_array()
+                .map(|opts| {
+                    opts.iter()
+                        .filter_map(|o| o["name"].as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .unwrap_or_default();
+            table.push(vec![name.clone(), kind, options]);
+        }
+        out.push('\n');
+        out.push_str(&table.render());
+    }
+    out
+}
+
+fn block_text(block: &Value) -> String {
+    let kind = block["type"].as_str().unwrap_or_default();
+    let data = &block[kind];
+    if let Some(title) = data["title"].as_str() {
+        return title.to_string();
+    }
+    if let Some(expression) = data["expression"].as_str() {
+        return expression.to_string();
+    }
+    plain_text(&data["rich_text"])
+}
+
+fn render_block(block: &Value) -> String {
+    details(
+        &str_field(block, "type"),
+        &[
+            ("ID", str_field(block, "id")),
+            ("Text", block_text(block)),
+            ("Parent", parent_text(&block["parent"])),
+            ("Children", yes_if(&block["has_children"])),
+            ("Last edited", str_field(block, "last_edited_time")),
+        ],
+    )
+}
+
+fn render_user(user: &Value) -> String {
+    details(
+        &or_untitled(str_field(user, "name")),
+        &[
+            ("ID", str_field(user, "id")),
+            ("Type", str_field(user, "type")),
+            (
+                "Email",
+                user["person"]["email"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+            (
+                "Workspace",
+                user["bot"]["workspace_name"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+        ],
+    )
+}
+
+fn render_comment(comment: &Value) -> String {
+    details(
+        &plain_text(&comment["rich_text"]),
+        &[
+            ("ID", str_field(comment, "id")),
+            ("Author", user_text(&comment["created_by"])),
+            ("Created", str_field(comment, "created_time")),
+            ("Discussion", str_field(comment, "discussion_id")),
+        ],
+    )
+}
+
+fn render_file_upload(upload: &Value) -> String {
+    details(
+        &or_untitled(str_field(upload, "filename")),
+        &[
+            ("ID", str_field(upload, "id")),
+            ("Status", str_field(upload, "status")),
+            ("Content type", str_field(upload, "content_type")),
+            (
+                "Size",
+                upload["content_length"]
+                    .as_u64()
+                    .map(|n| format!("{} bytes", n))
+                    .unwrap_or_default(),
+            ),
+            ("Expires", str_field(upload, "expiry_time")),
+        ],
+    )
+}
+
+#[cfg(test)]
+#[path = "render_tests.rs"]
+mod tests;
@@ -0,0 +1,225 @@
+use super::*;
+use serde_json::json;
+
+fn render_plain(value: &Value) -> Option<String> {
+    colored::control::set_override(false);
+    render(value)
+}
+
+fn title_prop(text: &str) -> Value {
+    json!({ "type": "title", "title": [{ "plain_text": text }] })
+}
+
+#[test]
+fn test_unknown_shape_falls_back() {
+    assert!(render_plain(&json!({"key": "value"})).is_none());
+    assert!(render_plain(&json!({"object": "mystery"})).is_none());
+    assert!(render_plain(&json!({"dry_run": true, "method": "POST"})).is_none());
+}
+
+#[test]
+fn test_render_search_results_table() {
+    let value = json!({
+        "object": "list",
+        "results": [
+            {
+                "object": "page",
+                "id": "page-1",
+                "last_edited_time": "2026-01-01T00:00:00.000Z",
+                "properties": { "Name": title_prop("Roadmap") }
+            },
+            {
+                "object": "data_source",
+                "id": "ds-1",
+                "last_edited_time": "2026-02-01T00:00:00.000Z",
+                "title": [{ "plain_text": "Tasks" }]
+            }
+        ],
+        "has_more": false
+    });
+    let out = render_plain(&value).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+
+    assert!(lines[0].starts_with("TYPE"));
+    assert!(lines[0].contains("TITLE"));
+    assert!(lines[1].contains("Roadmap"));
+    assert!(lines[2].contains("Tasks"));
+    // Columns are aligned
+    assert_eq!(lines[1].find("page-1"), lines[2].find("ds-1"));
+    assert_eq!(lines[3], "2 results");
+}
+
+#[test]
+fn test_render_list_footer_reports_more() {
+    let value = json!({
+        "object": "list",
+        "results": [{ "object": "user", "id": "u1", "name": "Ada", "type": "person" }],
+        "has_more": true,
+        "next_cursor": "c2"
+    });
+    let out = render_plain(&value).unwrap();
+    assert!(out.contains("Ada"));
+    assert!(out.ends_with("1 result (more available, next cursor: c2)\n"));
+}
+
+#[test]
+fn test_render_empty_list() {
+    let value = json!({ "object": "list", "results": [], "has_more": false });
+    assert_eq!(render_plain(&value).unwrap(), "0 results\n");
+}
+
+#[test]
+fn test_render_mixed_list_falls_back() {
+    let value = json!({
+        "object": "list",
+        "results": [{ "object": "user" }, { "object": "block" }]
+    });
+    assert!(render_plain(&value).is_none());
+}
+
+#[test]
+fn test_render_block_children() {
+    let value = json!({
+        "object": "list",
+        "results": [
+            {
+                "object": "block",
+                "id": "b1",
+                "type": "paragraph",
+                "has_children": true,
+                "paragraph": { "rich_text": [{ "plain_text": "Hello" }] }
+            },
+            {
+                "object": "block",
+                "id": "b2",
+                "type": "child_page",
+                "child_page": { "title": "Sub page" }
+            }
+        ]
+    });
+    let out = render_plain(&value).unwrap();
+    assert!(out.contains("paragraph"));
+    assert!(out.contains("Hello"));
+    assert!(out.contains("yes"));
+    assert!(out.contains("Sub page"));
+}
+
+#[test]
+fn test_render_page_properties() {
+    let value = json!({
+        "object": "page",
+        "id": "page-1",
+        "url": "https://www.notion.so/page-1",
+        "parent": { "type": "data_source_id", "data_source_id": "ds-1" },
+        "properties": {
+            "Name": title_prop("Roadmap"),
+            "Status": { "type": "status", "status": { "name": "Done" } },
+            "Tags": { "type": "multi_select", "multi_select": [{ "name": "a" }, { "name": "b" }] }
+        }
+    });
+    let out = render_plain(&value).unwrap();
+    assert!(out.starts_with("Roadmap\n"));
+    assert!(out.contains("https://www.notion.so/page-1"));
+    assert!(out.contains("data_source ds-1"));
+    assert!(out.contains("PROPERTY"));
+    let status = out.lines().find(|l| l.starts_with("Status")).unwrap();
+    assert!(status.ends_with("Done"));
+    assert!(out.contains("a, b"));
+}
+
+#[test]
+fn test_render_data_source_schema() {
+    let value = json!({
+        "object": "data_source",
+        "id": "ds-1",
+        "title": [{ "plain_text": "Tasks" }],
+        "properties": {
+            "Name": { "id": "title", "type": "title", "title": {} },
+            "Priority": {
+                "id": "p",
+                "type": "select",
+                "select": { "options": [{ "name": "High" }, { "name": "Low" }] }
+            }
+        }
+    });
+    let out = render_plain(&value).unwrap();
+    assert!(out.starts_with("Tasks\n"));
+    assert!(out.contains("High, Low"));
+}
+
+#[test]
+fn test_render_database() {
+    let value = json!({
+        "object": "database",
+        "id": "db-1",
+        "title": [{ "plain_text": "Projects" }],
+        "data_sources": [{ "id": "ds-1", "name": "Main" }]
+    });
+    let out = render_plain(&value).unwrap();
+    assert!(out.contains("Projects"));
+    assert!(out.contains("DATA SOURCE"));
+    assert!(out.contains("ds-1"));
+}
+
+#[test]
+fn test_render_user_comment_file_upload() {
+    let user = json!({
+        "object": "user",
+        "id": "u1",
+        "type": "bot",
+        "name": "CLI",
+        "bot": { "workspace_name": "Acme" }
+    });
+    assert!(render_plain(&user).unwrap().contains("Acme"));
+
+    let comment = json!({
+        "object": "comment",
+        "id": "c1",
+        "rich_text": [{ "plain_text": "Looks good" }],
+        "created_by": { "id": "u1" }
+    });
+    assert!(render_plain(&comment).unwrap().starts_with("Looks good\n"));
+
+    let upload = json!({
+        "object": "file_upload",
+        "id": "fu-1",
+        "filename": "report.pdf",
+        "status": "uploaded",
+        "content_length": 12
+    });
+    let out = render_plain(&upload).unwrap();
+    assert!(out.contains("report.pdf"));
+    assert!(out.contains("12 bytes"));
+}
+
+#[test]
+fn test_render_block_and_property_item() {
+    let block = json!({
+        "object": "block",
+        "id": "b1",
+        "type": "equation",
+        "equation": { "expression": "e=mc^2" }
+    });
+    assert!(render_plain(&block).unwrap().contains("e=mc^2"));
+
+    let item = json!({ "object": "property_item", "type": "number", "number": 3 });
+    assert_eq!(render_plain(&item).unwrap(), "3\n");
+}
+
+#[test]
+fn test_untitled_page() {
+    let value = json!({ "object": "page", "id": "p1", "properties": {} });
+    assert!(render_plain(&value).unwrap().starts_with("Untitled\n"));
+}
+
+#[test]
+fn test_long_cells_are_truncated() {
+    let long = "x".repeat(200);
+    let value = json!({
+        "object": "list",
+        "results": [{ "object": "user", "id": "u1", "name": long }]
+    });
+    let out = render_plain(&value).unwrap();
+    assert!(out.contains('…'));
+    assert!(!out.contains(&"x".repeat(MAX_CELL_WIDTH)));
+}
@@ -0,0 +1,158 @@
+use reqwest::StatusCode;
+use std::fmt;
+use std::time::Duration;
+
+/// When and how long to wait before retrying a failed request.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    /// Upper bound for computed backoff (a server's Retry-After is honored as is)
+    pub max_backoff: Duration,
+    /// Randomize each backoff between half and all of its value
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+}
+
+/// Why an attempt failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    Status {
+        status: StatusCode,
+        retry_after: Option<Duration>,
+    },
+    Timeout,
+    /// The connection could not be established, so nothing was sent
+    Connect,
+    /// Any other transport error (e.g. the connection dropped mid-request)
+    Network,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Status { status, .. } if *status == StatusCode::TOO_MANY_REQUESTS => {
+                write!(f, "Rate limited (429)")
+            }
+            Failure::Status { status, .. } if *status == StatusCode::CONFLICT => {
+                write!(f, "Conflict (409)")
+            }
+            Failure::Status { status, .. } => write!(f, "Server error ({})", status),
+            Failure::Timeout => write!(f, "Request timed out"),
+            Failure::Connect => write!(f, "Connection failed"),
+            Failure::Network => write!(f, "Connection lost"),
+        }
+    }
+}
+
+impl Failure {
+    pub fn from_error(error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Failure::Timeout
+        } else if error.is_connect() {
+            Failure::Connect
+        } else {
+            Failure::Network
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the next attempt, or `None` to give up.
+    ///
+    /// `attempt` counts from 1. Rate limits (429) and `conflict_error` (409)
+    /// mean Notion did not apply the request, and connect errors mean it was
+    /// never sent, so those are retried for every method. Server errors,
+    /// timeouts and dropped connections may have been applied already and are
+    /// only retried for idempotent methods.
+    pub fn delay(&self, method: &str, failure: &Failure, attempt: u32) -> Option<Duration> {
+        if attempt > self.max_retries {
+            return None;
+        }
+        let idempotent = matches!(method, "GET" | "HEAD" | "PUT" | "DELETE");
+        let retry = match failure {
+            Failure::Status { status, .. } => match *status {
+                StatusCode::TOO_MANY_REQUESTS | StatusCode::CONFLICT => true,
+                StatusCode::INTERNAL_SERVER_ERROR
+                | StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+                | StatusCode::GATEWAY_TIMEOUT => idempotent,
+                _ => false,
+            },
+            Failure::Connect => true,
+            Failure::Timeout | Failure::Network => idempotent,
+        };
+        if !retry {
+            return None;
+        }
+        if let Failure::Status {
+            retry_after: Some(wait),
+            ..
+        } = failure
+        {
+            return Some(*wait);
+        }
+        Some(self.backoff(attempt))
+    }
+
+    /// Exponential backoff for the given attempt (1-based), capped and jittered.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+        if !self.jitter {
+            return backoff;
+        }
+        let half = backoff / 2;
+        let spread = half.as_millis() as u64;
+        if spread == 0 {
+            return backoff;
+        }
+        // Without OS randomness, wait the full backoff instead
+        let Ok(random) = getrandom::u64() else {
+            return backoff;
+        };
+        half + Duration::from_millis(random % (spread + 1))
+    }
+}
+
+/// Seconds from a `Retry-After` header.
+pub fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get("retry-after")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+/// One request attempt, kept for verbose output.
+#[derive(Debug, Clone)]
+pub struct Attempt {
+    pub method: String,
+    pub path: String,
+    pub number: u32,
+    /// HTTP status, or the failure for transport errors
+    pub outcome: String,
+    pub elapsed: Duration,
+    /// Delay before the next attempt, when one was made
+    pub retry_in: Option<Duration>,
+    /// `x-request-id` of the response, to quote in support requests
+    pub request_id: Option<String>,
+}
+
+#[cfg(test)]
+#[path = "retry_tests.rs"]
+mod tests;
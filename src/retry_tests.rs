@@ -0,0 +1,137 @@
+use super::*;
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        jitter: false,
+        ..RetryPolicy::default()
+    }
+}
+
+fn status(code: u16) -> Failure {
+    Failure::Status {
+        status: StatusCode::from_u16(code).unwrap(),
+        retry_after: None,
+    }
+}
+
+#[test]
+fn test_default_policy() {
+    let policy = RetryPolicy::default();
+    assert_eq!(policy.max_retries, 3);
+    assert_eq!(policy.initial_backoff, Duration::from_millis(500));
+    assert_eq!(policy.max_backoff, Duration::from_secs(30));
+    assert!(policy.jitter);
+}
+
+#[test]
+fn test_rate_limit_retried_for_every_method() {
+    for method in ["GET", "POST", "PATCH", "DELETE"] {
+        assert!(
+            policy().delay(method, &status(429), 1).is_some(),
+            "{}",
+            method
+        );
+    }
+}
+
+#[test]
+fn test_conflict_retried_for_every_method() {
+    assert!(policy().delay("POST", &status(409), 1).is_some());
+    assert!(policy().delay("PATCH", &status(409), 1).is_some());
+}
+
+#[test]
+fn test_server_errors_retried_only_when_idempotent() {
+    for code in [500, 502, 503, 504] {
+        assert!(policy().delay("GET", &status(code), 1).is_some());
+        assert!(policy().delay("DELETE", &status(code), 1).is_some());
+        assert!(policy().delay("POST", &status(code), 1).is_none());
+        assert!(policy().delay("PATCH", &status(code), 1).is_none());
+    }
+}
+
+#[test]
+fn test_client_errors_not_retried() {
+    for code in [400, 401, 403, 404] {
+        assert!(policy().delay("GET", &status(code), 1).is_none());
+    }
+}
+
+#[test]
+fn test_network_failures() {
+    assert!(policy().delay("POST", &Failure::Connect, 1).is_some());
+    assert!(policy().delay("GET", &Failure::Timeout, 1).is_some());
+    assert!(policy().delay("POST", &Failure::Timeout, 1).is_none());
+    assert!(policy().delay("GET", &Failure::Network, 1).is_some());
+    assert!(policy().delay("PATCH", &Failure::Network, 1).is_none());
+}
+
+#[test]
+fn test_gives_up_after_max_retries() {
+    let policy = policy();
+    assert!(policy.delay("GET", &status(503), 3).is_some());
+    assert!(policy.delay("GET", &status(503), 4).is_none());
+
+    let none = RetryPolicy {
+        max_retries: 0,
+        ..RetryPolicy::default()
+    };
+    assert!(none.delay("GET", &status(429), 1).is_none());
+}
+
+#[test]
+fn test_retry_after_is_honored() {
+    let failure = Failure::Status {
+        status: StatusCode::TOO_MANY_REQUESTS,
+        retry_after: Some(Duration::from_secs(120)),
+    };
+    assert_eq!(
+        policy().delay("GET", &failure, 1),
+        Some(Duration::from_secs(120))
+    );
+}
+
+#[test]
+fn test_backoff_doubles_and_caps() {
+    let policy = RetryPolicy {
+        max_backoff: Duration::from_secs(3),
+        ..policy()
+    };
+    assert_eq!(policy.backoff(1), Duration::from_millis(500));
+    assert_eq!(policy.backoff(2), Duration::from_millis(1000));
+    assert_eq!(policy.backoff(3), Duration::from_millis(2000));
+    assert_eq!(policy.backoff(4), Duration::from_secs(3));
+    assert_eq!(policy.backoff(40), Duration::from_secs(3));
+}
+
+#[test]
+fn test_backoff_jitter_stays_within_half_and_full() {
+    let policy = RetryPolicy::default();
+    for _ in 0..50 {
+        let wait = policy.backoff(2);
+        assert!(wait >= Duration::from_millis(500), "{:?}", wait);
+        assert!(wait <= Duration::from_millis(1000), "{:?}", wait);
+    }
+}
+
+#[test]
+fn test_retry_after_header() {
+    let mut headers = reqwest::header::HeaderMap::new();
+    assert_eq!(retry_after(&headers), None);
+    headers.insert("retry-after", "7".parse().unwrap());
+    assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+    headers.insert(
+        "retry-after",
+        "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+    );
+    assert_eq!(retry_after(&headers), None);
+}
+
+#[test]
+fn test_failure_display() {
+    assert_eq!(status(429).to_string(), "Rate limited (429)");
+    assert_eq!(status(409).to_string(), "Conflict (409)");
+    assert_eq!(status(502).to_string(), "Server error (502 Bad Gateway)");
+    assert_eq!(Failure::Timeout.to_string(), "Request timed out");
+    assert_eq!(Failure::Connect.to_string(), "Connection failed");
+}
@@ -0,0 +1,140 @@
+use anyhow::{Context, Result};
+use reqwest::header::{AUTHORIZATION, HeaderMap};
+use serde_json::Value;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::retry::Attempt;
+
+/// Body fields whose values are secrets (OAuth token exchange, introspection)
+const SECRET_FIELDS: &[&str] = &["token", "access_token", "refresh_token", "client_secret"];
+
+const REDACTED: &str = "[REDACTED]";
+
+/// HTTP trace for `-v` / `-vv`, written to stderr or a log file so stdout
+/// stays clean for `--raw` and piping.
+///
+/// Level 1 logs one line per request and per attempt outcome; level 2 adds
+/// headers and bodies, with credentials redacted.
+pub struct Tracer {
+    level: u8,
+    sink: Mutex<Box<dyn Write + Send>>,
+}
+
+impl Tracer {
+    pub fn new(level: u8, sink: Box<dyn Write + Send>) -> Self {
+        Self {
+            level,
+            sink: Mutex::new(sink),
+        }
+    }
+
+    pub fn stderr(level: u8) -> Self {
+        Self::new(level, Box::new(std::io::stderr()))
+    }
+
+    /// Append to `path`, creating it if needed.
+    pub fn to_file(level: u8, path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open log file {}", path.display()))?;
+        Ok(Self::new(level, Box::new(file)))
+    }
+
+    /// Log a request about to be sent; `defaults` are the client's default headers.
+    pub fn request(&self, request: &reqwest::Request, defaults: &HeaderMap) {
+        let mut lines = vec![format!("> {} {}", request.method(), request.url())];
+        if self.level >= 2 {
+            for (name, value) in defaults.iter().chain(request.headers()) {
+                let value = value.to_str().unwrap_or("<binary>");
+                lines.push(format!(
+                    "> {}: {}",
+                    name,
+                    redact_header(name.as_str(), value)
+                ));
+            }
+            if let Some(body) = request.body() {
+                match body.as_bytes() {
+                    Some(bytes) => lines.push(redact_body(&String::from_utf8_lossy(bytes))),
+                    None => lines.push("<streamed body>".to_string()),
+                }
+            }
+        }
+        self.write(&lines);
+    }
+
+    /// Log how an attempt ended.
+    pub fn attempt(&self, attempt: &Attempt) {
+        let mut line = format!("< {} in {}ms", attempt.outcome, attempt.elapsed.as_millis());
+        if let Some(id) = &attempt.request_id {
+            line.push_str(&format!(" (x-request-id: {})", id));
+        }
+        if let Some(wait) = attempt.retry_in {
+            line.push_str(&format!(", retrying in {}ms", wait.as_millis()));
+        }
+        self.write(&[line]);
+    }
+
+    /// Log a response body (level 2 only).
+    pub fn response_body(&self, text: &str) {
+        if self.level >= 2 && !text.is_empty() {
+            self.write(&[redact_body(text)]);
+        }
+    }
+
+    fn write(&self, lines: &[String]) {
+        let mut sink = self.sink.lock().unwrap_or_else(|e| e.into_inner());
+        // Tracing must never fail the request itself
+        for line in lines {
+            let _ = writeln!(sink, "{}", line);
+        }
+        let _ = sink.flush();
+    }
+}
+
+/// Header value safe to log: only the auth scheme of `Authorization` is kept.
+pub fn redact_header(name: &str, value: &str) -> String {
+    if !name.eq_ignore_ascii_case(AUTHORIZATION.as_str()) {
+        return value.to_string();
+    }
+    match value.split_once(' ') {
+        Some((scheme, _)) => format!("{} {}", scheme, REDACTED),
+        None => REDACTED.to_string(),
+    }
+}
+
+/// Body safe to log: pretty-printed JSON with secret fields masked.
+///
+/// Non-JSON bodies are returned unchanged.
+pub fn redact_body(text: &str) -> String {
+    let Ok(mut value) = serde_json::from_str::<Value>(text) else {
+        return text.to_string();
+    };
+    redact_value(&mut value);
+    serde_json::to_string_pretty(&value).unwrap_or_else(|_| text.to_string())
+}
+
+/// Mask secret fields (tokens, client secrets) anywhere in a JSON value.
+pub fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if SECRET_FIELDS.contains(&key.as_str()) && value.is_string() {
+                    *value = Value::from(REDACTED);
+                } else {
+                    redact_value(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+#[path = "trace_tests.rs"]
+mod tests;